@@ -63,7 +63,8 @@ async fn main(_spawner: Spawner) {
             ],
             ..Default::default()
         },
-    );
+    )
+    .unwrap();
 
     info!("Hello eSPI");
 
@@ -104,6 +105,11 @@ async fn main(_spawner: Spawner) {
                     espi.oob_reset_ack();
                 }
             }
+            Ok(Event::GpioChange(change)) => {
+                for (index, level) in change.changes() {
+                    info!("Virtual GPIO {} is now {}", index, level);
+                }
+            }
             Err(_) => {
                 error!("Failed");
             }
@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::adc::{Adc, ChannelConfig, Config, InterruptHandler, FIFO_DEPTH};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embassy_time::Timer;
+
+bind_interrupts!(struct Irqs {
+    ADC0 => InterruptHandler<peripherals::ADC0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+    let channel_config = [
+        ChannelConfig::single_ended(p.PIO0_5),
+        ChannelConfig::single_ended(p.PIO0_6),
+    ];
+    let mut adc = Adc::new(p.ADC0, Irqs, Config::default(), channel_config);
+
+    loop {
+        // Queue a few sequences, then collect them with a single burst read
+        for _ in 0..3 {
+            adc.trigger();
+            Timer::after_micros(100).await;
+        }
+
+        let mut results = [0u16; FIFO_DEPTH];
+        match adc.read_fifo_burst(&mut results) {
+            Ok(n) => info!("burst of {} results: {:#x}", n, results[..n]),
+            Err(_) => error!("ADC FIFO overflow"),
+        }
+
+        // Fill the FIFO and collect it in one go once the watermark interrupt fires
+        for _ in 0..FIFO_DEPTH / 2 {
+            adc.trigger();
+            Timer::after_micros(100).await;
+        }
+
+        match adc.drain_fifo_when_full(&mut results).await {
+            Ok(()) => info!("full FIFO: {:#x}", results),
+            Err(_) => error!("ADC FIFO overflow"),
+        }
+
+        Timer::after_millis(1000).await;
+    }
+}
@@ -0,0 +1,57 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::adc::{Adc, Calibration, ChannelConfig, Config, InterruptHandler};
+use embassy_imxrt::{bind_interrupts, peripherals};
+
+// Tie PIO0_5 (ADC0 channel 0A) to GND before running.
+
+bind_interrupts!(struct Irqs {
+    ADC0 => InterruptHandler<peripherals::ADC0>;
+});
+
+/// Samples averaged per reading
+const SAMPLES: i32 = 16;
+/// Largest accepted reading of the grounded channel, in 12-bit LSB
+const MAX_OFFSET_LSB: i32 = 3;
+
+async fn grounded_reading(adc: &mut Adc<'_, 1>) -> i32 {
+    let mut sum = 0;
+    for _ in 0..SAMPLES {
+        let mut data = [0i16; 1];
+        adc.sample(&mut data).await;
+        // 12-bit result in bits 14:3
+        sum += i32::from(data[0] >> 3);
+    }
+    sum / SAMPLES
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("ADC calibration test start");
+
+    let config = Config {
+        calibration: Calibration::Skip,
+        ..Default::default()
+    };
+    let mut adc = Adc::new(p.ADC0, Irqs, config, [ChannelConfig::single_ended(p.PIO0_5)]);
+    let uncalibrated = grounded_reading(&mut adc).await;
+    info!("uncalibrated: {} LSB", uncalibrated);
+
+    let values = adc.calibrate();
+    info!("calibration values: {}", values);
+    let calibrated = grounded_reading(&mut adc).await;
+    info!("calibrated: {} LSB", calibrated);
+
+    if calibrated.abs() <= MAX_OFFSET_LSB {
+        info!("ADC calibration test passed");
+    } else {
+        error!("ADC calibration test failed");
+    }
+}
@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::adc::{AdcMonitor, ChannelConfig, Config, InterruptHandler, MonitorConfig, MonitorRange};
+use embassy_imxrt::{bind_interrupts, peripherals};
+
+bind_interrupts!(struct Irqs {
+    ADC0 => InterruptHandler<peripherals::ADC0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    // Report PIO0_5 leaving the middle half of the input range
+    let monitor = MonitorConfig {
+        low: 0x4000,
+        high: 0xc000,
+        range: MonitorRange::Outside,
+        hysteresis: 0x0800,
+    };
+    let mut adc = AdcMonitor::new(
+        p.ADC0,
+        Irqs,
+        Config::default(),
+        ChannelConfig::single_ended(p.PIO0_5),
+        monitor,
+    )
+    .unwrap();
+
+    loop {
+        let sample = adc.wait_out_of_range().await;
+        info!("ADC input out of range: {:#x}", sample);
+    }
+}
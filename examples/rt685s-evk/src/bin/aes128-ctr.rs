@@ -0,0 +1,59 @@
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_imxrt::hashcrypt::Hashcrypt;
+use {defmt_rtt as _, panic_probe as _};
+
+// NIST SP 800-38A, F.5.1 CTR-AES128.Encrypt
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+const NONCE: [u8; 16] = [
+    0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff,
+];
+const PLAINTEXT: [u8; 64] = [
+    0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a, 0xae, 0x2d, 0x8a,
+    0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, 0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c,
+    0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef, 0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad,
+    0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10,
+];
+const CIPHERTEXT: [u8; 64] = [
+    0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce, 0x98, 0x06, 0xf6,
+    0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff, 0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5,
+    0xd3, 0x5e, 0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab, 0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, 0x79,
+    0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee,
+];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+    let mut hashcrypt = Hashcrypt::new_blocking(p.HASHCRYPT);
+
+    // Encrypt the whole stream in pieces that do not line up with the blocks
+    info!("Streaming encryption");
+    let mut buf = PLAINTEXT;
+    {
+        let mut ctr = hashcrypt.new_aes128_ctr(&KEY, &NONCE);
+        let (first, rest) = buf.split_at_mut(5);
+        let (second, third) = rest.split_at_mut(30);
+        ctr.encrypt_in_place(first);
+        ctr.encrypt_in_place(second);
+        ctr.encrypt_in_place(third);
+    }
+    defmt::assert_eq!(buf, CIPHERTEXT);
+
+    // Decrypt only the third block by seeking to it
+    info!("Random access decryption");
+    let mut block = [0u8; 16];
+    block.copy_from_slice(&CIPHERTEXT[32..48]);
+    {
+        let mut ctr = hashcrypt.new_aes128_ctr(&KEY, &NONCE);
+        ctr.set_counter_value(2);
+        ctr.decrypt_in_place(&mut block);
+    }
+    defmt::assert_eq!(&block[..], &PLAINTEXT[32..48]);
+
+    info!("AES-128 CTR test vectors passed");
+}
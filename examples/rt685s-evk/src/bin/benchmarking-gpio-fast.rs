@@ -0,0 +1,68 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use cortex_m::peripheral::DWT;
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::gpio::{DriveMode, DriveStrength, FastOutput, Level, Output, SlewRate};
+use embassy_time::Timer;
+
+// Cycle count of Output vs FastOutput level changes, measured with the DWT cycle counter.
+// FastOutput must stay well below Output, a single store per call.
+
+const ITERATIONS: u32 = 1000;
+
+fn measure(mut f: impl FnMut()) -> u32 {
+    cortex_m::interrupt::free(|_| {
+        let start = DWT::cycle_count();
+        for _ in 0..ITERATIONS {
+            f();
+        }
+        DWT::cycle_count().wrapping_sub(start)
+    })
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("GPIO fast path benchmark start");
+
+    // Unused pin, watch it on a scope to compare the toggle rates
+    let mut output = Output::new(
+        p.PIO1_0,
+        Level::Low,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+
+    let baseline = measure(|| cortex_m::asm::nop());
+    let slow = measure(|| {
+        output.set_high();
+        output.set_low();
+    });
+
+    let mut fast = FastOutput::new(output);
+    let quick = measure(|| {
+        fast.set_high();
+        fast.set_low();
+    });
+
+    let slow = slow.saturating_sub(baseline) / (2 * ITERATIONS);
+    let quick = quick.saturating_sub(baseline) / (2 * ITERATIONS);
+    info!("Output: {} cycles per level change, FastOutput: {}", slow, quick);
+
+    if quick < slow {
+        info!("GPIO fast path benchmark passed");
+    } else {
+        error!("GPIO fast path benchmark failed");
+    }
+
+    loop {
+        fast.toggle();
+        Timer::after_millis(500).await;
+    }
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::{bind_interrupts, dma, i2c, peripherals, uart};
+
+// Compile check of the `bind_interrupts!` syntax: a cfg'd-out arm next to an enabled arm
+// for the same interrupt, and an arm binding two handlers, one of them cfg'd out.
+bind_interrupts!(struct Irqs {
+    #[cfg(any())]
+    FLEXCOMM2 => uart::InterruptHandler<peripherals::FLEXCOMM2>;
+    #[cfg(all())]
+    FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+    FLEXCOMM4 =>
+        i2c::InterruptHandler<peripherals::FLEXCOMM4>,
+        uart::InterruptHandler<peripherals::FLEXCOMM4>,
+        #[cfg(any())]
+        uart::InterruptHandler<peripherals::FLEXCOMM5>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    // Only the enabled FLEXCOMM2 arm provides this binding. FLEXCOMM4 is never enabled here,
+    // as its two handlers would both run on the same flexcomm.
+    let _fc2 = I2cMaster::new_async(p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, Irqs, Speed::Standard, dma::NoDma).unwrap();
+
+    info!("bind_interrupts cfg arms compiled");
+}
@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::timer::{CaptureChEdge, CaptureStream, PinIntSlot, StreamMode, TimerClockSource};
+use {defmt_rtt as _, panic_probe as _};
+
+const TIMESTAMPS: usize = 4096;
+
+static mut TIMESTAMP_BUF: [u32; TIMESTAMPS] = [0; TIMESTAMPS];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    // SAFETY: the buffer is only handed out once, here
+    let buf = unsafe { &mut *core::ptr::addr_of_mut!(TIMESTAMP_BUF) };
+
+    info!("Collecting {} rising edge timestamps on PIO1_7", TIMESTAMPS);
+
+    let mut stream = CaptureStream::new(
+        p.CTIMER0_CAPTURE_CHANNEL0,
+        p.PIO1_7,
+        TimerClockSource::Sfro,
+        p.DMA0_CH10,
+        PinIntSlot::PinInt0,
+        CaptureChEdge::Rising,
+        buf,
+        StreamMode::OneShot,
+    )
+    .unwrap();
+
+    match stream.next().await {
+        Ok(timestamps) => {
+            let first = timestamps[0];
+            let last = timestamps[TIMESTAMPS - 1];
+            info!(
+                "Captured {} edges over {} timer counts",
+                timestamps.len(),
+                last.wrapping_sub(first)
+            );
+        }
+        Err(e) => error!("Capture stream failed: {}", e),
+    }
+}
@@ -0,0 +1,70 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_imxrt::crc::{Config, Crc, Polynomial};
+use embassy_imxrt::dma::Dma;
+use {defmt_rtt as _, panic_probe as _};
+
+const BLOCK_LEN: usize = 1024;
+
+/// Bitwise CRC32-ISO-HDLC, used as reference
+fn software_crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn crc32_config() -> Config {
+    Config {
+        polynomial: Polynomial::Crc32,
+        reverse_in: true,
+        reverse_out: true,
+        complement_out: true,
+        seed: 0xffff_ffff,
+        ..Default::default()
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut p = embassy_imxrt::init(Default::default());
+
+    let data = embassy_imxrt::static_dma_buffer!(BLOCK_LEN);
+    for (i, b) in data.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    let expected = software_crc32(data);
+
+    let mut crc = Crc::new(&mut p.CRC, crc32_config());
+    let output = crc.compute_blocking_bulk(data);
+    defmt::assert_eq!(output, expected);
+
+    let ch = Dma::reserve_channel(p.DMA0_CH0).unwrap();
+
+    let mut crc = Crc::new(&mut p.CRC, crc32_config());
+    let output = unwrap!(crc.compute_dma(data, &ch).await);
+    defmt::assert_eq!(output, expected);
+
+    // Unaligned start and end are fed by the CPU
+    let mut crc = Crc::new(&mut p.CRC, crc32_config());
+    let output = unwrap!(crc.compute_dma(&data[1..BLOCK_LEN - 1], &ch).await);
+    defmt::assert_eq!(output, software_crc32(&data[1..BLOCK_LEN - 1]));
+
+    info!("CRC DMA tests completed");
+}
@@ -170,5 +170,101 @@ async fn main(_spawner: Spawner) {
     let output = crc.feed_bytes(data);
     defmt::assert_eq!(output, 0x0376_e6e7);
 
+    // CRC16-KERMIT
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::CrcCcitt,
+            reverse_in: true,
+            reverse_out: true,
+            complement_out: false,
+            seed: 0,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0x2189);
+
+    // CRC16-XMODEM
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::CrcCcitt,
+            reverse_in: false,
+            reverse_out: false,
+            complement_out: false,
+            seed: 0,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0x31c3);
+
+    // CRC16-IBM-SDLC, xorout applied in software instead of by complement_out
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::CrcCcitt,
+            reverse_in: true,
+            reverse_out: true,
+            complement_out: false,
+            seed: 0xffff,
+            xor_out: 0xffff,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0x906e);
+
+    // CRC16-GENIBUS
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::CrcCcitt,
+            reverse_in: false,
+            reverse_out: false,
+            complement_out: false,
+            seed: 0xffff,
+            xor_out: 0xffff,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0xd64e);
+
+    // CRC32-BZIP2
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::Crc32,
+            reverse_in: false,
+            reverse_out: false,
+            complement_out: false,
+            seed: 0xffff_ffff,
+            xor_out: 0xffff_ffff,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0xfc89_1918);
+
+    // CRC32-ISO-HDLC
+    let mut crc = Crc::new(
+        &mut p.CRC,
+        Config {
+            polynomial: Polynomial::Crc32,
+            reverse_in: true,
+            reverse_out: true,
+            complement_out: false,
+            seed: 0xffff_ffff,
+            xor_out: 0xffff_ffff,
+            ..Default::default()
+        },
+    );
+    let output = crc.feed_bytes(data);
+    defmt::assert_eq!(output, 0xcbf4_3926);
+
+    info!("CRC test passed");
+
     loop {}
 }
@@ -0,0 +1,88 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_imxrt::prelude::*;
+use embassy_imxrt::timer::{Async, CountingTimer, TimerExternalClock};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+// CTIMER0 counts edges of a GPIO driven clock, wiring:
+//   PIO1_0 (GPIO, clock generator) -> PIO1_7 (CTIMER0 external clock input)
+
+bind_interrupts!(struct Irqs {
+    CTIMER0 => timer::CtimerInterruptHandler<peripherals::CTIMER0_COUNT_CHANNEL0>;
+});
+
+/// Rising edges the wait lasts, with the external clock declared as 1 MHz
+const EDGES: u32 = 10;
+
+async fn pulses(clk: &mut Output<'_>, count: u32) {
+    for _ in 0..count {
+        clk.set_high();
+        Timer::after_micros(100).await;
+        clk.set_low();
+        Timer::after_micros(100).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("CTimer external clock test start");
+
+    let mut clk = Output::new(
+        p.PIO1_0,
+        Level::Low,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+
+    let mut timer = CountingTimer::<Async>::new_with_external_clock(
+        p.CTIMER0_COUNT_CHANNEL0,
+        p.PIO1_7,
+        TimerExternalClock::default(),
+    )
+    .unwrap();
+    timer.set_external_freq_hz(1_000_000);
+
+    let mut passed = true;
+
+    // One edge short of the match, the wait must still be pending once the clock stops
+    let wait = timer.wait_us(EDGES);
+    let generate = async {
+        pulses(&mut clk, EDGES - 1).await;
+        Timer::after_millis(10).await;
+    };
+    match select(wait, generate).await {
+        Either::First(_) => {
+            error!("wait completed after {} edges", EDGES - 1);
+            passed = false;
+        }
+        Either::Second(_) => info!("wait pending after {} edges", EDGES - 1),
+    }
+
+    // The full count of edges ends the wait
+    let wait = timer.wait_us(EDGES);
+    let generate = async {
+        pulses(&mut clk, EDGES).await;
+        Timer::after_millis(10).await;
+    };
+    match select(wait, generate).await {
+        Either::First(_) => info!("wait completed after {} edges", EDGES),
+        Either::Second(_) => {
+            error!("wait still pending after {} edges", EDGES);
+            passed = false;
+        }
+    }
+
+    if passed {
+        info!("CTimer external clock test passed");
+    } else {
+        error!("CTimer external clock test failed");
+    }
+}
@@ -0,0 +1,86 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_imxrt::prelude::*;
+use embassy_imxrt::timer::{MatchAction, MatchMode, MatchOutput, MatchPulse, PulseWidthCapture};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+// Hardware timed pulses of CTIMER4 measured by CTIMER0, wiring:
+//   PIO3_9 (CTIMER4 match output 1) -> PIO1_7 (CTIMER0 capture input)
+// PIO3_10 (CTIMER4 match output 2) toggles as a square wave, for a scope.
+
+const WIDTHS_US: [u32; 4] = [5, 20, 200, 1_000];
+
+bind_interrupts!(struct Irqs {
+    CTIMER0 => timer::CtimerInterruptHandler<peripherals::CTIMER0_CAPTURE_CHANNEL0>;
+    CTIMER4 => timer::CtimerInterruptHandler<peripherals::CTIMER4_COUNT_CHANNEL0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("CTimer match output test start");
+
+    let mut capture = PulseWidthCapture::new(
+        p.CTIMER0_CAPTURE_CHANNEL0,
+        p.CTIMER0_CAPTURE_CHANNEL1,
+        p.PIO1_7,
+        TimerClockSource::Ffro,
+    )
+    .unwrap();
+
+    let mut passed = true;
+
+    {
+        let mut pulse = MatchPulse::new(
+            p.CTIMER4_COUNT_CHANNEL1,
+            p.CTIMER4_COUNT_CHANNEL0,
+            p.PIO3_9,
+            TimerClockSource::Ffro,
+        )
+        .unwrap();
+
+        for width in WIDTHS_US {
+            let measure = capture.measure_pulse_us();
+            let generate = async {
+                pulse.pulse(100, width).unwrap();
+                pulse.pulse_done().await;
+            };
+
+            let (measured, _) = join(measure, generate).await;
+
+            if measured.abs_diff(width) <= 1 {
+                info!("{} us pulse measured as {} us", width, measured);
+            } else {
+                error!("{} us pulse measured as {} us", width, measured);
+                passed = false;
+            }
+        }
+    }
+
+    let mut square = MatchOutput::new(p.CTIMER4_COUNT_CHANNEL2, p.PIO3_10, TimerClockSource::Ffro);
+
+    // One-shot set after 500 us
+    square.start(MatchAction::Set, 500, MatchMode::OneShot).unwrap();
+    square.match_done().await;
+    if !square.level() {
+        error!("one-shot set left the output low");
+        passed = false;
+    }
+
+    // 1 kHz square wave
+    square.start(MatchAction::Toggle, 500, MatchMode::Repeating).unwrap();
+    Timer::after_millis(100).await;
+    square.stop();
+
+    if passed {
+        info!("CTimer match output test passed");
+    } else {
+        error!("CTimer match output test failed");
+    }
+}
@@ -0,0 +1,64 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::pwm::{CentiPercent, MicroSeconds, Pwm};
+use embassy_imxrt::timer::{CTimerPwmBank, CTimerPwmOutput, TimerClockSource};
+use embassy_time::Timer;
+
+// Three PWM outputs of CTIMER4 with independent duty cycles, channel 0 sets the shared period.
+
+const DUTY: [CentiPercent; 3] = [CentiPercent(25, 0), CentiPercent(50, 0), CentiPercent(75, 0)];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("CTimer PWM bank test start");
+
+    let mut bank = CTimerPwmBank::new(
+        p.CTIMER4_COUNT_CHANNEL0,
+        MicroSeconds(10_000),
+        TimerClockSource::Sfro,
+        [
+            CTimerPwmOutput::new(p.CTIMER4_COUNT_CHANNEL1, p.PIO3_9),
+            CTimerPwmOutput::new(p.CTIMER4_COUNT_CHANNEL2, p.PIO3_10),
+            CTimerPwmOutput::new(p.CTIMER4_COUNT_CHANNEL3, p.PIO0_31),
+        ],
+    )
+    .unwrap();
+
+    for (output, duty) in DUTY.into_iter().enumerate() {
+        bank.enable(output);
+        bank.set_duty(output, duty);
+    }
+
+    let mut passed = true;
+
+    // Changing the period keeps the duty cycle of every output
+    for period in [MicroSeconds(10_000), MicroSeconds(2_000), MicroSeconds(20_000)] {
+        bank.set_period(period);
+        Timer::after_millis(100).await;
+
+        for (output, duty) in DUTY.into_iter().enumerate() {
+            // Allow for rounding to the match register resolution
+            if bank.get_duty(output).0.abs_diff(duty.0) > 1 {
+                error!("output {} duty changed with period {} us", output, period.0);
+                passed = false;
+            }
+        }
+    }
+
+    if passed {
+        info!("CTimer PWM bank test passed");
+    } else {
+        error!("CTimer PWM bank test failed");
+    }
+
+    loop {
+        Timer::after_millis(1000).await;
+    }
+}
@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::delay::Delay;
+use embassy_imxrt::gpio;
+use embedded_hal_1::delay::DelayNs;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("Delay example, core clock {} Hz", embassy_imxrt::clocks::core_clock_rate());
+
+    let mut led = gpio::Output::new(
+        p.PIO0_26,
+        gpio::Level::Low,
+        gpio::DriveMode::PushPull,
+        gpio::DriveStrength::Normal,
+        gpio::SlewRate::Standard,
+    );
+
+    let mut delay = Delay;
+
+    // Short pulses for a scope, then a visible blink using the async delay
+    for _ in 0..10 {
+        led.set_high();
+        delay.delay_us(10);
+        led.set_low();
+        delay.delay_ns(500);
+    }
+
+    loop {
+        led.toggle();
+        embedded_hal_async::delay::DelayNs::delay_ms(&mut delay, 500).await;
+    }
+}
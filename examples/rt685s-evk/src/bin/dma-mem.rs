@@ -3,6 +3,7 @@
 
 use defmt::*;
 use embassy_executor::Spawner;
+use embassy_imxrt::dma::buffer::DmaBuffer;
 use embassy_imxrt::dma::channel::Channel;
 use embassy_imxrt::dma::transfer::{Priority, Transfer, TransferOptions, Width};
 use embassy_imxrt::dma::Dma;
@@ -20,17 +21,22 @@ macro_rules! test_dma_channel {
 
 async fn dma_test(ch: Channel<'static>, number: usize) {
     for width in [Width::Bit8, Width::Bit16, Width::Bit32] {
-        let mut srcbuf: [u8; TEST_LEN] = [0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
-        let mut dstbuf = [0u8; TEST_LEN];
+        // Word aligned so the same buffers work for every transfer width
+        let mut srcbuf = DmaBuffer::<TEST_LEN>::new();
+        let mut dstbuf = DmaBuffer::<TEST_LEN>::new();
+        srcbuf.copy_from_slice(&[0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
         srcbuf[0] = number as u8;
 
         let mut options = TransferOptions::default();
         options.width = width;
         options.priority = Priority::Priority0;
 
-        Transfer::new_write_mem(&ch, &srcbuf, &mut dstbuf, options).await;
+        Transfer::new_write_mem(&ch, &srcbuf, &mut dstbuf, options)
+            .unwrap()
+            .await
+            .unwrap();
 
-        if srcbuf == dstbuf {
+        if srcbuf.as_slice() == dstbuf.as_slice() {
             info!(
                 "DMA transfer width: {}, on channel {} completed successfully: {:02x}",
                 width.byte_width(),
@@ -0,0 +1,88 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_imxrt::dma::transfer::Transfer;
+use embassy_imxrt::dma::Dma;
+use embassy_imxrt::peripherals::DMA0_CH8;
+use embassy_imxrt::uart::{Config, LoopbackMode, Uart};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+const LEN: usize = 64;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("DMA progress test start");
+
+    // Internal loopback, every byte written to FLEXCOMM4 is received by FLEXCOMM4
+    let config = Config {
+        loopback_mode: LoopbackMode::Loopback,
+        ..Default::default()
+    };
+    let uart = Uart::new_blocking(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, config).unwrap();
+    let (mut tx, _rx) = uart.split();
+
+    // Stream the receiver into memory with a raw DMA transfer
+    // SAFETY: the USART4 receiver is not used by the driver while the transfer runs
+    let usart = unsafe { embassy_imxrt::pac::Usart4::steal() };
+    usart.fifocfg().modify(|_, w| w.dmarx().enabled());
+
+    let ch = Dma::reserve_channel::<DMA0_CH8>(p.DMA0_CH8).unwrap();
+    let mut buf = [0u8; LEN];
+    let transfer = Transfer::new_read(&ch, usart.fiford().as_ptr() as *const u8, &mut buf, Default::default()).unwrap();
+
+    let send = async {
+        for i in 0..LEN {
+            tx.blocking_write(&[i as u8]).unwrap();
+            Timer::after_millis(5).await;
+        }
+    };
+
+    let monitor = async {
+        let mut last = 0;
+        let mut ok = true;
+
+        while last < LEN {
+            let completed = transfer.completed();
+
+            if completed < last {
+                error!("Progress went backwards: {} after {}", completed, last);
+                ok = false;
+            }
+
+            // Pause halfway, no byte may land while paused
+            if last < LEN / 2 && completed >= LEN / 2 {
+                transfer.pause();
+                let paused = transfer.completed();
+                Timer::after_millis(15).await;
+
+                if transfer.completed() != paused {
+                    error!("Transfer progressed while paused");
+                    ok = false;
+                }
+                transfer.resume();
+            }
+
+            last = completed;
+            Timer::after_millis(2).await;
+        }
+
+        ok
+    };
+
+    let (_, ok) = join(send, monitor).await;
+
+    transfer.await.unwrap();
+    usart.fifocfg().modify(|_, w| w.dmarx().disabled());
+
+    if ok && buf.iter().enumerate().all(|(i, b)| *b == i as u8) {
+        info!("DMA progress test passed");
+    } else {
+        error!("DMA progress test failed: {:02x}", buf);
+    }
+}
@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::flash::{FlashStorageAsync, SECTOR_SIZE};
+use embedded_storage_async::nor_flash::{NorFlash, ReadNorFlash};
+use {defmt_rtt as _, panic_probe as _};
+
+// Well past the end of the firmware image
+const STORAGE_BASE: u32 = 0x0100_0000;
+const STORAGE_LEN: u32 = 0x1_0000;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    let mut flash = FlashStorageAsync::new(p.FLEXSPI, STORAGE_BASE, STORAGE_LEN).unwrap();
+    info!("Flash geometry: {}", flash.capacity());
+
+    flash.erase(0, SECTOR_SIZE as u32).await.unwrap();
+
+    // Small writes are coalesced into a single page program
+    let mut data = [0u8; 100];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    for (i, chunk) in data.chunks(10).enumerate() {
+        flash.write(i as u32 * 10, chunk).await.unwrap();
+    }
+    flash.flush().await.unwrap();
+
+    let mut readback = [0u8; 100];
+    flash.read(0, &mut readback).await.unwrap();
+
+    if readback != data {
+        error!("Flash storage readback mismatch");
+        return;
+    }
+
+    // Overwriting programmed bytes erases and restores the sector around them
+    let patch = [0xA5u8; 8];
+    flash.smart_write(20, &patch).await.unwrap();
+    data[20..28].copy_from_slice(&patch);
+
+    flash.read(0, &mut readback).await.unwrap();
+
+    if readback == data {
+        info!("Flash storage test passed");
+    } else {
+        error!("Flash smart_write readback mismatch");
+    }
+}
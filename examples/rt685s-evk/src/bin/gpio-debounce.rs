@@ -0,0 +1,67 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_imxrt::gpio;
+use embassy_time::{Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+const STABLE_PERIOD: Duration = Duration::from_millis(20);
+
+#[embassy_executor::task]
+async fn monitor_task(mut monitor: gpio::Input<'static>) {
+    loop {
+        monitor.wait_for_stable_falling_edge(STABLE_PERIOD).await;
+        debug!("Debounced falling edge detected");
+
+        monitor.wait_for_stable_high(STABLE_PERIOD).await;
+        debug!("Debounced high level detected");
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    debug!("Initializing GPIO");
+
+    let mut output = gpio::Output::new(
+        p.PIO1_2,
+        gpio::Level::High,
+        gpio::DriveMode::PushPull,
+        gpio::DriveStrength::Normal,
+        gpio::SlewRate::Standard,
+    );
+
+    let monitor = gpio::Input::new(p.PIO1_0, gpio::Pull::None, gpio::Inverter::Disabled);
+
+    spawner.spawn(monitor_task(monitor)).unwrap();
+
+    loop {
+        Timer::after_millis(500).await;
+
+        // Simulate a bouncing switch press, only one edge should be reported
+        for _ in 0..5 {
+            output.set_low();
+            Timer::after_millis(2).await;
+            output.set_high();
+            Timer::after_millis(2).await;
+        }
+        output.set_low();
+        debug!("Switch pressed");
+
+        Timer::after_millis(500).await;
+
+        for _ in 0..5 {
+            output.set_high();
+            Timer::after_millis(2).await;
+            output.set_low();
+            Timer::after_millis(2).await;
+        }
+        output.set_high();
+        debug!("Switch released");
+    }
+}
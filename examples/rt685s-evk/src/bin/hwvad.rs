@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::hwvad::{self, Config, Hwvad};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use {defmt_rtt as _, panic_probe as _};
+
+// On-board PDM microphone: clock on PIO2_16, data on PIO2_15
+
+bind_interrupts!(struct Irqs {
+    HWVAD0 => hwvad::InterruptHandler<peripherals::DMIC0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    info!("HWVAD example");
+    let p = embassy_imxrt::init(Default::default());
+
+    let mut vad = Hwvad::new(p.DMIC0, p.PIO2_16, p.PIO2_15, Irqs, Config::default()).unwrap();
+
+    loop {
+        info!("Waiting for voice activity");
+        vad.wait_for_activity().await;
+        info!("Voice activity detected, noise envelope {}", vad.noise_envelope());
+    }
+}
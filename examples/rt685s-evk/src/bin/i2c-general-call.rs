@@ -0,0 +1,107 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::i2c::slave::{Address, Command, I2cSlave, Response};
+use embassy_imxrt::i2c::{self, Async, GENERAL_CALL_RESET};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+
+// Master on FLEXCOMM4 (PIO0_29/PIO0_30), slaves on FLEXCOMM2 (PIO0_18/PIO0_17)
+// and FLEXCOMM5 (PIO1_4/PIO1_5), all wired to the same bus with pull-ups.
+//
+// Each slave has its own address and also listens for general calls. A general call reaches
+// both, and a read addressed to one slave right after it is answered by that slave only.
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+    FLEXCOMM4 => i2c::InterruptHandler<peripherals::FLEXCOMM4>;
+    FLEXCOMM5 => i2c::InterruptHandler<peripherals::FLEXCOMM5>;
+});
+
+const SLAVE_ADDRESSES: [u8; 2] = [0x20, 0x21];
+
+static RECEIVED: [Signal<CriticalSectionRawMutex, u8>; 2] = [Signal::new(), Signal::new()];
+
+#[embassy_executor::task(pool_size = 2)]
+async fn slave_service(id: usize, mut slave: I2cSlave<'static, Async>) {
+    loop {
+        let mut buf = [0u8; 2];
+
+        match slave.listen().await {
+            Ok(Command::GeneralCall) => match slave.respond_to_write(&mut buf).await {
+                Ok(Response::Complete(n)) | Ok(Response::Pending(n)) if n > 0 => RECEIVED[id].signal(buf[0]),
+                Ok(_) => error!("slave {}: empty general call", id),
+                Err(e) => error!("slave {}: write failed {}", id, e),
+            },
+            Ok(Command::Read) => {
+                if let Err(e) = slave.respond_to_read(&[SLAVE_ADDRESSES[id]]).await {
+                    error!("slave {}: read failed {}", id, e);
+                }
+            }
+            Ok(Command::Write) => error!("slave {}: unexpected directed write", id),
+            Ok(Command::Probe) => {}
+            Err(e) => error!("slave {}: listen failed {}", id, e),
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    info!("i2c general call example");
+    let p = embassy_imxrt::init(Default::default());
+
+    let mut slave0 = I2cSlave::new_async(
+        p.FLEXCOMM2,
+        p.PIO0_18,
+        p.PIO0_17,
+        Irqs,
+        Address::new(SLAVE_ADDRESSES[0]).unwrap(),
+        p.DMA0_CH4,
+    )
+    .unwrap();
+    let mut slave1 = I2cSlave::new_async(
+        p.FLEXCOMM5,
+        p.PIO1_4,
+        p.PIO1_5,
+        Irqs,
+        Address::new(SLAVE_ADDRESSES[1]).unwrap(),
+        p.DMA0_CH10,
+    )
+    .unwrap();
+    slave0.enable_general_call(true);
+    slave1.enable_general_call(true);
+    let mut master =
+        I2cMaster::new_async(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, Irqs, Speed::Standard, p.DMA0_CH9).unwrap();
+
+    spawner.must_spawn(slave_service(0, slave0));
+    spawner.must_spawn(slave_service(1, slave1));
+
+    // Address byte 0x00 followed by the reset command byte, then a repeated start and a read
+    // directed at slave 1
+    let mut id = [0u8; 1];
+    master
+        .general_call_read(&[GENERAL_CALL_RESET], SLAVE_ADDRESSES[1].into(), &mut id)
+        .await
+        .unwrap();
+
+    let (cmd0, cmd1) = join(RECEIVED[0].wait(), RECEIVED[1].wait()).await;
+
+    if cmd0 == GENERAL_CALL_RESET && cmd1 == GENERAL_CALL_RESET {
+        info!("Both slaves received the general call reset");
+    } else {
+        error!("Unexpected general call commands: {:#x} {:#x}", cmd0, cmd1);
+    }
+
+    if id[0] == SLAVE_ADDRESSES[1] {
+        info!("Directed read after the general call answered by slave 1");
+    } else {
+        error!("Directed read answered with {:#x}", id[0]);
+    }
+}
@@ -59,7 +59,7 @@ async fn slave_service(mut slave: I2cSlave<'static, Async>) {
                     }
                 }
             }
-            Command::Write => {
+            Command::Write | Command::GeneralCall => {
                 info!("Write");
                 loop {
                     match slave.respond_to_write(&mut r_buf).await.unwrap() {
@@ -0,0 +1,153 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::i2c::slave::{Address, Command, I2cSlave, Response};
+use embassy_imxrt::i2c::{self, Async};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embedded_hal_async::i2c::I2c;
+
+// Transfers around the 1024 byte limit of a single DMA descriptor, master on FLEXCOMM4
+// (PIO0_29/PIO0_30) and slave on FLEXCOMM2 (PIO0_18/PIO0_17) wired to the same bus.
+
+const ADDR: u8 = 0x20;
+const MAX_LEN: usize = 4096;
+const SIZES: [usize; 4] = [1023, 1024, 1025, 4096];
+// The slave DMA is programmed one descriptor at a time
+const SLAVE_CHUNK: usize = 1024;
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+    FLEXCOMM4 => i2c::InterruptHandler<peripherals::FLEXCOMM4>;
+});
+
+/// Number of bytes the slave received in the last write
+static WRITTEN: Signal<CriticalSectionRawMutex, (usize, bool)> = Signal::new();
+
+fn pattern(i: usize) -> u8 {
+    (i ^ (i >> 8)) as u8
+}
+
+#[embassy_executor::task]
+async fn slave_service(mut slave: I2cSlave<'static, Async>) {
+    let mut t_buf = [0u8; MAX_LEN];
+    let mut r_buf = [0u8; MAX_LEN];
+
+    for (i, e) in t_buf.iter_mut().enumerate() {
+        *e = pattern(i);
+    }
+
+    loop {
+        match slave.listen().await {
+            Ok(Command::Read) => {
+                let mut offset = 0;
+
+                while offset < MAX_LEN {
+                    let chunk = &t_buf[offset..(offset + SLAVE_CHUNK).min(MAX_LEN)];
+
+                    match slave.respond_to_read(chunk).await {
+                        Ok(Response::Complete(n)) | Ok(Response::Pending(n)) => {
+                            offset += n;
+                            if n < chunk.len() {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            error!("slave read failed {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(Command::Write) => {
+                let mut offset = 0;
+
+                while offset < MAX_LEN {
+                    let end = (offset + SLAVE_CHUNK).min(MAX_LEN);
+
+                    match slave.respond_to_write(&mut r_buf[offset..end]).await {
+                        Ok(Response::Pending(n)) => offset += n,
+                        Ok(Response::Complete(n)) => {
+                            offset += n;
+                            break;
+                        }
+                        Err(e) => {
+                            error!("slave write failed {}", e);
+                            break;
+                        }
+                    }
+                }
+
+                let ok = r_buf[..offset].iter().enumerate().all(|(i, b)| *b == pattern(i));
+                WRITTEN.signal((offset, ok));
+            }
+            Ok(_) => {}
+            Err(e) => error!("slave listen failed {}", e),
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn master_service(mut master: I2cMaster<'static, Async>) {
+    let mut w_buf = [0u8; MAX_LEN];
+    let mut r_buf = [0u8; MAX_LEN];
+
+    for (i, e) in w_buf.iter_mut().enumerate() {
+        *e = pattern(i);
+    }
+
+    for len in SIZES {
+        master.write(ADDR, &w_buf[..len]).await.unwrap();
+
+        match WRITTEN.wait().await {
+            (n, true) if n == len => info!("write of {} bytes ok", len),
+            (n, ok) => error!("write of {} bytes: slave got {} bytes, data ok {}", len, n, ok),
+        }
+
+        // Repeated start between the write and the read, no STOP between read chunks
+        r_buf.fill(0);
+        master.write_read(ADDR, &w_buf[..1], &mut r_buf[..len]).await.unwrap();
+        let _ = WRITTEN.wait().await;
+
+        if r_buf[..len].iter().enumerate().all(|(i, b)| *b == pattern(i)) {
+            info!("write_read of {} bytes ok", len);
+        } else {
+            error!("write_read of {} bytes returned wrong data", len);
+        }
+    }
+
+    // Zero length reads are rejected before touching the bus
+    match master.read(ADDR, &mut r_buf[..0]).await {
+        Err(i2c::Error::Transfer(i2c::TransferError::ZeroLengthRead)) => info!("zero length read rejected"),
+        other => error!("zero length read: unexpected {}", other),
+    }
+
+    info!("i2c large transfer test done");
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    info!("i2c large transfer example");
+    let p = embassy_imxrt::init(Default::default());
+
+    let slave = I2cSlave::new_async(
+        p.FLEXCOMM2,
+        p.PIO0_18,
+        p.PIO0_17,
+        Irqs,
+        Address::new(ADDR).unwrap(),
+        p.DMA0_CH4,
+    )
+    .unwrap();
+
+    let master = I2cMaster::new_async(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, Irqs, Speed::Fast, p.DMA0_CH9).unwrap();
+
+    spawner.must_spawn(slave_service(slave));
+    spawner.must_spawn(master_service(master));
+}
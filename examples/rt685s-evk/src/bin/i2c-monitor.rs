@@ -0,0 +1,69 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::i2c::monitor::{I2cMonitor, MonitorEvent};
+use embassy_imxrt::i2c::{self, Async};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embedded_hal_async::i2c::I2c;
+
+// Bus monitor on FLEXCOMM2 (PIO0_18/PIO0_17) wired to the bus of the master on FLEXCOMM4
+// (PIO0_29/PIO0_30). Nothing answers on the bus, so every address is NACKed.
+
+const ADDR: u8 = 0x20;
+const TRACE_LEN: usize = 64;
+
+static mut TRACE_BUF: [u32; TRACE_LEN] = [0; TRACE_LEN];
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+    FLEXCOMM4 => i2c::InterruptHandler<peripherals::FLEXCOMM4>;
+});
+
+#[embassy_executor::task]
+async fn master_service(mut master: I2cMaster<'static, Async>) {
+    loop {
+        // The write fails with an address NACK, the monitor still sees it on the bus
+        let _ = master.write(ADDR, &[0x55]).await;
+        embassy_time::Timer::after_millis(100).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    info!("i2c monitor example");
+    let p = embassy_imxrt::init(Default::default());
+
+    // SAFETY: the buffer is only handed out once, here
+    let trace = unsafe { &mut *core::ptr::addr_of_mut!(TRACE_BUF) };
+
+    let mut monitor = I2cMonitor::new(p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, Irqs, trace, false).unwrap();
+
+    let master = I2cMaster::new_async(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, Irqs, Speed::Standard, p.DMA0_CH9).unwrap();
+    spawner.must_spawn(master_service(master));
+
+    loop {
+        match monitor.next_event().await {
+            MonitorEvent::Start {
+                address,
+                read,
+                repeated,
+                acked,
+            } => info!(
+                "{} 0x{:02x} {} {}",
+                if repeated { "Sr" } else { "S" },
+                address,
+                if read { "R" } else { "W" },
+                if acked { "ACK" } else { "NACK" }
+            ),
+            MonitorEvent::Data(byte) => info!("  0x{:02x} ACK", byte),
+            MonitorEvent::NackedData(byte) => info!("  0x{:02x} NACK", byte),
+            MonitorEvent::Stop => info!("P"),
+            MonitorEvent::Overflow => error!("trace gap"),
+        }
+    }
+}
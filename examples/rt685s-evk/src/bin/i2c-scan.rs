@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::gpio::{DriveMode, DriveStrength, Level, Output, SlewRate};
+use embassy_imxrt::i2c;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    // Keep the on-board accelerometer out of reset so it shows up in the scan
+    let _reset_pin = Output::new(
+        p.PIO1_7,
+        Level::Low,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+
+    // FLEXCOMM2 on P0_18 (SCL) and P0_17 (SDA)
+    let mut i2c =
+        i2c::master::I2cMaster::new_blocking(p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, i2c::master::Speed::Standard).unwrap();
+
+    info!("Scanning I2C bus");
+
+    match i2c.scan(i2c::SCAN_ADDRESSES) {
+        Ok(found) => {
+            for address in found {
+                info!("Found device at 0x{:02x}", address);
+            }
+        }
+        Err(e) => error!("Scan failed: {}", e),
+    }
+}
@@ -0,0 +1,68 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+use embassy_executor::{InterruptExecutor, Spawner};
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::i2c::{self, Async};
+use embassy_imxrt::interrupt::{InterruptExt, Priority};
+use embassy_imxrt::{bind_interrupts, interrupt, peripherals};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::once_lock::OnceLock;
+use embassy_time::Timer;
+use embedded_hal_async::i2c::I2c;
+
+// One I2C master shared by a thread mode and an interrupt mode executor through a mutex.
+// Both tasks read the WHO_AM_I register of the FXOS8700 accelerometer on the RT685S-EVK.
+
+const ACC_ADDR: u8 = 0x1E;
+const ACC_ID_REG: u8 = 0x0D;
+const ACC_ID: u8 = 0xC7;
+
+type Bus = Mutex<CriticalSectionRawMutex, I2cMaster<'static, Async>>;
+
+static BUS: OnceLock<Bus> = OnceLock::new();
+static EXECUTOR_HIGH: InterruptExecutor = InterruptExecutor::new();
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+});
+
+#[embassy_imxrt::pac::interrupt]
+unsafe fn MU_A() {
+    EXECUTOR_HIGH.on_interrupt()
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn reader(name: &'static str, bus: &'static Bus, interval_ms: u64) {
+    let mut dev = I2cDevice::new(bus);
+
+    loop {
+        let mut id = [0u8; 1];
+        match dev.write_read(ACC_ADDR, &[ACC_ID_REG], &mut id).await {
+            Ok(()) if id[0] == ACC_ID => info!("{}: accelerometer id ok", name),
+            Ok(()) => error!("{}: unexpected id {:02x}", name, id[0]),
+            Err(e) => error!("{}: read failed {}", name, e),
+        }
+
+        Timer::after_millis(interval_ms).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    let i2c = I2cMaster::new_async(p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, Irqs, Speed::Standard, p.DMA0_CH5).unwrap();
+    let bus = BUS.get_or_init(|| Mutex::new(i2c));
+
+    interrupt::MU_A.set_priority(Priority::P3);
+    let high_spawner = EXECUTOR_HIGH.start(interrupt::MU_A);
+
+    high_spawner.must_spawn(reader("high", bus, 10));
+    spawner.must_spawn(reader("low", bus, 25));
+}
@@ -41,7 +41,7 @@ async fn slave_service(mut i2c: I2cSlave<'static, Async>) {
                     }
                 }
             }
-            Command::Write => {
+            Command::Write | Command::GeneralCall => {
                 info!("Write");
                 loop {
                     match i2c.respond_to_write(&mut buf).await.unwrap() {
@@ -36,7 +36,7 @@ async fn slave_service(i2c: I2cSlave<'static, Blocking>) {
                     }
                 }
             }
-            Command::Write => {
+            Command::Write | Command::GeneralCall => {
                 info!("Write");
                 loop {
                     match i2c.respond_to_write(&mut buf).unwrap() {
@@ -0,0 +1,101 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::clocks::core_clock_rate;
+use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+use embassy_imxrt::pmic::{Pca9420, PowerMode, Rail};
+use embassy_imxrt::{bind_interrupts, dma, i2c, pac, peripherals};
+use embassy_time::Timer;
+
+// Dynamic voltage and frequency scaling with the PCA9420 PMIC of the EVK: VDDCORE (SW1) is lowered
+// only after the core clock has been divided down, and restored before the clock is raised again.
+// Voltages are changed in single 25 mV steps to keep the transitions small.
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM15 => i2c::InterruptHandler<peripherals::FLEXCOMM15>;
+});
+
+/// The EVK runs in PMIC mode 0 while active
+const RUN_MODE: PowerMode = PowerMode::Mode0;
+/// VDDCORE reduction at the lower core clock
+const UNDERVOLT_MV: u16 = 100;
+const STEP_MV: u16 = 25;
+
+/// Set the SYSCPUAHBCLK divider, the core clock is the main clock divided by `div`
+fn set_core_clock_div(div: u8) {
+    // SAFETY: only the core clock divider is written, waiting for the change to complete
+    let clkctl0 = unsafe { pac::Clkctl0::steal() };
+    // SAFETY: unsafe only used for .bits()
+    clkctl0.syscpuahbclkdiv().write(|w| unsafe { w.div().bits(div - 1) });
+    while clkctl0.syscpuahbclkdiv().read().reqflag().bit_is_set() {}
+}
+
+async fn ramp_vddcore(pmic: &mut Pca9420<'_>, from_mv: u16, to_mv: u16) -> Result<(), embassy_imxrt::pmic::Error> {
+    let mut mv = from_mv;
+    while mv != to_mv {
+        mv = if to_mv > mv { mv + STEP_MV } else { mv - STEP_MV };
+        pmic.set_rail_voltage_mv(RUN_MODE, Rail::Sw1, mv).await?;
+        Timer::after_millis(1).await;
+    }
+    Ok(())
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("PMIC DVFS test start");
+
+    let i2c = I2cMaster::new_async(
+        p.FLEXCOMM15,
+        p.PIOFC15_SCL,
+        p.PIOFC15_SDA,
+        Irqs,
+        Speed::Fast,
+        dma::NoDma,
+    )
+    .unwrap();
+    let mut pmic = Pca9420::new(i2c);
+
+    info!("PCA9420 device info: {:02x}", pmic.device_info().await.unwrap());
+
+    let nominal_mv = pmic.rail_voltage_mv(RUN_MODE, Rail::Sw1).await.unwrap();
+    let low_mv = nominal_mv - UNDERVOLT_MV;
+    // SAFETY: read only
+    let nominal_div = unsafe { pac::Clkctl0::steal() }.syscpuahbclkdiv().read().div().bits() + 1;
+    info!("core clock {} Hz, VDDCORE {} mV", core_clock_rate(), nominal_mv);
+
+    // Values outside of the SW1 range are refused
+    if pmic.set_rail_voltage_mv(RUN_MODE, Rail::Sw1, 400).await.is_ok() {
+        error!("PMIC DVFS test failed: out of range voltage accepted");
+        return;
+    }
+
+    // Scale down: frequency first, then voltage
+    set_core_clock_div(nominal_div * 2);
+    ramp_vddcore(&mut pmic, nominal_mv, low_mv).await.unwrap();
+    info!(
+        "core clock {} Hz, VDDCORE {} mV",
+        core_clock_rate(),
+        pmic.rail_voltage_mv(RUN_MODE, Rail::Sw1).await.unwrap()
+    );
+
+    Timer::after_millis(100).await;
+
+    // Scale up: voltage first, then frequency
+    ramp_vddcore(&mut pmic, low_mv, nominal_mv).await.unwrap();
+    set_core_clock_div(nominal_div);
+
+    let restored_mv = pmic.rail_voltage_mv(RUN_MODE, Rail::Sw1).await.unwrap();
+    info!("core clock {} Hz, VDDCORE {} mV", core_clock_rate(), restored_mv);
+
+    if restored_mv == nominal_mv {
+        info!("PMIC DVFS test passed");
+    } else {
+        error!("PMIC DVFS test failed");
+    }
+}
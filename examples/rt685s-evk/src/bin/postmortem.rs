@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::{exception, ExceptionFrame};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::postmortem;
+use embassy_imxrt::prelude::*;
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+// Faults after a while and snapshots the UART registers from the HardFault handler. After the
+// reset the snapshot of the previous run is logged.
+
+#[exception]
+unsafe fn HardFault(_frame: &ExceptionFrame) -> ! {
+    postmortem::capture_all();
+    cortex_m::peripheral::SCB::sys_reset()
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    postmortem::dump_previous();
+
+    let mut uart = Uart::new_blocking(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, Default::default()).unwrap();
+    uart.blocking_write(b"about to fault\r\n").unwrap();
+
+    Timer::after_secs(1).await;
+
+    info!("Faulting");
+    cortex_m::asm::udf();
+}
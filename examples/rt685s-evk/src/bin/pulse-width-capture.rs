@@ -0,0 +1,73 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_imxrt::prelude::*;
+use embassy_imxrt::timer::PulseWidthCapture;
+use embassy_time::{block_for, Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+// Pulses shorter than the interrupt latency, wiring:
+//   PIO1_0 (GPIO, pulse generator) -> PIO1_7 (CTIMER0 capture input)
+
+const WIDTHS_US: [u32; 4] = [20, 50, 200, 1_000];
+
+bind_interrupts!(struct Irqs {
+    CTIMER0 => timer::CtimerInterruptHandler<peripherals::CTIMER0_CAPTURE_CHANNEL0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("Pulse width capture test start");
+
+    let mut pulse = Output::new(
+        p.PIO1_0,
+        Level::Low,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+
+    let mut capture = PulseWidthCapture::new(
+        p.CTIMER0_CAPTURE_CHANNEL0,
+        p.CTIMER0_CAPTURE_CHANNEL1,
+        p.PIO1_7,
+        TimerClockSource::Ffro,
+    )
+    .unwrap();
+
+    let mut passed = true;
+
+    for width in WIDTHS_US {
+        let measure = capture.measure_pulse_us();
+
+        // Emit the pulse with interrupts masked so both edges land before the capture interrupt runs
+        let generate = async {
+            Timer::after_millis(1).await;
+            cortex_m::interrupt::free(|_| {
+                pulse.set_high();
+                block_for(Duration::from_micros(width as u64));
+                pulse.set_low();
+            });
+        };
+
+        let (measured, _) = join(measure, generate).await;
+
+        if measured.abs_diff(width) <= 2 {
+            info!("{} us pulse measured as {} us", width, measured);
+        } else {
+            error!("{} us pulse measured as {} us", width, measured);
+            passed = false;
+        }
+    }
+
+    if passed {
+        info!("Pulse width capture test passed");
+    } else {
+        error!("Pulse width capture test failed");
+    }
+}
@@ -0,0 +1,120 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_imxrt::gpio::{DriveMode, DriveStrength, Flex, Function, Level, Output, SlewRate};
+use embassy_imxrt::pwm::{
+    self, CentiPercent, Channel, FaultConfig, FaultInput, MicroSeconds, Pwm, SCTClockSource, SCTPwm,
+};
+use embassy_imxrt::timer::{self, CaptureChEdge, CaptureTimer, TimerClockSource};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+// Hardware fault input of the SCT PWM, wiring:
+//   PIO0_26 (SCT0_OUT6, PWM output) -> PIO1_7 (CTIMER0 capture input)
+//   PIO1_0 (GPIO, fault driver)     -> PIO0_1 (SCT0_GPI1, fault input)
+
+const PERIOD_US: u32 = 1_000;
+
+bind_interrupts!(struct Irqs {
+    SCT0 => pwm::InterruptHandler<peripherals::SCT0>;
+    CTIMER0 => timer::CtimerInterruptHandler<peripherals::CTIMER0_CAPTURE_CHANNEL0>;
+});
+
+/// Waits for a rising PWM edge for up to `periods` PWM periods
+async fn edge_within<P: timer::CaptureEvent>(cap: &mut CaptureTimer<timer::Async, P>, periods: u32) -> bool {
+    let edge = cap.capture_event_time_us(CaptureChEdge::Rising);
+    matches!(
+        select(edge, Timer::after_micros((periods * PERIOD_US) as u64)).await,
+        Either::First(_)
+    )
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("PWM fault test start");
+
+    let mut fault_out = Output::new(
+        p.PIO1_0,
+        Level::Low,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+
+    let mut fault_in = Flex::new(p.PIO0_1);
+    fault_in.set_as_peripheral(Function::F3);
+    let mut pwm_pin = Flex::new(p.PIO0_26);
+    pwm_pin.set_as_peripheral(Function::F3);
+
+    let mut sct = SCTPwm::new(p.SCT0, MicroSeconds(PERIOD_US), SCTClockSource::FFRO);
+
+    let mut safe_state = [None; 10];
+    safe_state[6] = Some(Level::Low);
+    sct.enable_fault(
+        Irqs,
+        FaultConfig {
+            input: FaultInput::Gpi1,
+            active_level: Level::High,
+            safe_state,
+        },
+    );
+
+    sct.set_duty(Channel::Ch6, CentiPercent(50, 0));
+    sct.enable(Channel::Ch6);
+
+    let mut cap = CaptureTimer::new_async(p.CTIMER0_CAPTURE_CHANNEL0, p.PIO1_7, TimerClockSource::Sfro);
+
+    let mut passed = true;
+
+    if !edge_within(&mut cap, 2).await {
+        error!("PWM not running");
+        passed = false;
+    }
+
+    // Trigger the fault, software must learn about it
+    fault_out.set_high();
+    match select(sct.wait_for_fault(), Timer::after_micros(PERIOD_US as u64)).await {
+        Either::First(Ok(())) => info!("fault reported"),
+        _ => {
+            error!("fault not reported within one period");
+            passed = false;
+        }
+    }
+
+    // Any edge already in flight must land within one period, none after that
+    Timer::after_micros(PERIOD_US as u64).await;
+    if edge_within(&mut cap, 5).await {
+        error!("PWM output still toggling after the fault");
+        passed = false;
+    }
+
+    if sct.clear_fault() != Err(pwm::Error::FaultActive) {
+        error!("fault cleared while the input is still active");
+        passed = false;
+    }
+
+    // Release the fault input, the output stays safe until the fault is cleared explicitly
+    fault_out.set_low();
+    Timer::after_micros(PERIOD_US as u64).await;
+    if edge_within(&mut cap, 3).await {
+        error!("PWM output resumed without clear_fault");
+        passed = false;
+    }
+
+    if sct.clear_fault().is_err() || !edge_within(&mut cap, 3).await {
+        error!("PWM did not resume after clear_fault");
+        passed = false;
+    }
+
+    if passed {
+        info!("PWM fault test passed");
+    } else {
+        error!("PWM fault test failed");
+    }
+}
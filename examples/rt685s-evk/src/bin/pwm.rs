@@ -7,7 +7,7 @@ use defmt::info;
 use embassy_executor::Spawner;
 use embassy_imxrt::pac;
 use embassy_imxrt::pwm::{CentiPercent, Channel, MicroSeconds, SCTClockSource, SCTPwm};
-use embassy_imxrt::timer::{CTimerPwm, CTimerPwmPeriodChannel};
+use embassy_imxrt::timer::{CTimerPwm, CTimerPwmPeriodChannel, TimerClockSource};
 use embassy_time::Timer;
 
 // TODO: connect with GPIO port when that is ready
@@ -60,7 +60,8 @@ async fn main(_spawner: Spawner) {
 
     let mut sct0 = SCTPwm::new(p.SCT0, MicroSeconds(10_000), SCTClockSource::Main);
 
-    let ctimerperiodchannel = CTimerPwmPeriodChannel::new(p.CTIMER4_COUNT_CHANNEL0, MicroSeconds(10_000)).unwrap();
+    let ctimerperiodchannel =
+        CTimerPwmPeriodChannel::new(p.CTIMER4_COUNT_CHANNEL0, MicroSeconds(10_000), TimerClockSource::Sfro).unwrap();
 
     // CTIMER4_MAT3 configuration for PIO0_31
     info!("GPIO0_31 is red LED on rt685-evk");
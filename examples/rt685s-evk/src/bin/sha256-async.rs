@@ -3,21 +3,26 @@
 
 use defmt::*;
 use embassy_executor::Spawner;
-use embassy_imxrt::hashcrypt::{hasher, Hashcrypt};
+use embassy_imxrt::bind_interrupts;
+use embassy_imxrt::hashcrypt::{self, hasher, Hashcrypt};
 use {defmt_rtt as _, panic_probe as _};
 
+bind_interrupts!(struct Irqs {
+    HASHCRYPT => hashcrypt::InterruptHandler;
+});
+
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
     let p = embassy_imxrt::init(Default::default());
     let mut hash = [0u8; hasher::HASH_LEN];
 
     info!("Initializing Hashcrypt");
-    let mut hashcrypt = Hashcrypt::new_async(p.HASHCRYPT, p.DMA0_CH30);
+    let mut hashcrypt = Hashcrypt::new_async(p.HASHCRYPT, p.DMA0_CH30, Irqs);
 
     info!("Starting hashes");
     // Data that fits into a single block
     info!("Single hash block");
-    hashcrypt.new_sha256().hash(b"abc", &mut hash).await;
+    hashcrypt.new_sha256().hash(b"abc", &mut hash).await.unwrap();
     defmt::assert_eq!(
         &hash,
         &[
@@ -34,7 +39,8 @@ async fn main(_spawner: Spawner) {
             b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890!@#$%^&*()",
             &mut hash,
         )
-        .await;
+        .await
+        .unwrap();
     defmt::assert_eq!(
         &hash,
         &[
@@ -51,7 +57,8 @@ async fn main(_spawner: Spawner) {
             b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890!@",
             &mut hash,
         )
-        .await;
+        .await
+        .unwrap();
     defmt::assert_eq!(
         &hash,
         &[
@@ -65,7 +72,7 @@ async fn main(_spawner: Spawner) {
     hashcrypt.new_sha256().hash(
         b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890!@abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ12345678",
         &mut hash,
-    ).await;
+    ).await.unwrap();
     defmt::assert_eq!(
         &hash,
         &[
@@ -3,7 +3,7 @@
 
 use defmt::*;
 use embassy_executor::Spawner;
-use embassy_imxrt::hashcrypt::{hasher, Hashcrypt};
+use embassy_imxrt::hashcrypt::{hasher, HashVerifyError, Hashcrypt};
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
@@ -67,5 +67,22 @@ async fn main(_spawner: Spawner) {
             0x44, 0x95, 0x1d, 0xcd, 0xfc, 0xd0, 0x89, 0x90, 0xef, 0xe2, 0xb2, 0x4d, 0xac, 0x79
         ]
     );
+
+    // Constant time comparison against a reference hash
+    info!("Verify");
+    let expected = hash;
+    let data = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890!@abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ12345678";
+    let mut hasher = hashcrypt.new_sha256();
+    hasher.submit_blocks(&data[..hasher::BLOCK_LEN]);
+    defmt::assert_eq!(hasher.verify(&data[hasher::BLOCK_LEN..], &expected), Ok(()));
+
+    let mut corrupted = expected;
+    corrupted[hasher::HASH_LEN - 1] ^= 1;
+    let mut hasher = hashcrypt.new_sha256();
+    hasher.submit_blocks(&data[..hasher::BLOCK_LEN]);
+    defmt::assert_eq!(
+        hasher.verify(&data[hasher::BLOCK_LEN..], &corrupted),
+        Err(HashVerifyError::Mismatch)
+    );
     trace!("Hashes complete");
 }
@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::gpio::{DriveMode, DriveStrength, Level, Output, SlewRate};
+use embassy_imxrt::spi::{Config, SpiDeviceExclusive, SpiMaster};
+use embedded_hal_1::spi::{Operation, SpiDevice};
+use {defmt_rtt as _, panic_probe as _};
+
+// embedded-hal SpiDevice over internal loopback, with PIO1_6 driven as a GPIO chip select
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("SPI device test start");
+
+    let config = Config {
+        loopback: true,
+        ..Default::default()
+    };
+    let spi = SpiMaster::new_blocking(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, config).unwrap();
+    let cs = Output::new(
+        p.PIO1_6,
+        Level::High,
+        DriveMode::PushPull,
+        DriveStrength::Normal,
+        SlewRate::Standard,
+    );
+    let mut device = SpiDeviceExclusive::new(spi, cs).unwrap();
+
+    let command = [0x9Fu8];
+    let mut data = [0x12u8, 0x34, 0x56, 0x78];
+    let expected = data;
+
+    device
+        .transaction(&mut [
+            Operation::Write(&command),
+            Operation::DelayNs(1_000),
+            Operation::TransferInPlace(&mut data),
+        ])
+        .unwrap();
+
+    if data == expected {
+        info!("SPI device test passed");
+    } else {
+        error!("SPI device test failed: {:#x}", data);
+    }
+}
@@ -0,0 +1,85 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::spi::{Config, InterruptHandler, SpiMaster};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use {defmt_rtt as _, panic_probe as _};
+
+// Async transfer_in_place over internal loopback at the highest SCK the FFRO allows, once
+// through the FIFO interrupt path and once through DMA, with lengths that are not a multiple of
+// the FIFO trigger level.
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM5 => InterruptHandler<peripherals::FLEXCOMM5>;
+});
+
+const LENGTHS: [usize; 4] = [1, 3, 17, 300];
+
+async fn run(spi: &mut SpiMaster<'_, embassy_imxrt::spi::Async>, name: &str) -> bool {
+    let mut ok = true;
+
+    for len in LENGTHS {
+        let mut buf = [0u8; 300];
+        for (i, b) in buf[..len].iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(37) ^ 0xA5;
+        }
+        let expected = buf;
+
+        if let Err(e) = spi.transfer_in_place(&mut buf[..len]).await {
+            error!("{}: {} bytes failed: {}", name, len, e);
+            ok = false;
+            continue;
+        }
+        spi.flush().await.unwrap();
+
+        if buf[..len] != expected[..len] {
+            error!("{}: {} bytes corrupted", name, len);
+            ok = false;
+        }
+    }
+
+    ok
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut p = embassy_imxrt::init(Default::default());
+
+    info!("SPI async loopback test start");
+
+    let mut passed = true;
+
+    for (name, dma_threshold) in [("fifo", 1024), ("dma", 0)] {
+        let config = Config {
+            frequency: 50_000_000,
+            clock: embassy_imxrt::flexcomm::Clock::Ffro,
+            loopback: true,
+            dma_threshold,
+            ..Default::default()
+        };
+
+        let mut spi = SpiMaster::new_async(
+            &mut p.FLEXCOMM5,
+            &mut p.PIO1_3,
+            &mut p.PIO1_5,
+            &mut p.PIO1_4,
+            Irqs,
+            &mut p.DMA0_CH11,
+            &mut p.DMA0_CH10,
+            config,
+        )
+        .unwrap();
+
+        passed &= run(&mut spi, name).await;
+    }
+
+    if passed {
+        info!("SPI async loopback test passed");
+    } else {
+        error!("SPI async loopback test failed");
+    }
+}
@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::spi::SpiMaster;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("SPI loopback test start");
+
+    let mut spi = SpiMaster::new_blocking(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, Default::default()).unwrap();
+
+    spi.self_test().unwrap();
+
+    info!("SPI loopback test passed");
+}
@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::dma::Dma;
+use embassy_imxrt::spi::{Error, InterruptHandler, SpiTarget};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use embassy_time::Duration;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM5 => InterruptHandler<peripherals::FLEXCOMM5>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("SPI target DMA receive example");
+
+    let mut spi = SpiTarget::new_async(
+        p.FLEXCOMM5,
+        p.PIO1_3,
+        p.PIO1_5,
+        p.PIO1_4,
+        p.PIO1_6,
+        Irqs,
+        Default::default(),
+    )
+    .unwrap();
+    spi.set_cs_timeout(Some(Duration::from_secs(5)));
+
+    let rx_dma = Dma::reserve_channel(p.DMA0_CH10).unwrap();
+    let mut buf = [0u8; 256];
+
+    loop {
+        match spi.receive_dma(&mut buf, &rx_dma).await {
+            Ok(n) => info!("Received {} bytes: {:02x}", n, buf[..n]),
+            Err(Error::Timeout) => info!("No transaction within 5s"),
+            Err(e) => error!("Receive failed: {}", e),
+        }
+    }
+}
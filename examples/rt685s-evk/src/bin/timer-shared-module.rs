@@ -0,0 +1,118 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_imxrt::clocks::get_ctimer_clock_hz;
+use embassy_imxrt::timer::{Async, CountingTimer, TimerClockSource};
+use embassy_imxrt::{bind_interrupts, peripherals, timer};
+use embassy_time::{Instant, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+// Channels of CTIMER2 are created in varying orders while another channel of the module is
+// counting. Creating a channel must neither reset the running module nor change its clock.
+
+const MODULE: usize = 2;
+const ORDERS: [[usize; 4]; 4] = [[0, 1, 2, 3], [3, 2, 1, 0], [1, 3, 0, 2], [2, 0, 3, 1]];
+const LONG_WAIT_MS: u32 = 40;
+const SHORT_WAIT_MS: u32 = 10;
+const SFRO_HZ: u32 = 16_000_000;
+
+bind_interrupts!(struct Irqs {
+    CTIMER2 => timer::CtimerInterruptHandler<peripherals::CTIMER2_COUNT_CHANNEL0>;
+});
+
+fn new_channel(channel: usize, clock: TimerClockSource) -> CountingTimer<Async> {
+    // SAFETY: each channel is owned by at most one timer at a time in this test
+    unsafe {
+        match channel {
+            0 => CountingTimer::new_async(peripherals::CTIMER2_COUNT_CHANNEL0::steal(), clock),
+            1 => CountingTimer::new_async(peripherals::CTIMER2_COUNT_CHANNEL1::steal(), clock),
+            2 => CountingTimer::new_async(peripherals::CTIMER2_COUNT_CHANNEL2::steal(), clock),
+            _ => CountingTimer::new_async(peripherals::CTIMER2_COUNT_CHANNEL3::steal(), clock),
+        }
+    }
+}
+
+fn module_clock_enabled() -> bool {
+    // SAFETY: only reads the peripheral clock enables
+    let clkctl1 = unsafe { embassy_imxrt::pac::Clkctl1::steal() };
+    clkctl1.pscctl2().read().bits() & (1 << MODULE) != 0
+}
+
+async fn run(order: [usize; 4]) -> bool {
+    let mut ok = true;
+
+    // The first channel selects the module clock and keeps counting while the others are created
+    let mut first = new_channel(order[0], TimerClockSource::Sfro);
+    let start = Instant::now();
+
+    let others = async {
+        let mut ok = true;
+
+        for &channel in &order[1..] {
+            Timer::after_millis(2).await;
+
+            // Ignored while the module is in use
+            let mut tmr = new_channel(channel, TimerClockSource::Ffro);
+            if get_ctimer_clock_hz(MODULE) != SFRO_HZ {
+                error!("channel {} changed the module clock", channel);
+                ok = false;
+            }
+
+            let t = Instant::now();
+            tmr.wait_ms(SHORT_WAIT_MS).await;
+            let elapsed = t.elapsed().as_millis() as u32;
+            if elapsed.abs_diff(SHORT_WAIT_MS) > 2 {
+                error!("channel {} waited {} ms", channel, elapsed);
+                ok = false;
+            }
+        }
+
+        ok
+    };
+
+    let (_, others_ok) = join(first.wait_ms(LONG_WAIT_MS), others).await;
+
+    let elapsed = start.elapsed().as_millis() as u32;
+    if elapsed.abs_diff(LONG_WAIT_MS) > 2 {
+        error!("channel {} waited {} ms while others were created", order[0], elapsed);
+        ok = false;
+    }
+
+    drop(first);
+
+    if module_clock_enabled() {
+        error!("module clock still enabled after the last channel was dropped");
+        ok = false;
+    }
+
+    ok && others_ok
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let _p = embassy_imxrt::init(Default::default());
+
+    info!("CTimer shared module test start");
+
+    if module_clock_enabled() {
+        error!("module clock enabled before first use");
+    }
+
+    let mut passed = true;
+    for order in ORDERS {
+        if run(order).await {
+            info!("order {} ok", order);
+        } else {
+            passed = false;
+        }
+    }
+
+    if passed {
+        info!("CTimer shared module test passed");
+    } else {
+        error!("CTimer shared module test failed");
+    }
+}
@@ -3,8 +3,7 @@
 
 use defmt::info;
 use embassy_executor::Spawner;
-use embassy_imxrt::clocks::ClockConfig;
-use embassy_imxrt::timer::{CaptureChEdge, CaptureTimer, CountingTimer, TriggerInput};
+use embassy_imxrt::timer::{CaptureChEdge, CaptureTimer, CountingTimer, TimerClockSource, TriggerInput};
 use embassy_imxrt::{bind_interrupts, peripherals, timer};
 use embassy_time::Timer as Tmr;
 use {defmt_rtt as _, panic_probe as _};
@@ -32,11 +31,9 @@ async fn main(_spawner: Spawner) {
 
     _spawner.spawn(monitor_task()).unwrap();
 
-    let sfro = ClockConfig::crystal().sfro;
-    let mut tmr1 = CountingTimer::new_blocking(p.CTIMER0_COUNT_CHANNEL0, sfro);
+    let mut tmr1 = CountingTimer::new_blocking(p.CTIMER0_COUNT_CHANNEL0, TimerClockSource::Sfro);
 
-    let sfro = ClockConfig::crystal().sfro;
-    let mut tmr2 = CountingTimer::new_async(p.CTIMER1_COUNT_CHANNEL0, sfro);
+    let mut tmr2 = CountingTimer::new_async(p.CTIMER1_COUNT_CHANNEL0, TimerClockSource::Sfro);
 
     tmr1.wait_us(3000000); // 3 seoconds wait
     info!("First Counting timer expired");
@@ -46,8 +43,7 @@ async fn main(_spawner: Spawner) {
 
     // Creating a separate block to test Timer Drop logic
     {
-        let sfro = ClockConfig::crystal().sfro;
-        let mut cap_async_tmr = CaptureTimer::new_async(p.CTIMER0_CAPTURE_CHANNEL0, p.PIO1_7, sfro);
+        let mut cap_async_tmr = CaptureTimer::new_async(p.CTIMER0_CAPTURE_CHANNEL0, p.PIO1_7, TimerClockSource::Sfro);
 
         // pass the input mux number, Input pin and Input pin edge user is interested in
         // Input mux details can be found in NXP user manual section 8.6.8 and Pin Function Table in section 7.5.3
@@ -55,8 +51,7 @@ async fn main(_spawner: Spawner) {
 
         info!("Capture timer expired in = {} us", event_time_us);
 
-        let sfro = ClockConfig::crystal().sfro;
-        let mut cap_async_tmr = CaptureTimer::new_async(p.CTIMER4_CAPTURE_CHANNEL0, p.PIO0_5, sfro);
+        let mut cap_async_tmr = CaptureTimer::new_async(p.CTIMER4_CAPTURE_CHANNEL0, p.PIO0_5, TimerClockSource::Sfro);
         let event_time_us = cap_async_tmr.capture_cycle_time_us(CaptureChEdge::Rising).await;
 
         info!("Capture timer expired, time between two capture = {} us", event_time_us);
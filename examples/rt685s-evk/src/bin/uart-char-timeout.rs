@@ -0,0 +1,114 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::{error, info};
+use embassy_executor::{InterruptExecutor, SendSpawner, Spawner};
+use embassy_imxrt::interrupt;
+use embassy_imxrt::interrupt::{InterruptExt, Priority};
+use embassy_imxrt::uart::{Blocking, Config, LoopbackMode, Uart, UartRx, UartTx};
+use embassy_time::Timer;
+
+// Frames delimited by line silence over internal loopback, at 9600 and 115200 baud. A higher
+// priority task sends two segments separated by a pause shorter than the threshold, which must
+// arrive as one frame, then a third segment after a pause longer than the threshold, which must
+// arrive as a second frame. The reader blocks in thread mode. No wiring needed.
+
+const SEGMENT_A: [u8; 4] = [0x01, 0x03, 0x00, 0x10];
+const SEGMENT_B: [u8; 4] = [0x00, 0x02, 0xC5, 0xCE];
+const SEGMENT_C: [u8; 3] = [0x11, 0x22, 0x33];
+
+/// Pause after the first frame, well above both thresholds
+const LONG_PAUSE_MS: u64 = 20;
+
+static EXECUTOR_HIGH: InterruptExecutor = InterruptExecutor::new();
+
+#[embassy_imxrt::pac::interrupt]
+unsafe fn MU_A() {
+    EXECUTOR_HIGH.on_interrupt()
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn writer(mut tx: UartTx<'static, Blocking>, short_pause_ms: u64) {
+    // Let the reader start waiting first
+    Timer::after_millis(10).await;
+
+    // Pauses are timed from the end of the last character on the wire
+    tx.blocking_write(&SEGMENT_A).unwrap();
+    tx.blocking_flush().unwrap();
+    Timer::after_millis(short_pause_ms).await;
+
+    tx.blocking_write(&SEGMENT_B).unwrap();
+    tx.blocking_flush().unwrap();
+    Timer::after_millis(LONG_PAUSE_MS).await;
+
+    tx.blocking_write(&SEGMENT_C).unwrap();
+}
+
+fn run(
+    spawner: &SendSpawner,
+    (tx, mut rx): (UartTx<'static, Blocking>, UartRx<'static, Blocking>),
+    baudrate: u32,
+    timeout_chars: u8,
+    short_pause_ms: u64,
+) -> bool {
+    spawner.must_spawn(writer(tx, short_pause_ms));
+
+    let mut buf = [0u8; 16];
+    let mut ok = true;
+
+    let n = rx.blocking_read_with_char_timeout(&mut buf, timeout_chars).unwrap();
+    if n != SEGMENT_A.len() + SEGMENT_B.len() || buf[..4] != SEGMENT_A || buf[4..n] != SEGMENT_B {
+        error!("{} baud: short pause split the frame, got {:02x}", baudrate, &buf[..n]);
+        ok = false;
+    }
+
+    let n = rx.blocking_read_with_char_timeout(&mut buf, timeout_chars).unwrap();
+    if buf[..n] != SEGMENT_C {
+        error!(
+            "{} baud: long pause did not end the frame, got {:02x}",
+            baudrate,
+            &buf[..n]
+        );
+        ok = false;
+    }
+
+    if ok {
+        info!("{} baud ok", baudrate);
+    }
+
+    ok
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("UART inter-character timeout test start");
+
+    interrupt::MU_A.set_priority(Priority::P3);
+    let high_spawner = EXECUTOR_HIGH.start(interrupt::MU_A);
+
+    let config = |baudrate| Config {
+        baudrate,
+        loopback_mode: LoopbackMode::Loopback,
+        ..Default::default()
+    };
+
+    let mut passed = true;
+
+    // One character is 1.04 ms, the threshold of 4 characters 4.2 ms
+    let uart = Uart::new_blocking(p.FLEXCOMM4, p.PIO0_29, p.PIO0_30, config(9600)).unwrap();
+    passed &= run(&high_spawner, uart.split(), 9600, 4, 2);
+
+    // One character is 87 us, the threshold of 40 characters 3.5 ms, one embassy-time tick is 1 ms
+    let uart = Uart::new_blocking(p.FLEXCOMM2, p.PIO0_15, p.PIO0_16, config(115_200)).unwrap();
+    passed &= run(&high_spawner, uart.split(), 115_200, 40, 1);
+
+    if passed {
+        info!("UART inter-character timeout test passed");
+    } else {
+        error!("UART inter-character timeout test failed");
+    }
+}
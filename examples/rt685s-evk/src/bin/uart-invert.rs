@@ -0,0 +1,65 @@
+#![no_std]
+#![no_main]
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_imxrt::prelude::*;
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+// Signal inversion self test, wiring:
+//   PIO0_29 (FLEXCOMM4 TX) -> PIO0_30 (FLEXCOMM4 RX)
+//
+// Data must pass when both lines are inverted the same way, and must not pass when only one of
+// them is inverted.
+
+const CASES: [(bool, bool); 4] = [(false, false), (true, true), (true, false), (false, true)];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut p = embassy_imxrt::init(Default::default());
+
+    info!("UART invert test start");
+
+    let data = [0x00u8, 0x55, 0xA5, 0xFF];
+    let mut passed = true;
+
+    for (tx_invert, rx_invert) in CASES {
+        let config = uart::Config {
+            tx_invert,
+            rx_invert,
+            ..Default::default()
+        };
+        let mut uart = Uart::new_blocking(&mut p.FLEXCOMM4, &mut p.PIO0_29, &mut p.PIO0_30, config).unwrap();
+
+        // Let the receiver settle on the new idle level, then drop anything seen while switching
+        Timer::after_millis(1).await;
+        let mut byte = [0u8; 1];
+        while !matches!(uart.read(&mut byte), Err(uart::Error::RxFifoEmpty)) {}
+
+        uart.blocking_write(&data).unwrap();
+        uart.blocking_flush().unwrap();
+        Timer::after_millis(1).await;
+
+        let mut received = [0u8; 4];
+        let ok = uart.read(&mut received).is_ok() && received == data;
+
+        if ok == (tx_invert == rx_invert) {
+            info!("tx_invert {} rx_invert {} ok", tx_invert, rx_invert);
+        } else {
+            error!(
+                "tx_invert {} rx_invert {}: data {}",
+                tx_invert,
+                rx_invert,
+                if ok { "passed" } else { "lost" }
+            );
+            passed = false;
+        }
+    }
+
+    if passed {
+        info!("UART invert test passed");
+    } else {
+        error!("UART invert test failed");
+    }
+}
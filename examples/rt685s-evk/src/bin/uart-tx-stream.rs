@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::uart::{Config, UartTx, UartTxDmaStream};
+use embassy_imxrt::{bind_interrupts, peripherals, uart};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    FLEXCOMM4 => uart::InterruptHandler<peripherals::FLEXCOMM4>;
+});
+
+const PACKET_LEN: usize = 64;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    info!("UART TX DMA stream example");
+
+    let config = Config {
+        baudrate: 1_000_000,
+        ..Default::default()
+    };
+    let tx = UartTx::new_async(p.FLEXCOMM4, p.PIO0_29, Irqs, p.DMA0_CH9, config).unwrap();
+
+    let mut staging = [0u8; 4 * PACKET_LEN];
+    let mut stream = UartTxDmaStream::new(tx, &mut staging).unwrap();
+
+    // Packets are queued back-to-back, without idle time on the line between them
+    for i in 0..100u8 {
+        let packet = [i; PACKET_LEN];
+        stream.write_queued(&packet).await.unwrap();
+    }
+
+    stream.flush().await.unwrap();
+    info!("All packets sent");
+}
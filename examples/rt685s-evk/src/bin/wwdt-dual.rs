@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+
+extern crate embassy_imxrt_examples;
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_imxrt::wwdt::{self, Async, WindowedWatchdog};
+use embassy_imxrt::{bind_interrupts, peripherals};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    WDT0 => wwdt::InterruptHandler<peripherals::WDT0>;
+    WDT1 => wwdt::InterruptHandler<peripherals::WDT1>;
+});
+
+#[embassy_executor::task(pool_size = 2)]
+async fn watchdog_task(name: &'static str, mut wwdt: WindowedWatchdog<'static, Async>) {
+    wwdt.unleash();
+
+    // Without `enable_reset` a timeout only raises the warning, feed on every warning
+    loop {
+        wwdt.wait_for_warning().await;
+        wwdt.clear_warning_flag();
+        wwdt.feed();
+        info!("{}: warning, fed with {} us left", name, wwdt.timeout());
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_imxrt::init(Default::default());
+
+    let mut wdt0 = WindowedWatchdog::new_async(p.WDT0, Irqs, 1_000_000);
+    wdt0.clear_timeout_flag();
+    wdt0.set_warning_threshold(4_096);
+
+    let mut wdt1 = WindowedWatchdog::new_async(p.WDT1, Irqs, 2_500_000);
+    wdt1.clear_timeout_flag();
+    wdt1.set_warning_threshold(2_048);
+
+    info!("WDT0 locked: {}, WDT1 locked: {}", wdt0.is_locked(), wdt1.is_locked());
+
+    spawner.must_spawn(watchdog_task("WDT0", wdt0));
+    spawner.must_spawn(watchdog_task("WDT1", wdt1));
+}
@@ -4,33 +4,82 @@
 
 use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::ptr::{self, addr_of_mut};
 use core::task::Poll;
 
 use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_hal_internal::{impl_peripheral, into_ref, Peripheral, PeripheralRef};
 use embassy_sync::waitqueue::AtomicWaker;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{clock_guard, ClockGuard};
+use crate::dma::channel::Channel;
+use crate::dma::transfer::{TransferOptions, Width};
+use crate::dma::ChannelDescriptor;
 use crate::interrupt::typelevel::Binding;
-use crate::iopctl::{DriveMode, DriveStrength, Function, Inverter, IopctlPin, Pull, SlewRate};
+use crate::iopctl::{AnyPin, DriveMode, DriveStrength, Function, Inverter, IopctlPin, Pull, SlewRate};
 use crate::pac::adc0;
 use crate::peripherals::ADC0;
-use crate::{interrupt, peripherals};
+use crate::{dma, interrupt, peripherals};
 
 static WAKER: AtomicWaker = AtomicWaker::new();
 
+/// Waker of the threshold monitor, woken by the same ADC interrupt
+static MONITOR_WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Reload descriptors for the streaming ping-pong DMA transfer
+static mut STREAM_DESCRIPTORS: [ChannelDescriptor; 2] = [ChannelDescriptor::EMPTY; 2];
+
+/// Number of hardware trigger inputs (one TCTRL register each)
+const HW_TRIGGER_COUNT: u8 = 16;
+
+/// Number of entries in the result FIFO
+pub const FIFO_DEPTH: usize = 16;
+
+/// STAT: calibration completed
+const STAT_CAL_RDY: u32 = 1 << 10;
+
+/// CTRL: request gain calibration, cleared by hardware once started
+const CTRL_CAL_REQ: u32 = 1 << 3;
+/// CTRL: request offset calibration
+const CTRL_CALOFS: u32 = 1 << 4;
+/// CTRL: averages taken per calibration step, as a power of two
+const CTRL_CAL_AVGS_SHIFT: u32 = 16;
+const CTRL_CAL_AVGS_MASK: u32 = 0x7 << CTRL_CAL_AVGS_SHIFT;
+/// CTRL: 128 averages, the most accurate calibration
+const CTRL_CAL_AVGS_128: u32 = 0x7 << CTRL_CAL_AVGS_SHIFT;
+
+/// Offset of the OFSTRIM register, the offset calibration result
+const OFSTRIM_OFFSET: usize = 0x98;
+/// Offset of the CAL_GAR registers, the gain calibration results
+const CAL_GAR_OFFSET: usize = 0x400;
+/// Number of CAL_GAR registers
+pub const CAL_GAR_COUNT: usize = 33;
+
+/// Voltage of the VDDA_ADC1V8 reference in mV
+const VDDA_ADC1V8_MV: u32 = 1800;
+
 /// ADC error
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
     /// Invalid ADC configuration
     InvalidConfig,
+
+    /// Samples were overwritten before being read
+    Overrun,
 }
 
 /// ADC config
 pub struct Config {
     /// ADC voltage reference
     pub vref: Reference,
+    /// Voltage applied to the VREFP pin in mV, used with [`Reference::VRefP`]
+    pub vrefp_mv: u16,
+    /// Scaling of the inputs of all channels
+    pub scale: InputScale,
+    /// Calibration done when the driver is created
+    pub calibration: Calibration,
 }
 
 impl Default for Config {
@@ -38,10 +87,37 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             vref: Reference::VddaAdc1v8,
+            vrefp_mv: 1800,
+            scale: InputScale::Full,
+            calibration: Calibration::Auto,
         }
     }
 }
 
+/// ADC calibration when the driver is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Calibration {
+    /// Run the offset and gain calibration, see [`Adc::calibrate`]
+    Auto,
+    /// Load the results of an earlier calibration, e.g. stored in flash or OTP
+    ///
+    /// Skips the calibration, which takes a few milliseconds, when waking from deep sleep.
+    Stored(CalibrationValues),
+    /// Leave the ADC uncalibrated
+    Skip,
+}
+
+/// Raw ADC calibration registers, to be stored and loaded again with [`Calibration::Stored`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CalibrationValues {
+    /// OFSTRIM register, the offset trim of both sides
+    pub ofstrim: u32,
+    /// CAL_GAR registers, the gain calibration results
+    pub gain: [u32; CAL_GAR_COUNT],
+}
+
 /// ADC channel config
 pub struct ChannelConfig<'d> {
     /// Positive channel to sample
@@ -52,17 +128,24 @@ pub struct ChannelConfig<'d> {
 
 impl<'d> ChannelConfig<'d> {
     /// Default configuration for single ended channel sampling.
-    pub fn single_ended(input: impl Peripheral<P = impl Input> + 'd) -> Self {
+    ///
+    /// The pin is switched to analog input here and returned to its reset state when the
+    /// ADC driver owning this config is dropped.
+    pub fn single_ended(input: impl Peripheral<P = impl AdcPin<ADC0>> + 'd) -> Self {
         into_ref!(input);
+
+        let p: PeripheralRef<'_, AnyInput> = input.map_into();
+        p.configure();
+
         Self {
-            p_channel: input.map_into(),
+            p_channel: p,
             n_channel: None,
         }
     }
     /// Default configuration for differential channel sampling.
     pub fn differential(
-        p_input: impl Peripheral<P = impl Input> + 'd,
-        n_input: impl Peripheral<P = impl Input> + 'd,
+        p_input: impl Peripheral<P = impl AdcPin<ADC0>> + 'd,
+        n_input: impl Peripheral<P = impl AdcPin<ADC0>> + 'd,
     ) -> Result<Self, Error> {
         into_ref!(p_input, n_input);
 
@@ -72,10 +155,13 @@ impl<'d> ChannelConfig<'d> {
         // Check matching positive and negative pin are passed in
         // Do not need to check for side as there are only 1 channel for each
         //   polarity
-        if p.channel().ch != n.channel().ch {
+        if p.channel().index() != n.channel().index() {
             return Err(Error::InvalidConfig);
         }
 
+        p.configure();
+        n.configure();
+
         Ok(Self {
             p_channel: p,
             n_channel: Some(n),
@@ -95,13 +181,17 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
         // Disable fifo watermark interrupt
         reg.ie().write(|w| w.fwmie().fwmie_0());
         WAKER.wake();
+        MONITOR_WAKER.wake();
     }
 }
 
 /// ADC driver
 pub struct Adc<'p, const N: usize> {
     info: Info,
-    _lifetime: PhantomData<&'p ()>,
+    // Owns the analog pins for as long as the driver lives
+    channels: [ChannelConfig<'p>; N],
+    _clock: ClockGuard,
+    full_scale_mv: u32,
 }
 
 struct Info {
@@ -109,7 +199,7 @@ struct Info {
 }
 
 impl<const N: usize> Adc<'_, N> {
-    fn init() {
+    fn init() -> ClockGuard {
         let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
         let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
 
@@ -130,7 +220,7 @@ impl<const N: usize> Adc<'_, N> {
             .write(|w| unsafe { w.div().bits(0x0).halt().clear_bit() });
         while clkctl0.adc0fclkdiv().read().reqflag().bit_is_set() {}
 
-        enable_and_reset::<ADC0>();
+        clock_guard::<ADC0>()
     }
 
     fn configure_adc(&mut self, config: Config) {
@@ -170,9 +260,112 @@ impl<const N: usize> Adc<'_, N> {
 
         // Reset ADC fifo
         self.info.regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+
+        match config.calibration {
+            Calibration::Auto => {
+                self.calibrate();
+            }
+            Calibration::Stored(values) => self.load_calibration(&values),
+            Calibration::Skip => {}
+        }
+
+        let vref_mv = match config.vref {
+            Reference::VRefP => u32::from(config.vrefp_mv),
+            Reference::VddaAdc1v8 => VDDA_ADC1V8_MV,
+        };
+        self.full_scale_mv = match config.scale {
+            InputScale::Full => vref_mv,
+            InputScale::Scaled30Of64 => vref_mv * 64 / 30,
+        };
+
+        self.configure_channels(config.scale);
+    }
+
+    /// Input voltage in mV converted to the largest result, to convert results to millivolts
+    ///
+    /// Derived from the reference and input scale of the [`Config`] the driver was created with.
+    pub fn get_full_scale_mv(&self) -> u32 {
+        self.full_scale_mv
+    }
+
+    /// Run the offset and gain calibration and return its results
+    ///
+    /// Follows the auto-calibration procedure of the reference manual: offset calibration first,
+    /// then gain calibration averaging 128 conversions per step, with the analog circuit in its
+    /// highest power mode and pre-energized. The averaging and power settings are restored
+    /// afterwards. Blocks for a few milliseconds, and results queued in the FIFO are discarded.
+    ///
+    /// Store the returned values and pass them in [`Calibration::Stored`] to skip the procedure
+    /// next time.
+    pub fn calibrate(&mut self) -> CalibrationValues {
+        let regs = &self.info.regs;
+        let cfg = regs.cfg().read().bits();
+        let ctrl = regs.ctrl().read().bits();
+
+        // Calibration needs an empty FIFO
+        regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+
+        // CFG may only change while the ADC is disabled
+        regs.ctrl().modify(|_, w| w.adcen().adcen_0());
+        regs.cfg().modify(|_, w| w.pwrsel().pwrsel_3().pwren().pwren_1());
+        regs.ctrl().modify(|_, w| w.adcen().adcen_1());
+
+        // SAFETY: unsafe only used for .bits()
+        regs.ctrl().modify(|r, w| unsafe { w.bits(r.bits() | CTRL_CALOFS) });
+        while regs.stat().read().bits() & STAT_CAL_RDY == 0 {}
+        // SAFETY: unsafe only used for .bits()
+        regs.ctrl().modify(|r, w| unsafe { w.bits(r.bits() & !CTRL_CALOFS) });
+
+        // SAFETY: unsafe only used for .bits()
+        regs.ctrl()
+            .modify(|r, w| unsafe { w.bits((r.bits() & !CTRL_CAL_AVGS_MASK) | CTRL_CAL_AVGS_128 | CTRL_CAL_REQ) });
+        while regs.stat().read().bits() & STAT_CAL_RDY == 0 {}
+
+        regs.ctrl().modify(|_, w| w.adcen().adcen_0());
+        // SAFETY: restoring values read back from the same registers
+        regs.cfg().write(|w| unsafe { w.bits(cfg) });
+        // SAFETY: unsafe only used for .bits()
+        regs.ctrl()
+            .modify(|r, w| unsafe { w.bits((r.bits() & !CTRL_CAL_AVGS_MASK) | (ctrl & CTRL_CAL_AVGS_MASK)) });
+        regs.ctrl().modify(|_, w| w.adcen().adcen_1());
+
+        let values = self.calibration_values();
+        debug!("ADC calibrated, OFSTRIM {:#x}", values.ofstrim);
+        values
+    }
+
+    /// Current contents of the calibration registers
+    pub fn calibration_values(&self) -> CalibrationValues {
+        let base = crate::pac::Adc0::ptr() as *const u8;
+        let mut values = CalibrationValues {
+            // SAFETY: OFSTRIM is a readable register of this ADC
+            ofstrim: unsafe { ptr::read_volatile(base.add(OFSTRIM_OFFSET).cast::<u32>()) },
+            gain: [0; CAL_GAR_COUNT],
+        };
+
+        for (i, gain) in values.gain.iter_mut().enumerate() {
+            // SAFETY: CAL_GAR is a readable register array of this ADC
+            *gain = unsafe { ptr::read_volatile(base.add(CAL_GAR_OFFSET).cast::<u32>().add(i)) };
+        }
+
+        values
+    }
+
+    /// Load the results of an earlier [`Adc::calibrate`] instead of calibrating again
+    pub fn load_calibration(&mut self, values: &CalibrationValues) {
+        let base = crate::pac::Adc0::ptr() as *mut u8;
+
+        // SAFETY: OFSTRIM and CAL_GAR are writable registers of this ADC, owned by the driver
+        unsafe {
+            ptr::write_volatile(base.add(OFSTRIM_OFFSET).cast::<u32>(), values.ofstrim);
+            for (i, gain) in values.gain.iter().enumerate() {
+                ptr::write_volatile(base.add(CAL_GAR_OFFSET).cast::<u32>().add(i), *gain);
+            }
+        }
     }
 
-    fn configure_channels(&mut self, channel_config: &[ChannelConfig; N]) {
+    fn configure_channels(&mut self, scale: InputScale) {
+        let channel_config = &self.channels;
         let mut cmd = channel_config.len();
 
         // Configure conversion CMD configuration
@@ -196,7 +389,7 @@ impl<const N: usize> Adc<'_, N> {
                     .diff()
                     .variant(diff) /* Differential or single-ended */
                     .cscale()
-                    .cscale_1() /* Full scale */
+                    .variant(scale.into()) /* Input scaling */
             });
 
             self.info.regs.cmdh(cmd_index).write(|w| unsafe {
@@ -238,18 +431,18 @@ impl<'p, const N: usize> Adc<'p, N> {
         _adc: impl Peripheral<P = T> + 'p,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'p,
         config: Config,
-        channel_config: [ChannelConfig; N],
+        channel_config: [ChannelConfig<'p>; N],
     ) -> Self {
         into_ref!(_adc);
 
         let mut inst = Self {
             info: T::info(),
-            _lifetime: PhantomData,
+            channels: channel_config,
+            _clock: Self::init(),
+            full_scale_mv: 0,
         };
 
-        Self::init();
         inst.configure_adc(config);
-        inst.configure_channels(&channel_config);
 
         // Enable interrupt
         interrupt::ADC0.unpend();
@@ -299,8 +492,433 @@ impl<'p, const N: usize> Adc<'p, N> {
         // Disable the watermark interrupt
         self.info.regs.ie().write(|w| w.fwmie().fwmie_0());
     }
+
+    /// Start one conversion of every configured channel without waiting for the results
+    ///
+    /// The results are queued in the result FIFO, to be collected with
+    /// [`Adc::read_fifo_burst`] or [`Adc::drain_fifo_when_full`].
+    pub fn trigger(&mut self) {
+        self.info.regs.swtrig().write(|w| w.swt0().swt0_1());
+    }
+
+    /// Read the results queued in the FIFO, up to `results.len()`, without waiting
+    ///
+    /// Returns the number of results read. Returns [`Error::Overrun`] if results were lost
+    /// because the FIFO overflowed since the last read, the FIFO is reset in that case.
+    pub fn read_fifo_burst(&mut self, results: &mut [u16]) -> Result<usize, Error> {
+        self.check_fifo_overflow()?;
+
+        let count = (self.info.regs.fctrl().read().fcount().bits() as usize).min(results.len());
+        for r in &mut results[..count] {
+            *r = self.info.regs.resfifo().read().d().bits();
+        }
+
+        Ok(count)
+    }
+
+    /// Wait for the FIFO to fill up and read all of its results
+    ///
+    /// Uses the FIFO watermark interrupt, so only one interrupt is taken per [`FIFO_DEPTH`]
+    /// results. Returns [`Error::Overrun`] if results were lost because the FIFO overflowed.
+    pub async fn drain_fifo_when_full(&mut self, results: &mut [u16; FIFO_DEPTH]) -> Result<(), Error> {
+        // Interrupt once the FIFO holds more than FWMARK results
+        self.info
+            .regs
+            .fctrl()
+            .write(|w| unsafe { w.fwmark().bits((FIFO_DEPTH - 1) as u8) });
+
+        poll_fn(|cx| {
+            WAKER.register(cx.waker());
+
+            if self.info.regs.fctrl().read().fcount().bits() as usize >= FIFO_DEPTH {
+                return Poll::Ready(());
+            }
+
+            // Re-armed on every poll, the interrupt handler disables it
+            self.info.regs.ie().write(|w| w.fwmie().fwmie_1());
+            Poll::Pending
+        })
+        .await;
+
+        self.info.regs.ie().write(|w| w.fwmie().fwmie_0());
+
+        self.check_fifo_overflow()?;
+
+        for r in results {
+            *r = self.info.regs.resfifo().read().d().bits();
+        }
+
+        Ok(())
+    }
+
+    fn check_fifo_overflow(&mut self) -> Result<(), Error> {
+        if self.info.regs.stat().read().fof().bit_is_set() {
+            // FOF is write 1 to clear
+            self.info.regs.stat().write(|w| w.fof().fof_1());
+            self.info.regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+            return Err(Error::Overrun);
+        }
+
+        Ok(())
+    }
+}
+
+/// ADC hardware trigger input.
+///
+/// Each trigger input of the ADC has its own trigger control register. The
+/// source wired to each input (e.g. CTimer match outputs) is listed in the
+/// ADC trigger table of the reference manual (UM11147).
+#[derive(Clone, Copy)]
+pub struct HwTrigger(u8);
+
+impl HwTrigger {
+    /// Select hardware trigger input `index`
+    pub fn new(index: u8) -> Result<Self, Error> {
+        if index < HW_TRIGGER_COUNT {
+            Ok(Self(index))
+        } else {
+            Err(Error::InvalidConfig)
+        }
+    }
+}
+
+/// Continuous, hardware triggered ADC sampling into a DMA ring buffer.
+///
+/// Each trigger converts all `CHANNELS` once. The ring holds `DEPTH` batches
+/// and is filled by DMA in two halves (ping-pong), so one half can be read
+/// while the other is being written.
+pub struct AdcStream<'d, const CHANNELS: usize, const DEPTH: usize> {
+    info: Info,
+    _channels: [ChannelConfig<'d>; CHANNELS],
+    _clock: ClockGuard,
+    dma_ch: Channel<'d>,
+    ring: &'d mut [[u32; CHANNELS]; DEPTH],
+    batch: [u16; CHANNELS],
+    read_pos: usize,
+    start_count: u32,
+    consumed_halves: u32,
+    trigger: usize,
+}
+
+impl<'d, const CHANNELS: usize, const DEPTH: usize> AdcStream<'d, CHANNELS, DEPTH> {
+    /// Create a streaming ADC driver. `DEPTH` must be even.
+    pub fn new<T: Instance>(
+        _adc: impl Peripheral<P = T> + 'd,
+        config: Config,
+        channel_config: [ChannelConfig<'d>; CHANNELS],
+        dma_ch: impl Peripheral<P = impl AdcDma> + 'd,
+        ring: &'d mut [[u32; CHANNELS]; DEPTH],
+        trigger: HwTrigger,
+    ) -> Result<Self, Error> {
+        into_ref!(_adc);
+
+        if CHANNELS == 0 || CHANNELS > 15 || DEPTH < 2 || DEPTH % 2 != 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let dma_ch = dma::Dma::reserve_channel(dma_ch).ok_or(Error::InvalidConfig)?;
+
+        let mut adc = Adc::<CHANNELS> {
+            info: T::info(),
+            channels: channel_config,
+            _clock: Adc::<CHANNELS>::init(),
+            full_scale_mv: 0,
+        };
+
+        adc.configure_adc(config);
+
+        let Adc {
+            info, channels, _clock, ..
+        } = adc;
+        let mut stream = Self {
+            info,
+            _channels: channels,
+            _clock,
+            dma_ch,
+            ring,
+            batch: [0; CHANNELS],
+            read_pos: 0,
+            start_count: 0,
+            consumed_halves: 0,
+            trigger: usize::from(trigger.0),
+        };
+        stream.start();
+
+        Ok(stream)
+    }
+
+    fn start(&mut self) {
+        let regs = &self.info.regs;
+
+        // Request DMA as soon as any result is in the fifo
+        regs.fctrl().write(|w| unsafe { w.fwmark().bits(0) });
+        regs.de().write(|w| w.fwmde().fwmde_1());
+
+        let half_len = DEPTH / 2;
+        let half_bytes = half_len * CHANNELS * 4;
+        let base = self.ring.as_mut_ptr() as *mut u32;
+
+        // SAFETY: the stream owns the ADC, so no other user of the descriptors exists
+        let reload = unsafe { &mut *addr_of_mut!(STREAM_DESCRIPTORS) };
+
+        self.dma_ch.configure_ping_pong(
+            regs.resfifo().as_ptr() as *const u32,
+            [base, base.wrapping_add(half_len * CHANNELS)],
+            half_bytes,
+            TransferOptions {
+                width: Width::Bit32,
+                ..Default::default()
+            },
+            reload,
+        );
+
+        self.start_count = self.dma_ch.completion_count();
+        self.dma_ch.enable_channel();
+        self.dma_ch.trigger_channel();
+
+        // Run the whole command chain on each hardware trigger
+        regs.tctrl(self.trigger).write(|w| unsafe {
+            w.hten()
+                .set_bit()
+                .tpri()
+                .tpri_0()
+                .tdly()
+                .bits(0)
+                .tcmd()
+                .bits(CHANNELS as u8)
+        });
+    }
+
+    /// Wait for the next batch of readings, one per configured channel.
+    ///
+    /// Returns [`Error::Overrun`] once if the DMA lapped the reader; reading
+    /// then resumes with the most recently completed half of the ring.
+    pub async fn next_batch(&mut self) -> Result<&[u16; CHANNELS], Error> {
+        let half_len = DEPTH / 2;
+
+        let completed = poll_fn(|cx| {
+            self.dma_ch.get_waker().register(cx.waker());
+
+            let completed = self.dma_ch.completion_count().wrapping_sub(self.start_count);
+
+            if completed > self.consumed_halves {
+                Poll::Ready(completed)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        // The DMA finished the other half too and is now overwriting the one being read
+        if completed - self.consumed_halves >= 2 {
+            self.consumed_halves = completed - 1;
+            self.read_pos = (self.consumed_halves as usize % 2) * half_len;
+            return Err(Error::Overrun);
+        }
+
+        for (dst, src) in self.batch.iter_mut().zip(self.ring[self.read_pos].iter()) {
+            // SAFETY: the ring is written by DMA, read the completed entry volatile
+            *dst = unsafe { core::ptr::read_volatile(src) } as u16;
+        }
+
+        self.read_pos += 1;
+        if self.read_pos % half_len == 0 {
+            self.consumed_halves += 1;
+            self.read_pos %= DEPTH;
+        }
+
+        Ok(&self.batch)
+    }
+}
+
+impl<const CHANNELS: usize, const DEPTH: usize> Drop for AdcStream<'_, CHANNELS, DEPTH> {
+    fn drop(&mut self) {
+        let regs = &self.info.regs;
+
+        regs.tctrl(self.trigger).write(|w| w.hten().clear_bit());
+        self.dma_ch.abort();
+        regs.de().write(|w| w.fwmde().fwmde_0());
+        regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+    }
+}
+
+/// Range of the threshold monitor window that is reported
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MonitorRange {
+    /// Report samples below `low` or above `high`
+    Outside,
+    /// Report samples between `low` and `high`
+    Inside,
 }
 
+/// Threshold monitor config
+///
+/// Thresholds are in the same units as the returned samples.
+#[derive(Clone, Copy)]
+pub struct MonitorConfig {
+    /// Low threshold
+    pub low: u16,
+    /// High threshold
+    pub high: u16,
+    /// Which side of the thresholds is reported
+    pub range: MonitorRange,
+    /// Distance the input has to move back past a threshold before it is reported again
+    pub hysteresis: u16,
+}
+
+/// Threshold monitor on a single ADC channel.
+///
+/// The hardware compare converts the channel in the background and only stores a result once it
+/// falls in the reported range, so no CPU time is spent while the input is in its normal range.
+///
+/// The monitor owns the ADC, so regular [`Adc`] conversions can't run at the same time. Drop the
+/// monitor to sample other channels and create a new one afterwards.
+pub struct AdcMonitor<'d> {
+    info: Info,
+    _channel: ChannelConfig<'d>,
+    _clock: ClockGuard,
+    config: MonitorConfig,
+    // A sample was reported, the input has to return past the hysteresis before the next one
+    tripped: bool,
+}
+
+impl<'d> AdcMonitor<'d> {
+    /// Create a threshold monitor.
+    ///
+    /// `low` must not be above `high`. For [`MonitorRange::Outside`] the thresholds must be at
+    /// least twice the hysteresis apart.
+    pub fn new<T: Instance>(
+        _adc: impl Peripheral<P = T> + 'd,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        config: Config,
+        channel_config: ChannelConfig<'d>,
+        monitor: MonitorConfig,
+    ) -> Result<Self, Error> {
+        into_ref!(_adc);
+
+        if monitor.low > monitor.high {
+            return Err(Error::InvalidConfig);
+        }
+        if monitor.range == MonitorRange::Outside
+            && u32::from(monitor.high - monitor.low) < 2 * u32::from(monitor.hysteresis)
+        {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mut adc = Adc::<1> {
+            info: T::info(),
+            channels: [channel_config],
+            _clock: Adc::<1>::init(),
+            full_scale_mv: 0,
+        };
+
+        adc.configure_adc(config);
+
+        // Repeat the conversion until the compare is true, only then store the result
+        adc.info.regs.cmdh(0).modify(|_, w| unsafe { w.cmpen().bits(0b11) });
+
+        let Adc {
+            info,
+            channels: [channel],
+            _clock,
+            ..
+        } = adc;
+
+        interrupt::ADC0.unpend();
+        unsafe { interrupt::ADC0.enable() };
+
+        Ok(Self {
+            info,
+            _channel: channel,
+            _clock,
+            config: monitor,
+            tripped: false,
+        })
+    }
+
+    /// Wait for a sample in the reported range and return it.
+    ///
+    /// After a sample has been reported, the input first has to move back past the thresholds by
+    /// the hysteresis before another sample is reported, so a signal sitting on a threshold
+    /// doesn't retrigger continuously.
+    pub async fn wait_out_of_range(&mut self) -> u16 {
+        loop {
+            let reported = !self.tripped;
+            self.arm(reported);
+
+            let sample = poll_fn(|cx| {
+                MONITOR_WAKER.register(cx.waker());
+
+                if self.info.regs.fctrl().read().fcount().bits() > 0 {
+                    return Poll::Ready(self.info.regs.resfifo().read().d().bits());
+                }
+
+                // The interrupt disables itself, re-enable it until a result is stored
+                self.info.regs.ie().write(|w| w.fwmie().fwmie_1());
+                Poll::Pending
+            })
+            .await;
+
+            self.info.regs.ie().write(|w| w.fwmie().fwmie_0());
+
+            self.tripped = reported;
+            if reported {
+                return sample;
+            }
+        }
+    }
+
+    /// Start a compare conversion for the reported window, or for the window the input has to
+    /// return to when `reported` is false.
+    fn arm(&self, reported: bool) {
+        let MonitorConfig {
+            low,
+            high,
+            range,
+            hysteresis,
+        } = self.config;
+
+        // Compare is true inside [CVL, CVH] when CVL <= CVH, and outside [CVH, CVL] otherwise
+        let (inside, low, high) = match (range, reported) {
+            (MonitorRange::Outside, true) => (false, low, high),
+            (MonitorRange::Outside, false) => (true, low + hysteresis, high - hysteresis),
+            (MonitorRange::Inside, true) => (true, low, high),
+            (MonitorRange::Inside, false) => (false, low.saturating_sub(hysteresis), high.saturating_add(hysteresis)),
+        };
+        let (cvl, cvh) = if inside { (low, high) } else { (high, low) };
+
+        let regs = &self.info.regs;
+
+        // SAFETY: unsafe only used for .bits()
+        regs.cv(0)
+            .write(|w| unsafe { w.bits((u32::from(cvh) << 16) | u32::from(cvl)) });
+
+        // Interrupt on the first stored result
+        regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+        regs.fctrl().write(|w| unsafe { w.fwmark().bits(0) });
+        regs.ie().write(|w| w.fwmie().fwmie_1());
+
+        regs.swtrig().write(|w| w.swt0().swt0_1());
+    }
+}
+
+impl Drop for AdcMonitor<'_> {
+    fn drop(&mut self) {
+        let regs = &self.info.regs;
+
+        // Stop the repeating compare conversion
+        regs.ie().write(|w| w.fwmie().fwmie_0());
+        regs.ctrl().modify(|_, w| w.adcen().adcen_0());
+        regs.ctrl().modify(|_, w| w.rstfifo().rstfifo_1());
+    }
+}
+
+/// ADC DMA channel trait
+#[allow(private_bounds)]
+pub trait AdcDma: dma::Instance {}
+impl AdcDma for peripherals::DMA0_CH24 {}
+
 trait SealedInstance {
     fn info() -> Info;
 }
@@ -346,6 +964,28 @@ impl From<Reference> for adc0::cfg::Refsel {
     }
 }
 
+/// Scaling of the ADC input
+///
+/// The ADC has no programmable gain, the input can only be attenuated to extend the measured
+/// range beyond the reference voltage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InputScale {
+    /// Input converted as is, full scale is the reference voltage
+    Full,
+    /// Input scaled by 30/64, full scale is about 2.13 times the reference voltage
+    Scaled30Of64,
+}
+
+impl From<InputScale> for adc0::cmdl::Cscale {
+    fn from(scale: InputScale) -> Self {
+        match scale {
+            InputScale::Full => adc0::cmdl::Cscale::Cscale1,
+            InputScale::Scaled30Of64 => adc0::cmdl::Cscale::Cscale0,
+        }
+    }
+}
+
 /// ADC channel side
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -364,16 +1004,52 @@ impl From<Side> for adc0::cmdl::Absel {
     }
 }
 
-/// ADC channel
+/// ADC channel, fixed by the pin it belongs to
 #[derive(Clone, Copy, PartialEq, Eq)]
-#[allow(missing_docs)]
 pub struct AdcChannel {
-    pub ch: adc0::cmdl::Adch,
-    pub side: Side,
+    ch: adc0::cmdl::Adch,
+    side: Side,
+}
+
+impl AdcChannel {
+    /// Analog channel number
+    pub fn index(&self) -> u8 {
+        self.ch as u8
+    }
+
+    /// Channel side
+    pub fn side(&self) -> Side {
+        self.side
+    }
 }
 
 pub(crate) trait SealedInput {
     fn channel(&self) -> AdcChannel;
+
+    /// GPIO index of the pin (`port * 32 + pin`)
+    fn pin_index(&self) -> usize;
+
+    /// Switch the pad to analog input
+    fn configure(&self) {
+        let pin = self.iopctl_pin();
+
+        // Digital input buffer and pulls would load the analog signal
+        pin.set_function(Function::F0)
+            .set_pull(Pull::None)
+            .disable_input_buffer()
+            .set_slew_rate(SlewRate::Standard)
+            .set_drive_strength(DriveStrength::Normal)
+            .enable_analog_multiplex()
+            .set_drive_mode(DriveMode::PushPull)
+            .set_input_inverter(Inverter::Disabled);
+    }
+
+    fn iopctl_pin(&self) -> AnyPin {
+        let index = self.pin_index();
+
+        // SAFETY: the index comes from an owned ADC pin, so no one else configures this pad
+        unsafe { AnyPin::new((index / 32) as u8, (index % 32) as u8) }
+    }
 }
 
 /// A dual purpose (digital/analog) input that can be used as analog input to ADC peripheral.
@@ -386,16 +1062,22 @@ pub trait Input: SealedInput + Into<AnyInput> + Peripheral<P = Self> + Sized + '
     fn degrade_adc(self) -> AnyInput {
         AnyInput {
             channel: self.channel(),
+            pin_index: self.pin_index() as u8,
         }
     }
 }
 
+/// A pin that can be sampled by ADC instance `T`.
+pub trait AdcPin<T: Instance>: Input {}
+
 /// A type-erased ADC input.
 ///
 /// This allows using several inputs in situations that might require
-/// them to be the same type, like putting them in an array.
+/// them to be the same type, like putting them in an array. The pad is
+/// returned to its reset state when this is dropped.
 pub struct AnyInput {
     channel: AdcChannel,
+    pin_index: u8,
 }
 
 impl_peripheral!(AnyInput);
@@ -404,9 +1086,20 @@ impl SealedInput for AnyInput {
     fn channel(&self) -> AdcChannel {
         self.channel
     }
+
+    fn pin_index(&self) -> usize {
+        usize::from(self.pin_index)
+    }
 }
 
 impl Input for AnyInput {}
+impl AdcPin<ADC0> for AnyInput {}
+
+impl Drop for AnyInput {
+    fn drop(&mut self) {
+        self.iopctl_pin().reset();
+    }
+}
 
 /// Macro to implement required types for dual purpose pins
 macro_rules! impl_pin {
@@ -416,23 +1109,19 @@ macro_rules! impl_pin {
     (@local, $pin:ty, $ch:ident, $side:ident) => {
         impl crate::adc::SealedInput for $pin {
             fn channel(&self) -> crate::adc::AdcChannel {
-                self.set_function(Function::F0)
-                    .set_pull(Pull::None)
-                    .disable_input_buffer()
-                    .set_slew_rate(SlewRate::Standard)
-                    .set_drive_strength(DriveStrength::Normal)
-                    .enable_analog_multiplex()
-                    .set_drive_mode(DriveMode::PushPull)
-                    .set_input_inverter(Inverter::Disabled);
-
                 AdcChannel {
                     ch: crate::pac::adc0::cmdl::Adch::$ch,
                     side: crate::adc::Side::$side
                 }
             }
+
+            fn pin_index(&self) -> usize {
+                crate::gpio::pin_index(self)
+            }
         }
 
         impl crate::adc::Input for $pin {}
+        impl crate::adc::AdcPin<ADC0> for $pin {}
 
         impl From<$pin> for crate::adc::AnyInput {
             fn from(val: $pin) -> Self {
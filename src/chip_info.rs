@@ -0,0 +1,81 @@
+//! Silicon identification
+//!
+//! The die revision is read from SYSCTL0 once at [`crate::init`] and cached, so it can be
+//! queried from anywhere afterwards. Drivers use it through the crate-internal errata
+//! registry to apply revision specific workarounds.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Marker for a revision that has not been read yet
+const REV_UNKNOWN: u8 = u8::MAX;
+
+static REVISION: AtomicU8 = AtomicU8::new(REV_UNKNOWN);
+
+/// Part family, fixed by the chip feature the HAL is built for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Part {
+    /// i.MX RT685S
+    Rt685s,
+    /// i.MX RT633S
+    Rt633s,
+}
+
+/// Silicon revision, the metal revision field of the die ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Revision {
+    /// Revision A0, pre-production
+    A0,
+    /// Revision B0 and later metal fixes, identified by their raw revision number
+    B(u8),
+    /// Revision not read yet, [`crate::init`] has not run
+    Unknown,
+}
+
+impl From<u8> for Revision {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Revision::A0,
+            REV_UNKNOWN => Revision::Unknown,
+            n => Revision::B(n),
+        }
+    }
+}
+
+/// Identification of the running chip
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChipInfo {
+    /// Part family
+    pub part: Part,
+    /// Silicon revision
+    pub rev: Revision,
+}
+
+/// Returns the identification of the running chip
+pub fn chip_info() -> ChipInfo {
+    ChipInfo {
+        #[cfg(feature = "mimxrt685s")]
+        part: Part::Rt685s,
+        #[cfg(feature = "mimxrt633s")]
+        part: Part::Rt633s,
+        rev: Revision::from(REVISION.load(Ordering::Relaxed)),
+    }
+}
+
+/// Raw metal revision, `u8::MAX` before init
+pub(crate) fn raw_revision() -> u8 {
+    REVISION.load(Ordering::Relaxed)
+}
+
+/// Read and cache the die revision, must run before any driver consults the errata registry
+pub(crate) fn init() {
+    // SAFETY: unsafe needed to take pointer to Sysctl0, only reads the read-only die ID
+    let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+    let rev = sysctl0.dieid().read().rev_id().bits();
+
+    REVISION.store(rev, Ordering::Relaxed);
+
+    info!("Detected {:?} silicon revision {:?}", chip_info().part, chip_info().rev);
+}
@@ -207,6 +207,7 @@ embassy_hal_internal::peripherals!(
     MRT0,
     MU_A,
     OS_EVENT,
+    OTP,
     PIN_INT0,
     PIN_INT1,
     PIN_INT2,
@@ -1,5 +1,5 @@
 //! Clock configuration for the `RT6xx`
-use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
 
 #[cfg(feature = "defmt")]
 use defmt;
@@ -425,6 +425,265 @@ pub struct SysOscConfig {
 }
 const SYS_OSC_DEFAULT_FREQ: u32 = 24_000_000;
 
+/// Frequency of the CPU core clock, kept up to date whenever the main clock changes
+static CORE_CLOCK_FREQ: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the current CPU core clock frequency (Hz)
+pub fn core_clock_rate() -> u32 {
+    CORE_CLOCK_FREQ.load(Ordering::Relaxed)
+}
+
+/// Recompute the core clock from the main clock and the CPU/AHB divider
+fn update_core_clock(main_clk_freq: u32) {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the divider
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    let div = u32::from(clkctl0.syscpuahbclkdiv().read().div().bits()) + 1;
+
+    CORE_CLOCK_FREQ.store(main_clk_freq / div, Ordering::Relaxed);
+}
+
+// Clock mux selector values, as listed in the CLKCTL0/CLKCTL1 chapters of the reference manual.
+// The same value means different sources on different muxes, so they are kept per mux,
+// and any value not listed selects no clock.
+const MAINCLKSELA_FFRO_DIV4: u8 = 0;
+const MAINCLKSELA_SYSXTAL: u8 = 1;
+const MAINCLKSELA_LPOSC: u8 = 2;
+const MAINCLKSELB_MAIN_1ST: u8 = 0;
+const MAINCLKSELB_SFRO: u8 = 1;
+const MAINCLKSELB_MAIN_PLL: u8 = 2;
+
+const PLLCLKSEL_SFRO: u8 = 0;
+const PLLCLKSEL_SYSXTAL: u8 = 1;
+const PLLCLKSEL_FFRO_DIV2: u8 = 2;
+
+const FCFCLKSEL_SFRO: u8 = 0;
+const FCFCLKSEL_FFRO: u8 = 1;
+const FCFCLKSEL_AUDIO_PLL: u8 = 2;
+const FCFCLKSEL_FRG: u8 = 4;
+const FRGCLKSEL_MAIN: u8 = 0;
+const FRGCLKSEL_FRG_PLL: u8 = 1;
+const FRGCLKSEL_SFRO: u8 = 2;
+const FRGCLKSEL_FFRO: u8 = 3;
+
+const CT32BITFCLKSEL_MAIN: u8 = 0;
+const CT32BITFCLKSEL_SFRO: u8 = 1;
+const CT32BITFCLKSEL_FFRO: u8 = 2;
+const CT32BITFCLKSEL_AUDIO_PLL: u8 = 3;
+const CT32BITFCLKSEL_LPOSC: u8 = 5;
+
+const ADC0FCLKSEL0_SFRO: u8 = 0;
+const ADC0FCLKSEL0_SYSXTAL: u8 = 1;
+const ADC0FCLKSEL0_LPOSC: u8 = 2;
+const ADC0FCLKSEL0_FFRO: u8 = 3;
+const ADC0FCLKSEL1_SEL0_MUX_OUT: u8 = 0;
+const ADC0FCLKSEL1_MAIN_PLL: u8 = 1;
+const ADC0FCLKSEL1_AUX0_PLL: u8 = 2;
+const ADC0FCLKSEL1_AUX1_PLL: u8 = 3;
+
+/// RTC oscillator frequency, feeds the 32k main clock option
+const RTC_OSC_FREQ: u32 = 32_768;
+
+/// Current FFRO frequency, read back from the trim range
+fn ffro_clock_hz() -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the trim range
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    if clkctl0.ffroctl0().read().trim_range().is_ffro_60mhz() {
+        FfroFreq::Ffro60m.into()
+    } else {
+        FfroFreq::Ffro48m.into()
+    }
+}
+
+/// PLL output for the given input, `mult + num / denom`, then scaled by the PFD (18 / pfd)
+fn pll_pfd_clock_hz(input: u32, mult: u8, num: u32, denom: u32, pfd: u8) -> u32 {
+    if pfd == 0 || denom == 0 {
+        return 0;
+    }
+
+    let vco = u64::from(input) * (u64::from(mult) * u64::from(denom) + u64::from(num)) / u64::from(denom);
+    (vco * 18 / u64::from(pfd)) as u32
+}
+
+/// Input frequency of a PLL for the given clock select value
+fn pll_input_clock_hz(sel: u8) -> u32 {
+    match sel {
+        PLLCLKSEL_SFRO => SFRO_FREQ,
+        PLLCLKSEL_SYSXTAL => SYS_OSC_DEFAULT_FREQ,
+        PLLCLKSEL_FFRO_DIV2 => ffro_clock_hz() / 2,
+        _ => 0,
+    }
+}
+
+/// Output frequency of one of the four SYSPLL0 PFDs, before any downstream divider
+fn syspll_pfd_clock_hz(pfd_num: usize) -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the PLL configuration
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    let input = pll_input_clock_hz(clkctl0.syspll0clksel().read().sel().bits());
+    let ctl0 = clkctl0.syspll0ctl0().read();
+    if ctl0.bypass().is_programmed_clk() {
+        let pfd = clkctl0.syspll0pfd().read();
+        let pfd = match pfd_num {
+            0 => pfd.pfd0().bits(),
+            1 => pfd.pfd1().bits(),
+            2 => pfd.pfd2().bits(),
+            _ => pfd.pfd3().bits(),
+        };
+
+        pll_pfd_clock_hz(
+            input,
+            ctl0.mult().bits(),
+            clkctl0.syspll0num().read().num().bits(),
+            clkctl0.syspll0denom().read().denom().bits(),
+            pfd,
+        )
+    } else {
+        input
+    }
+}
+
+/// Main PLL clock, SYSPLL0 PFD0 after the main PLL divider
+fn main_pll_clock_hz() -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the divider
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    syspll_pfd_clock_hz(0) / (u32::from(clkctl0.mainpllclkdiv().read().div().bits()) + 1)
+}
+
+/// Audio PLL clock, AUDIOPLL0 PFD0 after the audio PLL divider
+fn audio_pll_clock_hz() -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only reads the PLL configuration
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    let input = pll_input_clock_hz(clkctl1.audiopll0clksel().read().sel().bits());
+    let ctl0 = clkctl1.audiopll0ctl0().read();
+    let pfd_out = if ctl0.bypass().is_programmed_clk() {
+        pll_pfd_clock_hz(
+            input,
+            ctl0.mult().bits(),
+            clkctl1.audiopll0num().read().num().bits(),
+            clkctl1.audiopll0denom().read().denom().bits(),
+            clkctl1.audiopll0pfd().read().pfd0().bits(),
+        )
+    } else {
+        input
+    };
+
+    pfd_out / (u32::from(clkctl1.audiopllclkdiv().read().div().bits()) + 1)
+}
+
+/// Returns the current main clock frequency (Hz), read back from the clock muxes
+pub fn get_main_clock_hz() -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the clock selects
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    match clkctl0.mainclkselb().read().sel().bits() {
+        MAINCLKSELB_MAIN_1ST => match clkctl0.mainclksela().read().sel().bits() {
+            MAINCLKSELA_FFRO_DIV4 => ffro_clock_hz() / 4,
+            MAINCLKSELA_SYSXTAL => SYS_OSC_DEFAULT_FREQ,
+            MAINCLKSELA_LPOSC => LposcFreq::Lp1m.into(),
+            _ => ffro_clock_hz(),
+        },
+        MAINCLKSELB_SFRO => SFRO_FREQ,
+        MAINCLKSELB_MAIN_PLL => main_pll_clock_hz(),
+        _ => RTC_OSC_FREQ,
+    }
+}
+
+/// Returns the functional clock frequency (Hz) of FLEXCOMM `n`, or 0 if it has no clock
+///
+/// `n` is the flexcomm number, 0 to 7, 14 or 15. The MCLK input is external and reads as 0.
+pub fn get_flexcomm_clock_hz(n: usize) -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only reads the clock selects
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    let (fclksel, frgclksel, frgctl) = match n {
+        0..=7 => {
+            let fc = clkctl1.flexcomm(n);
+            (
+                fc.fcfclksel().read().sel().bits(),
+                fc.frgclksel().read().sel().bits(),
+                fc.frgctl().read().mult().bits(),
+            )
+        }
+        14 => (
+            clkctl1.fc14fclksel().read().sel().bits(),
+            clkctl1.frg14clksel().read().sel().bits(),
+            clkctl1.frg14ctl().read().mult().bits(),
+        ),
+        15 => (
+            clkctl1.fc15fclksel().read().sel().bits(),
+            clkctl1.frg15clksel().read().sel().bits(),
+            clkctl1.frg15ctl().read().mult().bits(),
+        ),
+        _ => return 0,
+    };
+
+    match fclksel {
+        FCFCLKSEL_SFRO => SFRO_FREQ,
+        FCFCLKSEL_FFRO => ffro_clock_hz(),
+        FCFCLKSEL_AUDIO_PLL => audio_pll_clock_hz(),
+        FCFCLKSEL_FRG => {
+            let frg_in = match frgclksel {
+                FRGCLKSEL_MAIN => get_main_clock_hz(),
+                FRGCLKSEL_FRG_PLL => {
+                    syspll_pfd_clock_hz(0) / (u32::from(clkctl1.frgpllclkdiv().read().div().bits()) + 1)
+                }
+                FRGCLKSEL_SFRO => SFRO_FREQ,
+                FRGCLKSEL_FFRO => ffro_clock_hz(),
+                _ => 0,
+            };
+
+            // The FRG divides by 1 + MULT / 256
+            (u64::from(frg_in) * 256 / (256 + u64::from(frgctl))) as u32
+        }
+        _ => 0,
+    }
+}
+
+/// Returns the functional clock frequency (Hz) of CTIMER `n`, or 0 if it has no clock
+///
+/// The MCLK input is external and reads as 0.
+pub fn get_ctimer_clock_hz(n: usize) -> u32 {
+    if n > 4 {
+        return 0;
+    }
+
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only reads the clock select
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    match clkctl1.ct32bitfclksel(n).read().sel().bits() {
+        CT32BITFCLKSEL_MAIN => get_main_clock_hz(),
+        CT32BITFCLKSEL_SFRO => SFRO_FREQ,
+        CT32BITFCLKSEL_FFRO => ffro_clock_hz(),
+        CT32BITFCLKSEL_AUDIO_PLL => audio_pll_clock_hz(),
+        CT32BITFCLKSEL_LPOSC => LposcFreq::Lp1m.into(),
+        _ => 0,
+    }
+}
+
+/// Returns the ADC functional clock frequency (Hz) after the ADC divider, or 0 if it has no clock
+pub fn get_adc_clock_hz() -> u32 {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only reads the clock selects
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    let src = match clkctl0.adc0fclksel1().read().sel().bits() {
+        ADC0FCLKSEL1_SEL0_MUX_OUT => match clkctl0.adc0fclksel0().read().sel().bits() {
+            ADC0FCLKSEL0_SFRO => SFRO_FREQ,
+            ADC0FCLKSEL0_SYSXTAL => SYS_OSC_DEFAULT_FREQ,
+            ADC0FCLKSEL0_LPOSC => LposcFreq::Lp1m.into(),
+            ADC0FCLKSEL0_FFRO => ffro_clock_hz(),
+            _ => 0,
+        },
+        ADC0FCLKSEL1_MAIN_PLL => main_pll_clock_hz(),
+        ADC0FCLKSEL1_AUX0_PLL => syspll_pfd_clock_hz(2) / (u32::from(clkctl0.aux0pllclkdiv().read().div().bits()) + 1),
+        ADC0FCLKSEL1_AUX1_PLL => syspll_pfd_clock_hz(3) / (u32::from(clkctl0.aux1pllclkdiv().read().div().bits()) + 1),
+        _ => 0,
+    };
+
+    src / (u32::from(clkctl0.adc0fclkdiv().read().div().bits()) + 1)
+}
+
 /// Clock Errors
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -1016,7 +1275,7 @@ impl MultiSourceClock for MainClkConfig {
         if !clock_src_config.is_enabled() {
             return Err(ClockError::ClockNotEnabled);
         }
-        if let Ok(c) = <Clocks as TryInto<MainClkSrc>>::try_into(*clock_src) {
+        let res = if let Ok(c) = <Clocks as TryInto<MainClkSrc>>::try_into(*clock_src) {
             // SAFETY: unsafe needed to take pointer to Clkctl0
             // needed to change the clock source
             let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
@@ -1102,7 +1361,13 @@ impl MultiSourceClock for MainClkConfig {
             }
         } else {
             Err(ClockError::ClockNotSupported)
+        };
+
+        if res.is_ok() {
+            update_core_clock(self.freq.load(Ordering::Relaxed));
         }
+
+        res
     }
 }
 
@@ -1246,6 +1511,7 @@ impl ConfigurableClock for RtcClkConfig {
 impl SysClkConfig {
     /// Updates the system core clock frequency, SW concept used for systick
     fn update_sys_core_clock(&self) {
+        CORE_CLOCK_FREQ.store(self.sysclkfreq.load(Ordering::Relaxed), Ordering::Relaxed);
         trace!(
             "System core clock has been updated to {:?}, this involves no HW reg writes",
             self.sysclkfreq.load(Ordering::Relaxed)
@@ -1517,6 +1783,8 @@ fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
         return Err(e);
     }
 
+    pre_reconfigure();
+
     if let Err(e) = config.sys_osc.enable_and_reset() {
         error!("Couldn't enable sys oscillator {:?}", e);
         return Err(e);
@@ -1527,10 +1795,30 @@ fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
         return Err(e);
     }
 
-    // Move FLEXSPI clock source from main clock to FFRO to avoid instruction/data fetch issue in XIP when
-    // updating PLL and main clock.
+    init_syscpuahb_clk();
+
+    if let Err(e) = post_reconfigure(&config) {
+        error!("Couldn't enable main clock {:?}", e);
+        return Err(e);
+    }
+
+    config.sys_clk.update_sys_core_clock();
+    Ok(())
+}
+
+/// Moves the main clock to the FFRO ahead of PLL changes.
+///
+/// The FFRO runs without a PLL, so the core keeps a stable clock while the PLLs are powered down
+/// and relock, instead of running from an output that may glitch. FLEXSPI and eSPI are moved to
+/// the FFRO first, so XIP fetches are not affected by the main clock switch.
+///
+/// Peripherals clocked from the main clock run at the FFRO rate until [`post_reconfigure`].
+pub fn pre_reconfigure() {
     // SAFETY: unsafe needed to take pointers to Clkctl0
     let cc0 = unsafe { pac::Clkctl0::steal() };
+
+    // Move FLEXSPI clock source from main clock to FFRO to avoid instruction/data fetch issue in XIP when
+    // updating PLL and main clock.
     cc0.flexspifclksel().write(|w| w.sel().ffro_clk());
 
     // Move ESPI clock source to FFRO
@@ -1539,17 +1827,27 @@ fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
         cc0.espiclksel().write(|w| w.sel().use_48_60m());
     }
 
-    init_syscpuahb_clk();
+    cc0.mainclksela().write(|w| w.sel().ffro_clk());
+    cc0.mainclkselb().write(|w| w.sel().main_1st_clk());
 
-    if let Err(e) = config.main_clk.enable_and_reset() {
-        error!("Couldn't enable main clock {:?}", e);
-        return Err(e);
-    }
+    update_core_clock(get_main_clock_hz());
+}
 
-    config.sys_clk.update_sys_core_clock();
+/// Switches the main clock back to the source of `config` once the PLLs are configured.
+pub fn post_reconfigure(config: &ClockConfig) -> Result<(), ClockError> {
+    config.main_clk.enable_and_reset()?;
+
+    update_core_clock(get_main_clock_hz());
     Ok(())
 }
 
+/// Takes over a clock tree configured by a bootloader, only the cached clock rates are updated.
+///
+/// SAFETY: must be called exactly once at bootup, instead of [`init`]
+pub(crate) unsafe fn init_from_hardware() {
+    update_core_clock(get_main_clock_hz());
+}
+
 /// SAFETY: must be called exactly once at bootup
 pub(crate) unsafe fn init(config: ClockConfig) -> Result<(), ClockError> {
     init_clock_hw(config)?;
@@ -1563,6 +1861,9 @@ pub(crate) unsafe fn init(config: ClockConfig) -> Result<(), ClockError> {
 trait SealedSysconPeripheral {
     fn enable_and_reset_perph_clock();
     fn disable_perph_clock();
+    fn perph_clock_enabled() -> bool;
+    /// Number of [`ClockGuard`]s held for the peripheral
+    fn clock_users() -> &'static AtomicUsize;
 }
 
 /// Clock and Reset control for peripherals
@@ -1570,11 +1871,13 @@ trait SealedSysconPeripheral {
 pub trait SysconPeripheral: SealedSysconPeripheral + 'static {}
 /// Enables and resets peripheral `T`.
 ///
+/// The peripheral stays enabled for good, drivers that can be dropped use [`clock_guard`].
+///
 /// # Safety
 ///
 /// Peripheral must not be in use.
 pub fn enable_and_reset<T: SysconPeripheral>() {
-    T::enable_and_reset_perph_clock();
+    core::mem::forget(clock_guard::<T>());
 }
 
 /// Disables peripheral `T`.
@@ -1585,6 +1888,101 @@ pub fn enable_and_reset<T: SysconPeripheral>() {
 pub fn disable<T: SysconPeripheral>() {
     T::disable_perph_clock();
 }
+
+/// Keeps the clock of a peripheral enabled, see [`clock_guard`]
+pub struct ClockGuard {
+    acquire: fn(),
+    release: fn(),
+    /// Cleared while the reference is given up with [`ClockGuard::suspend`]
+    held: bool,
+}
+
+impl ClockGuard {
+    /// Give up the clock reference until [`ClockGuard::resume`], does nothing if already done
+    ///
+    /// If this was the last reference, the peripheral is put into reset and loses its
+    /// configuration.
+    pub(crate) fn suspend(&mut self) {
+        if self.held {
+            self.held = false;
+            (self.release)();
+        }
+    }
+
+    /// Take the clock reference again after [`ClockGuard::suspend`], does nothing if held
+    pub(crate) fn resume(&mut self) {
+        if !self.held {
+            (self.acquire)();
+            self.held = true;
+        }
+    }
+}
+
+impl Clone for ClockGuard {
+    /// Another guard for the same peripheral, counted separately
+    fn clone(&self) -> Self {
+        (self.acquire)();
+
+        Self {
+            acquire: self.acquire,
+            release: self.release,
+            held: true,
+        }
+    }
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        self.suspend();
+    }
+}
+
+/// Takes one reference on the clock of peripheral `T`, see [`clock_guard`]
+pub(crate) fn acquire_clock<T: SysconPeripheral>() {
+    critical_section::with(|_| {
+        let users = T::clock_users().load(Ordering::Relaxed);
+
+        // The peripheral is held in reset while nobody uses it, so it starts from its reset
+        // state on the first acquisition and keeps its configuration for later ones
+        if users == 0 {
+            T::enable_and_reset_perph_clock();
+        }
+
+        T::clock_users().store(users + 1, Ordering::Relaxed);
+    });
+}
+
+/// Drops a reference taken with [`acquire_clock`]
+pub(crate) fn release_clock<T: SysconPeripheral>() {
+    critical_section::with(|_| {
+        let users = T::clock_users().load(Ordering::Relaxed).saturating_sub(1);
+        T::clock_users().store(users, Ordering::Relaxed);
+
+        if users == 0 {
+            T::disable_perph_clock();
+        }
+    });
+}
+
+/// Enables peripheral `T` until the last returned guard is dropped.
+///
+/// The clock is enabled and the peripheral taken out of reset on the first acquisition. Dropping
+/// the last guard puts the peripheral back into reset and gates its clock, so drivers sharing a
+/// peripheral never reset or disable it under each other.
+pub fn clock_guard<T: SysconPeripheral>() -> ClockGuard {
+    acquire_clock::<T>();
+
+    ClockGuard {
+        acquire: acquire_clock::<T>,
+        release: release_clock::<T>,
+        held: true,
+    }
+}
+
+/// Returns true if the clock of peripheral `T` is enabled
+pub fn is_enabled<T: SysconPeripheral>() -> bool {
+    T::perph_clock_enabled()
+}
 macro_rules! impl_perph_clk {
     ($peripheral:ident, $clkctl:ident, $clkreg:ident, $rstctl:ident, $rstreg:ident, $bit:expr) => {
         impl SealedSysconPeripheral for crate::peripherals::$peripheral {
@@ -1615,6 +2013,18 @@ macro_rules! impl_perph_clk {
                     cc1.[<$clkreg _clr>]().write(|w| unsafe { w.bits(1 << $bit) });
                 }
             }
+
+            fn perph_clock_enabled() -> bool {
+                // SAFETY: unsafe needed to take pointers to Clkctl, only reads
+                let cc1 = unsafe { pac::$clkctl::steal() };
+
+                cc1.$clkreg().read().bits() & (1 << $bit) != 0
+            }
+
+            fn clock_users() -> &'static AtomicUsize {
+                static USERS: AtomicUsize = AtomicUsize::new(0);
+                &USERS
+            }
         }
 
         impl SysconPeripheral for crate::peripherals::$peripheral {}
@@ -1623,7 +2033,6 @@ macro_rules! impl_perph_clk {
 
 // These should enabled once the relevant peripherals are implemented.
 // impl_perph_clk!(GPIOINTCTL, Clkctl1, pscctl2, Rstctl1, prstctl2, 30);
-// impl_perph_clk!(OTP, Clkctl0, pscctl0, Rstctl0, prstctl0, 17);
 
 // impl_perph_clk!(ROM_CTL_128KB, Clkctl0, pscctl0, Rstctl0, prstctl0, 2);
 // impl_perph_clk!(USBHS_SRAM, Clkctl0, pscctl0, Rstctl0, prstctl0, 23);
@@ -1670,6 +2079,7 @@ impl_perph_clk!(I3C0, Clkctl1, pscctl2, Rstctl1, prstctl2, 16);
 impl_perph_clk!(MRT0, Clkctl1, pscctl2, Rstctl1, prstctl2, 8);
 impl_perph_clk!(MU_A, Clkctl1, pscctl1, Rstctl1, prstctl1, 28);
 impl_perph_clk!(OS_EVENT, Clkctl1, pscctl0, Rstctl1, prstctl0, 27);
+impl_perph_clk!(OTP, Clkctl0, pscctl0, Rstctl0, prstctl0, 17);
 impl_perph_clk!(POWERQUAD, Clkctl0, pscctl0, Rstctl0, prstctl0, 8);
 impl_perph_clk!(PUF, Clkctl0, pscctl0, Rstctl0, prstctl0, 11);
 impl_perph_clk!(RNG, Clkctl0, pscctl0, Rstctl0, prstctl0, 12);
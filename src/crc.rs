@@ -4,14 +4,17 @@ use core::marker::PhantomData;
 
 use embassy_hal_internal::into_ref;
 
-use crate::clocks::{enable_and_reset, SysconPeripheral};
+use crate::clocks::{clock_guard, ClockGuard, SysconPeripheral};
+use crate::dma::channel::Channel;
+use crate::dma::transfer::{Transfer, TransferOptions, Width};
 pub use crate::pac::crc_engine::mode::CrcPolynomial as Polynomial;
-use crate::{peripherals, Peripheral};
+use crate::{dma, peripherals, Peripheral};
 
 /// CRC driver.
 pub struct Crc<'d> {
     info: Info,
     _config: Config,
+    _clock: ClockGuard,
     _lifetime: PhantomData<&'d ()>,
 }
 
@@ -20,13 +23,13 @@ pub struct Config {
     /// Polynomial to be used
     pub polynomial: Polynomial,
 
-    /// Reverse bit order of input?
+    /// Reverse bit order of input? (`refin` in the CRC catalogue)
     pub reverse_in: bool,
 
     /// 1's complement input?
     pub complement_in: bool,
 
-    /// Reverse CRC bit order?
+    /// Reverse CRC bit order? (`refout` in the CRC catalogue)
     pub reverse_out: bool,
 
     /// 1's complement CRC?
@@ -34,6 +37,11 @@ pub struct Config {
 
     /// CRC Seed
     pub seed: u32,
+
+    /// Value XORed into the CRC after the hardware is done with it (`xorout` in the CRC catalogue)
+    ///
+    /// Applied on top of `complement_out`, so an all ones `xorout` can be had either way.
+    pub xor_out: u32,
 }
 
 impl Config {
@@ -54,6 +62,7 @@ impl Config {
             reverse_out,
             complement_out,
             seed,
+            xor_out: 0,
         }
     }
 }
@@ -67,6 +76,7 @@ impl Default for Config {
             reverse_out: false,
             complement_out: false,
             seed: 0xffff,
+            xor_out: 0,
         }
     }
 }
@@ -75,13 +85,14 @@ impl<'d> Crc<'d> {
     /// Instantiates new CRC peripheral and initializes to default values.
     pub fn new<T: Instance>(_peripheral: impl Peripheral<P = T> + 'd, config: Config) -> Self {
         // enable CRC clock
-        enable_and_reset::<T>();
+        let _clock = clock_guard::<T>();
 
         into_ref!(_peripheral);
 
         let mut instance = Self {
             info: T::info(),
             _config: config,
+            _clock,
             _lifetime: PhantomData,
         };
 
@@ -111,37 +122,55 @@ impl<'d> Crc<'d> {
             .write(|w| unsafe { w.crc_seed().bits(self._config.seed) });
     }
 
+    /// Current checksum, with `xor_out` applied
+    fn sum(&self) -> u32 {
+        self.info.regs.sum().read().bits() ^ self._config.xor_out
+    }
+
     /// Feeds a byte into the CRC peripheral. Returns the computed checksum.
     pub fn feed_byte(&mut self, byte: u8) -> u32 {
         self.info.regs.wr_data8().write(|w| unsafe { w.bits(byte) });
 
-        self.info.regs.sum().read().bits()
+        self.sum()
     }
 
     /// Feeds an slice of bytes into the CRC peripheral. Returns the computed checksum.
     pub fn feed_bytes(&mut self, bytes: &[u8]) -> u32 {
-        let (prefix, data, suffix) = unsafe { bytes.align_to::<u32>() };
+        let (prefix, words, suffix) = unsafe { bytes.align_to::<u32>() };
+        let wr_data32 = self.info.regs.wr_data32().as_ptr();
 
         for b in prefix {
             self.info.regs.wr_data8().write(|w| unsafe { w.bits(*b) });
         }
 
-        for d in data {
-            self.info.regs.wr_data32().write(|w| unsafe { w.bits(*d) });
+        let mut chunks = words.chunks_exact(4);
+        for chunk in &mut chunks {
+            // SAFETY: wr_data32 is a valid, write-only register
+            unsafe {
+                wr_data32.write_volatile(chunk[0]);
+                wr_data32.write_volatile(chunk[1]);
+                wr_data32.write_volatile(chunk[2]);
+                wr_data32.write_volatile(chunk[3]);
+            }
+        }
+
+        for word in chunks.remainder() {
+            // SAFETY: wr_data32 is a valid, write-only register
+            unsafe { wr_data32.write_volatile(*word) };
         }
 
         for b in suffix {
             self.info.regs.wr_data8().write(|w| unsafe { w.bits(*b) });
         }
 
-        self.info.regs.sum().read().bits()
+        self.sum()
     }
 
     /// Feeds a halfword into the CRC peripheral. Returns the computed checksum.
     pub fn feed_halfword(&mut self, halfword: u16) -> u32 {
         self.info.regs.wr_data16().write(|w| unsafe { w.bits(halfword) });
 
-        self.info.regs.sum().read().bits()
+        self.sum()
     }
 
     /// Feeds an slice of halfwords into the CRC peripheral. Returns the computed checksum.
@@ -150,14 +179,14 @@ impl<'d> Crc<'d> {
             self.info.regs.wr_data16().write(|w| unsafe { w.bits(*halfword) });
         }
 
-        self.info.regs.sum().read().bits()
+        self.sum()
     }
 
     /// Feeds a words into the CRC peripheral. Returns the computed checksum.
     pub fn feed_word(&mut self, word: u32) -> u32 {
         self.info.regs.wr_data32().write(|w| unsafe { w.bits(word) });
 
-        self.info.regs.sum().read().bits()
+        self.sum()
     }
 
     /// Feeds an slice of words into the CRC peripheral. Returns the computed checksum.
@@ -166,7 +195,45 @@ impl<'d> Crc<'d> {
             self.info.regs.wr_data32().write(|w| unsafe { w.bits(*word) });
         }
 
-        self.info.regs.sum().read().bits()
+        self.sum()
+    }
+
+    /// Blocking counterpart of [`Self::compute_dma`], feeds `data` like [`Self::feed_bytes`].
+    /// Returns the computed checksum.
+    pub fn compute_blocking_bulk(&mut self, data: &[u8]) -> u32 {
+        self.feed_bytes(data)
+    }
+
+    /// Feeds a slice of bytes into the CRC peripheral, moving the word aligned
+    /// part with DMA. Returns the computed checksum.
+    ///
+    /// Unaligned leading and trailing bytes are written by the CPU.
+    pub async fn compute_dma(&mut self, data: &[u8], dma_ch: &Channel<'_>) -> Result<u32, dma::Error> {
+        // SAFETY: only the length of the aligned part is used to split `data`
+        let (prefix, words, _) = unsafe { data.align_to::<u32>() };
+        let (prefix, rest) = data.split_at(prefix.len());
+        let (words, suffix) = rest.split_at(words.len() * 4);
+
+        for b in prefix {
+            self.info.regs.wr_data8().write(|w| unsafe { w.bits(*b) });
+        }
+
+        let options = TransferOptions {
+            width: Width::Bit32,
+            ..Default::default()
+        };
+
+        // A single DMA descriptor moves at most 1024 transfers
+        for chunk in words.chunks(1024 * 4) {
+            Transfer::new_write_register(dma_ch, chunk, self.info.regs.wr_data32().as_ptr() as *mut u8, options)?
+                .await?;
+        }
+
+        for b in suffix {
+            self.info.regs.wr_data8().write(|w| unsafe { w.bits(*b) });
+        }
+
+        Ok(self.sum())
     }
 }
 
@@ -0,0 +1,177 @@
+//! POST code mirroring to a debug display
+//!
+//! Mirrors a stream of Port 80 POST codes to a physical display, such as 8 LEDs on GPIOs or a
+//! 7-segment driver behind a shift register, so boot progress is visible without a debugger.
+//!
+//! Codes are handed over through a [`PostCodeQueue`], which can be fed from the eSPI event
+//! handling or an interrupt, and are shown by [`mirror`] for at least a minimum hold time each so
+//! fast sequences stay readable. When codes arrive faster than they are shown, the queue drops the
+//! oldest ones and counts them, the most recent codes are the interesting ones after a hang.
+
+use core::cell::RefCell;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::waitqueue::AtomicWaker;
+use heapless::Deque;
+
+use crate::delay::Delay;
+use crate::gpio::{Level, Output, PortMasked};
+
+struct State<const N: usize> {
+    codes: Deque<u8, N>,
+    dropped: u32,
+}
+
+/// Bounded queue of POST codes between the producer and [`mirror`]
+///
+/// Holds up to `N` codes. Pushing to a full queue drops the oldest code and counts it, see
+/// [`PostCodeQueue::dropped`].
+pub struct PostCodeQueue<const N: usize> {
+    state: Mutex<CriticalSectionRawMutex, RefCell<State<N>>>,
+    waker: AtomicWaker,
+}
+
+impl<const N: usize> PostCodeQueue<N> {
+    /// Create an empty queue, usable in a `static`
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::const_new(
+                CriticalSectionRawMutex::new(),
+                RefCell::new(State {
+                    codes: Deque::new(),
+                    dropped: 0,
+                }),
+            ),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// Queue a code, dropping the oldest one if the queue is full
+    ///
+    /// Never blocks, so it can be called from interrupt context.
+    pub fn push(&self, code: u8) {
+        self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+
+            if state.codes.is_full() {
+                state.codes.pop_front();
+                state.dropped = state.dropped.wrapping_add(1);
+            }
+
+            // Cannot fail, there is room after dropping the oldest code
+            let _ = state.codes.push_back(code);
+        });
+
+        self.waker.wake();
+    }
+
+    /// Take the oldest queued code, if any
+    pub fn try_receive(&self) -> Option<u8> {
+        self.state.lock(|state| state.borrow_mut().codes.pop_front())
+    }
+
+    /// Wait for the oldest queued code
+    pub async fn receive(&self) -> u8 {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            match self.try_receive() {
+                Some(code) => Poll::Ready(code),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Number of codes dropped because the queue was full, wraps around
+    pub fn dropped(&self) -> u32 {
+        self.state.lock(|state| state.borrow().dropped)
+    }
+}
+
+impl<const N: usize> Default for PostCodeQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Display showing one POST code at a time
+pub trait PostCodeDisplay {
+    /// Show `code` until the next call
+    fn show(&mut self, code: u8);
+}
+
+/// One output per bit, the first pin is bit 0
+impl PostCodeDisplay for [Output<'_>; 8] {
+    fn show(&mut self, code: u8) {
+        for (bit, pin) in self.iter_mut().enumerate() {
+            pin.set_level(Level::from(code & (1 << bit) != 0));
+        }
+    }
+}
+
+/// The code is spread over the selected pins of the port in ascending order, lowest bit first,
+/// so all bits change with a single store
+impl<const PORT: usize> PostCodeDisplay for PortMasked<'_, PORT> {
+    fn show(&mut self, code: u8) {
+        let mask = self.mask();
+        let mut value = 0;
+        let mut bit = 0;
+
+        for pin in 0..32 {
+            if mask & (1 << pin) != 0 && bit < 8 {
+                if code & (1 << bit) != 0 {
+                    value |= 1 << pin;
+                }
+                bit += 1;
+            }
+        }
+
+        self.write(value);
+    }
+}
+
+/// Serial-in shift register, e.g. a 74HC595 driving a 7-segment decoder, behind an SPI device
+///
+/// The chip select of the device doubles as the latch strobe.
+pub struct ShiftRegister<S>(pub S);
+
+impl<S: embedded_hal_1::spi::SpiDevice> PostCodeDisplay for ShiftRegister<S> {
+    fn show(&mut self, code: u8) {
+        // A failed write leaves the previous code up, there is nowhere to report it
+        let _ = self.0.write(&[code]);
+    }
+}
+
+/// Show the codes of `queue` on `display`, each for at least `hold_ms` milliseconds
+///
+/// Runs forever. Embassy tasks cannot be generic, so spawn it from a task for the concrete
+/// display type:
+///
+/// ```rust,ignore
+/// static POST_CODES: PostCodeQueue<32> = PostCodeQueue::new();
+///
+/// #[embassy_executor::task]
+/// async fn post_code_task(mut leds: [Output<'static>; 8]) {
+///     debugport::mirror(&POST_CODES, &mut leds, 250).await
+/// }
+/// ```
+pub async fn mirror<const N: usize>(queue: &PostCodeQueue<N>, display: &mut impl PostCodeDisplay, hold_ms: u32) -> ! {
+    let mut dropped = queue.dropped();
+
+    loop {
+        let code = queue.receive().await;
+        display.show(code);
+
+        let now = queue.dropped();
+        if now != dropped {
+            warn!("{} POST codes dropped", now.wrapping_sub(dropped));
+            dropped = now;
+        }
+
+        embedded_hal_async::delay::DelayNs::delay_ms(&mut Delay, hold_ms).await;
+    }
+}
@@ -0,0 +1,100 @@
+//! Delay providers
+//!
+//! Blocking delays busy-wait on the DWT cycle counter, using the core clock frequency
+//! reported by the clocks module at the start of every delay. Async delays hand long
+//! waits to embassy-time when the `time` feature is enabled and busy-wait otherwise.
+
+use cortex_m::peripheral::{DCB, DWT};
+
+use crate::clocks::core_clock_rate;
+
+/// DEMCR trace enable, required for the DWT to run
+const DEMCR_TRCENA: u32 = 1 << 24;
+
+/// DWT_CTRL cycle counter enable
+const DWT_CTRL_CYCCNTENA: u32 = 1;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Start the DWT cycle counter
+pub(crate) fn init() {
+    // SAFETY: only sets the trace and cycle counter enable bits, which are never cleared by the HAL
+    unsafe {
+        (*DCB::PTR).demcr.modify(|r| r | DEMCR_TRCENA);
+        (*DWT::PTR).ctrl.modify(|r| r | DWT_CTRL_CYCCNTENA);
+    }
+}
+
+/// Delay provider
+///
+/// Zero-sized, create one wherever a delay is needed.
+#[derive(Clone, Copy, Default)]
+pub struct Delay;
+
+impl Delay {
+    /// Create a new delay provider
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Busy-wait for `cycles` core clock cycles
+    pub fn delay_cycles(&mut self, cycles: u64) {
+        let mut remaining = cycles;
+        let mut last = DWT::cycle_count();
+
+        // Accumulating the wrapping difference keeps counting across counter overflow
+        while remaining > 0 {
+            let now = DWT::cycle_count();
+            remaining = remaining.saturating_sub(u64::from(now.wrapping_sub(last)));
+            last = now;
+        }
+    }
+
+    fn ns_to_cycles(ns: u64) -> u64 {
+        // Round up so a delay is never shorter than requested
+        (ns * u64::from(core_clock_rate())).div_ceil(NANOS_PER_SEC)
+    }
+}
+
+impl embedded_hal_1::delay::DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay_cycles(Self::ns_to_cycles(u64::from(ns)));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay_cycles(Self::ns_to_cycles(u64::from(us) * 1_000));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay_cycles(Self::ns_to_cycles(u64::from(ms) * 1_000_000));
+    }
+}
+
+impl Delay {
+    async fn delay_ns_async(&mut self, ns: u64) {
+        #[cfg(feature = "time")]
+        {
+            // Anything shorter than a tick would be rounded up to a whole tick
+            if ns >= NANOS_PER_SEC / embassy_time::TICK_HZ {
+                embassy_time::Timer::after(embassy_time::Duration::from_nanos(ns)).await;
+                return;
+            }
+        }
+
+        self.delay_cycles(Self::ns_to_cycles(ns));
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.delay_ns_async(u64::from(ns)).await;
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        self.delay_ns_async(u64::from(us) * 1_000).await;
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.delay_ns_async(u64::from(ms) * 1_000_000).await;
+    }
+}
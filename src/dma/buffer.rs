@@ -0,0 +1,138 @@
+//! DMA buffer helpers
+//!
+//! The DMA controller can only write to on-chip SRAM and only moves data at
+//! addresses aligned to the transfer width. [`DmaBuffer`] provides storage that
+//! satisfies both when placed in RAM, and [`static_dma_buffer`](crate::static_dma_buffer)
+//! places one in a specific link section.
+
+use core::ops::{Deref, DerefMut};
+
+use super::transfer::Width;
+use super::Error;
+
+/// SRAM aliases reachable by the DMA controller (code/data bus, non-secure/secure)
+const SRAM_REGIONS: [(usize, usize); 4] = [
+    (0x0000_0000, 0x0048_0000),
+    (0x1000_0000, 0x1048_0000),
+    (0x2000_0000, 0x2048_0000),
+    (0x3000_0000, 0x3048_0000),
+];
+
+/// FlexSPI memory aliases, readable by the DMA controller (non-secure/secure)
+const FLEXSPI_REGIONS: [(usize, usize); 2] = [(0x0800_0000, 0x1000_0000), (0x1800_0000, 0x2000_0000)];
+
+fn in_regions(regions: &[(usize, usize)], addr: usize, len: usize) -> bool {
+    regions
+        .iter()
+        .any(|&(start, end)| addr >= start && addr.checked_add(len).is_some_and(|last| last <= end))
+}
+
+fn check_alignment(addr: usize, len: usize, width: Width) -> Result<(), Error> {
+    let width = width.byte_width();
+
+    if len == 0 || addr % width != 0 || len % width != 0 {
+        Err(Error::InvalidBuffer)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a buffer can be used as a DMA source
+pub(crate) fn check_source(addr: *const u8, len: usize, width: Width) -> Result<(), Error> {
+    let addr = addr as usize;
+
+    check_alignment(addr, len, width)?;
+
+    if in_regions(&SRAM_REGIONS, addr, len) || in_regions(&FLEXSPI_REGIONS, addr, len) {
+        Ok(())
+    } else {
+        Err(Error::InvalidBuffer)
+    }
+}
+
+/// Check that a buffer can be used as a DMA destination
+pub(crate) fn check_destination(addr: *const u8, len: usize, width: Width) -> Result<(), Error> {
+    let addr = addr as usize;
+
+    check_alignment(addr, len, width)?;
+
+    if in_regions(&SRAM_REGIONS, addr, len) {
+        Ok(())
+    } else {
+        Err(Error::InvalidBuffer)
+    }
+}
+
+/// Word aligned byte buffer, suitable for DMA transfers of any width
+#[repr(C, align(4))]
+pub struct DmaBuffer<const N: usize> {
+    buf: [u8; N],
+}
+
+impl<const N: usize> DmaBuffer<N> {
+    /// Create a zero-filled buffer
+    pub const fn new() -> Self {
+        Self { buf: [0; N] }
+    }
+
+    /// Buffer contents
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Mutable buffer contents
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl<const N: usize> Default for DmaBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for DmaBuffer<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl<const N: usize> DerefMut for DmaBuffer<N> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+/// Declare a static [`DmaBuffer`] and return a `&'static mut` reference to it.
+///
+/// The optional section name places the buffer in a specific link section,
+/// which must be located in SRAM by the linker script. Each invocation site
+/// can only be evaluated once, a second evaluation panics.
+///
+/// ```ignore
+/// let buf = static_dma_buffer!(256);
+/// let buf = static_dma_buffer!(256, ".dma_buffers");
+/// ```
+#[macro_export]
+macro_rules! static_dma_buffer {
+    (@take $buf:ident) => {{
+        static TAKEN: ::core::sync::atomic::AtomicBool = ::core::sync::atomic::AtomicBool::new(false);
+        if TAKEN.swap(true, ::core::sync::atomic::Ordering::AcqRel) {
+            panic!("static DMA buffer already taken");
+        }
+        // SAFETY: the flag above guarantees this is the only reference
+        unsafe { &mut *::core::ptr::addr_of_mut!($buf) }
+    }};
+    ($n:expr) => {{
+        static mut BUF: $crate::dma::buffer::DmaBuffer<$n> = $crate::dma::buffer::DmaBuffer::new();
+        $crate::static_dma_buffer!(@take BUF)
+    }};
+    ($n:expr, $section:literal) => {{
+        #[link_section = $section]
+        static mut BUF: $crate::dma::buffer::DmaBuffer<$n> = $crate::dma::buffer::DmaBuffer::new();
+        $crate::static_dma_buffer!(@take BUF)
+    }};
+}
@@ -2,13 +2,14 @@
 
 use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
 use core::task::Poll;
 
 use embassy_sync::waitqueue::AtomicWaker;
 
-use super::{DESCRIPTORS, DMA_WAKERS};
-use crate::dma::transfer::{Direction, Transfer, TransferOptions};
-use crate::dma::DmaInfo;
+use super::{ChannelDescriptor, DmaChannelStatus, DESCRIPTORS, DMA_COMPLETIONS, DMA_RESERVED, DMA_STATUS, DMA_WAKERS};
+use crate::dma::transfer::{Direction, Transfer, TransferOptions, Trigger};
+use crate::dma::{DmaInfo, Error, XferCfg};
 
 /// DMA channel
 pub struct Channel<'d> {
@@ -19,18 +20,28 @@ pub struct Channel<'d> {
 }
 
 impl<'d> Channel<'d> {
+    /// Releases the channel so it can be reserved again
+    ///
+    /// Dropping the channel has the same effect.
+    pub fn release(self) {}
+
     /// Reads from a peripheral into a memory buffer
     pub fn read_from_peripheral(
         &'d self,
         peri_addr: *const u8,
         buf: &'d mut [u8],
         options: TransferOptions,
-    ) -> Transfer<'d> {
+    ) -> Result<Transfer<'d>, Error> {
         Transfer::new_read(self, peri_addr, buf, options)
     }
 
     /// Writes from a memory buffer to a peripheral
-    pub fn write_to_peripheral(&'d self, buf: &'d [u8], peri_addr: *mut u8, options: TransferOptions) -> Transfer<'d> {
+    pub fn write_to_peripheral(
+        &'d self,
+        buf: &'d [u8],
+        peri_addr: *mut u8,
+        options: TransferOptions,
+    ) -> Result<Transfer<'d>, Error> {
         Transfer::new_write(self, buf, peri_addr, options)
     }
 
@@ -40,10 +51,10 @@ impl<'d> Channel<'d> {
         src_buf: &'d [u8],
         dst_buf: &'d mut [u8],
         options: TransferOptions,
-    ) -> Transfer<'d> {
-        let transfer = Transfer::new_write_mem(self, src_buf, dst_buf, options);
-        self.poll_transfer_complete().await;
-        transfer
+    ) -> Result<Transfer<'d>, Error> {
+        let transfer = Transfer::new_write_mem(self, src_buf, dst_buf, options)?;
+        self.poll_transfer_complete().await?;
+        Ok(transfer)
     }
 
     /// Return a reference to the channel's waker
@@ -70,6 +81,51 @@ impl<'d> Channel<'d> {
         self.info.regs.channel(channel).xfercfg().read().xfercount().bits()
     }
 
+    /// Number of bytes the current transfer still has to move, 0 once the channel is idle
+    ///
+    /// Can be called while a transfer is in progress, the count is sampled from XFERCOUNT.
+    pub fn remaining(&self) -> usize {
+        let channel = self.info.ch_num;
+        let xfercfg = self.info.regs.channel(channel).xfercfg().read();
+
+        // XFERCOUNT wraps to its maximum after the last element, only trust it while active
+        if !self.is_active() {
+            return 0;
+        }
+
+        let width = 1 << xfercfg.width().bits();
+        (xfercfg.xfercount().bits() as usize + 1) * width
+    }
+
+    /// Pause the channel without losing its progress
+    ///
+    /// No new requests are served once this returns, an element already being moved is
+    /// completed first. The descriptor and pending trigger are kept for [`Self::resume`].
+    pub fn pause(&self) {
+        self.disable_channel();
+
+        // Wait for a request accepted before the disable to finish its bus accesses
+        while self.is_busy() {}
+    }
+
+    /// Resume a channel stopped with [`Self::pause`]
+    pub fn resume(&self) {
+        let channel = self.info.ch_num;
+
+        // Unlike `enable_channel`, the error status of the transfer is kept
+        // SAFETY: unsafe due to .bits usage
+        self.info
+            .regs
+            .enableset0()
+            .modify(|_, w| unsafe { w.ena().bits(1 << channel) });
+    }
+
+    /// Check whether the channel is enabled, i.e. not paused or disabled
+    pub fn is_enabled(&self) -> bool {
+        let channel = self.info.ch_num;
+        self.info.regs.enableset0().read().ena().bits() & (1 << channel) != 0
+    }
+
     /// Abort DMA operation
     pub fn abort(&self) {
         let channel = self.info.ch_num;
@@ -80,27 +136,35 @@ impl<'d> Channel<'d> {
             unsafe { w.bits(1 << channel) });
     }
 
-    async fn poll_transfer_complete(&'d self) {
+    async fn poll_transfer_complete(&'d self) -> Result<(), Error> {
         poll_fn(|cx| {
-            // TODO - handle transfer failure
-
             let channel = self.info.ch_num;
 
             // Has the transfer already completed?
+            if let DmaChannelStatus::Error(_) = self.status() {
+                return Poll::Ready(Err(Error::TransferError));
+            }
             if self.info.regs.active0().read().act().bits() & (1 << channel) == 0 {
-                return Poll::Ready(());
+                return Poll::Ready(Ok(()));
             }
 
             DMA_WAKERS[channel].register(cx.waker());
 
             // Has the transfer completed now?
-            if self.info.regs.active0().read().act().bits() & (1 << channel) == 0 {
-                Poll::Ready(())
+            if let DmaChannelStatus::Error(_) = self.status() {
+                Poll::Ready(Err(Error::TransferError))
+            } else if self.info.regs.active0().read().act().bits() & (1 << channel) == 0 {
+                Poll::Ready(Ok(()))
             } else {
                 Poll::Pending
             }
         })
-        .await;
+        .await
+    }
+
+    /// Error status of the channel since it was last enabled
+    pub fn status(&self) -> DmaChannelStatus {
+        DmaChannelStatus::from(DMA_STATUS[self.info.ch_num].load(Ordering::Acquire))
     }
 
     /// Prepare the DMA channel for the transfer
@@ -119,6 +183,13 @@ impl<'d> Channel<'d> {
                 options.width.byte_width()
             );
         }
+        if (mem_len / options.width.byte_width()) % options.burst_size.elements() != 0 {
+            panic!(
+                "Transfer count({}) must be a multiple of the burst size({})",
+                mem_len / options.width.byte_width(),
+                options.burst_size.elements()
+            );
+        }
 
         let xferwidth: usize = options.width.byte_width();
         let xfercount = (mem_len / xferwidth) - 1;
@@ -129,7 +200,7 @@ impl<'d> Channel<'d> {
         // SAFETY: unsafe due to use of a mutable static (DESCRIPTORS.list)
         unsafe {
             DESCRIPTORS.list[channel].reserved = 0;
-            if dir == Direction::MemoryToPeripheral {
+            if dir == Direction::MemoryToPeripheral || dir == Direction::MemoryToRegister {
                 DESCRIPTORS.list[channel].dst_data_end_addr = dstbase as u32;
             } else {
                 DESCRIPTORS.list[channel].dst_data_end_addr = dstbase as u32 + (xfercount * xferwidth) as u32;
@@ -145,12 +216,13 @@ impl<'d> Channel<'d> {
         // Configure for transfer type, no hardware triggering (we'll trigger via software), high priority
         // SAFETY: unsafe due to .bits usage
         self.info.regs.channel(channel).cfg().write(|w| unsafe {
-            if dir == Direction::MemoryToMemory {
+            if dir == Direction::MemoryToMemory || dir == Direction::MemoryToRegister {
                 w.periphreqen().clear_bit();
             } else {
                 w.periphreqen().set_bit();
             }
             w.hwtrigen().clear_bit();
+            w.burstpower().bits(options.burst_size.burst_power());
             w.chpriority().bits(0)
         });
 
@@ -173,7 +245,7 @@ impl<'d> Channel<'d> {
             } else {
                 w.srcinc().bits(1);
             }
-            if dir == Direction::MemoryToPeripheral {
+            if dir == Direction::MemoryToPeripheral || dir == Direction::MemoryToRegister {
                 w.dstinc().bits(0);
             } else {
                 w.dstinc().bits(1);
@@ -182,10 +254,218 @@ impl<'d> Channel<'d> {
         });
     }
 
+    /// Number of descriptors completed on this channel since reset, wraps around
+    pub fn completion_count(&self) -> u32 {
+        DMA_COMPLETIONS[self.info.ch_num].load(Ordering::Acquire)
+    }
+
+    /// Prepare the DMA channel for a continuous peripheral-to-memory transfer
+    /// alternating between two buffers of `mem_len` bytes each (ping-pong).
+    ///
+    /// Completion of the first buffer raises interrupt A, of the second interrupt B,
+    /// after which the channel reloads and starts over. The transfer runs until the
+    /// channel is aborted.
+    pub(crate) fn configure_ping_pong(
+        &self,
+        srcbase: *const u32,
+        dstbase: [*mut u32; 2],
+        mem_len: usize,
+        options: TransferOptions,
+        reload: &'d mut [ChannelDescriptor; 2],
+    ) {
+        self.configure_chain(srcbase, &dstbase, mem_len, options, reload, true);
+    }
+
+    /// Prepare the DMA channel for a peripheral-to-memory transfer into a chain of
+    /// `dstbase.len()` buffers of `mem_len` bytes each.
+    ///
+    /// Each completed buffer raises interrupt A or B, alternating, and is counted in
+    /// [`Self::completion_count`]. With `circular` the chain wraps around to the first
+    /// buffer and runs until the channel is aborted, otherwise the channel stops after
+    /// the last buffer. `reload` must hold one descriptor per buffer.
+    pub(crate) fn configure_chain(
+        &self,
+        srcbase: *const u32,
+        dstbase: &[*mut u32],
+        mem_len: usize,
+        options: TransferOptions,
+        reload: &'d mut [ChannelDescriptor],
+        circular: bool,
+    ) {
+        if mem_len % options.width.byte_width() != 0 {
+            panic!(
+                "Memory length({}) must be a multiple of the transfer width({})",
+                mem_len,
+                options.width.byte_width()
+            );
+        }
+        if (mem_len / options.width.byte_width()) % options.burst_size.elements() != 0 {
+            panic!(
+                "Transfer count({}) must be a multiple of the burst size({})",
+                mem_len / options.width.byte_width(),
+                options.burst_size.elements()
+            );
+        }
+
+        let segments = dstbase.len();
+        if segments == 0 || reload.len() < segments {
+            panic!("A descriptor is required for each of the {} buffers", segments);
+        }
+
+        let xferwidth: usize = options.width.byte_width();
+        let xfercount = (mem_len / xferwidth) - 1;
+        let channel = self.info.ch_num;
+
+        let xfercfg = |segment: usize| -> u32 {
+            let last = segment == segments - 1 && !circular;
+
+            let cfg = XferCfg::new(options.width, xfercount + 1).dst_increment();
+            let cfg = if last { cfg.clear_trigger() } else { cfg.reload() };
+            let cfg = if segment % 2 == 1 {
+                cfg.interrupt_b()
+            } else {
+                cfg.interrupt_a()
+            };
+            cfg.bits()
+        };
+
+        let end = |base: *mut u32| base as u32 + (xfercount * xferwidth) as u32;
+
+        for segment in 0..segments {
+            let next = if segment + 1 < segments {
+                &reload[segment + 1] as *const _ as u32
+            } else if circular {
+                &reload[0] as *const _ as u32
+            } else {
+                0
+            };
+
+            reload[segment] = ChannelDescriptor {
+                reserved: xfercfg(segment),
+                src_data_end_addr: srcbase as u32,
+                dst_data_end_addr: end(dstbase[segment]),
+                nxt_desc_link_addr: next,
+            };
+        }
+
+        // The channel starts on the first buffer and then follows the reload chain
+        // SAFETY: unsafe due to use of a mutable static (DESCRIPTORS.list)
+        unsafe {
+            DESCRIPTORS.list[channel].reserved = 0;
+            DESCRIPTORS.list[channel].src_data_end_addr = srcbase as u32;
+            DESCRIPTORS.list[channel].dst_data_end_addr = end(dstbase[0]);
+            DESCRIPTORS.list[channel].nxt_desc_link_addr = reload[0].nxt_desc_link_addr;
+        }
+
+        // SAFETY: unsafe due to .bits usage
+        self.info.regs.channel(channel).cfg().write(|w| unsafe {
+            w.periphreqen().set_bit();
+            w.hwtrigen().clear_bit();
+            w.burstpower().bits(options.burst_size.burst_power());
+            w.chpriority().bits(0)
+        });
+
+        // Enable the interrupt on this channel
+        self.info
+            .regs
+            .intenset0()
+            .write(|w| unsafe { w.inten().bits(1 << channel) });
+
+        // SAFETY: unsafe due to .bits usage
+        self.info
+            .regs
+            .channel(channel)
+            .xfercfg()
+            .write(|w| unsafe { w.bits(xfercfg(0)) });
+    }
+
+    /// Start the channel on `desc`, a linked descriptor carrying its own transfer
+    /// configuration, for a peripheral-paced transfer.
+    ///
+    /// The channel follows the descriptor links until it reloads a descriptor whose
+    /// configuration is not marked valid.
+    pub(crate) fn start_linked(&self, desc: &ChannelDescriptor) {
+        let channel = self.info.ch_num;
+
+        self.abort();
+
+        // SAFETY: unsafe due to use of a mutable static (DESCRIPTORS.list)
+        unsafe {
+            DESCRIPTORS.list[channel].reserved = 0;
+            DESCRIPTORS.list[channel].src_data_end_addr = desc.src_data_end_addr;
+            DESCRIPTORS.list[channel].dst_data_end_addr = desc.dst_data_end_addr;
+            DESCRIPTORS.list[channel].nxt_desc_link_addr = desc.nxt_desc_link_addr;
+        }
+
+        // SAFETY: unsafe due to .bits usage
+        self.info.regs.channel(channel).cfg().write(|w| unsafe {
+            w.periphreqen().set_bit();
+            w.hwtrigen().clear_bit();
+            w.chpriority().bits(0)
+        });
+
+        // Enable the interrupt on this channel
+        self.info
+            .regs
+            .intenset0()
+            .write(|w| unsafe { w.inten().bits(1 << channel) });
+
+        // SAFETY: unsafe due to .bits usage
+        self.info
+            .regs
+            .channel(channel)
+            .xfercfg()
+            .write(|w| unsafe { w.bits(desc.reserved) });
+
+        self.enable_channel();
+        self.trigger_channel();
+    }
+
+    /// Whether the channel's current transfer configuration is valid, i.e. it has
+    /// not stopped on a reloaded descriptor that was not ready yet
+    pub(crate) fn is_config_valid(&self) -> bool {
+        let channel = self.info.ch_num;
+        self.info.regs.channel(channel).xfercfg().read().cfgvalid().bit_is_set()
+    }
+
+    /// Switch a configured channel to hardware triggering, moving one burst of the
+    /// configured [`TransferOptions::burst_size`] per rising edge of `trigger` instead
+    /// of following peripheral requests.
+    /// The channel must not be software triggered afterwards.
+    pub fn set_hw_trigger(&self, trigger: Trigger) {
+        let channel = self.info.ch_num;
+
+        // SAFETY: only one channel is routed per INPUTMUX register, unsafe only used for .bits()
+        let inputmux = unsafe { crate::pac::Inputmux::steal() };
+        inputmux
+            .dma0_itrig_inmux(channel)
+            .write(|w| unsafe { w.inp().bits(trigger.into()) });
+
+        // SAFETY: unsafe due to .bits usage
+        self.info.regs.channel(channel).cfg().modify(|_, w| unsafe {
+            w.periphreqen().clear_bit();
+            w.hwtrigen().set_bit();
+            w.trigpol().set_bit();
+            w.trigtype().clear_bit();
+            w.trigburst().set_bit()
+        });
+
+        // Triggers start each burst, clear any pending software trigger
+        self.info
+            .regs
+            .channel(channel)
+            .xfercfg()
+            .modify(|_, w| w.swtrig().clear_bit());
+    }
+
     /// Enable the DMA channel (only after configuring)
     // SAFETY: unsafe due to .bits usage
     pub fn enable_channel(&self) {
         let channel = self.info.ch_num;
+
+        // A new transfer starts without the errors of the previous one
+        DMA_STATUS[channel].store(0, Ordering::Relaxed);
+
         self.info
             .regs
             .enableset0()
@@ -210,3 +490,9 @@ impl<'d> Channel<'d> {
             .modify(|_, w| w.swtrig().set_bit());
     }
 }
+
+impl Drop for Channel<'_> {
+    fn drop(&mut self) {
+        DMA_RESERVED[self.info.ch_num].store(false, Ordering::Release);
+    }
+}
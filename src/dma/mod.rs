@@ -1,10 +1,12 @@
 //! DMA
 
+pub mod buffer;
 pub mod channel;
 pub mod transfer;
 
 use core::marker::PhantomData;
 use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
 
 use embassy_hal_internal::impl_peripheral;
 use embassy_hal_internal::interrupt::InterruptExt;
@@ -25,12 +27,90 @@ const DMA_CHANNEL_COUNT: usize = 33;
 
 /// DMA channel descriptor
 #[derive(Copy, Clone, Debug)]
-#[repr(C)]
-struct ChannelDescriptor {
-    reserved: u32,
-    src_data_end_addr: u32,
-    dst_data_end_addr: u32,
-    nxt_desc_link_addr: u32,
+#[repr(C, align(16))]
+pub(crate) struct ChannelDescriptor {
+    /// Transfer configuration to load on reload (unused for the channel's primary descriptor)
+    pub(crate) reserved: u32,
+    pub(crate) src_data_end_addr: u32,
+    pub(crate) dst_data_end_addr: u32,
+    pub(crate) nxt_desc_link_addr: u32,
+}
+
+impl ChannelDescriptor {
+    /// Empty descriptor
+    pub(crate) const EMPTY: Self = Self {
+        reserved: 0,
+        src_data_end_addr: 0,
+        dst_data_end_addr: 0,
+        nxt_desc_link_addr: 0,
+    };
+}
+
+/// Transfer configuration word of a reload descriptor, laid out like the channel's XFERCFG
+/// register
+///
+/// Reload descriptors live in memory, so their configuration cannot be built through the PAC
+/// register writer. Starts with both addresses fixed, no reload, no trigger change and no
+/// interrupt.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct XferCfg(u32);
+
+impl XferCfg {
+    const CFGVALID: u32 = 1 << 0;
+    const RELOAD: u32 = 1 << 1;
+    const CLRTRIG: u32 = 1 << 3;
+    const SETINTA: u32 = 1 << 4;
+    const SETINTB: u32 = 1 << 5;
+    const WIDTH_SHIFT: u32 = 8;
+    /// SRCINC and DSTINC value for an increment of one transfer width
+    const INC_WIDTH: u32 = 1;
+    const SRCINC_SHIFT: u32 = 12;
+    const DSTINC_SHIFT: u32 = 14;
+    const XFERCOUNT_SHIFT: u32 = 16;
+
+    /// Valid configuration moving `count` elements of `width`, `count` must be 1 to 1024
+    pub(crate) fn new(width: transfer::Width, count: usize) -> Self {
+        Self(
+            Self::CFGVALID
+                | (u32::from(u8::from(width)) << Self::WIDTH_SHIFT)
+                | ((count as u32 - 1) << Self::XFERCOUNT_SHIFT),
+        )
+    }
+
+    /// Load the linked descriptor once this one completes
+    pub(crate) fn reload(self) -> Self {
+        Self(self.0 | Self::RELOAD)
+    }
+
+    /// Clear the channel trigger once this descriptor completes
+    pub(crate) fn clear_trigger(self) -> Self {
+        Self(self.0 | Self::CLRTRIG)
+    }
+
+    /// Raise interrupt A once this descriptor completes
+    pub(crate) fn interrupt_a(self) -> Self {
+        Self(self.0 | Self::SETINTA)
+    }
+
+    /// Raise interrupt B once this descriptor completes
+    pub(crate) fn interrupt_b(self) -> Self {
+        Self(self.0 | Self::SETINTB)
+    }
+
+    /// Step the source address by the transfer width after each element
+    pub(crate) fn src_increment(self) -> Self {
+        Self(self.0 | (Self::INC_WIDTH << Self::SRCINC_SHIFT))
+    }
+
+    /// Step the destination address by the transfer width after each element
+    pub(crate) fn dst_increment(self) -> Self {
+        Self(self.0 | (Self::INC_WIDTH << Self::DSTINC_SHIFT))
+    }
+
+    /// Raw configuration word, for [`ChannelDescriptor::reserved`] or the XFERCFG register
+    pub(crate) fn bits(self) -> u32 {
+        self.0
+    }
 }
 
 /// DMA channel descriptor memory block (1KB aligned)
@@ -42,12 +122,7 @@ struct DescriptorBlock {
 
 /// DMA channel descriptor list
 static mut DESCRIPTORS: DescriptorBlock = DescriptorBlock {
-    list: [ChannelDescriptor {
-        reserved: 0,
-        src_data_end_addr: 0,
-        dst_data_end_addr: 0,
-        nxt_desc_link_addr: 0,
-    }; DMA_CHANNEL_COUNT],
+    list: [ChannelDescriptor::EMPTY; DMA_CHANNEL_COUNT],
 };
 
 /// DMA errors
@@ -56,11 +131,51 @@ static mut DESCRIPTORS: DescriptorBlock = DescriptorBlock {
 pub enum Error {
     /// Configuration requested is not supported
     UnsupportedConfiguration,
+
+    /// Buffer is not in DMA accessible memory, not aligned to the transfer width or not a whole
+    /// number of bursts long
+    InvalidBuffer,
+
+    /// The channel raised an error interrupt, e.g. an AHB bus fault on its source or destination
+    TransferError,
+}
+
+/// DMA channel completion status
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmaChannelStatus {
+    /// No error since the channel was last enabled
+    Success,
+
+    /// The channel raised an error interrupt, carries the channel's CTLSTAT flags at that time
+    Error(u8),
+}
+
+/// Marks a stored status as an error, the low bits hold the channel's CTLSTAT flags
+const STATUS_ERROR: u8 = 1 << 7;
+
+impl From<u8> for DmaChannelStatus {
+    fn from(value: u8) -> Self {
+        if value & STATUS_ERROR != 0 {
+            DmaChannelStatus::Error(value & !STATUS_ERROR)
+        } else {
+            DmaChannelStatus::Success
+        }
+    }
 }
 
 // One waker per channel
 static DMA_WAKERS: [AtomicWaker; DMA_CHANNEL_COUNT] = [const { AtomicWaker::new() }; DMA_CHANNEL_COUNT];
 
+// Number of completed descriptors per channel, used to track ping-pong progress
+static DMA_COMPLETIONS: [AtomicU32; DMA_CHANNEL_COUNT] = [const { AtomicU32::new(0) }; DMA_CHANNEL_COUNT];
+
+// Error status per channel, set by the error interrupt and cleared when the channel is enabled
+static DMA_STATUS: [AtomicU8; DMA_CHANNEL_COUNT] = [const { AtomicU8::new(0) }; DMA_CHANNEL_COUNT];
+
+// Set while a Channel handle exists, catches channels reserved twice through stolen peripherals
+static DMA_RESERVED: [AtomicBool; DMA_CHANNEL_COUNT] = [const { AtomicBool::new(false) }; DMA_CHANNEL_COUNT];
+
 #[cfg(feature = "rt")]
 #[interrupt]
 #[allow(non_snake_case)]
@@ -80,6 +195,9 @@ fn dma0_irq_handler<const N: usize>(wakers: &[AtomicWaker; N]) {
         for channel in err.trailing_zeros()..(32 - err.leading_zeros()) {
             if err & (1 << channel) != 0 {
                 error!("DMA error interrupt on channel {}!", channel);
+                // Record the error before waking, so the transfer sees it when polled
+                let ctlstat = reg.channel(channel as usize).ctlstat().read().bits() as u8;
+                DMA_STATUS[channel as usize].store(STATUS_ERROR | (ctlstat & !STATUS_ERROR), Ordering::Release);
                 // Clear the pending interrupt for this channel
                 // SAFETY: unsafe due to .bits usage
                 reg.errint0().write(|w| unsafe { w.err().bits(1 << channel) });
@@ -97,20 +215,38 @@ fn dma0_irq_handler<const N: usize>(wakers: &[AtomicWaker; N]) {
                 // Clear the pending interrupt for this channel
                 // SAFETY: unsafe due to .bits usage
                 reg.inta0().write(|w| unsafe { w.ia().bits(1 << channel) });
+                DMA_COMPLETIONS[channel as usize].fetch_add(1, Ordering::Release);
+                wakers[channel as usize].wake();
+            }
+        }
+
+        let ib = reg.intb0().read().bits();
+        // Interrupt B is only raised by ping-pong descriptors
+        for channel in ib.trailing_zeros()..(32 - ib.leading_zeros()) {
+            if ib & (1 << channel) != 0 {
+                // Clear the pending interrupt for this channel
+                // SAFETY: unsafe due to .bits usage
+                reg.intb0().write(|w| unsafe { w.ib().bits(1 << channel) });
+                DMA_COMPLETIONS[channel as usize].fetch_add(1, Ordering::Release);
                 wakers[channel as usize].wake();
             }
         }
     }
 }
 
-/// Initialize DMA controllers (DMA0 only, for now)
-pub(crate) fn init() {
+/// Initialize DMA controllers (DMA0 only, for now), with the controller interrupt at `priority`
+///
+/// DMA1 will take the same priority once it is supported.
+pub(crate) fn init_with_priority(priority: interrupt::Priority) {
     // SAFETY: init should only be called once during HAL initialization
     let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
     let dmactl0 = unsafe { crate::pac::Dma0::steal() };
 
     enable_and_reset::<DMA0>();
 
+    #[cfg(feature = "postmortem")]
+    crate::postmortem::register::<DMA0>(crate::postmortem::Kind::Dma, 0, crate::pac::Dma0::ptr() as usize);
+
     // Enable DMA controller
     dmactl0.ctrl().modify(|_, w| w.enable().set_bit());
 
@@ -128,6 +264,7 @@ pub(crate) fn init() {
 
     // Enable DMA interrupts on DMA0
     interrupt::DMA0.unpend();
+    interrupt::DMA0.set_priority(priority);
     // SAFETY: enabling the dma0 controller interrupt is an unsafe call
     unsafe {
         interrupt::DMA0.enable();
@@ -146,15 +283,24 @@ struct DmaInfo {
 
 impl<'d> Dma<'d> {
     /// Reserves a DMA channel for exclusive use
+    ///
+    /// The channel stays reserved until the returned [`Channel`] is released or dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel is already reserved, which is only possible if the channel
+    /// peripheral was duplicated with `steal()` or similar unsafe APIs.
     pub fn reserve_channel<T: Instance>(_inner: impl Peripheral<P = T> + 'd) -> Option<Channel<'d>> {
-        if T::info().is_some() {
-            Some(Channel {
-                info: T::info().unwrap(),
-                _lifetime: PhantomData,
-            })
-        } else {
-            None
+        let info = T::info()?;
+
+        if DMA_RESERVED[info.ch_num].swap(true, Ordering::AcqRel) {
+            panic!("DMA channel {} reserved twice", info.ch_num);
         }
+
+        Some(Channel {
+            info,
+            _lifetime: PhantomData,
+        })
     }
 }
 
@@ -222,7 +368,8 @@ dma_channel_instance!(DMA0_CH31, Dma0, DMA0, 31);
 dma_channel_instance!(DMA0_CH32, Dma0, DMA0, 32);
 
 /// IMPORTANT: DO NOT USE unless you are aware of the performance implications of not using DMA.
-/// NoDma should only be used when a Flexcomm doesn't support DMA, such as Flexcomm 15.
+/// NoDma is meant for a Flexcomm that doesn't support DMA, such as Flexcomm 15, or for an
+/// async I2C master on systems that run out of DMA channels.
 ///
 /// For other transport layers, like UART, NoDma is not supported.
 pub struct NoDma;
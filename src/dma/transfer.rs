@@ -4,7 +4,9 @@ use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
+use crate::dma::buffer::{check_destination, check_source};
 use crate::dma::channel::Channel;
+use crate::dma::{DmaChannelStatus, Error};
 
 /// DMA transfer options
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -16,6 +18,12 @@ pub struct TransferOptions {
 
     /// Transfer priority level
     pub priority: Priority,
+
+    /// Number of elements moved per hardware trigger, see [`DmaBurst`]
+    ///
+    /// The SPI, I2C and UART drivers keep [`DmaBurst::Single`]: their transfers are paced by
+    /// FLEXCOMM FIFO requests, which move one element each whatever the burst size.
+    pub burst_size: DmaBurst,
 }
 
 impl Default for TransferOptions {
@@ -23,6 +31,7 @@ impl Default for TransferOptions {
         Self {
             width: Width::Bit8,
             priority: Priority::Priority0,
+            burst_size: DmaBurst::Single,
         }
     }
 }
@@ -82,6 +91,97 @@ impl Width {
     }
 }
 
+/// DMA burst size, the `CFG.BURSTPOWER` field of a channel
+///
+/// A burst only applies to hardware triggered channels (see [`Channel::set_hw_trigger`]): each
+/// trigger edge moves one burst of elements. Peripheral requests, e.g. from the FLEXCOMM FIFOs,
+/// always move one element per request, whatever the burst size. The transfer length must be a
+/// whole number of bursts, [`Transfer`] rejects other lengths with [`Error::InvalidBuffer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmaBurst {
+    /// 1 element per trigger
+    Single,
+    /// 4 elements per trigger
+    Burst4,
+    /// 8 elements per trigger
+    Burst8,
+    /// 16 elements per trigger
+    Burst16,
+}
+
+impl DmaBurst {
+    /// `BURSTPOWER` field value, the burst is `2^BURSTPOWER` elements
+    pub(crate) fn burst_power(self) -> u8 {
+        match self {
+            DmaBurst::Single => 0,
+            DmaBurst::Burst4 => 2,
+            DmaBurst::Burst8 => 3,
+            DmaBurst::Burst16 => 4,
+        }
+    }
+
+    /// Number of elements in a burst
+    pub fn elements(self) -> usize {
+        1 << self.burst_power()
+    }
+}
+
+/// DMA hardware trigger source, routed to a channel through INPUTMUX
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trigger {
+    /// Pin interrupt 0
+    PinInt0,
+    /// Pin interrupt 1
+    PinInt1,
+    /// Pin interrupt 2
+    PinInt2,
+    /// Pin interrupt 3
+    PinInt3,
+    /// CTIMER0 match 0
+    Ctimer0Match0,
+    /// CTIMER0 match 1
+    Ctimer0Match1,
+    /// CTIMER1 match 0
+    Ctimer1Match0,
+    /// CTIMER1 match 1
+    Ctimer1Match1,
+    /// CTIMER2 match 0
+    Ctimer2Match0,
+    /// CTIMER2 match 1
+    Ctimer2Match1,
+    /// CTIMER3 match 0
+    Ctimer3Match0,
+    /// CTIMER3 match 1
+    Ctimer3Match1,
+    /// CTIMER4 match 0
+    Ctimer4Match0,
+    /// CTIMER4 match 1
+    Ctimer4Match1,
+}
+
+impl From<Trigger> for u8 {
+    fn from(t: Trigger) -> Self {
+        match t {
+            Trigger::PinInt0 => 0,
+            Trigger::PinInt1 => 1,
+            Trigger::PinInt2 => 2,
+            Trigger::PinInt3 => 3,
+            Trigger::Ctimer0Match0 => 4,
+            Trigger::Ctimer0Match1 => 5,
+            Trigger::Ctimer1Match0 => 6,
+            Trigger::Ctimer1Match1 => 7,
+            Trigger::Ctimer2Match0 => 8,
+            Trigger::Ctimer2Match1 => 9,
+            Trigger::Ctimer3Match0 => 10,
+            Trigger::Ctimer3Match1 => 11,
+            Trigger::Ctimer4Match0 => 12,
+            Trigger::Ctimer4Match1 => 13,
+        }
+    }
+}
+
 /// DMA transfer direction
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -92,12 +192,15 @@ pub enum Direction {
     MemoryToPeripheral,
     /// Peripheral-to-memory
     PeripheralToMemory,
+    /// Memory-to-register, not paced by peripheral requests
+    MemoryToRegister,
 }
 
 /// DMA transfer
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Transfer<'d> {
     _inner: &'d Channel<'d>,
+    len: usize,
 }
 
 impl<'d> Transfer<'d> {
@@ -107,7 +210,9 @@ impl<'d> Transfer<'d> {
         peri_addr: *const u8,
         buf: &'d mut [u8],
         options: TransferOptions,
-    ) -> Self {
+    ) -> Result<Self, Error> {
+        check_destination(buf.as_ptr(), buf.len(), options.width)?;
+
         Self::new_inner_transfer(
             channel,
             Direction::PeripheralToMemory,
@@ -119,7 +224,14 @@ impl<'d> Transfer<'d> {
     }
 
     /// Writes a memory buffer into a peripheral register using DMA
-    pub fn new_write(channel: &'d Channel<'d>, buf: &'d [u8], peri_addr: *mut u8, options: TransferOptions) -> Self {
+    pub fn new_write(
+        channel: &'d Channel<'d>,
+        buf: &'d [u8],
+        peri_addr: *mut u8,
+        options: TransferOptions,
+    ) -> Result<Self, Error> {
+        check_source(buf.as_ptr(), buf.len(), options.width)?;
+
         Self::new_inner_transfer(
             channel,
             Direction::MemoryToPeripheral,
@@ -130,13 +242,39 @@ impl<'d> Transfer<'d> {
         )
     }
 
+    /// Writes a memory buffer into a single register using DMA, as fast as the
+    /// bus allows. For registers that accept data at any rate and have no DMA request.
+    pub fn new_write_register(
+        channel: &'d Channel<'d>,
+        buf: &'d [u8],
+        reg_addr: *mut u8,
+        options: TransferOptions,
+    ) -> Result<Self, Error> {
+        check_source(buf.as_ptr(), buf.len(), options.width)?;
+
+        Self::new_inner_transfer(
+            channel,
+            Direction::MemoryToRegister,
+            buf as *const [u8] as *const u32,
+            reg_addr as *mut u32,
+            buf.len(),
+            options,
+        )
+    }
+
     /// Writes a memory buffer into another memory buffer using DMA
     pub fn new_write_mem(
         channel: &'d Channel<'d>,
         src_buf: &'d [u8],
         dst_buf: &'d mut [u8],
         options: TransferOptions,
-    ) -> Self {
+    ) -> Result<Self, Error> {
+        check_source(src_buf.as_ptr(), src_buf.len(), options.width)?;
+        check_destination(dst_buf.as_ptr(), src_buf.len(), options.width)?;
+        if dst_buf.len() < src_buf.len() {
+            return Err(Error::InvalidBuffer);
+        }
+
         Self::new_inner_transfer(
             channel,
             Direction::MemoryToMemory,
@@ -155,7 +293,12 @@ impl<'d> Transfer<'d> {
         dst_buf: *mut u32,
         mem_len: usize,
         options: TransferOptions,
-    ) -> Self {
+    ) -> Result<Self, Error> {
+        // Length is a whole number of elements, checked with the buffers
+        if (mem_len / options.width.byte_width()) % options.burst_size.elements() != 0 {
+            return Err(Error::InvalidBuffer);
+        }
+
         // Configure the DMA channel descriptor and registers
         channel.configure_channel(dir, src_buf, dst_buf, mem_len, options);
 
@@ -165,13 +308,50 @@ impl<'d> Transfer<'d> {
         // Generate a software channel trigger to start the transfer
         channel.trigger_channel();
 
-        Self { _inner: channel }
+        Ok(Self {
+            _inner: channel,
+            len: mem_len,
+        })
+    }
+
+    /// Total number of bytes of the transfer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the transfer moves no data
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes still to be moved
+    pub fn remaining(&self) -> usize {
+        self._inner.remaining()
+    }
+
+    /// Number of bytes moved so far, can be polled while the transfer runs
+    ///
+    /// The count advances when the controller has issued the write of an element, which
+    /// may still sit in the AHB write buffer at that point. Before reading the data, issue
+    /// a barrier (e.g. `compiler_fence` plus a DSB) or wait for the transfer to complete.
+    pub fn completed(&self) -> usize {
+        self.len - self._inner.remaining()
+    }
+
+    /// Pause the transfer, see [`Channel::pause`]
+    pub fn pause(&self) {
+        self._inner.pause()
+    }
+
+    /// Resume a paused transfer, see [`Channel::resume`]
+    pub fn resume(&self) {
+        self._inner.resume()
     }
 }
 
 impl Unpin for Transfer<'_> {}
 impl Future for Transfer<'_> {
-    type Output = ();
+    type Output = Result<(), Error>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let channel = self._inner.info.ch_num;
@@ -180,8 +360,11 @@ impl Future for Transfer<'_> {
         // wake will deregister the waker.
         super::DMA_WAKERS[channel].register(cx.waker());
 
-        if self._inner.info.regs.active0().read().act().bits() & (1 << channel) == 0 {
-            Poll::Ready(())
+        // An error also wakes the transfer, it must not look like a completion
+        if let DmaChannelStatus::Error(_) = self._inner.status() {
+            Poll::Ready(Err(Error::TransferError))
+        } else if self._inner.info.regs.active0().read().act().bits() & (1 << channel) == 0 {
+            Poll::Ready(Ok(()))
         } else {
             Poll::Pending
         }
@@ -0,0 +1,38 @@
+//! Silicon errata registry
+//!
+//! Each erratum lists the die revisions it affects. Drivers ask the registry whether a
+//! workaround applies instead of checking revisions themselves, so adding a new erratum is
+//! one enum variant, one match arm and, if useful, one helper below.
+//!
+//! Every entry must name the erratum ID of the NXP errata sheet for the part (e.g. the
+//! `ERR` number) in its doc comment. No erratum is registered yet.
+
+// Unused until the first erratum is registered
+#![allow(dead_code)]
+
+use crate::chip_info::raw_revision;
+
+/// Known errata that the HAL works around
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Erratum {}
+
+/// Inclusive range of raw die revisions affected by an erratum
+struct Revisions {
+    first: u8,
+    last: u8,
+}
+
+impl Erratum {
+    const fn revisions(self) -> Revisions {
+        match self {}
+    }
+
+    /// Returns whether the running silicon is affected
+    pub(crate) fn applies(self) -> bool {
+        let rev = raw_revision();
+        let revs = self.revisions();
+
+        // Before the revision is known, assume the newest silicon
+        rev != u8::MAX && (revs.first..=revs.last).contains(&rev)
+    }
+}
@@ -8,7 +8,7 @@ use embassy_hal_internal::into_ref;
 use embassy_sync::waitqueue::AtomicWaker;
 use paste::paste;
 
-use crate::clocks::{enable_and_reset, SysconPeripheral};
+use crate::clocks::{clock_guard, ClockGuard, SysconPeripheral};
 use crate::gpio::{DriveMode, DriveStrength, Function, GpioPin as Pin, Inverter, Pull, SlewRate};
 use crate::interrupt::typelevel::Interrupt;
 pub use crate::pac::espi::espicap::{Flashmx, Maxspd, Safera, Spicap};
@@ -21,6 +21,20 @@ use crate::{interrupt, peripherals, Peripheral};
 // This controller has 5 different eSPI ports
 const ESPI_PORTS: usize = 5;
 
+/// PORTn_CFG.DIRECTION: data flows from the host, the EC only reads the window
+const DIRECTION_FROM_HOST: u8 = 0;
+/// PORTn_CFG.DIRECTION: data flows to the host, the EC only writes the window
+const DIRECTION_TO_HOST: u8 = 1;
+
+/// Number of virtual wire GPIO groups.
+pub const VW_GPIO_GROUPS: usize = 4;
+
+/// Number of virtual wire GPIOs in a group.
+pub const VW_GPIO_PER_GROUP: usize = 8;
+
+/// Total number of virtual wire GPIOs in each direction.
+pub const VW_GPIO_COUNT: usize = VW_GPIO_GROUPS * VW_GPIO_PER_GROUP;
+
 static ESPI_WAKER: AtomicWaker = AtomicWaker::new();
 
 /// Result type alias
@@ -35,6 +49,24 @@ pub enum Error {
 
     /// HStall Error
     HStall,
+
+    /// Port number out of range or port type doesn't support the operation
+    InvalidPort,
+
+    /// Access falls outside of the port's RAM window
+    OutOfWindow,
+
+    /// Port RAM window, or an offset or length within it, is not word aligned
+    Misaligned,
+
+    /// Port direction does not let the EC read or write the window
+    WrongDirection,
+
+    /// Port RAM window overlaps the window of another port
+    Overlap,
+
+    /// Host access to the port has not been completed yet
+    Busy,
 }
 
 /// eSPI Command Length
@@ -184,6 +216,45 @@ impl Default for PortConfig {
     }
 }
 
+impl PortConfig {
+    /// Byte range of eSPI RAM, relative to `ram_base`, used by the port
+    fn ram_window(&self) -> Option<core::ops::Range<usize>> {
+        match *self {
+            PortConfig::MailboxShared { offset, length, .. } | PortConfig::MailboxSingle { offset, length, .. } => {
+                // Window length is encoded as a power of two, starting at 4 bytes
+                let start = offset as usize;
+                Some(start..start + (4usize << u8::from(length)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Check that the RAM window of `port` configured as `config` is word aligned and does not
+/// overlap the window of any other port in `ports`
+fn check_ram_window(ports: &[PortConfig; ESPI_PORTS], port: usize, config: &PortConfig) -> Result<()> {
+    let Some(window) = config.ram_window() else {
+        return Ok(());
+    };
+
+    if window.start % 4 != 0 {
+        return Err(Error::Misaligned);
+    }
+
+    let overlaps = ports
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != port)
+        .filter_map(|(_, other)| other.ram_window())
+        .any(|other| other.start < window.end && window.start < other.end);
+
+    if overlaps {
+        Err(Error::Overlap)
+    } else {
+        Ok(())
+    }
+}
+
 /// eSPI capabilities.
 #[derive(Clone, Copy)]
 pub struct Capabilities {
@@ -251,6 +322,21 @@ pub struct Config {
     pub ports_config: [PortConfig; ESPI_PORTS],
 }
 
+impl Config {
+    /// Check the configuration for port RAM windows that are misaligned or overlap each other
+    fn validate(&self) -> Result<()> {
+        if self.ram_base % 4 != 0 {
+            return Err(Error::Misaligned);
+        }
+
+        for (port, config) in self.ports_config.iter().enumerate() {
+            check_ram_window(&self.ports_config, port, config)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -376,6 +462,90 @@ impl WireChangeEvent {
     }
 }
 
+/// Levels of one virtual wire GPIO group.
+///
+/// Bit `n` holds the level of GPIO `n` of the group.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VwGpioGroup(pub u8);
+
+impl VwGpioGroup {
+    /// Level of GPIO `n` of the group.
+    pub fn level(&self, n: usize) -> bool {
+        assert!(n < VW_GPIO_PER_GROUP);
+        self.0 & (1 << n) != 0
+    }
+
+    /// Set the level of GPIO `n` of the group.
+    pub fn set_level(&mut self, n: usize, level: bool) {
+        assert!(n < VW_GPIO_PER_GROUP);
+        if level {
+            self.0 |= 1 << n;
+        } else {
+            self.0 &= !(1 << n);
+        }
+    }
+}
+
+/// Levels of all virtual wire GPIO groups in one direction.
+///
+/// GPIO `index` is GPIO `index % VW_GPIO_PER_GROUP` of group `index / VW_GPIO_PER_GROUP`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VwGpioState(pub u32);
+
+impl VwGpioState {
+    /// Level of virtual wire GPIO `index`.
+    pub fn level(&self, index: usize) -> bool {
+        assert!(index < VW_GPIO_COUNT);
+        self.0 & (1 << index) != 0
+    }
+
+    /// Levels of group `group`.
+    pub fn group(&self, group: usize) -> VwGpioGroup {
+        assert!(group < VW_GPIO_GROUPS);
+        VwGpioGroup((self.0 >> (group * VW_GPIO_PER_GROUP)) as u8)
+    }
+
+    fn set_group(&mut self, group: usize, levels: VwGpioGroup) {
+        let shift = group * VW_GPIO_PER_GROUP;
+        self.0 = (self.0 & !(0xff << shift)) | ((levels.0 as u32) << shift);
+    }
+}
+
+/// Change of the host driven virtual wire GPIOs.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VwGpioChange {
+    changed: u32,
+    levels: VwGpioState,
+}
+
+impl VwGpioChange {
+    /// Set if virtual wire GPIO `index` changed.
+    pub fn is_changed(&self, index: usize) -> bool {
+        assert!(index < VW_GPIO_COUNT);
+        self.changed & (1 << index) != 0
+    }
+
+    /// Mask of the changed virtual wire GPIOs, bit `n` is GPIO index `n`.
+    pub fn changed_mask(&self) -> u32 {
+        self.changed
+    }
+
+    /// Indices of the changed virtual wire GPIOs along with their new level.
+    pub fn changes(&self) -> impl Iterator<Item = (usize, bool)> + '_ {
+        (0..VW_GPIO_COUNT)
+            .filter(|&index| self.is_changed(index))
+            .map(|index| (index, self.levels.level(index)))
+    }
+
+    /// Levels of all host driven virtual wire GPIOs after the change.
+    pub fn levels(&self) -> VwGpioState {
+        self.levels
+    }
+}
+
 /// eSPI events.
 pub enum Event {
     /// Port 0 has pending events
@@ -398,6 +568,9 @@ pub enum Event {
 
     /// Change in virtual wires
     WireChange(WireChangeEvent),
+
+    /// Change in host driven virtual wire GPIOs
+    GpioChange(VwGpioChange),
 }
 
 /// eSPI Boot Status.
@@ -421,11 +594,18 @@ impl From<BootStatus> for bool {
 /// eSPI driver.
 pub struct Espi<'d> {
     info: Info,
+    ram_base: u32,
+    ports_config: [PortConfig; ESPI_PORTS],
+    vw_gpio_host: VwGpioState,
+    vw_gpio_ec: VwGpioState,
+    _clock: ClockGuard,
     _phantom: PhantomData<&'d ()>,
 }
 
 impl<'d> Espi<'d> {
     /// Instantiates new eSPI peripheral and initializes to default values.
+    ///
+    /// Fails if the RAM windows of the configured ports are misaligned or overlap.
     pub fn new<T: Instance>(
         _peripheral: impl Peripheral<P = T> + 'd,
         _clk: impl Peripheral<P = impl ClkPin<T>> + 'd,
@@ -438,7 +618,9 @@ impl<'d> Espi<'d> {
         _alert: impl Peripheral<P = impl AlertPin<T>> + 'd,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
         config: Config,
-    ) -> Espi<'d> {
+    ) -> Result<Espi<'d>> {
+        config.validate()?;
+
         into_ref!(_peripheral);
         into_ref!(_clk);
         into_ref!(_cs);
@@ -459,19 +641,30 @@ impl<'d> Espi<'d> {
         _io3.as_io3();
 
         // enable ESPI clock
-        enable_and_reset::<T>();
+        let _clock = clock_guard::<T>();
+
+        #[cfg(feature = "postmortem")]
+        crate::postmortem::register::<T>(crate::postmortem::Kind::Espi, 0, T::info().regs as *const _ as usize);
 
         let mut instance = Espi::<'d> {
             info: T::info(),
+            ram_base: config.ram_base,
+            ports_config: Default::default(),
+            vw_gpio_host: VwGpioState::default(),
+            vw_gpio_ec: VwGpioState::default(),
+            _clock,
             _phantom: PhantomData,
         };
 
+        // Start tracking host driven virtual GPIOs from their current levels
+        instance.vw_gpio_host = instance.vw_gpio_host_state();
+
         // Set ESPI mode
         instance.info.regs.mctrl().modify(|_, w| w.enable().espi());
 
         // Configure ports
         for port in 0..ESPI_PORTS {
-            instance.configure(port, config.ports_config[port]);
+            instance.configure(port, config.ports_config[port])?;
         }
 
         // Set eSPI status block address
@@ -537,11 +730,60 @@ impl<'d> Espi<'d> {
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
 
-        instance
+        Ok(instance)
     }
 
     /// Configure the port to a given mode
-    pub fn configure(&mut self, port: usize, config: PortConfig) {
+    ///
+    /// A port that is already enabled is disabled, its pending status is cleared and its
+    /// registers are reset before the new mode is programmed, so nothing of the previous mode is
+    /// left behind. Host accesses to the port are not matched while this happens.
+    ///
+    /// Fails with [`Error::Busy`] if a host access to the port has not been completed yet, and
+    /// with [`Error::Overlap`] if the new RAM window overlaps the window of another port.
+    pub fn configure(&mut self, port: usize, config: PortConfig) -> Result<()> {
+        if port >= ESPI_PORTS {
+            return Err(Error::InvalidPort);
+        }
+
+        check_ram_window(&self.ports_config, port, &config)?;
+
+        critical_section::with(|_| {
+            if self.port_enabled(port) && self.port_status_pending(port) {
+                return Err(Error::Busy);
+            }
+
+            // Take the port off the bus before its registers change
+            self.info.regs.mctrl().modify(|_, w| w.pena(port as u8).disabled());
+            self.clear_port_status(port);
+            self.info.regs.port(port).cfg().reset();
+            self.info.regs.port(port).addr().reset();
+            self.info.regs.port(port).ramuse().reset();
+
+            self.ports_config[port] = config;
+            self.apply_port_config(port, config);
+
+            Ok(())
+        })
+    }
+
+    fn port_enabled(&self, port: usize) -> bool {
+        self.info.regs.mctrl().read().pena(port as u8).bit_is_set()
+    }
+
+    fn port_status_pending(&self, port: usize) -> bool {
+        let stat = self.info.regs.port(port).stat().read();
+
+        stat.interr().bit_is_set()
+            || stat.intrd().bit_is_set()
+            || stat.intwr().bit_is_set()
+            || stat.intspc0().bit_is_set()
+            || stat.intspc1().bit_is_set()
+            || stat.intspc2().bit_is_set()
+            || stat.intspc3().bit_is_set()
+    }
+
+    fn apply_port_config(&mut self, port: usize, config: PortConfig) {
         match config {
             PortConfig::AcpiEndpoint { direction, addr } => {
                 self.acpi_endpoint(port, direction, addr);
@@ -573,6 +815,131 @@ impl<'d> Espi<'d> {
 
     /// Complete port status
     pub async fn complete_port(&mut self, port: usize) {
+        self.clear_port_status(port);
+    }
+
+    /// Read `buf.len()` bytes at `offset` from the RAM window of a mailbox port
+    ///
+    /// `offset` and the length must be word aligned, and the port must not be configured to send
+    /// data to the host only.
+    pub fn port_read(&self, port: usize, offset: usize, buf: &mut [u8]) -> Result<()> {
+        let base = self.port_window(port, offset, buf.len())?;
+        self.check_port_access(port, offset, buf.len(), false)?;
+
+        // SAFETY: the access lies within the port window, checked above
+        unsafe { ram_read(base, buf) };
+
+        Ok(())
+    }
+
+    /// Write `data` at `offset` into the RAM window of a mailbox port
+    ///
+    /// `offset` and the length must be word aligned, and the port must not be configured to
+    /// receive data from the host only.
+    pub fn port_write(&mut self, port: usize, offset: usize, data: &[u8]) -> Result<()> {
+        let base = self.port_window(port, offset, data.len())?;
+        self.check_port_access(port, offset, data.len(), true)?;
+
+        // SAFETY: the access lies within the port window, checked above
+        unsafe { ram_write(base, data) };
+
+        Ok(())
+    }
+
+    /// Borrow the RAM window of a mailbox port for a read-modify-write sequence
+    ///
+    /// The guard holds the driver exclusively, so no other driver call can touch the window
+    /// until it is dropped. The host is not locked out, complete the port only once the window
+    /// holds consistent data.
+    pub fn port_data(&mut self, port: usize) -> Result<PortDataGuard<'_>> {
+        let base = self.port_window(port, 0, 0)?;
+        let len = self.port_window_len(port);
+
+        Ok(PortDataGuard {
+            base,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Place up to 4 response bytes in an ACPI endpoint's DATAOUT and complete the port
+    pub fn respond_acpi(&mut self, port: usize, data: &[u8]) -> Result<()> {
+        if port >= ESPI_PORTS || !matches!(self.ports_config[port], PortConfig::AcpiEndpoint { .. }) {
+            return Err(Error::InvalidPort);
+        }
+
+        if data.is_empty() || data.len() > 4 {
+            return Err(Error::OutOfWindow);
+        }
+
+        let mut word = [0; 4];
+        word[..data.len()].copy_from_slice(data);
+
+        // SAFETY: unsafe only used for .bits()
+        self.info
+            .regs
+            .port(port)
+            .dataout()
+            .write(|w| unsafe { w.data().bits(u32::from_le_bytes(word)) });
+
+        self.clear_port_status(port);
+
+        Ok(())
+    }
+
+    /// Length of the RAM window of a port in bytes
+    fn port_window_len(&self, port: usize) -> usize {
+        // Window length is encoded as a power of two, starting at 4 bytes
+        4usize << self.info.regs.port(port).ramuse().read().len().bits()
+    }
+
+    /// Validate an access against a mailbox port's RAM window and return its address
+    fn port_window(&self, port: usize, offset: usize, len: usize) -> Result<usize> {
+        if port >= ESPI_PORTS {
+            return Err(Error::InvalidPort);
+        }
+
+        let window_offset = match self.ports_config[port] {
+            PortConfig::MailboxShared { offset, .. } | PortConfig::MailboxSingle { offset, .. } => offset as usize,
+            _ => return Err(Error::InvalidPort),
+        };
+
+        let window_len = self.port_window_len(port);
+
+        let base = self.ram_base as usize + window_offset;
+        if base % 4 != 0 {
+            return Err(Error::Misaligned);
+        }
+
+        match offset.checked_add(len) {
+            Some(end) if end <= window_len => Ok(base + offset),
+            _ => Err(Error::OutOfWindow),
+        }
+    }
+
+    /// Check that an access of `len` bytes at `offset` to a mailbox port window is made of whole
+    /// words and goes the way the port direction allows
+    fn check_port_access(&self, port: usize, offset: usize, len: usize, write: bool) -> Result<()> {
+        if offset % 4 != 0 || len % 4 != 0 {
+            return Err(Error::Misaligned);
+        }
+
+        let direction = match self.ports_config[port] {
+            PortConfig::MailboxShared { direction, .. } | PortConfig::MailboxSingle { direction, .. } => {
+                u8::from(direction)
+            }
+            _ => return Err(Error::InvalidPort),
+        };
+
+        let denied = if write { DIRECTION_FROM_HOST } else { DIRECTION_TO_HOST };
+        if direction == denied {
+            Err(Error::WrongDirection)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn clear_port_status(&mut self, port: usize) {
         self.info.regs.port(port).stat().write(|w| {
             w.interr()
                 .clear_bit_by_one()
@@ -679,6 +1046,8 @@ impl<'d> Espi<'d> {
                     };
 
                     Poll::Ready(Ok(Event::WireChange(event)))
+                } else if let Some(change) = me.take_gpio_change() {
+                    Poll::Ready(Ok(Event::GpioChange(change)))
                 } else if me.info.regs.mstat().read().crcerr().bit_is_set() {
                     me.info.regs.mstat().write(|w| w.crcerr().clear_bit_by_one());
                     Poll::Ready(Err(Error::Crc))
@@ -705,6 +1074,8 @@ impl<'d> Espi<'d> {
                         .set_bit()
                         .wire_chg()
                         .set_bit()
+                        .gpio()
+                        .set_bit()
                         .hstall()
                         .set_bit()
                         .crcerr()
@@ -715,6 +1086,89 @@ impl<'d> Espi<'d> {
         .await
     }
 
+    /// Wait for a change of the host driven virtual wire GPIOs.
+    ///
+    /// Changes are tracked against the levels last reported here or by
+    /// [`Espi::wait_for_event`], so every change is reported by exactly one
+    /// of them.
+    pub async fn wait_for_gpio_change(&mut self) -> VwGpioChange {
+        self.wait_for(
+            |me| match me.take_gpio_change() {
+                Some(change) => Poll::Ready(change),
+                None => Poll::Pending,
+            },
+            |me| {
+                me.info.regs.intenset().write(|w| w.gpio().set_bit());
+            },
+        )
+        .await
+    }
+
+    /// Current levels of the host driven virtual wire GPIOs.
+    pub fn vw_gpio_host_state(&self) -> VwGpioState {
+        VwGpioState(self.info.regs.vwgpioin().read().bits())
+    }
+
+    /// Current levels of the host driven virtual wire GPIO group `group`.
+    pub fn vw_gpio_host_group(&self, group: usize) -> VwGpioGroup {
+        self.vw_gpio_host_state().group(group)
+    }
+
+    /// Levels last sent to the host on the EC driven virtual wire GPIOs.
+    pub fn vw_gpio_ec_state(&self) -> VwGpioState {
+        self.vw_gpio_ec
+    }
+
+    /// Drive the EC driven virtual wire GPIO group `group`.
+    ///
+    /// Waits until the controller has sent the virtual wires to the host.
+    pub async fn set_vw_gpio_group(&mut self, group: usize, levels: VwGpioGroup) {
+        assert!(group < VW_GPIO_GROUPS);
+
+        let mut state = self.vw_gpio_ec;
+        state.set_group(group, levels);
+        self.write_vw_gpio(state).await
+    }
+
+    /// Drive the EC driven virtual wire GPIO `index`.
+    ///
+    /// Waits until the controller has sent the virtual wires to the host.
+    pub async fn set_vw_gpio(&mut self, index: usize, level: bool) {
+        assert!(index < VW_GPIO_COUNT);
+
+        let mut state = self.vw_gpio_ec;
+        if level {
+            state.0 |= 1 << index;
+        } else {
+            state.0 &= !(1 << index);
+        }
+        self.write_vw_gpio(state).await
+    }
+
+    async fn write_vw_gpio(&mut self, state: VwGpioState) {
+        // SAFETY: every bit is a virtual GPIO level, any value is valid.
+        self.info.regs.vwgpioout().write(|w| unsafe { w.bits(state.0) });
+        self.vw_gpio_ec = state;
+        self.wait_for_vwire_done().await
+    }
+
+    /// Consume a pending virtual wire GPIO change, if any.
+    fn take_gpio_change(&mut self) -> Option<VwGpioChange> {
+        if self.info.regs.mstat().read().gpio().bit_is_clear() {
+            return None;
+        }
+
+        // Clear before reading so a change racing the read raises the flag again
+        self.info.regs.mstat().write(|w| w.gpio().clear_bit_by_one());
+
+        let levels = self.vw_gpio_host_state();
+        let changed = levels.0 ^ self.vw_gpio_host.0;
+        self.vw_gpio_host = levels;
+
+        // The host may rewrite a wire with its current level
+        (changed != 0).then_some(VwGpioChange { changed, levels })
+    }
+
     /// Wait for bus reset
     pub async fn wait_for_reset(&mut self) {
         self.wait_for(
@@ -884,6 +1338,19 @@ impl<'d> Espi<'d> {
         while self.info.regs.wirewo().read().done().bit_is_clear() {}
     }
 
+    async fn wait_for_vwire_done(&self) {
+        // No interrupt event available, yield to other tasks between polls
+        poll_fn(|cx| {
+            if self.info.regs.wirewo().read().done().bit_is_set() {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
     /// Calls `f` to check if we are ready or not.
     /// If not, `g` is called once the waker is set (to eg enable the required interrupts).
     async fn wait_for<F, U, G>(&mut self, mut f: F, mut g: G) -> U
@@ -1055,11 +1522,118 @@ impl Espi<'_> {
     }
 }
 
+/// Read `buf.len()` bytes of eSPI RAM at `base`
+///
+/// # Safety
+/// `base..base + buf.len()` must lie within a port RAM window.
+unsafe fn ram_read(base: usize, buf: &mut [u8]) {
+    // Word accesses for the aligned part so a word written by the host is read at once
+    let head_len = ((4 - base % 4) % 4).min(buf.len());
+    let (head, rest) = buf.split_at_mut(head_len);
+    let words_len = rest.len() & !3;
+    let (words, tail) = rest.split_at_mut(words_len);
+
+    for (i, b) in head.iter_mut().enumerate() {
+        // SAFETY: address lies within the port window, guaranteed by the caller
+        *b = unsafe { core::ptr::read_volatile((base + i) as *const u8) };
+    }
+
+    let word_base = base + head_len;
+    for (i, w) in words.chunks_exact_mut(4).enumerate() {
+        // SAFETY: address lies within the port window, guaranteed by the caller, and is word aligned
+        let word = unsafe { core::ptr::read_volatile((word_base + i * 4) as *const u32) };
+        w.copy_from_slice(&word.to_le_bytes());
+    }
+
+    let tail_base = word_base + words_len;
+    for (i, b) in tail.iter_mut().enumerate() {
+        // SAFETY: address lies within the port window, guaranteed by the caller
+        *b = unsafe { core::ptr::read_volatile((tail_base + i) as *const u8) };
+    }
+}
+
+/// Write `data` into eSPI RAM at `base`
+///
+/// # Safety
+/// `base..base + data.len()` must lie within a port RAM window.
+unsafe fn ram_write(base: usize, data: &[u8]) {
+    // Word accesses for the aligned part so the host never sees a partially updated word
+    let (head, rest) = data.split_at(((4 - base % 4) % 4).min(data.len()));
+    let (words, tail) = rest.split_at(rest.len() & !3);
+
+    for (i, b) in head.iter().enumerate() {
+        // SAFETY: address lies within the port window, guaranteed by the caller
+        unsafe { core::ptr::write_volatile((base + i) as *mut u8, *b) };
+    }
+
+    let word_base = base + head.len();
+    for (i, w) in words.chunks_exact(4).enumerate() {
+        let word = u32::from_le_bytes([w[0], w[1], w[2], w[3]]);
+        // SAFETY: address lies within the port window, guaranteed by the caller, and is word aligned
+        unsafe { core::ptr::write_volatile((word_base + i * 4) as *mut u32, word) };
+    }
+
+    let tail_base = word_base + words.len();
+    for (i, b) in tail.iter().enumerate() {
+        // SAFETY: address lies within the port window, guaranteed by the caller
+        unsafe { core::ptr::write_volatile((tail_base + i) as *mut u8, *b) };
+    }
+}
+
+/// Exclusive access to the RAM window of a mailbox port, see [`Espi::port_data`]
+pub struct PortDataGuard<'a> {
+    base: usize,
+    len: usize,
+    _phantom: PhantomData<&'a mut ()>,
+}
+
+impl PortDataGuard<'_> {
+    /// Length of the window in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always false, a window holds at least 4 bytes
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn check(&self, offset: usize, len: usize) -> Result<usize> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.len => Ok(self.base + offset),
+            _ => Err(Error::OutOfWindow),
+        }
+    }
+
+    /// Read `buf.len()` bytes at `offset` from the window
+    pub fn read(&self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        let base = self.check(offset, buf.len())?;
+
+        // SAFETY: the access lies within the port window, checked above
+        unsafe { ram_read(base, buf) };
+
+        Ok(())
+    }
+
+    /// Write `data` at `offset` into the window
+    pub fn write(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        let base = self.check(offset, data.len())?;
+
+        // SAFETY: the access lies within the port window, checked above
+        unsafe { ram_write(base, data) };
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy)]
 struct Info {
     regs: &'static crate::pac::espi::RegisterBlock,
 }
 
+// SAFETY: `Espi` owns the ESPI peripheral, the static register block has no other user.
+unsafe impl Send for Info {}
+
 trait SealedInstance {
     fn info() -> Info;
 }
@@ -1,4 +1,21 @@
 //! Flash
+//!
+//! Erase and program go through the FlexSPI NOR driver exposed by the boot ROM API
+//! tree, using the flash configuration block (FCB) the device booted from. Reads go
+//! through the memory-mapped (XIP) window. Since the firmware itself usually executes
+//! from the same flash, every ROM call runs inside a critical section.
+//!
+//! Additional flash devices on the other FlexSPI ports are set up with [`FlexspiNor`],
+//! which hands out a [`FlexspiDevice`] per port to build storages on.
+
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
+use embedded_storage_async::nor_flash::{ErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+
+use crate::otp::ROM_API_TREE;
+use crate::peripherals::FLEXSPI;
 
 /// Enable flash cache so we can execute out of flash faster
 /// SAFETY: Must be called after clock is initialized or else it will hang
@@ -31,3 +48,617 @@ pub(crate) unsafe fn init() {
         cortex_m::asm::isb();
     })
 }
+
+/// Start of the memory-mapped FlexSPI window
+const FLEXSPI_AHB_BASE: usize = 0x0800_0000;
+
+/// Size of the memory-mapped FlexSPI window, shared by the devices of all ports
+const FLEXSPI_AHB_SIZE: usize = 0x0800_0000;
+
+/// Location of the boot FCB in flash
+const FCB_OFFSET: usize = 0x400;
+
+/// FlexSPI instance number used by the ROM driver
+const FLEXSPI_INSTANCE: u32 = 0;
+
+/// Largest page size the write buffer can hold
+const MAX_PAGE_SIZE: usize = 512;
+
+/// Erase granularity exposed through [`NorFlash::ERASE_SIZE`]
+pub const SECTOR_SIZE: usize = 4096;
+
+// Word offsets into `flexspi_nor_config_t`
+const FCB_WORDS: usize = 128;
+const FCB_TAG: usize = 0;
+// Followed by the A2, B1 and B2 sizes
+const FCB_FLASH_A1_SIZE: usize = 0x50 / 4;
+const FCB_PAGE_SIZE: usize = 0x1c0 / 4;
+const FCB_SECTOR_SIZE: usize = 0x1c4 / 4;
+const FCB_BLOCK_SIZE: usize = 0x1d0 / 4;
+
+/// "FCFB", little-endian
+const FCB_TAG_VALUE: u32 = 0x4246_4346;
+
+/// FlexSPI NOR driver of the RT600 boot ROM, `flexspi_nor_driver_interface_t` in the SDK
+/// `fsl_iap` driver
+///
+/// UM11147 ROM API chapter, FlexSPI NOR flash driver API table, ROM API version 1.
+#[repr(C)]
+pub(crate) struct RomFlexspiNorDriver {
+    #[allow(dead_code)]
+    version: u32,
+    init: unsafe extern "C" fn(instance: u32, config: *mut NorConfig) -> u32,
+    page_program: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, dst: u32, src: *const u32) -> u32,
+    #[allow(dead_code)]
+    erase_all: unsafe extern "C" fn(instance: u32, config: *mut NorConfig) -> u32,
+    erase: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, start: u32, len: u32) -> u32,
+    #[allow(dead_code)]
+    erase_sector: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, addr: u32) -> u32,
+    #[allow(dead_code)]
+    erase_block: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, addr: u32) -> u32,
+    #[allow(dead_code)]
+    get_config: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, option: *const u32) -> u32,
+    #[allow(dead_code)]
+    read: unsafe extern "C" fn(instance: u32, config: *mut NorConfig, dst: *mut u32, start: u32, len: u32) -> u32,
+    #[allow(dead_code)]
+    xfer: unsafe extern "C" fn(instance: u32, xfer: *mut ()) -> u32,
+    #[allow(dead_code)]
+    update_lut: unsafe extern "C" fn(instance: u32, seq_index: u32, lut: *const u32, count: u32) -> u32,
+    #[allow(dead_code)]
+    set_clock_source: unsafe extern "C" fn(clock_src: u32) -> u32,
+    #[allow(dead_code)]
+    config_clock: unsafe extern "C" fn(instance: u32, freq_option: u32, sample_clk_mode: u32),
+}
+
+fn rom_nor() -> &'static RomFlexspiNorDriver {
+    // SAFETY: the ROM API tree lives in boot ROM and is always mapped and valid
+    unsafe { &*(*ROM_API_TREE).flexspi_nor_driver }
+}
+
+/// ROM `flexspi_nor_config_t`, kept opaque apart from the geometry fields
+#[derive(Clone)]
+#[repr(C, align(4))]
+pub(crate) struct NorConfig([u32; FCB_WORDS]);
+
+impl NorConfig {
+    /// Copy of the FCB the device booted from
+    fn boot() -> Result<Self> {
+        let mut config = NorConfig([0; FCB_WORDS]);
+
+        // SAFETY: the boot FCB is always readable through the XIP window
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                (FLEXSPI_AHB_BASE + FCB_OFFSET) as *const u32,
+                config.0.as_mut_ptr(),
+                FCB_WORDS,
+            )
+        };
+
+        if config.0[FCB_TAG] != FCB_TAG_VALUE {
+            return Err(Error::InvalidConfig);
+        }
+
+        Ok(config)
+    }
+
+    /// Size in bytes of the device on `port`, 0 if there is none
+    fn port_size(&self, port: FlashPort) -> u32 {
+        self.0[FCB_FLASH_A1_SIZE + port as usize]
+    }
+
+    /// Flash address of the first byte of `port`, the ports follow each other in the order A1, A2,
+    /// B1, B2 in both the command and the memory-mapped address space
+    fn port_start(&self, port: FlashPort) -> u32 {
+        self.0[FCB_FLASH_A1_SIZE..FCB_FLASH_A1_SIZE + port as usize]
+            .iter()
+            .sum()
+    }
+
+    /// Initialize the controller through the ROM driver
+    fn rom_init(&mut self) -> Result<()> {
+        // SAFETY: ROM routine, runs from ROM with interrupts masked while XIP is unavailable
+        critical_section::with(|_| check(unsafe { (rom_nor().init)(FLEXSPI_INSTANCE, self) }))
+    }
+}
+
+/// Flash error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// No valid flash configuration block found
+    InvalidConfig,
+
+    /// Page or sector size not supported by this driver
+    UnsupportedGeometry,
+
+    /// Offset or length not aligned to the erase size
+    NotAligned,
+
+    /// Access outside of the storage region
+    OutOfBounds,
+
+    /// ROM driver reported an error
+    Rom(u32),
+
+    /// No flash device configured on the FlexSPI port
+    NoDevice,
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn check(status: u32) -> Result<()> {
+    match status {
+        0 => Ok(()),
+        _ => Err(Error::Rom(status)),
+    }
+}
+
+impl NorFlashError for Error {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            Error::NotAligned => NorFlashErrorKind::NotAligned,
+            Error::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            _ => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+/// Geometry of the attached NOR flash and of the storage region
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FlashGeometry {
+    /// Program page size in bytes
+    pub page_size: usize,
+    /// Smallest erasable sector in bytes
+    pub sector_size: usize,
+    /// Erase block size in bytes
+    pub block_size: usize,
+    /// Size of the whole flash device in bytes
+    pub flash_size: usize,
+    /// Size of the storage region in bytes
+    pub size: usize,
+}
+
+/// Drop stale cache lines and prefetched data for the flash window
+fn invalidate_cache() {
+    // SAFETY: only triggers a cache invalidation
+    let cache64 = unsafe { crate::pac::Cache64::steal() };
+
+    cache64
+        .ccr()
+        .modify(|_, w| w.invw0().invw0().invw1().invw1().go().init_cmd());
+    while cache64.ccr().read().go().bit_is_set() {}
+
+    cortex_m::asm::dsb();
+    cortex_m::asm::isb();
+}
+
+/// FlexSPI port a flash device is attached to
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FlashPort {
+    /// Port A, chip select 1
+    A1,
+    /// Port A, chip select 2
+    A2,
+    /// Port B, chip select 1
+    B1,
+    /// Port B, chip select 2
+    B2,
+}
+
+/// FlexSPI controller with NOR flash devices on several ports
+///
+/// All devices share the command set (LUT) of the boot FCB, the FlexSPI has a single LUT for
+/// all ports and the ROM driver programs it from one configuration, so only the size of each
+/// device can differ. The pins of the added ports must be routed to the FlexSPI by the
+/// application.
+///
+/// Storages created on different devices with [`FlashStorageAsync::new_on_device`] can be used
+/// concurrently. Every ROM program or erase call runs inside a critical section, so erasing one
+/// device while reading the other is serialized one page or sector at a time.
+pub struct FlexspiNor<'d> {
+    config: NorConfig,
+    _flexspi: PeripheralRef<'d, FLEXSPI>,
+}
+
+impl<'d> FlexspiNor<'d> {
+    /// Reconfigure the controller for the boot device plus the devices in `ports`, given as port
+    /// and size in bytes.
+    ///
+    /// Only ports without a device in the boot FCB can be added, changing the size of the boot
+    /// device would move the running firmware. Returns [`Error::InvalidConfig`] for those and
+    /// [`Error::OutOfBounds`] if the devices don't fit in the memory-mapped window.
+    pub fn new(flexspi: impl Peripheral<P = FLEXSPI> + 'd, ports: &[(FlashPort, u32)]) -> Result<Self> {
+        into_ref!(flexspi);
+
+        let mut config = NorConfig::boot()?;
+
+        for &(port, size) in ports {
+            if config.port_size(port) != 0 {
+                return Err(Error::InvalidConfig);
+            }
+            config.0[FCB_FLASH_A1_SIZE + port as usize] = size;
+        }
+
+        let total: u64 = config.0[FCB_FLASH_A1_SIZE..FCB_FLASH_A1_SIZE + 4]
+            .iter()
+            .map(|&size| u64::from(size))
+            .sum();
+        if total > FLEXSPI_AHB_SIZE as u64 {
+            return Err(Error::OutOfBounds);
+        }
+
+        config.rom_init()?;
+
+        Ok(Self {
+            config,
+            _flexspi: flexspi,
+        })
+    }
+
+    /// Device attached to `port`, [`Error::NoDevice`] if there is none
+    pub fn device(&self, port: FlashPort) -> Result<FlexspiDevice<'_>> {
+        let size = self.config.port_size(port);
+        if size == 0 {
+            return Err(Error::NoDevice);
+        }
+
+        Ok(FlexspiDevice {
+            config: &self.config,
+            port,
+            start: self.config.port_start(port),
+            size,
+        })
+    }
+}
+
+/// Flash device on one port of a [`FlexspiNor`]
+#[derive(Clone, Copy)]
+pub struct FlexspiDevice<'d> {
+    config: &'d NorConfig,
+    port: FlashPort,
+    /// Flash address of the first byte of the device
+    start: u32,
+    size: u32,
+}
+
+impl FlexspiDevice<'_> {
+    /// Port the device is attached to
+    pub fn port(&self) -> FlashPort {
+        self.port
+    }
+
+    /// Size of the device in bytes
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Copy `buf.len()` bytes at `offset` in the device from the memory-mapped window.
+    ///
+    /// The flash cache is invalidated first, so data programmed by any storage before the call
+    /// is seen. Writes still buffered by a [`FlashStorageAsync`] are not, flush it first.
+    pub fn read_mapped(&self, offset: u32, buf: &mut [u8]) -> Result<()> {
+        if offset as usize + buf.len() > self.size as usize {
+            return Err(Error::OutOfBounds);
+        }
+
+        invalidate_cache();
+
+        // SAFETY: the range was checked against the device, mapped through the XIP window
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                (FLEXSPI_AHB_BASE + (self.start + offset) as usize) as *const u8,
+                buf.as_mut_ptr(),
+                buf.len(),
+            )
+        };
+
+        Ok(())
+    }
+}
+
+#[repr(C, align(4))]
+struct PageBuffer([u8; MAX_PAGE_SIZE]);
+
+/// Page currently being coalesced in the write buffer
+struct PendingPage {
+    /// Flash offset of the page
+    addr: u32,
+    /// Bytes of the page written so far
+    dirty: Range<usize>,
+}
+
+/// Async NOR flash storage on a region of the FlexSPI flash
+pub struct FlashStorageAsync<'d> {
+    config: NorConfig,
+    geometry: FlashGeometry,
+    base: u32,
+    page: PageBuffer,
+    pending: Option<PendingPage>,
+    _lifetime: PhantomData<&'d ()>,
+}
+
+impl<'d> FlashStorageAsync<'d> {
+    /// Create a storage driver for `len` bytes of flash starting at flash offset `base`.
+    ///
+    /// The region must be sector aligned and must not overlap the running firmware.
+    pub fn new(_flexspi: impl Peripheral<P = FLEXSPI> + 'd, base: u32, len: u32) -> Result<Self> {
+        into_ref!(_flexspi);
+
+        let mut config = NorConfig::boot()?;
+        let flash_size = config.port_size(FlashPort::A1);
+        Self::check_region(&config, flash_size, base, len)?;
+        config.rom_init()?;
+
+        Ok(Self::new_inner(config, flash_size, base, len))
+    }
+
+    /// Create a storage driver for `len` bytes of `device` starting at device offset `base`.
+    ///
+    /// The region must be sector aligned and must not overlap the running firmware.
+    pub fn new_on_device(device: FlexspiDevice<'d>, base: u32, len: u32) -> Result<Self> {
+        let config = device.config.clone();
+        Self::check_region(&config, device.size, base, len)?;
+
+        Ok(Self::new_inner(config, device.size, device.start + base, len))
+    }
+
+    /// Validate the geometry of `config` and a region of `len` bytes at offset `base` in a device
+    /// of `flash_size` bytes
+    fn check_region(config: &NorConfig, flash_size: u32, base: u32, len: u32) -> Result<()> {
+        let page_size = config.0[FCB_PAGE_SIZE] as usize;
+        let sector_size = config.0[FCB_SECTOR_SIZE] as usize;
+
+        if page_size == 0 || page_size > MAX_PAGE_SIZE || sector_size == 0 || SECTOR_SIZE % sector_size != 0 {
+            return Err(Error::UnsupportedGeometry);
+        }
+
+        if base as usize % SECTOR_SIZE != 0 || len as usize % SECTOR_SIZE != 0 {
+            return Err(Error::NotAligned);
+        }
+
+        if base as usize + len as usize > flash_size as usize {
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(())
+    }
+
+    /// `base` is a flash address, covering the ports before the device
+    fn new_inner(config: NorConfig, flash_size: u32, base: u32, len: u32) -> Self {
+        let geometry = FlashGeometry {
+            page_size: config.0[FCB_PAGE_SIZE] as usize,
+            sector_size: config.0[FCB_SECTOR_SIZE] as usize,
+            block_size: config.0[FCB_BLOCK_SIZE] as usize,
+            flash_size: flash_size as usize,
+            size: len as usize,
+        };
+
+        Self {
+            config,
+            geometry,
+            base,
+            page: PageBuffer([0xff; MAX_PAGE_SIZE]),
+            pending: None,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Page, sector and region sizes.
+    ///
+    /// The byte size of the region alone is available through [`ReadNorFlash::capacity`].
+    pub fn capacity(&self) -> FlashGeometry {
+        self.geometry
+    }
+
+    fn check_bounds(&self, offset: u32, len: usize) -> Result<()> {
+        if offset as usize + len > self.geometry.size {
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(())
+    }
+
+    /// Program the buffered page, if any
+    fn program_pending(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let src = self.page.0.as_ptr() as *const u32;
+
+        // SAFETY: ROM routine, `src` holds a full word-aligned page
+        let res = critical_section::with(|_| {
+            check(unsafe { (rom_nor().page_program)(FLEXSPI_INSTANCE, &mut self.config, pending.addr, src) })
+        });
+
+        self.page.0.fill(0xff);
+        invalidate_cache();
+
+        res
+    }
+
+    /// Program the partially filled page buffer, if any
+    pub async fn flush(&mut self) -> Result<()> {
+        self.program_pending()
+    }
+
+    async fn read_inner(&mut self, offset: u32, bytes: &mut [u8]) -> Result<()> {
+        self.check_bounds(offset, bytes.len())?;
+
+        // Make buffered writes visible to the read
+        if let Some(pending) = &self.pending {
+            let start = self.base + offset;
+            let end = start + bytes.len() as u32;
+            if pending.addr < end && start < pending.addr + self.geometry.page_size as u32 {
+                self.program_pending()?;
+            }
+        }
+
+        // SAFETY: the range was checked against the flash region, mapped through the XIP window
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                (FLEXSPI_AHB_BASE + (self.base + offset) as usize) as *const u8,
+                bytes.as_mut_ptr(),
+                bytes.len(),
+            )
+        };
+
+        Ok(())
+    }
+
+    async fn erase_inner(&mut self, from: u32, to: u32) -> Result<()> {
+        if from > to {
+            return Err(Error::OutOfBounds);
+        }
+        if from as usize % SECTOR_SIZE != 0 || to as usize % SECTOR_SIZE != 0 {
+            return Err(Error::NotAligned);
+        }
+        self.check_bounds(from, (to - from) as usize)?;
+
+        // Keep program and erase in the order they were issued
+        self.program_pending()?;
+
+        for sector in (from..to).step_by(SECTOR_SIZE) {
+            let addr = self.base + sector;
+
+            // SAFETY: ROM routine, range checked against the flash region
+            let res = critical_section::with(|_| {
+                check(unsafe { (rom_nor().erase)(FLEXSPI_INSTANCE, &mut self.config, addr, SECTOR_SIZE as u32) })
+            });
+            invalidate_cache();
+            res?;
+
+            // Erasing takes a while, let other tasks run between sectors
+            embassy_futures::yield_now().await;
+        }
+
+        Ok(())
+    }
+
+    async fn write_inner(&mut self, offset: u32, mut bytes: &[u8]) -> Result<()> {
+        self.check_bounds(offset, bytes.len())?;
+
+        let page_size = self.geometry.page_size;
+        let mut addr = self.base + offset;
+
+        while !bytes.is_empty() {
+            let page_addr = addr - addr % page_size as u32;
+            let start = (addr - page_addr) as usize;
+            let n = bytes.len().min(page_size - start);
+
+            if self.pending.as_ref().is_some_and(|p| p.addr != page_addr) {
+                self.program_pending()?;
+                embassy_futures::yield_now().await;
+            }
+
+            self.page.0[start..start + n].copy_from_slice(&bytes[..n]);
+
+            let pending = self.pending.get_or_insert(PendingPage {
+                addr: page_addr,
+                dirty: start..start + n,
+            });
+            pending.dirty = pending.dirty.start.min(start)..pending.dirty.end.max(start + n);
+
+            if pending.dirty == (0..page_size) {
+                self.program_pending()?;
+                embassy_futures::yield_now().await;
+            }
+
+            addr += n as u32;
+            bytes = &bytes[n..];
+        }
+
+        Ok(())
+    }
+
+    /// Write `data` at `offset`, erasing and restoring the affected sectors where needed.
+    ///
+    /// NOR flash programming can only clear bits. For every sector touched by the write, the current
+    /// contents are compared with `data`; if any bit would have to go from 0 to 1, the sector is read
+    /// into a [`SECTOR_SIZE`] buffer, merged with `data`, erased and programmed again page by page.
+    /// Sectors that only need bits cleared are programmed directly.
+    ///
+    /// This is not atomic: a reset or power loss between the erase and the end of reprogramming
+    /// loses the old contents of the sector, including bytes outside of `data`. It also wears the
+    /// sector on every rewrite. Use a wear leveling, power-loss safe layer such as
+    /// `sequential-storage` on top of [`NorFlash`] for data that must survive this.
+    pub async fn smart_write(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        self.check_bounds(offset, data.len())?;
+
+        let mut sector = [0xffu8; SECTOR_SIZE];
+        let mut addr = offset;
+        let mut data = data;
+
+        while !data.is_empty() {
+            let sector_addr = addr - addr % SECTOR_SIZE as u32;
+            let start = (addr - sector_addr) as usize;
+            let n = data.len().min(SECTOR_SIZE - start);
+
+            self.read_inner(sector_addr, &mut sector).await?;
+
+            let needs_erase = sector[start..start + n]
+                .iter()
+                .zip(&data[..n])
+                .any(|(old, new)| old & new != *new);
+
+            if needs_erase {
+                sector[start..start + n].copy_from_slice(&data[..n]);
+                self.erase_inner(sector_addr, sector_addr + SECTOR_SIZE as u32).await?;
+
+                // Erased pages are left alone
+                let page_size = self.geometry.page_size;
+                for (i, page) in sector.chunks(page_size).enumerate() {
+                    if page.iter().any(|b| *b != 0xff) {
+                        self.write_inner(sector_addr + (i * page_size) as u32, page).await?;
+                    }
+                }
+            } else {
+                self.write_inner(addr, &data[..n]).await?;
+            }
+
+            addr += n as u32;
+            data = &data[n..];
+        }
+
+        self.program_pending()
+    }
+}
+
+impl Drop for FlashStorageAsync<'_> {
+    fn drop(&mut self) {
+        // Don't lose coalesced data, there is no way to report the error here
+        let _ = self.program_pending();
+    }
+}
+
+impl ErrorType for FlashStorageAsync<'_> {
+    type Error = Error;
+}
+
+impl ReadNorFlash for FlashStorageAsync<'_> {
+    const READ_SIZE: usize = 1;
+
+    async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<()> {
+        self.read_inner(offset, bytes).await
+    }
+
+    fn capacity(&self) -> usize {
+        self.geometry.size
+    }
+}
+
+impl NorFlash for FlashStorageAsync<'_> {
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    async fn erase(&mut self, from: u32, to: u32) -> Result<()> {
+        self.erase_inner(from, to).await
+    }
+
+    async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<()> {
+        self.write_inner(offset, bytes).await
+    }
+}
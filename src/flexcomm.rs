@@ -1,9 +1,55 @@
 //! implements flexcomm interface wrapper for easier usage across modules
+//!
+//! # Ownership
+//!
+//! A flexcomm runs one function at a time (USART, SPI, I2C or I2S). Every driver
+//! constructor takes the flexcomm peripheral singleton and keeps its lifetime, so
+//! configuring the same flexcomm for two drivers at once is rejected at compile time:
+//!
+//! ```compile_fail,E0382
+//! use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+//! use embassy_imxrt::uart::{self, Uart};
+//! use embassy_imxrt::Peripherals;
+//!
+//! fn i2c_and_uart(p: Peripherals) {
+//!     let i2c = I2cMaster::new_blocking(p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, Speed::Standard).unwrap();
+//!     // error: use of moved value: `p.FLEXCOMM2`
+//!     let uart = Uart::new_blocking(p.FLEXCOMM2, p.PIO0_15, p.PIO0_16, uart::Config::default()).unwrap();
+//!     drop((i2c, uart));
+//! }
+//! ```
+//!
+//! The same holds for reborrowed singletons, the borrow lasts as long as the driver:
+//!
+//! ```compile_fail,E0499
+//! use embassy_imxrt::i2c::master::{I2cMaster, Speed};
+//! use embassy_imxrt::uart::{self, Uart};
+//! use embassy_imxrt::Peripherals;
+//!
+//! fn i2c_and_uart(mut p: Peripherals) {
+//!     let i2c = I2cMaster::new_blocking(&mut p.FLEXCOMM2, p.PIO0_18, p.PIO0_17, Speed::Standard).unwrap();
+//!     // error: cannot borrow `p.FLEXCOMM2` as mutable more than once at a time
+//!     let uart = Uart::new_blocking(&mut p.FLEXCOMM2, p.PIO0_15, p.PIO0_16, uart::Config::default()).unwrap();
+//!     drop((i2c, uart));
+//! }
+//! ```
+//!
+//! To use a flexcomm for different functions over time, reborrow it for a short-lived
+//! driver and drop that driver before creating the next one:
+//!
+//! ```ignore
+//! {
+//!     let mut i2c = I2cMaster::new_blocking(&mut p.FLEXCOMM2, &mut p.PIO0_18, &mut p.PIO0_17, Speed::Standard)?;
+//!     i2c.blocking_write(ADDR, &[0x01])?;
+//! } // the flexcomm borrow ends here
+//!
+//! let uart = Uart::new_blocking(&mut p.FLEXCOMM2, p.PIO0_15, p.PIO0_16, Default::default())?;
+//! ```
 
 use embassy_hal_internal::Peripheral;
 use paste::paste;
 
-use crate::clocks::{enable_and_reset, SysconPeripheral};
+use crate::clocks::{clock_guard, get_flexcomm_clock_hz, ClockGuard, SysconPeripheral};
 use crate::pac;
 use crate::peripherals::{
     FLEXCOMM0, FLEXCOMM1, FLEXCOMM14, FLEXCOMM15, FLEXCOMM2, FLEXCOMM3, FLEXCOMM4, FLEXCOMM5, FLEXCOMM6, FLEXCOMM7,
@@ -40,6 +86,9 @@ pub enum Clock {
     None,
 }
 
+/// Number of entries of each of the TX and RX FIFOs of a flexcomm in USART or SPI mode
+pub const FIFO_DEPTH: usize = 8;
+
 /// do not allow implementation of trait outside this mod
 mod sealed {
     /// trait does not get re-exported outside flexcomm mod, allowing us to safely expose only desired APIs
@@ -53,8 +102,12 @@ pub(crate) trait FlexcommLowLevel:
     // fetch the flexcomm register block for direct manipulation
     fn reg() -> &'static pac::flexcomm0::RegisterBlock;
 
-    // set the clock select for this flexcomm instance and remove from reset
-    fn enable(clk: Clock);
+    // set the clock select for this flexcomm instance and remove from reset, until the returned
+    // guard and all of its clones are dropped
+    fn enable(clk: Clock) -> ClockGuard;
+
+    // functional clock frequency currently selected for this flexcomm instance
+    fn clock_hz() -> u32;
 }
 
 macro_rules! impl_flexcomm {
@@ -72,7 +125,7 @@ macro_rules! impl_flexcomm {
 			}
 		    }
 
-		    fn enable(clk: Clock) {
+		    fn enable(clk: Clock) -> ClockGuard {
 			// SAFETY: safe from single executor
 			let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -102,7 +155,18 @@ macro_rules! impl_flexcomm {
 				   // SAFETY: unsafe only used for .bits() call
 				   unsafe { w.mult().bits(0) });
 
-			enable_and_reset::<[<FLEXCOMM $idx>]>();
+			#[cfg(feature = "postmortem")]
+			crate::postmortem::register::<[<FLEXCOMM $idx>]>(
+			    crate::postmortem::Kind::Flexcomm,
+			    $idx,
+			    crate::pac::[<Flexcomm $idx>]::ptr() as usize,
+			);
+
+			clock_guard::<[<FLEXCOMM $idx>]>()
+		    }
+
+		    fn clock_hz() -> u32 {
+			get_flexcomm_clock_hz($idx)
 		    }
 		}
 	    }
@@ -123,7 +187,7 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM14 {
         unsafe { &*crate::pac::Flexcomm14::ptr() }
     }
 
-    fn enable(clk: Clock) {
+    fn enable(clk: Clock) -> ClockGuard {
         // SAFETY: safe from single executor
         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -150,7 +214,18 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM14 {
                 // SAFETY: unsafe only used for .bits() call
                 unsafe { w.mult().bits(0) });
 
-        enable_and_reset::<FLEXCOMM14>();
+        #[cfg(feature = "postmortem")]
+        crate::postmortem::register::<FLEXCOMM14>(
+            crate::postmortem::Kind::Flexcomm,
+            14,
+            crate::pac::Flexcomm14::ptr() as usize,
+        );
+
+        clock_guard::<FLEXCOMM14>()
+    }
+
+    fn clock_hz() -> u32 {
+        get_flexcomm_clock_hz(14)
     }
 }
 
@@ -164,7 +239,7 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
         unsafe { &*crate::pac::Flexcomm15::ptr() }
     }
 
-    fn enable(clk: Clock) {
+    fn enable(clk: Clock) -> ClockGuard {
         // SAFETY: safe from single executor
         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -191,7 +266,18 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
                 // SAFETY: unsafe only used for .bits() call
                 unsafe { w.mult().bits(0) });
 
-        enable_and_reset::<FLEXCOMM15>();
+        #[cfg(feature = "postmortem")]
+        crate::postmortem::register::<FLEXCOMM15>(
+            crate::postmortem::Kind::Flexcomm,
+            15,
+            crate::pac::Flexcomm15::ptr() as usize,
+        );
+
+        clock_guard::<FLEXCOMM15>()
+    }
+
+    fn clock_hz() -> u32 {
+        get_flexcomm_clock_hz(15)
     }
 }
 
@@ -199,16 +285,18 @@ macro_rules! declare_into_mode {
     ($mode:ident) => {
         paste! {
             /// Sealed Mode trait
-            trait [<SealedInto $mode:camel>]: FlexcommLowLevel {}
-
-            /// Select mode of operation
-            #[allow(private_bounds)]
-            pub trait [<Into $mode:camel>]: [<SealedInto $mode:camel>] {
+            pub(crate) trait [<SealedInto $mode:camel>]: FlexcommLowLevel {
                 /// Set mode of operation
+                ///
+                /// Only drivers owning the flexcomm may call this, so it is not reachable from outside the crate.
                 fn [<into_ $mode>]() {
                     Self::reg().pselid().write(|w| w.persel().[<$mode>]());
                 }
             }
+
+            /// Flexcomm instances that support this mode of operation
+            #[allow(private_bounds)]
+            pub trait [<Into $mode:camel>]: [<SealedInto $mode:camel>] {}
         }
     };
 }
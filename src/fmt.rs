@@ -3,6 +3,9 @@
 
 use core::fmt::{Debug, Display, LowerHex};
 
+#[cfg(all(feature = "defmt", feature = "log"))]
+compile_error!("You may not enable both `defmt` and `log` features.");
+
 #[collapse_debuginfo(yes)]
 macro_rules! assert {
     ($($x:tt)*) => {
@@ -115,9 +118,11 @@ macro_rules! panic {
 macro_rules! trace {
     ($s:literal $(, $x:expr)* $(,)?) => {
         {
+            #[cfg(feature = "log")]
+            ::log::trace!($s $(, $x)*);
             #[cfg(feature = "defmt")]
             ::defmt::trace!($s $(, $x)*);
-            #[cfg(not(feature = "defmt"))]
+            #[cfg(not(any(feature = "log", feature = "defmt")))]
             let _ = ($( & $x ),*);
         }
     };
@@ -127,9 +132,11 @@ macro_rules! trace {
 macro_rules! debug {
     ($s:literal $(, $x:expr)* $(,)?) => {
         {
+            #[cfg(feature = "log")]
+            ::log::debug!($s $(, $x)*);
             #[cfg(feature = "defmt")]
             ::defmt::debug!($s $(, $x)*);
-            #[cfg(not(feature = "defmt"))]
+            #[cfg(not(any(feature = "log", feature = "defmt")))]
             let _ = ($( & $x ),*);
         }
     };
@@ -139,9 +146,11 @@ macro_rules! debug {
 macro_rules! info {
     ($s:literal $(, $x:expr)* $(,)?) => {
         {
+            #[cfg(feature = "log")]
+            ::log::info!($s $(, $x)*);
             #[cfg(feature = "defmt")]
             ::defmt::info!($s $(, $x)*);
-            #[cfg(not(feature = "defmt"))]
+            #[cfg(not(any(feature = "log", feature = "defmt")))]
             let _ = ($( & $x ),*);
         }
     };
@@ -151,9 +160,11 @@ macro_rules! info {
 macro_rules! warn {
     ($s:literal $(, $x:expr)* $(,)?) => {
         {
+            #[cfg(feature = "log")]
+            ::log::warn!($s $(, $x)*);
             #[cfg(feature = "defmt")]
             ::defmt::warn!($s $(, $x)*);
-            #[cfg(not(feature = "defmt"))]
+            #[cfg(not(any(feature = "log", feature = "defmt")))]
             let _ = ($( & $x ),*);
         }
     };
@@ -163,9 +174,11 @@ macro_rules! warn {
 macro_rules! error {
     ($s:literal $(, $x:expr)* $(,)?) => {
         {
+            #[cfg(feature = "log")]
+            ::log::error!($s $(, $x)*);
             #[cfg(feature = "defmt")]
             ::defmt::error!($s $(, $x)*);
-            #[cfg(not(feature = "defmt"))]
+            #[cfg(not(any(feature = "log", feature = "defmt")))]
             let _ = ($( & $x ),*);
         }
     };
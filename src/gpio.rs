@@ -6,13 +6,17 @@ use core::marker::PhantomData;
 use core::pin::Pin as FuturePin;
 use core::task::{Context, Poll};
 
+#[cfg(feature = "time")]
+use embassy_futures::select::{select, Either};
 use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_sync::waitqueue::AtomicWaker;
+#[cfg(feature = "time")]
+use embassy_time::{Duration, Timer};
 use sealed::Sealed;
 
 use crate::clocks::enable_and_reset;
-use crate::iopctl::IopctlPin;
 pub use crate::iopctl::{AnyPin, DriveMode, DriveStrength, Function, Inverter, Pull, SlewRate};
+use crate::iopctl::{IopctlPin, PinConfig};
 use crate::{interrupt, into_ref, peripherals, Peripheral, PeripheralRef};
 
 // This should be unique per IMXRT package
@@ -161,6 +165,8 @@ impl Sense for SenseDisabled {}
 /// output mode.
 pub struct Flex<'d, S: Sense> {
     pin: PeripheralRef<'d, AnyPin>,
+    // Pad configuration saved by `set_as_peripheral`, written back by `restore_gpio`
+    gpio_config: Option<PinConfig>,
     _sense_mode: PhantomData<S>,
 }
 
@@ -170,6 +176,8 @@ impl<S: Sense> Flex<'_, S> {
     /// The pin level will be whatever was set before (or low by default). If you want it to begin
     /// at a specific level, call `set_high`/`set_low` on the pin first.
     pub fn set_as_output(&mut self, mode: DriveMode, strength: DriveStrength, slew_rate: SlewRate) {
+        self.restore_gpio();
+
         self.pin
             .set_pull(Pull::None)
             .set_drive_mode(mode)
@@ -229,6 +237,30 @@ impl<S: Sense> Flex<'_, S> {
             // There is not currently a "safe" method for setting a single-bit.
             unsafe { w.notp().bits(1 << self.pin.pin()) });
     }
+
+    /// Hands the pin over to a peripheral function without releasing it.
+    ///
+    /// The current GPIO pad configuration is saved and the input buffer is enabled so that the
+    /// peripheral can sample the pin. The direction and output level registers are left untouched.
+    /// Call [`Flex::restore_gpio`] to switch back to GPIO, e.g. to park a UART TX pin while powered down.
+    pub fn set_as_peripheral(&mut self, func: Function) {
+        if self.gpio_config.is_none() {
+            self.gpio_config = Some(self.pin.get_config());
+        }
+
+        self.pin.set_function(func).enable_input_buffer();
+    }
+
+    /// Switches the pin back to GPIO after [`Flex::set_as_peripheral`].
+    ///
+    /// The pull, drive and inverter settings from before the switch are re-applied, and since the
+    /// direction and output level were never changed the pin resumes as the input or output it was.
+    /// Does nothing if the pin is already a GPIO.
+    pub fn restore_gpio(&mut self) {
+        if let Some(config) = self.gpio_config.take() {
+            self.pin.apply_config(&config);
+        }
+    }
 }
 
 impl<S: Sense> Drop for Flex<'_, S> {
@@ -249,12 +281,15 @@ impl<'d> Flex<'d, SenseEnabled> {
 
         Self {
             pin: pin.map_into(),
+            gpio_config: None,
             _sense_mode: PhantomData::<SenseEnabled>,
         }
     }
 
     /// Converts pin to input pin
     pub fn set_as_input(&mut self, pull: Pull, inverter: Inverter) {
+        self.restore_gpio();
+
         self.pin.set_pull(pull).set_input_inverter(inverter);
 
         self.pin.block().dirclr(self.pin.port()).write(|w|
@@ -264,6 +299,20 @@ impl<'d> Flex<'d, SenseEnabled> {
                     unsafe { w.dirclrp().bits(1 << self.pin.pin()) });
     }
 
+    /// Changes the input inverter of the pin without touching its other settings.
+    ///
+    /// Levels read from the pin, and the edges waited for, are those seen behind the inverter. Changing
+    /// it flips the level the pin reports, which an edge wait in progress will see as an edge.
+    pub fn set_inverter(&mut self, inverter: Inverter) {
+        self.pin.set_input_inverter(inverter);
+    }
+
+    /// Current input inverter setting
+    #[must_use]
+    pub fn get_inverter(&self) -> Inverter {
+        self.pin.input_inverter()
+    }
+
     /// Converts pin to special function pin
     /// # Safety
     /// Unsafe to require justifying change from default to a special function
@@ -315,12 +364,83 @@ impl<'d> Flex<'d, SenseEnabled> {
     }
 
     /// Wait for the pin to undergo any transition, i.e low to high OR high to low.
+    ///
+    /// A transition while the interrupt is being armed also completes the wait. A pulse that is
+    /// over before the interrupt is armed is missed.
     #[inline]
     pub async fn wait_for_any_edge(&mut self) {
-        if self.is_high() {
-            InputFuture::new(self.pin.reborrow(), InterruptType::Edge, Level::Low).await;
-        } else {
-            InputFuture::new(self.pin.reborrow(), InterruptType::Edge, Level::High).await;
+        InputFuture::new_any_edge(self.pin.reborrow()).await;
+    }
+
+    /// Wait until the pin is high and stays high for `stable_period`.
+    ///
+    /// Any high to low transition during the period restarts the wait.
+    #[cfg(feature = "time")]
+    pub async fn wait_for_stable_high(&mut self, stable_period: Duration) {
+        self.wait_for_stable_level(Level::High, stable_period).await;
+    }
+
+    /// Wait until the pin is low and stays low for `stable_period`.
+    ///
+    /// Any low to high transition during the period restarts the wait.
+    #[cfg(feature = "time")]
+    pub async fn wait_for_stable_low(&mut self, stable_period: Duration) {
+        self.wait_for_stable_level(Level::Low, stable_period).await;
+    }
+
+    /// Wait for a transition from low to high after which the pin stays high for `stable_period`.
+    #[cfg(feature = "time")]
+    pub async fn wait_for_stable_rising_edge(&mut self, stable_period: Duration) {
+        self.wait_for_stable_edge(Level::High, stable_period).await;
+    }
+
+    /// Wait for a transition from high to low after which the pin stays low for `stable_period`.
+    #[cfg(feature = "time")]
+    pub async fn wait_for_stable_falling_edge(&mut self, stable_period: Duration) {
+        self.wait_for_stable_edge(Level::Low, stable_period).await;
+    }
+
+    #[cfg(feature = "time")]
+    async fn wait_for_stable_level(&mut self, level: Level, stable_period: Duration) {
+        loop {
+            InputFuture::new(self.pin.reborrow(), InterruptType::Level, level).await;
+
+            if self.hold_level(level, stable_period).await {
+                return;
+            }
+        }
+    }
+
+    #[cfg(feature = "time")]
+    async fn wait_for_stable_edge(&mut self, level: Level, stable_period: Duration) {
+        loop {
+            InputFuture::new(self.pin.reborrow(), InterruptType::Edge, level).await;
+
+            if self.hold_level(level, stable_period).await {
+                return;
+            }
+        }
+    }
+
+    /// Check that the pin stays at `level` for `stable_period`.
+    ///
+    /// Bounces are caught with an edge interrupt so short glitches are not missed.
+    #[cfg(feature = "time")]
+    async fn hold_level(&mut self, level: Level, stable_period: Duration) -> bool {
+        let other = match level {
+            Level::High => Level::Low,
+            Level::Low => Level::High,
+        };
+
+        match select(
+            Timer::after(stable_period),
+            InputFuture::new(self.pin.reborrow(), InterruptType::Edge, other),
+        )
+        .await
+        {
+            // An edge right before arming the interrupt is only visible in the level
+            Either::First(()) => self.get_level() == level,
+            Either::Second(()) => false,
         }
     }
 
@@ -347,6 +467,7 @@ impl<'d> Flex<'d, SenseDisabled> {
 
         Self {
             pin: pin.map_into(),
+            gpio_config: None,
             _sense_mode: PhantomData::<SenseDisabled>,
         }
     }
@@ -392,6 +513,17 @@ impl<'d> Input<'d> {
         self.pin.get_level()
     }
 
+    /// Changes the input inverter of the pin at runtime, see [`Flex::set_inverter`].
+    pub fn set_inverter(&mut self, inverter: Inverter) {
+        self.pin.set_inverter(inverter);
+    }
+
+    /// Current input inverter setting
+    #[must_use]
+    pub fn get_inverter(&self) -> Inverter {
+        self.pin.get_inverter()
+    }
+
     /// Wait until the pin is high. If it is already high, return immediately.
     #[inline]
     pub async fn wait_for_high(&mut self) {
@@ -421,6 +553,34 @@ impl<'d> Input<'d> {
     pub async fn wait_for_any_edge(&mut self) {
         self.pin.wait_for_any_edge().await;
     }
+
+    /// Wait until the pin is high and stays high for `stable_period`, debouncing switches.
+    #[cfg(feature = "time")]
+    #[inline]
+    pub async fn wait_for_stable_high(&mut self, stable_period: Duration) {
+        self.pin.wait_for_stable_high(stable_period).await;
+    }
+
+    /// Wait until the pin is low and stays low for `stable_period`, debouncing switches.
+    #[cfg(feature = "time")]
+    #[inline]
+    pub async fn wait_for_stable_low(&mut self, stable_period: Duration) {
+        self.pin.wait_for_stable_low(stable_period).await;
+    }
+
+    /// Wait for a rising edge followed by `stable_period` of high level, debouncing switches.
+    #[cfg(feature = "time")]
+    #[inline]
+    pub async fn wait_for_stable_rising_edge(&mut self, stable_period: Duration) {
+        self.pin.wait_for_stable_rising_edge(stable_period).await;
+    }
+
+    /// Wait for a falling edge followed by `stable_period` of low level, debouncing switches.
+    #[cfg(feature = "time")]
+    #[inline]
+    pub async fn wait_for_stable_falling_edge(&mut self, stable_period: Duration) {
+        self.pin.wait_for_stable_falling_edge(stable_period).await;
+    }
 }
 
 #[must_use = "futures do nothing unless you `.await` or poll them"]
@@ -455,6 +615,33 @@ impl<'d> InputFuture<'d> {
 
         Self { pin: pin.map_into() }
     }
+
+    /// Arm an edge interrupt for the transition away from the current level.
+    ///
+    /// The level is sampled again once the interrupt is armed. If it changed, the edge happened
+    /// before the interrupt could see it and the future completes on its first poll.
+    fn new_any_edge(pin: impl Peripheral<P = impl GpioPin> + 'd) -> Self {
+        into_ref!(pin);
+        let pin: PeripheralRef<'d, AnyPin> = pin.map_into();
+
+        let is_high = |pin: &AnyPin| pin.block().b(pin.port()).b_(pin.pin()).read() != 0;
+
+        let was_high = is_high(&pin);
+        let level = if was_high { Level::Low } else { Level::High };
+        let this = Self::new(pin, InterruptType::Edge, level);
+
+        if is_high(&this.pin) != was_high {
+            // Disarm as the interrupt handler would, the handler may modify INTENA too
+            critical_section::with(|_| {
+                this.pin
+                    .block()
+                    .intena(this.pin.port())
+                    .modify(|r, w| unsafe { w.int_en().bits(r.int_en().bits() & !(1 << this.pin.pin())) });
+            });
+        }
+
+        this
+    }
 }
 
 impl Future for InputFuture<'_> {
@@ -514,6 +701,25 @@ impl<'d> Output<'d> {
         Self { pin }
     }
 
+    /// New open-drain output pin with the given pull resistor
+    ///
+    /// [`Pull::Up`] enables the internal pull-up, so the line idles high without external parts.
+    /// The internal resistor is weak: rising edges are slow, which limits it to slow signaling,
+    /// and while the pin drives low a current of VDD over the pull-up flows through it. For
+    /// I2C-style buses use an external pull-up sized for the bus capacitance and speed, with
+    /// [`Pull::None`], so the leakage is set by that resistor and only paid once per bus.
+    ///
+    /// The pad is pseudo open-drain, see [`IopctlPin::set_drive_mode`], so the line must not be
+    /// pulled above the pin supply.
+    pub fn new_open_drain(pin: impl Peripheral<P = impl GpioPin> + 'd, initial_output: Level, pull: Pull) -> Self {
+        let mut pin = Flex::<SenseDisabled>::new(pin);
+        pin.set_level(initial_output);
+        pin.set_as_output(DriveMode::OpenDrain, DriveStrength::Normal, SlewRate::Standard);
+        pin.pin.set_pull(pull);
+
+        Self { pin }
+    }
+
     /// Set high
     pub fn set_high(&mut self) {
         self.pin.set_high();
@@ -547,6 +753,205 @@ impl<'d> Output<'d> {
     }
 }
 
+/// Output pin with the cheapest possible level changes, for bit-banging
+///
+/// The SET, CLR and NOT register addresses and the pin mask are resolved once when the pin is
+/// converted, so each level change is a single store. The pin stays owned by the wrapped
+/// [`Output`], get it back with [`FastOutput::into_output`].
+pub struct FastOutput<'d> {
+    output: Output<'d>,
+    set: *mut u32,
+    clr: *mut u32,
+    not: *mut u32,
+    mask: u32,
+}
+
+// SAFETY: the pointers only address the port registers of the owned pin, see `FastOutput::write`
+unsafe impl Send for FastOutput<'_> {}
+
+impl<'d> FastOutput<'d> {
+    /// Convert an output pin, its current level is kept
+    pub fn new(output: Output<'d>) -> Self {
+        let pin = &output.pin.pin;
+        let block = pin.block();
+        let port = pin.port();
+
+        Self {
+            set: block.set(port).as_ptr(),
+            clr: block.clr(port).as_ptr(),
+            not: block.not(port).as_ptr(),
+            mask: 1 << pin.pin(),
+            output,
+        }
+    }
+
+    /// Give back the output pin
+    pub fn into_output(self) -> Output<'d> {
+        self.output
+    }
+
+    #[inline(always)]
+    fn write(reg: *mut u32, mask: u32) {
+        // SAFETY: SET, CLR and NOT only act on the bits written as 1, so storing our own pin's
+        // bit cannot race with accesses to other pins of the port and needs no read-modify-write.
+        unsafe { core::ptr::write_volatile(reg, mask) }
+    }
+
+    /// Set high
+    #[inline(always)]
+    pub fn set_high(&mut self) {
+        Self::write(self.set, self.mask);
+    }
+
+    /// Set low
+    #[inline(always)]
+    pub fn set_low(&mut self) {
+        Self::write(self.clr, self.mask);
+    }
+
+    /// Toggle
+    #[inline(always)]
+    pub fn toggle(&mut self) {
+        Self::write(self.not, self.mask);
+    }
+
+    /// Set level
+    #[inline(always)]
+    pub fn set_level(&mut self, level: Level) {
+        match level {
+            Level::High => self.set_high(),
+            Level::Low => self.set_low(),
+        }
+    }
+
+    /// Is set high?
+    #[must_use]
+    pub fn is_set_high(&self) -> bool {
+        self.output.is_set_high()
+    }
+
+    /// Is set low?
+    #[must_use]
+    pub fn is_set_low(&self) -> bool {
+        self.output.is_set_low()
+    }
+}
+
+impl<'d> From<Output<'d>> for FastOutput<'d> {
+    fn from(output: Output<'d>) -> Self {
+        Self::new(output)
+    }
+}
+
+trait SealedMaskedPin {
+    fn any_pin(&self) -> &AnyPin;
+}
+
+/// GPIO pin driver that can be selected for masked access, see [`PortMasked`]
+#[allow(private_bounds)]
+pub trait MaskedPin: SealedMaskedPin {}
+
+impl<S: Sense> SealedMaskedPin for Flex<'_, S> {
+    fn any_pin(&self) -> &AnyPin {
+        &self.pin
+    }
+}
+impl<S: Sense> MaskedPin for Flex<'_, S> {}
+
+impl SealedMaskedPin for Input<'_> {
+    fn any_pin(&self) -> &AnyPin {
+        self.pin.any_pin()
+    }
+}
+impl MaskedPin for Input<'_> {}
+
+impl SealedMaskedPin for Output<'_> {
+    fn any_pin(&self) -> &AnyPin {
+        self.pin.any_pin()
+    }
+}
+impl MaskedPin for Output<'_> {}
+
+/// Masked access to a group of pins on one GPIO port
+///
+/// Writes go through the port's MPIN register, which only updates the pins selected in the
+/// port's MASK register, so a parallel bus such as PIO0[7:0] is driven with a single store
+/// without disturbing the other pins of the port. Reads return the selected pins with all
+/// other bits cleared.
+///
+/// The selected pins are borrowed from their [`Output`], [`Input`] or [`Flex`] drivers for the
+/// lifetime of the `PortMasked`. The MASK register is shared by the whole port, so only one
+/// `PortMasked` may exist per port.
+pub struct PortMasked<'d, const PORT: usize> {
+    mask: u32,
+    _pins: PhantomData<&'d mut ()>,
+}
+
+impl<'d, const PORT: usize> PortMasked<'d, PORT> {
+    const PORT_VALID: () = assert!(PORT < PORT_COUNT, "GPIO port out of range");
+
+    /// Select `pins` for masked access, they stay borrowed until the `PortMasked` is dropped
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pin is not on port `PORT`.
+    pub fn new<P: MaskedPin>(pins: &'d mut [P]) -> Self {
+        let () = Self::PORT_VALID;
+
+        let mask = pins.iter().fold(0u32, |mask, pin| {
+            let pin = pin.any_pin();
+            assert!(pin.port() == PORT, "GPIO pin not on the selected port");
+            mask | (1 << pin.pin())
+        });
+
+        // MASK bits set to 0 are the ones MPIN reads and writes
+        Self::block().mask(PORT).write(|w|
+            // SAFETY: unsafe only used for .bits()
+            unsafe { w.bits(!mask) });
+
+        Self {
+            mask,
+            _pins: PhantomData,
+        }
+    }
+
+    fn block() -> crate::pac::Gpio {
+        // SAFETY: the MASK and MPIN registers of this port are only accessed through this
+        // instance, pin registers are only touched through masked accesses to the selected pins.
+        unsafe { crate::pac::Gpio::steal() }
+    }
+
+    /// The selected pins, bit `n` set for pin `n`
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Drive the selected pins to the matching bits of `value` with a single register write
+    ///
+    /// Bit `n` of `value` goes to pin `n`, bits of pins that are not selected are ignored.
+    #[inline]
+    pub fn write(&mut self, value: u32) {
+        Self::block().mpin(PORT).write(|w|
+            // SAFETY: unsafe only used for .bits()
+            unsafe { w.bits(value) });
+    }
+
+    /// Read the selected pins, bit `n` holds pin `n` and unselected bits read as 0
+    #[inline]
+    pub fn read_masked(&self) -> u32 {
+        Self::block().mpin(PORT).read().bits()
+    }
+}
+
+impl<const PORT: usize> Drop for PortMasked<'_, PORT> {
+    fn drop(&mut self) {
+        // Back to the reset value, all pins selected
+        Self::block().mask(PORT).write(|w|
+            // SAFETY: unsafe only used for .bits()
+            unsafe { w.bits(0) });
+    }
+}
+
 trait SealedPin: IopctlPin {
     fn pin_port(&self) -> usize;
 
@@ -566,6 +971,11 @@ trait SealedPin: IopctlPin {
     }
 }
 
+/// GPIO index of `pin` (`port * 32 + pin`), as used by the pin interrupt selection
+pub(crate) fn pin_index<P: GpioPin>(pin: &P) -> usize {
+    pin.pin_port()
+}
+
 /// GPIO pin trait.
 #[allow(private_bounds)]
 pub trait GpioPin: SealedPin + Sized + Into<AnyPin> + 'static {
@@ -1060,3 +1470,21 @@ impl embedded_hal_1::digital::StatefulOutputPin for Output<'_> {
         Ok((*self).is_set_low())
     }
 }
+
+impl embedded_hal_1::digital::ErrorType for FastOutput<'_> {
+    type Error = Infallible;
+}
+
+impl embedded_hal_1::digital::OutputPin for FastOutput<'_> {
+    #[inline(always)]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_high();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_low();
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use core::iter::zip;
+
+use super::{Algorithm, Hashcrypt, Mode};
+
+/// AES block length
+pub const AES_BLOCK_LEN: usize = 16;
+
+/// AES-128 in counter (CTR) mode
+///
+/// Block `n` of the stream is XORed with the encryption of the initial counter block plus `n`,
+/// computed by the hashcrypt AES engine in ECB mode. Encryption and decryption are the same
+/// operation. The counter is kept by the driver as a 128-bit big-endian value, so it wraps
+/// around the whole block rather than a 16 or 32-bit counter field.
+pub struct Aes128Ctr<'d, 'a, M: Mode> {
+    hashcrypt: &'a mut Hashcrypt<'d, M>,
+    nonce: u128,
+    counter: u64,
+    keystream: [u8; AES_BLOCK_LEN],
+    /// Bytes of `keystream` already used, a full block means none is left
+    used: usize,
+}
+
+impl<'d, 'a, M: Mode> Aes128Ctr<'d, 'a, M> {
+    pub(super) fn new_inner(hashcrypt: &'a mut Hashcrypt<'d, M>, key: &[u8; 16], nonce: &[u8; 16]) -> Self {
+        hashcrypt.start_algorithm(Algorithm::AES, false);
+
+        let regs = &hashcrypt.hashcrypt;
+        // ECB encryption with a 128-bit key, words most significant first and byte swapped so
+        // the key and blocks are taken in memory byte order
+        regs.cryptcfg().write(|w| {
+            w.aesmode()
+                .ecb()
+                .aesdecrypt()
+                .encrypt()
+                .aeskeysz()
+                .bits128()
+                .msw1st_out()
+                .set_bit()
+                .swapkey()
+                .set_bit()
+                .swapdat()
+                .set_bit()
+                .msw1st()
+                .set_bit()
+        });
+
+        // The AES engine expects the key on INDATA first
+        while regs.status().read().needkey().bit_is_clear() {}
+        for word in key.chunks(4) {
+            // SAFETY: unsafe only used for .bits()
+            regs.indata()
+                .write(|w| unsafe { w.data().bits(u32::from_le_bytes([word[0], word[1], word[2], word[3]])) });
+        }
+
+        Self {
+            hashcrypt,
+            nonce: u128::from_be_bytes(*nonce),
+            counter: 0,
+            keystream: [0; AES_BLOCK_LEN],
+            used: AES_BLOCK_LEN,
+        }
+    }
+
+    /// Seek to block `ctr` of the stream, the next byte processed is its first byte
+    pub fn set_counter_value(&mut self, ctr: u64) {
+        self.counter = ctr;
+        self.used = AES_BLOCK_LEN;
+    }
+
+    /// Encrypt `buf` in place, continuing the stream where the previous call stopped
+    pub fn encrypt_in_place(&mut self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            if self.used == AES_BLOCK_LEN {
+                self.next_keystream_block();
+            }
+
+            *byte ^= self.keystream[self.used];
+            self.used += 1;
+        }
+    }
+
+    /// Decrypt `buf` in place, the same operation as [`Self::encrypt_in_place`]
+    pub fn decrypt_in_place(&mut self, buf: &mut [u8]) {
+        self.encrypt_in_place(buf);
+    }
+
+    /// Encrypt the current counter block into the keystream and advance the counter
+    fn next_keystream_block(&mut self) {
+        let block = self.nonce.wrapping_add(u128::from(self.counter)).to_be_bytes();
+        let regs = &self.hashcrypt.hashcrypt;
+
+        while regs.status().read().waiting().bit_is_clear() {}
+        for word in block.chunks(4) {
+            // SAFETY: unsafe only used for .bits()
+            regs.indata()
+                .write(|w| unsafe { w.data().bits(u32::from_le_bytes([word[0], word[1], word[2], word[3]])) });
+        }
+
+        while regs.status().read().digest().is_not_ready() {}
+        for (reg, chunk) in zip(regs.digest0_iter(), self.keystream.chunks_mut(4)) {
+            chunk.copy_from_slice(&reg.read().bits().to_le_bytes());
+        }
+
+        self.counter = self.counter.wrapping_add(1);
+        self.used = 0;
+    }
+}
+
+impl<M: Mode> Drop for Aes128Ctr<'_, '_, M> {
+    fn drop(&mut self) {
+        // Clear the key and keystream from the engine
+        self.hashcrypt
+            .hashcrypt
+            .ctrl()
+            .write(|w| w.mode().disabled().new_hash().start());
+        self.keystream.fill(0);
+    }
+}
@@ -3,9 +3,9 @@ use core::iter::zip;
 use core::marker::PhantomData;
 use core::task::Poll;
 
-use embassy_futures::select::select;
+use embassy_futures::select::{select, Either};
 
-use super::{Async, Blocking, Hashcrypt, Mode};
+use super::{Async, Blocking, Error, HashVerifyError, Hashcrypt, Mode, Result, HASHCRYPT_WAKER};
 use crate::dma;
 use crate::dma::transfer::{Transfer, Width};
 
@@ -18,6 +18,25 @@ const END_BYTE: u8 = 0x80;
 // 9 from the end byte and the 64-bit length
 const LAST_BLOCK_MAX_DATA: usize = BLOCK_LEN - 9;
 
+/// Final block built on the stack, aligned for 32-bit DMA reads
+#[repr(C, align(4))]
+struct BlockBuffer([u8; BLOCK_LEN]);
+
+/// Compare `hash` against `expected` in constant time, e.g. to check a MAC
+///
+/// All bytes are compared before returning, so the time taken does not reveal how many
+/// leading bytes matched.
+pub fn verify_hash(hash: &[u8; HASH_LEN], expected: &[u8; HASH_LEN]) -> core::result::Result<(), HashVerifyError> {
+    let diff = zip(hash, expected).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+
+    // Keep the compiler from turning the fold into an early exit comparison
+    if core::hint::black_box(diff) == 0 {
+        Ok(())
+    } else {
+        Err(HashVerifyError::Mismatch)
+    }
+}
+
 /// A hasher
 pub struct Hasher<'d, 'a, M: Mode> {
     hashcrypt: &'a mut Hashcrypt<'d, M>,
@@ -58,13 +77,6 @@ impl<'d, 'a, M: Mode> Hasher<'d, 'a, M> {
             chunk.copy_from_slice(&reg.read().bits().to_be_bytes());
         }
     }
-}
-
-impl<'d, 'a> Hasher<'d, 'a, Blocking> {
-    /// Create a new hasher instance
-    pub fn new_blocking(hashcrypt: &'a mut Hashcrypt<'d, Blocking>) -> Self {
-        Self::new_inner(hashcrypt)
-    }
 
     fn transfer_block(&mut self, data: &[u8; BLOCK_LEN]) {
         for word in data.chunks(4) {
@@ -76,20 +88,8 @@ impl<'d, 'a> Hasher<'d, 'a, Blocking> {
         self.wait_for_digest();
     }
 
-    /// Submit one or more blocks of data to the hasher, data must be a multiple of the block length
-    pub fn submit_blocks(&mut self, data: &[u8]) {
-        if data.is_empty() || data.len() % BLOCK_LEN != 0 {
-            panic!("Invalid data length");
-        }
-
-        for block in data.chunks(BLOCK_LEN) {
-            self.transfer_block(block.try_into().unwrap());
-        }
-        self.written += data.len();
-    }
-
-    /// Submits the final data for hashing
-    pub fn finalize(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
+    /// Write the final data and padding with the CPU and read back the hash
+    fn finalize_blocking_inner(&mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
         let mut buffer = [0u8; BLOCK_LEN];
 
         self.written += data.len();
@@ -109,6 +109,30 @@ impl<'d, 'a> Hasher<'d, 'a, Blocking> {
 
         self.read_hash(hash);
     }
+}
+
+impl<'d, 'a> Hasher<'d, 'a, Blocking> {
+    /// Create a new hasher instance
+    pub fn new_blocking(hashcrypt: &'a mut Hashcrypt<'d, Blocking>) -> Self {
+        Self::new_inner(hashcrypt)
+    }
+
+    /// Submit one or more blocks of data to the hasher, data must be a multiple of the block length
+    pub fn submit_blocks(&mut self, data: &[u8]) {
+        if data.is_empty() || data.len() % BLOCK_LEN != 0 {
+            panic!("Invalid data length");
+        }
+
+        for block in data.chunks(BLOCK_LEN) {
+            self.transfer_block(block.try_into().unwrap());
+        }
+        self.written += data.len();
+    }
+
+    /// Submits the final data for hashing
+    pub fn finalize(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
+        self.finalize_blocking_inner(data, hash);
+    }
 
     /// Computes the hash of the given data
     pub fn hash(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
@@ -119,6 +143,14 @@ impl<'d, 'a> Hasher<'d, 'a, Blocking> {
         }
         self.finalize(&data[full_blocks * BLOCK_LEN..], hash);
     }
+
+    /// Submits the final data and compares the hash against `expected`, see [`verify_hash`]
+    pub fn verify(self, data: &[u8], expected: &[u8; HASH_LEN]) -> core::result::Result<(), HashVerifyError> {
+        let mut hash = [0u8; HASH_LEN];
+        self.finalize(data, &mut hash);
+
+        verify_hash(&hash, expected)
+    }
 }
 
 impl<'d, 'a> Hasher<'d, 'a, Async> {
@@ -127,7 +159,19 @@ impl<'d, 'a> Hasher<'d, 'a, Async> {
         Self::new_inner(hashcrypt)
     }
 
-    async fn transfer(&mut self, data: &[u8]) {
+    /// Check the error status bit, clearing it if set.
+    fn check_error(&self) -> Result<()> {
+        let regs = &self.hashcrypt.hashcrypt;
+
+        if regs.status().read().error().bit_is_set() {
+            regs.status().write(|w| w.error().clear_bit_by_one());
+            Err(Error::Hardware)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn transfer(&mut self, data: &[u8]) -> Result<()> {
         if data.is_empty() || data.len() % BLOCK_LEN != 0 {
             panic!("Invalid data length");
         }
@@ -142,60 +186,117 @@ impl<'d, 'a> Hasher<'d, 'a, Async> {
             data,
             self.hashcrypt.hashcrypt.indata().as_ptr() as *mut u8,
             options,
-        );
+        )?;
 
-        select(
+        let res = select(
             transfer,
-            poll_fn(|_| {
-                // Check if transfer is already complete
-                if self.hashcrypt.hashcrypt.status().read().waiting().is_waiting() {
-                    return Poll::Ready(());
+            poll_fn(|cx| {
+                HASHCRYPT_WAKER.register(cx.waker());
+
+                if let Err(e) = self.check_error() {
+                    return Poll::Ready(Err(e));
                 }
 
+                self.hashcrypt.hashcrypt.intenset().write(|w| w.error().set_bit());
+
                 Poll::Pending
             }),
         )
         .await;
 
-        // Wait for the digest to finish, this takes <100 clock cycles so it's not worth doing async
-        self.wait_for_digest();
+        match res {
+            Either::First(r) => r?,
+            Either::Second(e) => return e,
+        }
+
+        // Wait for the digest of the last block, or an error
+        poll_fn(|cx| {
+            HASHCRYPT_WAKER.register(cx.waker());
+
+            self.check_error()?;
+
+            if self.hashcrypt.hashcrypt.status().read().digest().is_ready() {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.hashcrypt
+                .hashcrypt
+                .intenset()
+                .write(|w| w.digest().set_bit().error().set_bit());
+
+            Poll::Pending
+        })
+        .await
     }
 
     /// Submit one or more blocks of data to the hasher, data must be a multiple of the block length
-    pub async fn submit_blocks(&mut self, data: &[u8]) {
-        self.transfer(data).await;
+    pub async fn submit_blocks(&mut self, data: &[u8]) -> Result<()> {
+        self.transfer(data).await?;
         self.written += data.len();
+        Ok(())
     }
 
     /// Submits the final data for hashing
-    pub async fn finalize(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
-        let mut buffer = [0u8; BLOCK_LEN];
+    pub async fn finalize(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) -> Result<()> {
+        let mut buffer = BlockBuffer([0u8; BLOCK_LEN]);
 
         self.written += data.len();
         if data.len() <= LAST_BLOCK_MAX_DATA {
             // Only have one final block
-            self.init_final_block(data, &mut buffer);
-            self.transfer(&buffer).await;
+            self.init_final_block(data, &mut buffer.0);
+            self.transfer(&buffer.0).await?;
         } else {
             //End byte and padding won't fit in this block, submit this block and an extra one
-            self.init_final_data(data, &mut buffer);
-            self.transfer(&buffer).await;
+            self.init_final_data(data, &mut buffer.0);
+            self.transfer(&buffer.0).await?;
 
-            buffer.fill(0);
-            self.init_final_len(&mut buffer);
-            self.transfer(&buffer).await;
+            buffer.0.fill(0);
+            self.init_final_len(&mut buffer.0);
+            self.transfer(&buffer.0).await?;
         }
 
         self.read_hash(hash);
+        Ok(())
+    }
+
+    /// Submits the final data with the CPU and spin-waits for the hash.
+    ///
+    /// Use after loading the bulk of the data with [`Self::submit_blocks`], when the
+    /// result is needed without yielding to the executor.
+    pub fn finish_blocking(mut self, data: &[u8]) -> [u8; HASH_LEN] {
+        // All DMA transfers have completed, hand INDATA back to the CPU
+        self.hashcrypt.hashcrypt.ctrl().modify(|_, w| w.dma_i().clear_bit());
+
+        let mut hash = [0u8; HASH_LEN];
+        self.finalize_blocking_inner(data, &mut hash);
+        hash
+    }
+
+    /// Submits the final data and compares the hash against `expected`, see [`verify_hash`]
+    pub async fn verify(self, data: &[u8], expected: &[u8; HASH_LEN]) -> core::result::Result<(), HashVerifyError> {
+        let mut hash = [0u8; HASH_LEN];
+        self.finalize(data, &mut hash).await?;
+
+        verify_hash(&hash, expected)
+    }
+
+    /// Like [`Self::finish_blocking`], then compares the hash against `expected`, see
+    /// [`verify_hash`]
+    pub fn finish_and_verify(
+        self,
+        data: &[u8],
+        expected: &[u8; HASH_LEN],
+    ) -> core::result::Result<(), HashVerifyError> {
+        verify_hash(&self.finish_blocking(data), expected)
     }
 
     /// Computes the hash of the given data
-    pub async fn hash(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) {
+    pub async fn hash(mut self, data: &[u8], hash: &mut [u8; HASH_LEN]) -> Result<()> {
         let full_blocks = data.len() / BLOCK_LEN;
 
         if full_blocks > 0 {
-            self.submit_blocks(&data[0..full_blocks * BLOCK_LEN]).await;
+            self.submit_blocks(&data[0..full_blocks * BLOCK_LEN]).await?;
         }
-        self.finalize(&data[full_blocks * BLOCK_LEN..], hash).await;
+        self.finalize(&data[full_blocks * BLOCK_LEN..], hash).await
     }
 }
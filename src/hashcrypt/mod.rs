@@ -1,16 +1,75 @@
 //! Hashcrypt
 use core::marker::PhantomData;
 
+use aes::Aes128Ctr;
 use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
+use embassy_sync::waitqueue::AtomicWaker;
 use hasher::Hasher;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{clock_guard, ClockGuard};
+use crate::interrupt::typelevel::Interrupt;
 use crate::peripherals::{DMA0_CH30, HASHCRYPT};
-use crate::{dma, pac};
+use crate::{dma, interrupt, pac};
 
+/// AES module
+pub mod aes;
 /// Hasher module
 pub mod hasher;
 
+static HASHCRYPT_WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Hashcrypt error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Hashcrypt reported an error, e.g. INDATA written while not ready
+    Hardware,
+
+    /// DMA transfer could not be set up
+    Dma(dma::Error),
+}
+
+impl From<dma::Error> for Error {
+    fn from(value: dma::Error) -> Self {
+        Error::Dma(value)
+    }
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Hash verification error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HashVerifyError {
+    /// The computed hash differs from the expected one
+    Mismatch,
+
+    /// The hash could not be computed
+    Hashcrypt(Error),
+}
+
+impl From<Error> for HashVerifyError {
+    fn from(value: Error) -> Self {
+        HashVerifyError::Hashcrypt(value)
+    }
+}
+
+/// Hashcrypt interrupt handler.
+pub struct InterruptHandler;
+
+impl interrupt::typelevel::Handler<interrupt::typelevel::HASHCRYPT> for InterruptHandler {
+    unsafe fn on_interrupt() {
+        let regs = unsafe { pac::Hashcrypt::steal() };
+        let status = regs.status().read();
+
+        if status.digest().is_ready() || status.error().bit_is_set() {
+            regs.intenclr().write(|w| w.digest().set_bit().error().set_bit());
+            HASHCRYPT_WAKER.wake();
+        }
+    }
+}
+
 trait Sealed {}
 
 /// Asynchronous or blocking mode
@@ -38,6 +97,7 @@ pub struct Hashcrypt<'d, M: Mode> {
     hashcrypt: pac::Hashcrypt,
     dma_ch: Option<dma::channel::Channel<'d>>,
     _peripheral: PeripheralRef<'d, HASHCRYPT>,
+    _clock: ClockGuard,
     _mode: PhantomData<M>,
 }
 
@@ -47,12 +107,15 @@ pub struct Hashcrypt<'d, M: Mode> {
 enum Algorithm {
     /// SHA256
     SHA256,
+    /// AES
+    AES,
 }
 
 impl From<Algorithm> for u8 {
     fn from(value: Algorithm) -> Self {
         match value {
             Algorithm::SHA256 => 0x2,
+            Algorithm::AES => 0x4,
         }
     }
 }
@@ -60,12 +123,13 @@ impl From<Algorithm> for u8 {
 impl<'d, M: Mode> Hashcrypt<'d, M> {
     /// Instantiate new Hashcrypt peripheral
     fn new_inner(peripheral: impl Peripheral<P = HASHCRYPT> + 'd, dma_ch: Option<dma::channel::Channel<'d>>) -> Self {
-        enable_and_reset::<HASHCRYPT>();
+        let _clock = clock_guard::<HASHCRYPT>();
 
         into_ref!(peripheral);
 
         Self {
             _peripheral: peripheral,
+            _clock,
             _mode: PhantomData,
             dma_ch,
             hashcrypt: unsafe { pac::Hashcrypt::steal() },
@@ -83,6 +147,13 @@ impl<'d, M: Mode> Hashcrypt<'d, M> {
             w
         });
     }
+
+    /// Start AES-128 CTR encryption and decryption with `key`, from initial counter block `nonce`
+    ///
+    /// The blocks are processed by the CPU in both modes.
+    pub fn new_aes128_ctr<'a>(&'a mut self, key: &[u8; 16], nonce: &[u8; 16]) -> Aes128Ctr<'d, 'a, M> {
+        Aes128Ctr::new_inner(self, key, nonce)
+    }
 }
 
 impl<'d> Hashcrypt<'d, Blocking> {
@@ -103,8 +174,14 @@ impl<'d> Hashcrypt<'d, Async> {
     pub fn new_async(
         peripheral: impl Peripheral<P = HASHCRYPT> + 'd,
         dma_ch: impl Peripheral<P = impl HashcryptDma> + 'd,
+        _irq: impl interrupt::typelevel::Binding<interrupt::typelevel::HASHCRYPT, InterruptHandler> + 'd,
     ) -> Self {
-        Self::new_inner(peripheral, dma::Dma::reserve_channel(dma_ch))
+        let hashcrypt = Self::new_inner(peripheral, dma::Dma::reserve_channel(dma_ch));
+
+        interrupt::typelevel::HASHCRYPT::unpend();
+        unsafe { interrupt::typelevel::HASHCRYPT::enable() };
+
+        hashcrypt
     }
 
     /// Start a new SHA256 hash
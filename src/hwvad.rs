@@ -0,0 +1,411 @@
+//! Hardware voice activity detector (HWVAD)
+//!
+//! The HWVAD watches the decimated output of DMIC channel 0. This driver runs only what the
+//! detector needs: the DMIC functional clock and the PDM front-end and decimator of channel 0.
+//! The channel FIFO and DMA stay disabled, so the detector can run while the core sleeps.
+//! The HWVAD interrupt is enabled as a deep sleep wake-up source.
+//!
+//! Starting buffered capture from the HWVAD interrupt, so the first ~100 ms of audio after
+//! the wake-up is kept, needs a DMIC capture driver and is not provided yet.
+
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::Poll;
+
+use embassy_hal_internal::into_ref;
+use embassy_sync::waitqueue::AtomicWaker;
+use embedded_hal_1::delay::DelayNs;
+
+use crate::clocks::{clock_guard, ClockGuard, SysconPeripheral};
+use crate::delay::Delay;
+use crate::interrupt::typelevel::Interrupt;
+use crate::iopctl::{DriveMode, DriveStrength, Inverter, IopctlPin as Pin, Pull, SlewRate};
+use crate::{interrupt, peripherals, Peripheral};
+
+static HWVAD_WAKER: AtomicWaker = AtomicWaker::new();
+static ACTIVITY: AtomicBool = AtomicBool::new(false);
+
+/// DMIC channel feeding the HWVAD
+const HWVAD_CHANNEL: usize = 0;
+
+/// DC_CTRL DCPOLE: DC removal filter cut-off at 155 Hz
+const DCPOLE_155HZ: u8 = 1;
+/// DC_CTRL DCGAIN: downshift by 1 bit after the DC removal filter
+const DCGAIN_SHIFT: u8 = 1;
+
+/// Largest value of the 4-bit gain fields
+const MAX_GAIN: u8 = 0xF;
+/// Largest HWVAD input gain setting
+const MAX_INPUT_GAIN: u8 = 12;
+
+/// Filter settling time after reset, before detection is enabled
+const SETTLE_MS: u32 = 20;
+/// Settling time after an activity, before detection is enabled again
+const REARM_US: u32 = 100;
+
+/// HWVAD error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Configuration value out of range
+    InvalidConfig,
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Functional clock of the DMIC
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmicClock {
+    /// 16 MHz SFRO
+    Sfro,
+    /// FFRO
+    Ffro,
+    /// Audio PLL
+    AudioPll,
+    /// 1 MHz low power oscillator
+    Lposc,
+}
+
+impl DmicClock {
+    /// DMIC0FCLKSEL value
+    fn sel(self) -> u32 {
+        match self {
+            DmicClock::Sfro => 0,
+            DmicClock::Ffro => 1,
+            DmicClock::AudioPll => 2,
+            DmicClock::Lposc => 4,
+        }
+    }
+}
+
+/// Divider from the DMIC functional clock to the PDM clock
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PdmDivider {
+    /// Divide by 1
+    Div1,
+    /// Divide by 2
+    Div2,
+    /// Divide by 3
+    Div3,
+    /// Divide by 4
+    Div4,
+    /// Divide by 6
+    Div6,
+    /// Divide by 8
+    Div8,
+    /// Divide by 12
+    Div12,
+    /// Divide by 16
+    Div16,
+    /// Divide by 24
+    Div24,
+    /// Divide by 32
+    Div32,
+    /// Divide by 48
+    Div48,
+    /// Divide by 64
+    Div64,
+    /// Divide by 96
+    Div96,
+    /// Divide by 128
+    Div128,
+}
+
+/// Microphone of a pair sharing the PDM data line
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PdmChannel {
+    /// Data sampled on the rising PDM clock edge
+    Left,
+    /// Data sampled on the falling PDM clock edge
+    Right,
+}
+
+/// High-pass filter in front of the detector
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HwvadFilter {
+    /// First filter bypassed
+    Bypass,
+    /// Filter shifter of 1
+    Shift1,
+    /// Filter shifter of 4
+    Shift4,
+}
+
+/// HWVAD configuration
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// DMIC functional clock source
+    pub clock: DmicClock,
+    /// DMIC functional clock divider, `1..=256`
+    pub clock_div: u16,
+    /// PDM clock divider from the functional clock
+    pub pdm_div: PdmDivider,
+    /// Decimator oversampling rate
+    pub osr: u8,
+    /// Microphone used
+    pub channel: PdmChannel,
+    /// Detector input right shift of `2 * input_gain - 10` bits, `0..=12`
+    pub input_gain: u8,
+    /// Noise estimator gain, `0..=15`
+    pub noise_gain: u8,
+    /// Signal estimator gain, `0..=15`
+    pub signal_gain: u8,
+    /// High-pass filter in front of the detector
+    pub filter: HwvadFilter,
+}
+
+impl Default for Config {
+    /// 1 MHz PDM clock from the SFRO
+    fn default() -> Self {
+        Self {
+            clock: DmicClock::Sfro,
+            clock_div: 1,
+            pdm_div: PdmDivider::Div16,
+            osr: 32,
+            channel: PdmChannel::Left,
+            input_gain: 4,
+            noise_gain: 2,
+            signal_gain: 1,
+            filter: HwvadFilter::Shift1,
+        }
+    }
+}
+
+/// HWVAD interrupt handler.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // The detector output is a level, keep it quiet until the next wait re-arms it
+        T::Interrupt::disable();
+        ACTIVITY.store(true, Ordering::Release);
+        HWVAD_WAKER.wake();
+    }
+}
+
+/// HWVAD driver.
+pub struct Hwvad<'d, T: Instance> {
+    info: Info,
+    _clock: ClockGuard,
+    _phantom: PhantomData<&'d T>,
+}
+
+impl<'d, T: Instance> Hwvad<'d, T> {
+    /// Start the PDM front-end of DMIC channel 0 and the detector.
+    ///
+    /// Blocks for the 20 ms the detector filters need to settle after reset.
+    pub fn new(
+        _inner: impl Peripheral<P = T> + 'd,
+        clk: impl Peripheral<P = impl PdmClkPin<T>> + 'd,
+        data: impl Peripheral<P = impl PdmDataPin<T>> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(clk);
+        into_ref!(data);
+
+        if !(1..=256).contains(&config.clock_div)
+            || config.input_gain > MAX_INPUT_GAIN
+            || config.noise_gain > MAX_GAIN
+            || config.signal_gain > MAX_GAIN
+        {
+            return Err(Error::InvalidConfig);
+        }
+
+        T::Interrupt::disable();
+
+        clk.as_pdm_clk();
+        data.as_pdm_data();
+
+        // SAFETY: only the DMIC functional clock is written
+        let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+        // SAFETY: unsafe only used for .bits()
+        clkctl1.dmic0fclksel().write(|w| unsafe { w.bits(config.clock.sel()) });
+        clkctl1.dmic0fclkdiv().modify(|_, w| w.reset().set_bit());
+        clkctl1
+            .dmic0fclkdiv()
+            .write(|w| unsafe { w.div().bits((config.clock_div - 1) as u8).halt().clear_bit() });
+        while clkctl1.dmic0fclkdiv().read().reqflag().bit_is_set() {}
+
+        let _clock = clock_guard::<T>();
+
+        let this = Self {
+            info: T::info(),
+            _clock,
+            _phantom: PhantomData,
+        };
+        this.configure(&config);
+
+        // Wake from deep sleep on activity
+        // SAFETY: only sets the wake-up enable of this interrupt
+        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+        let irq = cortex_m::interrupt::InterruptNumber::number(T::Interrupt::IRQ);
+        if irq < 32 {
+            // SAFETY: unsafe only used for .bits()
+            sysctl0.starten0_set().write(|w| unsafe { w.bits(1 << irq) });
+        } else {
+            // SAFETY: unsafe only used for .bits()
+            sysctl0.starten1_set().write(|w| unsafe { w.bits(1 << (irq - 32)) });
+        }
+
+        Ok(this)
+    }
+
+    fn configure(&self, config: &Config) {
+        let regs = self.info.regs;
+        let channel = regs.channel(HWVAD_CHANNEL);
+
+        // SAFETY: unsafe only used for .bits()
+        unsafe {
+            channel.divhfclk().write(|w| w.pdmdiv().bits(config.pdm_div as u8));
+            channel.osr().write(|w| w.osr().bits(config.osr));
+            channel.gainshift().write(|w| w.gain().bits(0));
+            channel.dc_ctrl().write(|w| {
+                w.dcpole()
+                    .bits(DCPOLE_155HZ)
+                    .dcgain()
+                    .bits(DCGAIN_SHIFT)
+                    .saturateat16bit()
+                    .set_bit()
+            });
+        }
+        // No decimator compensation
+        channel.preac2fscoef().reset();
+        channel.preac4fscoef().reset();
+        channel
+            .phy_ctrl()
+            .write(|w| w.phy_fall().bit(config.channel == PdmChannel::Right));
+
+        // The HWVAD works on the 2FS decimator output
+        regs.use2fs().write(|w| w.use2fs().set_bit());
+        regs.chanen().modify(|_, w| w.en_ch0().set_bit());
+
+        // SAFETY: unsafe only used for .bits()
+        unsafe {
+            regs.hwvadthgn().write(|w| w.thgn().bits(config.noise_gain));
+            regs.hwvadthgs().write(|w| w.thgs().bits(config.signal_gain));
+            regs.hwvadhpfs().write(|w| w.hpfs().bits(config.filter as u8));
+            regs.hwvadgain().write(|w| w.inputgain().bits(config.input_gain));
+        }
+
+        // Reset the filters and let them settle with the interrupt held off
+        regs.hwvadrstt().write(|w| w.rstt().set_bit());
+        regs.hwvadrstt().write(|w| w.rstt().clear_bit());
+        regs.hwvadst10().write(|w| w.st10().set_bit());
+
+        Delay.delay_ms(SETTLE_MS);
+
+        regs.hwvadst10().write(|w| w.st10().clear_bit());
+    }
+
+    /// Wait until the detector reports voice activity.
+    ///
+    /// The detector is re-armed first, so activity that is still ongoing from a previous wait
+    /// is reported again after a short settling time.
+    pub async fn wait_for_activity(&mut self) {
+        let regs = self.info.regs;
+
+        regs.hwvadst10().write(|w| w.st10().set_bit());
+        Delay.delay_us(REARM_US);
+        regs.hwvadst10().write(|w| w.st10().clear_bit());
+
+        ACTIVITY.store(false, Ordering::Release);
+        T::Interrupt::unpend();
+        // SAFETY: the handler is bound by the constructor
+        unsafe { T::Interrupt::enable() };
+
+        poll_fn(|cx| {
+            HWVAD_WAKER.register(cx.waker());
+
+            if ACTIVITY.load(Ordering::Acquire) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+
+    /// Current noise envelope estimate of the detector
+    pub fn noise_envelope(&self) -> u16 {
+        self.info.regs.hwvadlowz().read().bits() as u16
+    }
+}
+
+impl<T: Instance> Drop for Hwvad<'_, T> {
+    fn drop(&mut self) {
+        T::Interrupt::disable();
+        self.info.regs.chanen().modify(|_, w| w.en_ch0().clear_bit());
+    }
+}
+
+struct Info {
+    regs: crate::pac::Dmic0,
+}
+
+trait SealedInstance {
+    fn info() -> Info;
+}
+
+/// HWVAD instance trait.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + Peripheral<P = Self> + SysconPeripheral + 'static + Send {
+    /// Interrupt for this HWVAD instance.
+    type Interrupt: interrupt::typelevel::Interrupt;
+}
+
+impl Instance for peripherals::DMIC0 {
+    type Interrupt = crate::interrupt::typelevel::HWVAD0;
+}
+
+impl SealedInstance for peripherals::DMIC0 {
+    fn info() -> Info {
+        // SAFETY: safe from single executor
+        Info {
+            regs: unsafe { crate::pac::Dmic0::steal() },
+        }
+    }
+}
+
+/// A trait for pins that can drive the PDM clock of DMIC channel 0
+pub trait PdmClkPin<T: Instance>: Pin + crate::Peripheral {
+    /// Configures the pin as the PDM clock output
+    fn as_pdm_clk(&self);
+}
+
+/// A trait for pins that can receive the PDM data of DMIC channel 0
+pub trait PdmDataPin<T: Instance>: Pin + crate::Peripheral {
+    /// Configures the pin as the PDM data input
+    fn as_pdm_data(&self);
+}
+
+macro_rules! impl_pin {
+    ($trait:ident, $method:ident, $piom_n:ident, $fn:ident) => {
+        impl $trait<peripherals::DMIC0> for peripherals::$piom_n {
+            fn $method(&self) {
+                self.set_function(crate::iopctl::Function::$fn)
+                    .set_pull(Pull::None)
+                    .enable_input_buffer()
+                    .set_slew_rate(SlewRate::Standard)
+                    .set_drive_strength(DriveStrength::Normal)
+                    .disable_analog_multiplex()
+                    .set_drive_mode(DriveMode::PushPull)
+                    .set_input_inverter(Inverter::Disabled);
+            }
+        }
+    };
+}
+
+// DMIC0_CLK01 and DMIC0_DATA01, Pin Function Table in UM11147 section 7.5.3
+impl_pin!(PdmClkPin, as_pdm_clk, PIO2_16, F1);
+impl_pin!(PdmDataPin, as_pdm_data, PIO2_15, F1);
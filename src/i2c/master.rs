@@ -1,5 +1,5 @@
 /// I2C Master Driver
-use core::future::poll_fn;
+use core::future::{poll_fn, Future};
 use core::marker::PhantomData;
 use core::task::Poll;
 
@@ -8,12 +8,22 @@ use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::into_ref;
 
 use super::{
-    Async, Blocking, Error, Info, Instance, InterruptHandler, MasterDma, Mode, Result, SclPin, SdaPin, TransferError,
-    I2C_WAKERS, TEN_BIT_PREFIX,
+    Async, Blocking, BusAddress, Error, Info, Instance, InterruptHandler, MasterDma, Mode, Result, SclPin, SdaPin,
+    TransferError, GENERAL_CALL_ADDRESS, I2C_WAKERS, TEN_BIT_PREFIX,
 };
+use crate::clocks::ClockGuard;
 use crate::interrupt::typelevel::Interrupt;
 use crate::{dma, interrupt, Peripheral};
 
+/// Maximum number of non-reserved 7-bit addresses
+const MAX_SCAN_ADDRESSES: usize = 112;
+
+/// Polls of the master state to wait for a STOP before resetting the master
+const RELEASE_POLL_LIMIT: u32 = 10_000;
+
+/// Maximum number of bytes a single DMA descriptor can move
+const DMA_MAX_TRANSFER: usize = 1024;
+
 /// Bus speed (nominal SCL, no clock stretching)
 pub enum Speed {
     /// 100 kbit/s
@@ -29,11 +39,36 @@ pub enum Speed {
     High,
 }
 
+/// Longest hardware event timeout, in units of 16 function clocks
+const MAX_TIMEOUT_TICKS: u16 = 0x1000;
+
+/// Hardware bus event timeout
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeoutSettings {
+    /// Fail a transfer with [`TransferError::Timeout`] when the bus sees no event for `ticks`
+    pub hw_timeout: bool,
+
+    /// Time allowed between bus events, in units of 16 I2C function clocks (1..=4096)
+    pub ticks: u16,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            hw_timeout: false,
+            ticks: MAX_TIMEOUT_TICKS,
+        }
+    }
+}
+
 /// use `FCn` as I2C Master controller
 pub struct I2cMaster<'a, M: Mode> {
     info: Info,
-    _phantom: PhantomData<M>,
+    _clock: ClockGuard,
+    _phantom: PhantomData<(&'a (), M)>,
     dma_ch: Option<dma::channel::Channel<'a>>,
+    timeout: TimeoutSettings,
 }
 
 impl<'a, M: Mode> I2cMaster<'a, M> {
@@ -44,6 +79,7 @@ impl<'a, M: Mode> I2cMaster<'a, M> {
         // TODO - integrate clock APIs to allow dynamic freq selection | clock: crate::flexcomm::Clock,
         speed: Speed,
         dma_ch: Option<dma::channel::Channel<'a>>,
+        _clock: ClockGuard,
     ) -> Result<Self> {
         into_ref!(_bus);
         into_ref!(scl);
@@ -96,11 +132,84 @@ impl<'a, M: Mode> I2cMaster<'a, M> {
 
         Ok(Self {
             info,
+            _clock,
             _phantom: PhantomData,
             dma_ch,
+            timeout: TimeoutSettings::default(),
         })
     }
 
+    /// Change the hardware event timeout used by subsequent transfers.
+    ///
+    /// Lets devices with different clock stretching needs share a bus, e.g. by
+    /// relaxing the timeout around a transfer to a slow device.
+    pub fn set_timeout(&mut self, timeout: TimeoutSettings) -> Result<()> {
+        if timeout.ticks == 0 || timeout.ticks > MAX_TIMEOUT_TICKS {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        if timeout != self.timeout {
+            self.timeout = timeout;
+            self.apply_timeout();
+        }
+
+        Ok(())
+    }
+
+    /// Disable the hardware event timeout, a device may then stretch the clock indefinitely.
+    pub fn disable_timeout(&mut self) {
+        self.timeout.hw_timeout = false;
+        self.apply_timeout();
+    }
+
+    /// The hardware event timeout used by transfers
+    pub fn timeout(&self) -> TimeoutSettings {
+        self.timeout
+    }
+
+    /// Program the hardware event timeout from the settings
+    fn apply_timeout(&self) {
+        if self.timeout.hw_timeout {
+            self.program_timeout(self.timeout.ticks);
+        } else {
+            self.stop_timeout();
+        }
+    }
+
+    /// Enable the hardware event timeout after `ticks` * 16 function clocks between bus events
+    fn program_timeout(&self, ticks: u16) {
+        let i2cregs = self.info.regs;
+
+        // SAFETY: unsafe only used for .bits(), ticks is in 1..=0x1000
+        i2cregs
+            .timeout()
+            .write(|w| unsafe { w.tomin().bits(0xf).to().bits(ticks - 1) });
+        i2cregs.stat().write(|w| w.eventtimeout().clear_bit_by_one());
+        i2cregs.cfg().modify(|_, w| w.timeouten().set_bit());
+    }
+
+    fn stop_timeout(&self) {
+        let i2cregs = self.info.regs;
+
+        i2cregs.cfg().modify(|_, w| w.timeouten().clear_bit());
+        i2cregs.intenclr().write(|w| w.eventtimeoutclr().set_bit());
+        i2cregs.stat().write(|w| w.eventtimeout().clear_bit_by_one());
+    }
+
+    /// Drop a timeout left over from an earlier transfer
+    fn clear_timeout_flag(&self) {
+        self.info.regs.stat().write(|w| w.eventtimeout().clear_bit_by_one());
+    }
+
+    /// A timed out transfer leaves the master mid-transaction, return it to idle
+    fn release_on_timeout<U>(&self, res: Result<U>) -> Result<U> {
+        if let Err(Error::Transfer(TransferError::Timeout)) = res {
+            self.release_bus();
+        }
+
+        res
+    }
+
     fn check_for_bus_errors(&self) -> Result<()> {
         let i2cregs = self.info.regs;
 
@@ -112,6 +221,45 @@ impl<'a, M: Mode> I2cMaster<'a, M> {
             Ok(())
         }
     }
+
+    /// Enable the hardware event timeout, so a device holding the bus can't stall a probe
+    fn enable_probe_timeout(&self) {
+        // Probes always use the longest timeout, regardless of the configured one
+        self.program_timeout(MAX_TIMEOUT_TICKS);
+    }
+
+    /// Return to the configured timeout after a probe
+    fn restore_timeout(&self) {
+        self.apply_timeout();
+    }
+
+    /// Return the master to idle after a failed transaction, resetting it if STOP doesn't complete
+    fn release_bus(&self) {
+        let i2cregs = self.info.regs;
+
+        let is_idle = || {
+            let stat = i2cregs.stat().read();
+            stat.mstpending().is_pending() && stat.mststate().is_idle()
+        };
+
+        if is_idle() {
+            return;
+        }
+
+        if i2cregs.stat().read().mstpending().is_pending() {
+            i2cregs.mstctl().write(|w| w.mststop().set_bit());
+        }
+
+        for _ in 0..RELEASE_POLL_LIMIT {
+            if is_idle() {
+                return;
+            }
+        }
+
+        // Disable and re-enable master mode to clear out stalled HW state
+        i2cregs.cfg().modify(|_, w| w.msten().disabled());
+        i2cregs.cfg().modify(|_, w| w.msten().enabled());
+    }
 }
 
 impl<'a> I2cMaster<'a, Blocking> {
@@ -125,15 +273,17 @@ impl<'a> I2cMaster<'a, Blocking> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Sfro;
-        T::enable(clock);
+        let clock = T::enable(clock);
         T::into_i2c();
 
-        let this = Self::new_inner::<T>(fc, scl, sda, speed, None)?;
+        let this = Self::new_inner::<T>(fc, scl, sda, speed, None, clock)?;
 
         Ok(this)
     }
 
     fn start(&mut self, address: u16, is_read: bool) -> Result<()> {
+        self.clear_timeout_flag();
+
         // check if the address is 10-bit
         let is_10bit = address > 0x7F;
 
@@ -243,7 +393,7 @@ impl<'a> I2cMaster<'a, Blocking> {
 
         // read of 0 size is not allowed according to i2c spec
         if read.is_empty() {
-            return Err(TransferError::OtherBusError.into());
+            return Err(TransferError::ZeroLengthRead.into());
         }
 
         self.start(address, true)?;
@@ -308,14 +458,66 @@ impl<'a> I2cMaster<'a, Blocking> {
     }
 
     fn poll_ready(&mut self) -> Result<()> {
-        while self.info.regs.stat().read().mstpending().is_in_progress() {}
+        while self.info.regs.stat().read().mstpending().is_in_progress() {
+            // Only raised while the event timeout is enabled
+            if self.info.regs.stat().read().eventtimeout().bit_is_set() {
+                return Err(TransferError::Timeout.into());
+            }
+        }
 
         Ok(())
     }
+
+    /// Probe `address` with an address-only write. Returns `true` if a device ACKed.
+    ///
+    /// The transaction always ends with a STOP and leaves the bus idle. A device
+    /// holding the bus results in [`TransferError::Timeout`].
+    pub fn probe(&mut self, address: BusAddress) -> Result<bool> {
+        self.enable_probe_timeout();
+
+        let res = match self.start(address, false) {
+            Ok(()) => self.stop().map(|_| true),
+            // STOP was already sent by start
+            Err(Error::Transfer(TransferError::AddressNack)) => Ok(false),
+            Err(e) => Err(e),
+        };
+
+        if res.is_err() {
+            self.release_bus();
+        }
+
+        self.restore_timeout();
+
+        res
+    }
+
+    /// Probe every address in `range`, e.g. [`SCAN_ADDRESSES`], and return the ones that ACKed
+    ///
+    /// Each probe is the zero-length write of [`Self::probe`]. The scan stops at the first bus
+    /// error, such as a device holding the bus low, and returns that error.
+    ///
+    /// [`SCAN_ADDRESSES`]: super::SCAN_ADDRESSES
+    pub fn scan(
+        &mut self,
+        range: impl IntoIterator<Item = BusAddress>,
+    ) -> Result<heapless::Vec<BusAddress, MAX_SCAN_ADDRESSES>> {
+        let mut found = heapless::Vec::new();
+
+        for address in range {
+            if self.probe(address)? && found.push(address).is_err() {
+                break;
+            }
+        }
+
+        Ok(found)
+    }
 }
 
 impl<'a> I2cMaster<'a, Async> {
     /// use flexcomm fc with Pins scl, sda as an I2C Master bus, configuring to speed and pull
+    ///
+    /// Passing [`dma::NoDma`] as `dma_ch` runs all transfers byte by byte on the I2C
+    /// interrupt alone, for systems where DMA channels are scarce.
     pub fn new_async<T: Instance>(
         fc: impl Peripheral<P = T> + 'a,
         scl: impl Peripheral<P = impl SclPin<T>> + 'a,
@@ -327,11 +529,11 @@ impl<'a> I2cMaster<'a, Async> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Sfro;
-        T::enable(clock);
+        let clock = T::enable(clock);
         T::into_i2c();
 
         let ch = dma::Dma::reserve_channel(dma_ch);
-        let this = Self::new_inner::<T>(fc, scl, sda, speed, ch)?;
+        let this = Self::new_inner::<T>(fc, scl, sda, speed, ch, clock)?;
 
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
@@ -485,64 +687,28 @@ impl<'a> I2cMaster<'a, Async> {
 
         // read of 0 size is not allowed according to i2c spec
         if read.is_empty() {
-            return Err(TransferError::OtherBusError.into());
+            return Err(TransferError::ZeroLengthRead.into());
         }
 
         self.start(address, true).await?;
 
-        if self.dma_ch.is_some() {
+        if let Some(dma_ch) = self.dma_ch.as_ref() {
             // Do a DMA recv
             // Read one byte less using DMA and then read the last byte manually
             let (dma_read, last_byte) = read.split_at_mut(read.len() - 1);
 
-            if !dma_read.is_empty() {
+            // A descriptor moves at most 1024 bytes. Between chunks the master holds the
+            // received byte and stretches SCL, the transaction stays open (no STOP, no
+            // repeated START) while the channel is reprogrammed.
+            for chunk in dma_read.chunks_mut(DMA_MAX_TRANSFER) {
                 let transfer = dma::transfer::Transfer::new_read(
-                    self.dma_ch.as_mut().unwrap(),
+                    dma_ch,
                     i2cregs.mstdat().as_ptr() as *mut u8,
-                    dma_read,
+                    chunk,
                     Default::default(),
-                );
-
-                // According to sections 24.7.7.1 and 24.7.7.2, we should
-                // first program the DMA channel for carrying out a transfer
-                // and only then set MSTDMA bit.
-                //
-                // Additionally, at this point we know the slave has
-                // acknowledged the address.
-                i2cregs.mstctl().write(|w| w.mstdma().enabled());
-
-                let res = select(
-                    transfer,
-                    poll_fn(|cx| {
-                        I2C_WAKERS[self.info.index].register(cx.waker());
-
-                        i2cregs.intenset().write(|w| {
-                            w.mstpendingen()
-                                .set_bit()
-                                .mstarblossen()
-                                .set_bit()
-                                .mstststperren()
-                                .set_bit()
-                        });
+                )?;
 
-                        let stat = i2cregs.stat().read();
-
-                        if stat.mstarbloss().is_arbitration_loss() {
-                            Poll::Ready(Err::<(), Error>(TransferError::ArbitrationLoss.into()))
-                        } else if stat.mstststperr().is_error() {
-                            Poll::Ready(Err::<(), Error>(TransferError::StartStopError.into()))
-                        } else {
-                            Poll::Pending
-                        }
-                    }),
-                )
-                .await;
-
-                i2cregs.mstctl().write(|w| w.mstdma().disabled());
-
-                if let Either::Second(e) = res {
-                    e?;
-                }
+                self.wait_dma(transfer).await?;
             }
 
             self.wait_on(
@@ -634,53 +800,17 @@ impl<'a> I2cMaster<'a, Async> {
             return Ok(());
         }
 
-        if self.dma_ch.is_some() {
-            let transfer = dma::transfer::Transfer::new_write(
-                self.dma_ch.as_mut().unwrap(),
-                write,
-                i2cregs.mstdat().as_ptr() as *mut u8,
-                Default::default(),
-            );
-
-            // According to sections 24.7.7.1 and 24.7.7.2, we should
-            // first program the DMA channel for carrying out a transfer
-            // and only then set MSTDMA bit.
-            //
-            // Additionally, at this point we know the slave has
-            // acknowledged the address.
-            i2cregs.mstctl().write(|w| w.mstdma().enabled());
-
-            let res = select(
-                transfer,
-                poll_fn(|cx| {
-                    I2C_WAKERS[self.info.index].register(cx.waker());
-
-                    i2cregs.intenset().write(|w| {
-                        w.mstpendingen()
-                            .set_bit()
-                            .mstarblossen()
-                            .set_bit()
-                            .mstststperren()
-                            .set_bit()
-                    });
-
-                    let stat = i2cregs.stat().read();
-
-                    if stat.mstarbloss().is_arbitration_loss() {
-                        Poll::Ready(Err::<(), Error>(TransferError::ArbitrationLoss.into()))
-                    } else if stat.mstststperr().is_error() {
-                        Poll::Ready(Err::<(), Error>(TransferError::StartStopError.into()))
-                    } else {
-                        Poll::Pending
-                    }
-                }),
-            )
-            .await;
-
-            i2cregs.mstctl().write(|w| w.mstdma().disabled());
+        if let Some(dma_ch) = self.dma_ch.as_ref() {
+            // Same chunking as for reads, the master stretches SCL until the next chunk starts
+            for chunk in write.chunks(DMA_MAX_TRANSFER) {
+                let transfer = dma::transfer::Transfer::new_write(
+                    dma_ch,
+                    chunk,
+                    i2cregs.mstdat().as_ptr() as *mut u8,
+                    Default::default(),
+                )?;
 
-            if let Either::Second(e) = res {
-                e?;
+                self.wait_dma(transfer).await?;
             }
 
             self.wait_on(
@@ -788,6 +918,147 @@ impl<'a> I2cMaster<'a, Async> {
         .await
     }
 
+    /// Probe `address` with an address-only write. Returns `true` if a device ACKed.
+    ///
+    /// The transaction always ends with a STOP and leaves the bus idle. A device
+    /// holding the bus results in [`TransferError::Timeout`].
+    pub async fn probe(&mut self, address: BusAddress) -> Result<bool> {
+        let i2cregs = self.info.regs;
+        let index = self.info.index;
+
+        self.enable_probe_timeout();
+
+        let probe = async {
+            match self.start(address, false).await {
+                Ok(()) => self.stop().await.map(|_| true),
+                Err(Error::Transfer(TransferError::AddressNack)) => self.stop().await.map(|_| false),
+                Err(e) => Err(e),
+            }
+        };
+
+        let res = Self::with_event_timeout(i2cregs, index, probe).await;
+
+        if res.is_err() {
+            self.release_bus();
+        }
+
+        self.restore_timeout();
+
+        res
+    }
+
+    /// Probe every address in `range`, e.g. [`SCAN_ADDRESSES`], and return the ones that ACKed
+    ///
+    /// Each probe is the zero-length write of [`Self::probe`]. The scan stops at the first bus
+    /// error, such as a device holding the bus low, and returns that error.
+    ///
+    /// [`SCAN_ADDRESSES`]: super::SCAN_ADDRESSES
+    pub async fn scan(
+        &mut self,
+        range: impl IntoIterator<Item = BusAddress>,
+    ) -> Result<heapless::Vec<BusAddress, MAX_SCAN_ADDRESSES>> {
+        let mut found = heapless::Vec::new();
+
+        for address in range {
+            if self.probe(address).await? && found.push(address).is_err() {
+                break;
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Broadcast `write` as a general call, then read from `address` after a repeated start
+    ///
+    /// The read directly follows the general call with no STOP in between, so no other master
+    /// can take the bus before the addressed slave answers.
+    pub async fn general_call_read(&mut self, write: &[u8], address: BusAddress, read: &mut [u8]) -> Result<()> {
+        let (i2cregs, index) = (self.info.regs, self.info.index);
+
+        self.clear_timeout_flag();
+
+        let res = Self::with_event_timeout(i2cregs, index, async {
+            self.write_no_stop(GENERAL_CALL_ADDRESS, write).await?;
+            self.read_no_stop(address, read).await?;
+            self.stop().await
+        })
+        .await;
+
+        self.release_on_timeout(res)
+    }
+
+    /// Run a DMA transfer to or from MSTDAT until it completes or the bus reports an error
+    async fn wait_dma(&self, transfer: dma::transfer::Transfer<'_>) -> Result<()> {
+        let i2cregs = self.info.regs;
+
+        // According to sections 24.7.7.1 and 24.7.7.2, we should
+        // first program the DMA channel for carrying out a transfer
+        // and only then set MSTDMA bit.
+        //
+        // Additionally, at this point we know the slave has
+        // acknowledged the address.
+        i2cregs.mstctl().write(|w| w.mstdma().enabled());
+
+        let res = select(
+            transfer,
+            poll_fn(|cx| {
+                I2C_WAKERS[self.info.index].register(cx.waker());
+
+                i2cregs.intenset().write(|w| {
+                    w.mstpendingen()
+                        .set_bit()
+                        .mstarblossen()
+                        .set_bit()
+                        .mstststperren()
+                        .set_bit()
+                });
+
+                let stat = i2cregs.stat().read();
+
+                if stat.mstarbloss().is_arbitration_loss() {
+                    Poll::Ready(Err::<(), Error>(TransferError::ArbitrationLoss.into()))
+                } else if stat.mstststperr().is_error() {
+                    Poll::Ready(Err::<(), Error>(TransferError::StartStopError.into()))
+                } else {
+                    Poll::Pending
+                }
+            }),
+        )
+        .await;
+
+        i2cregs.mstctl().write(|w| w.mstdma().disabled());
+
+        match res {
+            Either::First(r) => Ok(r?),
+            Either::Second(r) => r,
+        }
+    }
+
+    /// Run `op`, aborting it with [`TransferError::Timeout`] once the hardware event timeout fires.
+    ///
+    /// Never times out while the event timeout is disabled.
+    async fn with_event_timeout<U>(
+        i2cregs: &'static crate::pac::i2c0::RegisterBlock,
+        index: usize,
+        op: impl Future<Output = Result<U>>,
+    ) -> Result<U> {
+        let timeout = poll_fn(|cx| {
+            I2C_WAKERS[index].register(cx.waker());
+
+            if i2cregs.stat().read().eventtimeout().bit_is_set() {
+                Poll::Ready(())
+            } else {
+                i2cregs.intenset().write(|w| w.eventtimeouten().set_bit());
+                Poll::Pending
+            }
+        });
+
+        match select(op, timeout).await {
+            Either::First(res) => res,
+            Either::Second(()) => Err(TransferError::Timeout.into()),
+        }
+    }
+
     /// Calls `f` to check if we are ready or not.
     /// If not, `g` is called once the waker is set (to eg enable the required interrupts).
     async fn wait_on<F, U, G>(&mut self, mut f: F, mut g: G) -> U
@@ -867,7 +1138,9 @@ impl embedded_hal_1::i2c::Error for Error {
                 TransferError::ArbitrationLoss => embedded_hal_1::i2c::ErrorKind::ArbitrationLoss,
                 TransferError::StartStopError => embedded_hal_1::i2c::ErrorKind::Bus,
                 TransferError::OtherBusError => embedded_hal_1::i2c::ErrorKind::Bus,
+                TransferError::ZeroLengthRead => embedded_hal_1::i2c::ErrorKind::Other,
             },
+            Self::Dma(_) => embedded_hal_1::i2c::ErrorKind::Other,
         }
     }
 }
@@ -879,82 +1152,120 @@ impl<M: Mode> embedded_hal_1::i2c::ErrorType for I2cMaster<'_, M> {
 // implement generic i2c interface for peripheral master type
 impl<A: embedded_hal_1::i2c::AddressMode + Into<u16>> embedded_hal_1::i2c::I2c<A> for I2cMaster<'_, Blocking> {
     fn read(&mut self, address: A, read: &mut [u8]) -> Result<()> {
-        self.read_no_stop(address.into(), read)?;
-        self.stop()
+        let res = self.read_no_stop(address.into(), read).and_then(|_| self.stop());
+        self.release_on_timeout(res)
     }
 
     fn write(&mut self, address: A, write: &[u8]) -> Result<()> {
-        self.write_no_stop(address.into(), write)?;
-        self.stop()
+        let res = self.write_no_stop(address.into(), write).and_then(|_| self.stop());
+        self.release_on_timeout(res)
     }
 
     fn write_read(&mut self, address: A, write: &[u8], read: &mut [u8]) -> Result<()> {
         let address = address.into();
-        self.write_no_stop(address, write)?;
-        self.read_no_stop(address, read)?;
-        self.stop()
+        let res = self
+            .write_no_stop(address, write)
+            .and_then(|_| self.read_no_stop(address, read))
+            .and_then(|_| self.stop());
+        self.release_on_timeout(res)
     }
 
     fn transaction(&mut self, address: A, operations: &mut [embedded_hal_1::i2c::Operation<'_>]) -> Result<()> {
         let needs_stop = !operations.is_empty();
         let address = address.into();
 
-        for op in operations {
-            match op {
-                embedded_hal_1::i2c::Operation::Read(read) => {
-                    self.read_no_stop(address, read)?;
-                }
-                embedded_hal_1::i2c::Operation::Write(write) => {
-                    self.write_no_stop(address, write)?;
+        let run = || {
+            for op in operations {
+                match op {
+                    embedded_hal_1::i2c::Operation::Read(read) => {
+                        self.read_no_stop(address, read)?;
+                    }
+                    embedded_hal_1::i2c::Operation::Write(write) => {
+                        self.write_no_stop(address, write)?;
+                    }
                 }
             }
-        }
 
-        if needs_stop {
-            self.stop()?;
-        }
+            if needs_stop {
+                self.stop()?;
+            }
 
-        Ok(())
+            Ok(())
+        };
+
+        let res = run();
+        self.release_on_timeout(res)
     }
 }
 
 impl<A: embedded_hal_1::i2c::AddressMode + Into<u16>> embedded_hal_async::i2c::I2c<A> for I2cMaster<'_, Async> {
     async fn read(&mut self, address: A, read: &mut [u8]) -> Result<()> {
-        self.read_no_stop(address.into(), read).await?;
-        self.stop().await
+        let (i2cregs, index) = (self.info.regs, self.info.index);
+        self.clear_timeout_flag();
+
+        let res = Self::with_event_timeout(i2cregs, index, async {
+            self.read_no_stop(address.into(), read).await?;
+            self.stop().await
+        })
+        .await;
+
+        self.release_on_timeout(res)
     }
 
     async fn write(&mut self, address: A, write: &[u8]) -> Result<()> {
-        self.write_no_stop(address.into(), write).await?;
-        self.stop().await
+        let (i2cregs, index) = (self.info.regs, self.info.index);
+        self.clear_timeout_flag();
+
+        let res = Self::with_event_timeout(i2cregs, index, async {
+            self.write_no_stop(address.into(), write).await?;
+            self.stop().await
+        })
+        .await;
+
+        self.release_on_timeout(res)
     }
 
     async fn write_read(&mut self, address: A, write: &[u8], read: &mut [u8]) -> Result<()> {
+        let (i2cregs, index) = (self.info.regs, self.info.index);
         let address = address.into();
-        self.write_no_stop(address, write).await?;
-        self.read_no_stop(address, read).await?;
-        self.stop().await
+        self.clear_timeout_flag();
+
+        let res = Self::with_event_timeout(i2cregs, index, async {
+            self.write_no_stop(address, write).await?;
+            self.read_no_stop(address, read).await?;
+            self.stop().await
+        })
+        .await;
+
+        self.release_on_timeout(res)
     }
 
     async fn transaction(&mut self, address: A, operations: &mut [embedded_hal_1::i2c::Operation<'_>]) -> Result<()> {
+        let (i2cregs, index) = (self.info.regs, self.info.index);
         let needs_stop = !operations.is_empty();
         let address = address.into();
+        self.clear_timeout_flag();
 
-        for op in operations {
-            match op {
-                embedded_hal_1::i2c::Operation::Read(read) => {
-                    self.read_no_stop(address, read).await?;
-                }
-                embedded_hal_1::i2c::Operation::Write(write) => {
-                    self.write_no_stop(address, write).await?;
+        let res = Self::with_event_timeout(i2cregs, index, async {
+            for op in operations {
+                match op {
+                    embedded_hal_1::i2c::Operation::Read(read) => {
+                        self.read_no_stop(address, read).await?;
+                    }
+                    embedded_hal_1::i2c::Operation::Write(write) => {
+                        self.write_no_stop(address, write).await?;
+                    }
                 }
             }
-        }
 
-        if needs_stop {
-            self.stop().await?;
-        }
+            if needs_stop {
+                self.stop().await?;
+            }
 
-        Ok(())
+            Ok(())
+        })
+        .await;
+
+        self.release_on_timeout(res)
     }
 }
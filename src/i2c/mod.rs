@@ -16,6 +16,9 @@ pub mod master;
 /// I2C Slave Driver
 pub mod slave;
 
+/// I2C Bus Monitor Driver
+pub mod monitor;
+
 /// shorthand for -> `Result<T>`
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -52,6 +55,8 @@ pub enum TransferError {
     StartStopError,
     /// state mismatch or other internal register unexpected state
     OtherBusError,
+    /// Read of zero bytes, the master has no way to NACK an address-only read
+    ZeroLengthRead,
 }
 
 /// Error information type
@@ -63,6 +68,15 @@ pub enum Error {
 
     /// transaction failure types
     Transfer(TransferError),
+
+    /// DMA transfer could not be set up
+    Dma(dma::Error),
+}
+
+impl From<dma::Error> for Error {
+    fn from(value: dma::Error) -> Self {
+        Error::Dma(value)
+    }
 }
 
 impl From<TransferError> for Error {
@@ -83,6 +97,10 @@ struct Info {
     index: usize,
 }
 
+// SAFETY: the register block is static MMIO, and a driver owning the FLEXCOMM instance is its
+// only user, so moving it to another executor can't create concurrent access.
+unsafe impl Send for Info {}
+
 trait SealedInstance {
     fn info() -> Info;
     fn index() -> usize;
@@ -135,9 +153,27 @@ impl_instance!(0, 1, 2, 3, 4, 5, 6, 7, 15);
 const I2C_COUNT: usize = 9;
 static I2C_WAKERS: [AtomicWaker; I2C_COUNT] = [const { AtomicWaker::new() }; I2C_COUNT];
 
+/// I2C bus address, addresses above 0x7F are sent as 10-bit addresses
+pub type BusAddress = u16;
+
 /// Ten bit addresses start with first byte 0b11110XXX
 pub const TEN_BIT_PREFIX: u8 = 0b11110 << 3;
 
+/// 7-bit addresses a bus scan probes, skipping the addresses reserved by the I2C specification
+pub const SCAN_ADDRESSES: core::ops::RangeInclusive<BusAddress> = 0x08..=0x77;
+
+/// General call address, writing to it broadcasts the data to all slaves.
+///
+/// The controller sends it like any other 7-bit address (address byte 0x00, R/W = 0),
+/// followed by a general call command byte.
+pub const GENERAL_CALL_ADDRESS: BusAddress = 0x00;
+
+/// General call command: reset and write the programmable part of the slave address
+pub const GENERAL_CALL_RESET: u8 = 0x06;
+
+/// General call command: write the programmable part of the slave address, without reset
+pub const GENERAL_CALL_WRITE_ADDRESS: u8 = 0x04;
+
 /// I2C interrupt handler.
 pub struct InterruptHandler<T: Instance> {
     _phantom: PhantomData<T>,
@@ -161,6 +197,10 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
             i2c.intenclr().write(|w| w.mstststperrclr().set_bit());
         }
 
+        if i2c.intstat().read().eventtimeout().bit_is_set() {
+            i2c.intenclr().write(|w| w.eventtimeoutclr().set_bit());
+        }
+
         if i2c.intstat().read().slvpending().bit_is_set() {
             i2c.intenclr().write(|w| w.slvpendingclr().set_bit());
         }
@@ -169,6 +209,11 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
             i2c.intenclr().write(|w| w.slvdeselclr().set_bit());
         }
 
+        // The monitor keeps its interrupts enabled and queues events itself
+        if monitor::is_pending(i2c) {
+            monitor::on_interrupt(T::index(), i2c);
+        }
+
         waker.wake();
     }
 }
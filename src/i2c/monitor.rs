@@ -0,0 +1,306 @@
+//! I2C bus monitor, passively captures all traffic on the bus
+
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use core::task::Poll;
+
+use embassy_hal_internal::{into_ref, Peripheral};
+
+use super::{Error, Info, Instance, InterruptHandler, Result, SclPin, SdaPin, I2C_COUNT, I2C_WAKERS};
+use crate::clocks::ClockGuard;
+use crate::interrupt;
+use crate::interrupt::typelevel::Interrupt;
+
+/// Record: received byte
+const RECORD_DATA: u32 = 0xFF;
+/// Record: byte follows a start condition
+const RECORD_START: u32 = 1 << 8;
+/// Record: byte follows a repeated start condition
+const RECORD_RESTART: u32 = 1 << 9;
+/// Record: byte was not acknowledged
+const RECORD_NACK: u32 = 1 << 10;
+/// Record marker: the bus went idle after a stop
+const RECORD_STOP: u32 = 1 << 30;
+/// Record marker: records were lost before this point
+const RECORD_OVERFLOW: u32 = 1 << 31;
+
+/// Smallest ring buffer, one entry is kept free for the overflow marker
+const MIN_BUFFER_LEN: usize = 2;
+
+/// Capture ring buffer shared between the interrupt handler and the monitor driver
+struct MonitorState {
+    buf: AtomicPtr<u32>,
+    len: AtomicUsize,
+    /// Next entry written by the interrupt handler
+    head: AtomicUsize,
+    /// Next entry read by the driver
+    tail: AtomicUsize,
+    /// Records are being dropped, the overflow marker was already written
+    gap: AtomicBool,
+}
+
+impl MonitorState {
+    const fn new() -> Self {
+        Self {
+            buf: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            gap: AtomicBool::new(false),
+        }
+    }
+
+    /// Stores a record, or an overflow marker in the last free entry
+    fn push(&self, record: u32) {
+        let buf = self.buf.load(Ordering::Relaxed);
+        let len = self.len.load(Ordering::Relaxed);
+        if buf.is_null() {
+            return;
+        }
+
+        let head = self.head.load(Ordering::Relaxed);
+        let used = head.wrapping_sub(self.tail.load(Ordering::Acquire));
+
+        let record = if used + 1 < len {
+            self.gap.store(false, Ordering::Relaxed);
+            record
+        } else if used + 1 == len && !self.gap.load(Ordering::Relaxed) {
+            self.gap.store(true, Ordering::Relaxed);
+            RECORD_OVERFLOW
+        } else {
+            return;
+        };
+
+        // SAFETY: the buffer outlives the monitor owning it, which clears `buf` before releasing
+        // it, and the entry at `head` is not read by the driver until `head` is advanced.
+        unsafe { buf.add(head % len).write_volatile(record) };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+    }
+
+    fn pop(&self) -> Option<u32> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let len = self.len.load(Ordering::Relaxed);
+        // SAFETY: entries between tail and head were written by the interrupt handler and are
+        // not touched again until `tail` is advanced.
+        let record = unsafe { self.buf.load(Ordering::Relaxed).add(tail % len).read_volatile() };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+
+        Some(record)
+    }
+}
+
+static MONITOR_STATE: [MonitorState; I2C_COUNT] = [const { MonitorState::new() }; I2C_COUNT];
+
+/// Returns whether one of the monitor interrupts is pending
+pub(super) fn is_pending(i2c: &crate::pac::i2c0::RegisterBlock) -> bool {
+    let intstat = i2c.intstat().read();
+    intstat.monrdy().bit_is_set() || intstat.monov().bit_is_set() || intstat.monidle().bit_is_set()
+}
+
+/// Drains the monitor receive data and bus events into the ring buffer of instance `index`
+pub(super) fn on_interrupt(index: usize, i2c: &crate::pac::i2c0::RegisterBlock) {
+    let state = &MONITOR_STATE[index];
+
+    loop {
+        let stat = i2c.stat().read();
+
+        // Data received before the overrun is still valid, the overrun marks the gap after it
+        if stat.monrdy().bit_is_set() {
+            let dat = i2c.monrxdat().read();
+
+            let mut record = u32::from(dat.monrxdat().bits());
+            if dat.monstart().bit_is_set() {
+                record |= RECORD_START;
+            }
+            if dat.monrestart().bit_is_set() {
+                record |= RECORD_RESTART;
+            }
+            if dat.monnack().bit_is_set() {
+                record |= RECORD_NACK;
+            }
+            state.push(record);
+        } else if stat.monov().bit_is_set() {
+            i2c.stat().write(|w| w.monov().overrun());
+            state.push(RECORD_OVERFLOW);
+        } else if stat.monidle().bit_is_set() {
+            i2c.stat().write(|w| w.monidle().idle());
+            state.push(RECORD_STOP);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Bus event captured by the monitor
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MonitorEvent {
+    /// Start or repeated start followed by the address byte
+    Start {
+        /// 7-bit address, or the first address byte of a 10-bit address shifted right by one
+        address: u8,
+        /// Read transfer
+        read: bool,
+        /// Repeated start
+        repeated: bool,
+        /// Address acknowledged by a slave
+        acked: bool,
+    },
+    /// Data byte acknowledged by the receiver
+    Data(u8),
+    /// Data byte not acknowledged by the receiver
+    NackedData(u8),
+    /// Stop, the bus is idle
+    Stop,
+    /// Events were lost here, because the monitor or the ring buffer overflowed
+    Overflow,
+}
+
+impl From<u32> for MonitorEvent {
+    fn from(record: u32) -> Self {
+        let data = (record & RECORD_DATA) as u8;
+
+        if record & RECORD_OVERFLOW != 0 {
+            MonitorEvent::Overflow
+        } else if record & RECORD_STOP != 0 {
+            MonitorEvent::Stop
+        } else if record & (RECORD_START | RECORD_RESTART) != 0 {
+            MonitorEvent::Start {
+                address: data >> 1,
+                read: data & 1 != 0,
+                repeated: record & RECORD_RESTART != 0,
+                acked: record & RECORD_NACK == 0,
+            }
+        } else if record & RECORD_NACK != 0 {
+            MonitorEvent::NackedData(data)
+        } else {
+            MonitorEvent::Data(data)
+        }
+    }
+}
+
+/// use `FCn` as a passive I2C bus monitor
+///
+/// The monitor never drives SDA, it only sees what the other devices on the bus send. Captured
+/// events are queued by the interrupt handler into the ring buffer given to [`I2cMonitor::new`],
+/// so bursts of traffic are not lost while the application is busy.
+pub struct I2cMonitor<'a> {
+    info: Info,
+    index: usize,
+    _clock: ClockGuard,
+    _phantom: PhantomData<&'a mut [u32]>,
+}
+
+impl<'a> I2cMonitor<'a> {
+    /// use flexcomm fc with Pins scl, sda as a bus monitor, queueing events into `buf`
+    ///
+    /// With `clock_stretch` the monitor holds SCL low until the interrupt handler has read its
+    /// receive data, so the capture hardware never overruns but the bus is slowed down by the
+    /// monitor. Without it the bus is left alone. Either way an [`MonitorEvent::Overflow`] marks
+    /// each gap in the trace, including events dropped because `buf` was full. `buf` must hold
+    /// at least 2 entries.
+    pub fn new<T: Instance>(
+        _bus: impl Peripheral<P = T> + 'a,
+        scl: impl Peripheral<P = impl SclPin<T>> + 'a,
+        sda: impl Peripheral<P = impl SdaPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        buf: &'a mut [u32],
+        clock_stretch: bool,
+    ) -> Result<Self> {
+        if buf.len() < MIN_BUFFER_LEN {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        into_ref!(_bus);
+        into_ref!(scl);
+        into_ref!(sda);
+
+        // TODO - clock integration
+        let clock = crate::flexcomm::Clock::Sfro;
+        let _clock = T::enable(clock);
+        T::into_i2c();
+
+        sda.as_sda();
+        scl.as_scl();
+
+        let info = T::info();
+        let i2c = info.regs;
+        let index = T::index();
+
+        let state = &MONITOR_STATE[index];
+        critical_section::with(|_| {
+            state.head.store(0, Ordering::Relaxed);
+            state.tail.store(0, Ordering::Relaxed);
+            state.gap.store(false, Ordering::Relaxed);
+            state.len.store(buf.len(), Ordering::Relaxed);
+            state.buf.store(buf.as_mut_ptr(), Ordering::Relaxed);
+        });
+
+        // Sample the bus with the undivided function clock
+        i2c.clkdiv().write(|w|
+            // SAFETY: only unsafe due to .bits usage
+            unsafe { w.divval().bits(0) });
+
+        // Start from a clean monitor state
+        i2c.stat().write(|w| w.monov().overrun().monidle().idle());
+
+        // Only the monitor is enabled
+        i2c.cfg().write(|w| w.monen().enabled().monclkstr().bit(clock_stretch));
+
+        i2c.intenset()
+            .write(|w| w.monrdyen().set_bit().monoven().set_bit().monidleen().set_bit());
+
+        T::Interrupt::unpend();
+        unsafe { T::Interrupt::enable() };
+
+        Ok(Self {
+            info,
+            index,
+            _clock,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wait for the next captured bus event
+    pub async fn next_event(&mut self) -> MonitorEvent {
+        let state = &MONITOR_STATE[self.index];
+
+        poll_fn(|cx| {
+            I2C_WAKERS[self.index].register(cx.waker());
+
+            match state.pop() {
+                Some(record) => Poll::Ready(record.into()),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Returns the next captured bus event if one is queued
+    pub fn try_next_event(&mut self) -> Option<MonitorEvent> {
+        MONITOR_STATE[self.index].pop().map(MonitorEvent::from)
+    }
+}
+
+impl Drop for I2cMonitor<'_> {
+    fn drop(&mut self) {
+        let i2c = self.info.regs;
+
+        i2c.intenclr()
+            .write(|w| w.monrdyclr().set_bit().monovclr().set_bit().monidleclr().set_bit());
+        i2c.cfg().write(|w| w.monen().disabled());
+
+        // The interrupt handler must not see the buffer once it is handed back
+        let state = &MONITOR_STATE[self.index];
+        critical_section::with(|_| {
+            state.buf.store(ptr::null_mut(), Ordering::Relaxed);
+            state.len.store(0, Ordering::Relaxed);
+        });
+    }
+}
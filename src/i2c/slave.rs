@@ -4,18 +4,24 @@ use core::future::poll_fn;
 use core::marker::PhantomData;
 use core::task::Poll;
 
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{into_ref, Peripheral};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::signal::Signal;
 
 use super::{
     Async, Blocking, Info, Instance, InterruptHandler, Mode, Result, SclPin, SdaPin, SlaveDma, TransferError,
     I2C_WAKERS, TEN_BIT_PREFIX,
 };
+use crate::clocks::ClockGuard;
 use crate::interrupt::typelevel::Interrupt;
 use crate::pac::i2c0::stat::Slvstate;
 use crate::{dma, interrupt};
 
 /// Address errors
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AddressError {
     /// Invalid address conversion
     InvalidConversion,
@@ -31,6 +37,16 @@ pub enum Address {
 }
 
 impl Address {
+    /// General call address (0x00).
+    ///
+    /// A master write to this address is a broadcast to every slave on the bus that
+    /// acknowledges general calls. The first data byte is the general call command, such as
+    /// [`super::GENERAL_CALL_RESET`]. Giving it to a slave makes that slave listen for
+    /// broadcasts only, use [`I2cSlave::enable_general_call`] to receive them next to the
+    /// slave's own address. It is deliberately not accepted by [`Address::new`], which only
+    /// covers addresses that can be assigned to a single device.
+    pub const GENERAL_CALL: Address = Address::SevenBit(0x00);
+
     /// Construct a 7-bit address type
     #[must_use]
     pub const fn new(addr: u8) -> Option<Self> {
@@ -115,6 +131,9 @@ pub enum Command {
 
     /// I2C Write
     Write,
+
+    /// I2C Write to the general call address, see [`I2cSlave::enable_general_call`]
+    GeneralCall,
 }
 
 /// Result of response functions
@@ -126,10 +145,44 @@ pub enum Response {
     Pending(usize),
 }
 
+/// Direction of the transaction following a read, see [`I2cSlave::respond_and_listen`]
+pub enum I2cSlaveDirection {
+    /// The master is reading, answer with [`I2cSlave::respond_to_read`] or
+    /// [`I2cSlave::respond_and_listen`]
+    Read,
+
+    /// The master wrote, the data went into the request buffer
+    Write(Response),
+}
+
+/// Bus event seen by the slave, see [`I2cSlave::next_event`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum I2cSlaveEvent {
+    /// The master addressed this slave, the address has been acknowledged
+    AddressMatch,
+
+    /// The master wrote to the general call address, the address has been acknowledged
+    GeneralCall,
+
+    /// The master wrote a byte, it has been acknowledged
+    DataReceived(u8),
+
+    /// The master is reading, answer with [`I2cSlave::respond_to_read`]
+    ReadRequested,
+
+    /// The master ended the transaction with a STOP
+    StopCondition,
+
+    /// The transaction was aborted, the master ended a read the slave had not answered yet
+    NackError,
+}
+
 /// use `FCn` as I2C Slave controller
 pub struct I2cSlave<'a, M: Mode> {
     info: Info,
-    _phantom: PhantomData<M>,
+    _clock: ClockGuard,
+    _phantom: PhantomData<(&'a (), M)>,
     dma_ch: Option<dma::channel::Channel<'a>>,
     ten_bit_info: Option<TenBitAddressInfo>,
 }
@@ -143,6 +196,7 @@ impl<'a, M: Mode> I2cSlave<'a, M> {
         // TODO - integrate clock APIs to allow dynamic freq selection | clock: crate::flexcomm::Clock,
         address: Address,
         dma_ch: Option<dma::channel::Channel<'a>>,
+        _clock: ClockGuard,
     ) -> Result<Self> {
         into_ref!(_bus);
         into_ref!(scl);
@@ -196,11 +250,37 @@ impl<'a, M: Mode> I2cSlave<'a, M> {
 
         Ok(Self {
             info,
+            _clock,
             _phantom: PhantomData,
             dma_ch,
             ten_bit_info,
         })
     }
+
+    /// Also acknowledge general calls (address 0x00), in addition to the slave's own address.
+    ///
+    /// [`I2cSlave::listen`] reports them as [`Command::GeneralCall`] instead of [`Command::Write`],
+    /// so broadcasts can be told apart from writes addressed to this slave.
+    pub fn enable_general_call(&mut self, enable: bool) {
+        // Address 1 is not used otherwise, address 0 holds the slave's own address
+        self.info.regs.slvadr(GENERAL_CALL_SLVADR).write(|w| {
+            // SAFETY: unsafe only required due to use of unnamed "bits" field
+            let w = unsafe { w.slvadr().bits(0x00) };
+            if enable {
+                w.sadisable().enabled()
+            } else {
+                w.sadisable().disabled()
+            }
+        });
+    }
+}
+
+/// Slave address match register used for the general call address
+const GENERAL_CALL_SLVADR: usize = 1;
+
+/// Whether the address byte the slave is addressed with is the general call address
+fn is_general_call(address_byte: u8) -> bool {
+    address_byte == 0x00
 }
 
 impl<'a> I2cSlave<'a, Blocking> {
@@ -214,10 +294,10 @@ impl<'a> I2cSlave<'a, Blocking> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Sfro;
-        T::enable(clock);
+        let clock = T::enable(clock);
         T::into_i2c();
 
-        Self::new_inner::<T>(_bus, scl, sda, address, None)
+        Self::new_inner::<T>(_bus, scl, sda, address, None, clock)
     }
 
     fn poll(&self) -> Result<()> {
@@ -228,7 +308,8 @@ impl<'a> I2cSlave<'a, Blocking> {
         Ok(())
     }
 
-    fn block_until_addressed(&self) -> Result<()> {
+    /// Returns the address byte the slave was addressed with
+    fn block_until_addressed(&self) -> Result<u8> {
         self.poll()?;
 
         let i2c = self.info.regs;
@@ -236,8 +317,9 @@ impl<'a> I2cSlave<'a, Blocking> {
             return Err(TransferError::AddressNack.into());
         }
 
+        let address_byte = i2c.slvdat().read().data().bits();
         i2c.slvctl().write(|w| w.slvcontinue().continue_());
-        Ok(())
+        Ok(address_byte)
     }
 }
 
@@ -254,13 +336,13 @@ impl<'a> I2cSlave<'a, Async> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Sfro;
-        T::enable(clock);
+        let clock = T::enable(clock);
         T::into_i2c();
 
         let ch = dma::Dma::reserve_channel(dma_ch);
 
         if ch.is_some() {
-            let this = Self::new_inner::<T>(_bus, scl, sda, address, Some(ch.unwrap()))?;
+            let this = Self::new_inner::<T>(_bus, scl, sda, address, Some(ch.unwrap()), clock)?;
 
             T::Interrupt::unpend();
             unsafe { T::Interrupt::enable() };
@@ -277,7 +359,7 @@ impl I2cSlave<'_, Blocking> {
     pub fn listen(&self) -> Result<Command> {
         let i2c = self.info.regs;
 
-        self.block_until_addressed()?;
+        let general_call = is_general_call(self.block_until_addressed()?);
 
         //Block until we know it is read or write
         self.poll()?;
@@ -328,6 +410,7 @@ impl I2cSlave<'_, Blocking> {
 
         let state = i2c.stat().read().slvstate().variant();
         match state {
+            Some(Slvstate::SlaveReceive) if general_call => Ok(Command::GeneralCall),
             Some(Slvstate::SlaveReceive) => Ok(Command::Write),
             Some(Slvstate::SlaveTransmit) => Ok(Command::Read),
             _ => Err(TransferError::OtherBusError.into()),
@@ -443,12 +526,14 @@ impl I2cSlave<'_, Async> {
             self.poll_sw_action().await;
         }
 
-        if i2c.stat().read().slvstate().is_slave_address() {
+        let general_call = if i2c.stat().read().slvstate().is_slave_address() {
+            let general_call = is_general_call(i2c.slvdat().read().data().bits());
             i2c.slvctl().write(|w| w.slvcontinue().continue_());
+            general_call
         } else {
             // If we are not addressed here, then we have issues.
             return Err(TransferError::OtherBusError.into());
-        }
+        };
 
         // Poll for HW to transitioning from addressed to receive/transmit
         self.poll_sw_action().await;
@@ -499,6 +584,7 @@ impl I2cSlave<'_, Async> {
 
         let state = i2c.stat().read().slvstate().variant();
         match state {
+            Some(Slvstate::SlaveReceive) if general_call => Ok(Command::GeneralCall),
             Some(Slvstate::SlaveReceive) => Ok(Command::Write),
             Some(Slvstate::SlaveTransmit) => Ok(Command::Read),
             _ => Err(TransferError::OtherBusError.into()),
@@ -533,7 +619,7 @@ impl I2cSlave<'_, Async> {
             self.dma_ch
                 .as_ref()
                 .unwrap()
-                .read_from_peripheral(i2c.slvdat().as_ptr() as *mut u8, buf, options);
+                .read_from_peripheral(i2c.slvdat().as_ptr() as *mut u8, buf, options)?;
 
         poll_fn(|cx| {
             let i2c = self.info.regs;
@@ -606,7 +692,7 @@ impl I2cSlave<'_, Async> {
             self.dma_ch
                 .as_ref()
                 .unwrap()
-                .write_to_peripheral(buf, i2c.slvdat().as_ptr() as *mut u8, options);
+                .write_to_peripheral(buf, i2c.slvdat().as_ptr() as *mut u8, options)?;
 
         poll_fn(|cx| {
             let i2c = self.info.regs;
@@ -651,6 +737,99 @@ impl I2cSlave<'_, Async> {
         Err(TransferError::WriteFail.into())
     }
 
+    /// Answer the current read with `response`, then wait for the next transaction.
+    ///
+    /// When the master follows up with a repeated start, the slave is still holding the bus in the
+    /// addressed state and the new address is acknowledged right away, without waiting for a
+    /// STOP. Otherwise this waits for the next time the slave is addressed, like
+    /// [`Self::listen`]. If the next transaction is a write, it is received into `next_request`,
+    /// a write without data is reported as `Write(Response::Complete(0))`.
+    pub async fn respond_and_listen(&mut self, response: &[u8], next_request: &mut [u8]) -> Result<I2cSlaveDirection> {
+        self.respond_to_read(response).await?;
+
+        match self.listen().await? {
+            Command::Read => Ok(I2cSlaveDirection::Read),
+            Command::Write | Command::GeneralCall => {
+                Ok(I2cSlaveDirection::Write(self.respond_to_write(next_request).await?))
+            }
+            Command::Probe => Ok(I2cSlaveDirection::Write(Response::Complete(0))),
+        }
+    }
+
+    /// Wait for the next bus event.
+    ///
+    /// Written data is acknowledged byte by byte as it is reported. A read by the master is
+    /// reported as [`I2cSlaveEvent::ReadRequested`] until it is answered with
+    /// [`I2cSlave::respond_to_read`], which completes the whole read.
+    pub async fn next_event(&mut self) -> I2cSlaveEvent {
+        let i2c = self.info.regs;
+
+        // Data is handled byte by byte
+        i2c.slvctl().write(|w| w.slvdma().disabled());
+
+        loop {
+            let stat = i2c.stat().read();
+            if !stat.slvpending().is_pending() && !stat.slvdesel().is_deselected() {
+                self.poll_sw_action().await;
+            }
+
+            let stat = i2c.stat().read();
+            if stat.slvdesel().is_deselected() {
+                // Clear the deselected bit, a pending new address is reported on the next call
+                i2c.stat().write(|w| w.slvdesel().deselected());
+
+                // A read still waiting for data means the master NACKed and stopped early
+                if stat.slvpending().is_pending() && stat.slvstate().is_slave_transmit() {
+                    return I2cSlaveEvent::NackError;
+                }
+                return I2cSlaveEvent::StopCondition;
+            }
+
+            match stat.slvstate().variant() {
+                Some(Slvstate::SlaveAddress) => {
+                    let general_call = is_general_call(i2c.slvdat().read().data().bits());
+                    i2c.slvctl().write(|w| w.slvcontinue().continue_());
+
+                    if general_call {
+                        return I2cSlaveEvent::GeneralCall;
+                    }
+
+                    // A 10 bit write is only ours once the second address byte matches
+                    if let Some(ten_bit_address) = self.ten_bit_info {
+                        if i2c.slvdat().read().data().bits() == ten_bit_address.first_byte {
+                            self.poll_sw_action().await;
+
+                            let stat = i2c.stat().read();
+                            if !stat.slvpending().is_pending() || !stat.slvstate().is_slave_receive() {
+                                continue;
+                            }
+
+                            if i2c.slvdat().read().data().bits() == ten_bit_address.second_byte {
+                                i2c.slvctl().write(|w| w.slvcontinue().continue_());
+                            } else {
+                                i2c.slvctl().write(|w| w.slvnack().nack());
+                                continue;
+                            }
+                        }
+                    }
+
+                    return I2cSlaveEvent::AddressMatch;
+                }
+                Some(Slvstate::SlaveReceive) => {
+                    let data = i2c.slvdat().read().data().bits();
+                    i2c.slvctl().write(|w| w.slvcontinue().continue_());
+
+                    return I2cSlaveEvent::DataReceived(data);
+                }
+                Some(Slvstate::SlaveTransmit) => return I2cSlaveEvent::ReadRequested,
+                _ => {
+                    i2c.slvctl().write(|w| w.slvnack().nack());
+                    return I2cSlaveEvent::NackError;
+                }
+            }
+        }
+    }
+
     async fn poll_sw_action(&self) {
         let i2c = self.info.regs;
 
@@ -687,3 +866,81 @@ impl I2cSlave<'_, Async> {
         xfer_count
     }
 }
+
+/// Async [`I2cSlave`] shared by a command receiving and a read answering task.
+///
+/// Hardware access is serialized by a mutex held for one operation at a time. Reads are handed
+/// from the [`I2cSlaveRx`] half, which receives all commands, to the [`I2cSlaveTx`] half, which
+/// answers them.
+pub struct I2cSlaveSplit<'d> {
+    slave: Mutex<CriticalSectionRawMutex, I2cSlave<'d, Async>>,
+    read_requested: Signal<CriticalSectionRawMutex, ()>,
+    read_answered: Signal<CriticalSectionRawMutex, ()>,
+}
+
+impl<'d> I2cSlaveSplit<'d> {
+    /// Share `slave` between the halves returned by [`I2cSlaveSplit::split`]
+    pub fn new(slave: I2cSlave<'d, Async>) -> Self {
+        Self {
+            slave: Mutex::new(slave),
+            read_requested: Signal::new(),
+            read_answered: Signal::new(),
+        }
+    }
+
+    /// Split the slave into a receiver and a transmitter, which is particularly useful when
+    /// having separate tasks for receiving commands and answering reads.
+    pub fn split(&'d self) -> (I2cSlaveRx<'d>, I2cSlaveTx<'d>) {
+        (I2cSlaveRx { shared: self }, I2cSlaveTx { shared: self })
+    }
+}
+
+/// Receiving half of an [`I2cSlaveSplit`]
+pub struct I2cSlaveRx<'d> {
+    shared: &'d I2cSlaveSplit<'d>,
+}
+
+impl I2cSlaveRx<'_> {
+    /// Listen for write commands from the I2C Master.
+    ///
+    /// Reads are passed on to [`I2cSlaveTx::respond_to_read`] and listening resumes once they
+    /// are answered, so this never returns [`Command::Read`]. The bus is stretched until the
+    /// transmitting half answers.
+    pub async fn listen(&mut self) -> Result<Command> {
+        loop {
+            let command = self.shared.slave.lock().await.listen().await?;
+
+            if let Command::Read = command {
+                self.shared.read_answered.reset();
+                self.shared.read_requested.signal(());
+                self.shared.read_answered.wait().await;
+            } else {
+                return Ok(command);
+            }
+        }
+    }
+
+    /// Respond to write command from master, see [`I2cSlave::respond_to_write`]
+    pub async fn respond_to_write(&mut self, buf: &mut [u8]) -> Result<Response> {
+        self.shared.slave.lock().await.respond_to_write(buf).await
+    }
+}
+
+/// Transmitting half of an [`I2cSlaveSplit`]
+pub struct I2cSlaveTx<'d> {
+    shared: &'d I2cSlaveSplit<'d>,
+}
+
+impl I2cSlaveTx<'_> {
+    /// Wait for a read received by [`I2cSlaveRx::listen`] and answer it with `buf`, see
+    /// [`I2cSlave::respond_to_read`].
+    pub async fn respond_to_read(&mut self, buf: &[u8]) -> Result<Response> {
+        let shared = self.shared;
+        shared.read_requested.wait().await;
+
+        // Hand the bus back to the receiver, even after an error or when this future is dropped
+        let _answered = OnDrop::new(|| shared.read_answered.signal(()));
+
+        shared.slave.lock().await.respond_to_read(buf).await
+    }
+}
@@ -91,6 +91,240 @@ pub enum Inverter {
     Enabled,
 }
 
+impl Inverter {
+    /// Input inverter for a signal whose polarity should be inverted or not.
+    ///
+    /// Drivers that detect edges on the pin use the same setting to translate the requested edge,
+    /// so active-low signals can be handled without swapping edges in the application.
+    #[must_use]
+    pub const fn with_polarity_inversion(invert: bool) -> Self {
+        if invert {
+            Self::Enabled
+        } else {
+            Self::Disabled
+        }
+    }
+
+    /// Returns whether the input inverter is enabled.
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        matches!(self, Self::Enabled)
+    }
+}
+
+/// Complete configuration of a pad.
+///
+/// Read from a pin with [`IopctlPin::get_config`] and written back with [`IopctlPin::apply_config`],
+/// so a pin borrowed for another use returns exactly to its previous setup. Build one from scratch,
+/// also in a `const`, with [`PinConfigBuilder`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PinConfig {
+    /// Function number
+    pub function: Function,
+    /// Pull-up/down resistor
+    pub pull: Pull,
+    /// Input buffer enabled
+    pub input_buffer: bool,
+    /// Slew rate
+    pub slew_rate: SlewRate,
+    /// Output drive strength
+    pub drive_strength: DriveStrength,
+    /// Analog multiplexer enabled
+    pub analog_multiplex: bool,
+    /// Output drive mode
+    pub drive_mode: DriveMode,
+    /// Input inverter
+    pub input_inverter: Inverter,
+}
+
+impl PinConfig {
+    /// Reset state of a pad
+    const RESET: Self = Self {
+        function: Function::F0,
+        pull: Pull::None,
+        input_buffer: false,
+        slew_rate: SlewRate::Standard,
+        drive_strength: DriveStrength::Normal,
+        analog_multiplex: false,
+        drive_mode: DriveMode::PushPull,
+        input_inverter: Inverter::Disabled,
+    };
+
+    /// Decodes a pad register.
+    ///
+    /// Reserved function numbers read back as [`Function::F0`], and the pull direction is only kept
+    /// while a resistor is enabled since it has no effect otherwise.
+    fn read(r: &iopctl::pio0_0::R) -> Self {
+        use iopctl::pio0_0::Fsel;
+
+        Self {
+            function: match r.fsel().variant() {
+                Some(Fsel::Function1) => Function::F1,
+                Some(Fsel::Function2) => Function::F2,
+                Some(Fsel::Function3) => Function::F3,
+                Some(Fsel::Function4) => Function::F4,
+                Some(Fsel::Function5) => Function::F5,
+                Some(Fsel::Function6) => Function::F6,
+                Some(Fsel::Function7) => Function::F7,
+                Some(Fsel::Function8) => Function::F8,
+                _ => Function::F0,
+            },
+            pull: match (r.pupdena().is_enabled(), r.pupdsel().is_pull_up()) {
+                (false, _) => Pull::None,
+                (true, true) => Pull::Up,
+                (true, false) => Pull::Down,
+            },
+            input_buffer: r.ibena().is_enabled(),
+            slew_rate: if r.slewrate().is_slow() {
+                SlewRate::Slow
+            } else {
+                SlewRate::Standard
+            },
+            drive_strength: if r.fulldrive().is_full_drive() {
+                DriveStrength::Full
+            } else {
+                DriveStrength::Normal
+            },
+            analog_multiplex: r.amena().is_enabled(),
+            drive_mode: if r.odena().is_enabled() {
+                DriveMode::OpenDrain
+            } else {
+                DriveMode::PushPull
+            },
+            input_inverter: Inverter::with_polarity_inversion(r.iiena().is_enabled()),
+        }
+    }
+
+    /// Encodes the configuration into every field of a pad register.
+    fn write<'w>(&self, w: &'w mut iopctl::pio0_0::W) -> &'w mut iopctl::pio0_0::W {
+        match self.function {
+            Function::F0 => w.fsel().function_0(),
+            Function::F1 => w.fsel().function_1(),
+            Function::F2 => w.fsel().function_2(),
+            Function::F3 => w.fsel().function_3(),
+            Function::F4 => w.fsel().function_4(),
+            Function::F5 => w.fsel().function_5(),
+            Function::F6 => w.fsel().function_6(),
+            Function::F7 => w.fsel().function_7(),
+            Function::F8 => w.fsel().function_8(),
+        };
+        match self.pull {
+            Pull::None => w.pupdena().disabled(),
+            Pull::Up => w.pupdena().enabled().pupdsel().pull_up(),
+            Pull::Down => w.pupdena().enabled().pupdsel().pull_down(),
+        };
+        match self.slew_rate {
+            SlewRate::Standard => w.slewrate().normal(),
+            SlewRate::Slow => w.slewrate().slow(),
+        };
+        match self.drive_strength {
+            DriveStrength::Normal => w.fulldrive().normal_drive(),
+            DriveStrength::Full => w.fulldrive().full_drive(),
+        };
+        match self.drive_mode {
+            DriveMode::PushPull => w.odena().disabled(),
+            DriveMode::OpenDrain => w.odena().enabled(),
+        };
+        match self.input_inverter {
+            Inverter::Disabled => w.iiena().disabled(),
+            Inverter::Enabled => w.iiena().enabled(),
+        };
+
+        w.ibena().bit(self.input_buffer).amena().bit(self.analog_multiplex)
+    }
+}
+
+/// Builder for a complete [`PinConfig`].
+///
+/// Starts from the reset state of a pad: function 0, no pull resistor, input buffer disabled,
+/// standard slew rate, normal drive, analog multiplexer disabled, push-pull and no input inverter.
+///
+/// ```rust,ignore
+/// const I2C_PAD: PinConfig = PinConfigBuilder::new()
+///     .function(Function::F1)
+///     .pull(Pull::Up)
+///     .input_buffer(true)
+///     .drive_mode(DriveMode::OpenDrain)
+///     .build();
+///
+/// p.PIO0_18.apply_config(&I2C_PAD);
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[must_use]
+pub struct PinConfigBuilder {
+    config: PinConfig,
+}
+
+impl PinConfigBuilder {
+    /// Starts from the reset state of a pad.
+    pub const fn new() -> Self {
+        Self {
+            config: PinConfig::RESET,
+        }
+    }
+
+    /// Sets the function number.
+    pub const fn function(mut self, function: Function) -> Self {
+        self.config.function = function;
+        self
+    }
+
+    /// Sets the pull-up/down resistor.
+    pub const fn pull(mut self, pull: Pull) -> Self {
+        self.config.pull = pull;
+        self
+    }
+
+    /// Enables or disables the input buffer.
+    pub const fn input_buffer(mut self, enabled: bool) -> Self {
+        self.config.input_buffer = enabled;
+        self
+    }
+
+    /// Sets the slew rate.
+    pub const fn slew_rate(mut self, slew_rate: SlewRate) -> Self {
+        self.config.slew_rate = slew_rate;
+        self
+    }
+
+    /// Sets the output drive strength.
+    pub const fn drive_strength(mut self, strength: DriveStrength) -> Self {
+        self.config.drive_strength = strength;
+        self
+    }
+
+    /// Enables or disables the analog multiplexer.
+    pub const fn analog_multiplex(mut self, enabled: bool) -> Self {
+        self.config.analog_multiplex = enabled;
+        self
+    }
+
+    /// Sets the output drive mode.
+    pub const fn drive_mode(mut self, mode: DriveMode) -> Self {
+        self.config.drive_mode = mode;
+        self
+    }
+
+    /// Sets the input inverter.
+    pub const fn input_inverter(mut self, inverter: Inverter) -> Self {
+        self.config.input_inverter = inverter;
+        self
+    }
+
+    /// Returns the finished configuration.
+    #[must_use]
+    pub const fn build(self) -> PinConfig {
+        self.config
+    }
+}
+
+impl Default for PinConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 trait SealedPin {}
 trait ToAnyPin: SealedPin {
     #[inline]
@@ -182,6 +416,15 @@ pub trait IopctlPin: SealedPin {
 
     /// Returns a pin to its reset state.
     fn reset(&self) -> &Self;
+
+    /// Reads back the complete pad configuration of a pin.
+    fn get_config(&self) -> PinConfig;
+
+    /// Applies a complete pad configuration with a single register write.
+    ///
+    /// Unlike a chain of the setters above, the pad never passes through a mix of the old and new
+    /// settings.
+    fn apply_config(&self, config: &PinConfig) -> &Self;
 }
 
 /// Represents a pin peripheral created at run-time from given port and pin numbers.
@@ -190,6 +433,10 @@ pub struct AnyPin {
     reg: &'static PioM_N,
 }
 
+// SAFETY: `reg` is the static IOPCTL register of this pin alone, and an `AnyPin` is the unique
+// owner of its port/pin pair (see `AnyPin::new`), so it may be moved between executors.
+unsafe impl Send for AnyPin {}
+
 impl AnyPin {
     /// Creates a pin from raw port and pin numbers which can then be configured.
     ///
@@ -229,6 +476,11 @@ impl AnyPin {
     pub fn pin_port(&self) -> usize {
         self.pin_port as usize
     }
+
+    /// Returns the current input inverter setting of the pin.
+    pub(crate) fn input_inverter(&self) -> Inverter {
+        Inverter::with_polarity_inversion(self.reg.read().iiena().is_enabled())
+    }
 }
 
 /// Represents a FC15 pin peripheral created at run-time from given pin number.
@@ -404,6 +656,15 @@ macro_rules! impl_iopctlpin {
                 self.reg.reset();
                 self
             }
+
+            fn get_config(&self) -> PinConfig {
+                PinConfig::read(&self.reg.read())
+            }
+
+            fn apply_config(&self, config: &PinConfig) -> &Self {
+                self.reg.write(|w| config.write(w));
+                self
+            }
         }
     };
 }
@@ -481,6 +742,21 @@ macro_rules! impl_FC15pin {
                 Self::to_raw($pin_no).reset();
                 self
             }
+
+            #[inline]
+            fn get_config(&self) -> PinConfig {
+                Self::to_raw($pin_no).get_config()
+            }
+
+            #[inline]
+            fn apply_config(&self, config: &PinConfig) -> &Self {
+                //No function configuration for FC15 pin
+                Self::to_raw($pin_no).apply_config(&PinConfig {
+                    function: Function::F0,
+                    ..*config
+                });
+                self
+            }
         }
     };
 }
@@ -555,6 +831,17 @@ macro_rules! impl_pin {
                 Self::to_raw($pin_port, $pin_no).reset();
                 self
             }
+
+            #[inline]
+            fn get_config(&self) -> PinConfig {
+                Self::to_raw($pin_port, $pin_no).get_config()
+            }
+
+            #[inline]
+            fn apply_config(&self, config: &PinConfig) -> &Self {
+                Self::to_raw($pin_port, $pin_no).apply_config(config);
+                self
+            }
         }
     };
 }
@@ -18,9 +18,13 @@ compile_error!(
 pub(crate) mod fmt;
 
 pub mod adc;
+pub mod chip_info;
 pub mod clocks;
 pub mod crc;
+pub mod debugport;
+pub mod delay;
 pub mod dma;
+pub(crate) mod errata;
 
 #[cfg(feature = "_espi")]
 pub mod espi;
@@ -29,11 +33,23 @@ pub mod flash;
 pub mod flexcomm;
 pub mod gpio;
 pub mod hashcrypt;
+pub mod hwvad;
 pub mod i2c;
 pub mod iopctl;
+pub mod otp;
+#[cfg(feature = "pmic-pca9420")]
+pub mod pmic;
+#[cfg(feature = "postmortem")]
+pub mod postmortem;
+pub mod prelude;
 pub mod pwm;
 pub mod rng;
+pub mod spi;
 /// Time driver for the iMX RT600 series.
+///
+/// The driver runs on the RTC: its 1 kHz wake timer provides the tick, general purpose registers
+/// GPREG0 to GPREG2 hold the extended counter and alarm, and it owns the RTC interrupt. It claims
+/// no CTIMER or OSTIMER, all CTIMER modules remain available to the application.
 #[cfg(feature = "time-driver")]
 pub mod time_driver;
 /// NXP Timer Driver for handling timer-related functionalities.
@@ -78,27 +94,62 @@ pub use crate::pac::NVIC_PRIO_BITS;
 /// });
 /// ```
 ///
+/// Several handlers can be bound to the same interrupt, and both interrupts and handlers can be
+/// guarded by `cfg` attributes, e.g. to use a flexcomm for a different driver per board variant:
+///
+/// ```rust,ignore
+/// use embassy_imxrt::{bind_interrupts, i2c, peripherals, uart};
+///
+/// bind_interrupts!(struct Irqs {
+///     #[cfg(feature = "board-a")]
+///     FLEXCOMM2 => i2c::InterruptHandler<peripherals::FLEXCOMM2>;
+///     #[cfg(feature = "board-b")]
+///     FLEXCOMM2 => uart::InterruptHandler<peripherals::FLEXCOMM2>;
+///     FLEXCOMM4 =>
+///         i2c::InterruptHandler<peripherals::FLEXCOMM4>,
+///         #[cfg(feature = "debug-uart")]
+///         uart::InterruptHandler<peripherals::FLEXCOMM4>;
+/// });
+/// ```
+///
 // developer note: this macro can't be in `embassy-hal-internal` due to the use of `$crate`.
 #[macro_export]
 macro_rules! bind_interrupts {
-    ($vis:vis struct $name:ident { $($irq:ident => $($handler:ty),*;)* }) => {
+    ($vis:vis struct $name:ident {
+        $(
+            $(#[cfg($cond_irq:meta)])?
+            $irq:ident => $(
+                $(#[cfg($cond_handler:meta)])?
+                $handler:ty
+            ),*;
+        )*
+    }) => {
             #[derive(Copy, Clone)]
             $vis struct $name;
 
         $(
             #[allow(non_snake_case)]
             #[no_mangle]
+            $(#[cfg($cond_irq)])?
             unsafe extern "C" fn $irq() {
                 $(
+                    $(#[cfg($cond_handler)])?
                     <$handler as $crate::interrupt::typelevel::Handler<$crate::interrupt::typelevel::$irq>>::on_interrupt();
                 )*
             }
 
-            $(
-                unsafe impl $crate::interrupt::typelevel::Binding<$crate::interrupt::typelevel::$irq, $handler> for $name {}
-            )*
+            $(#[cfg($cond_irq)])?
+            $crate::bind_interrupts!(@inner
+                $(
+                    $(#[cfg($cond_handler)])?
+                    unsafe impl $crate::interrupt::typelevel::Binding<$crate::interrupt::typelevel::$irq, $handler> for $name {}
+                )*
+            );
         )*
     };
+    (@inner $($t:tt)*) => {
+        $($t)*
+    }
 }
 
 /// HAL configuration for iMX RT600.
@@ -113,6 +164,23 @@ pub mod config {
         /// Time driver interrupt priority. Should be lower priority than softdevice if used.
         #[cfg(feature = "time-driver")]
         pub time_interrupt_priority: crate::interrupt::Priority,
+        /// DMA controller interrupt priority. Raise it above application interrupts for streaming
+        /// users such as audio, so channel completions are serviced before the buffers underrun.
+        pub dma_interrupt_priority: crate::interrupt::Priority,
+        /// The application was started by a bootloader that already initialized the chip.
+        ///
+        /// [`crate::init`] then leaves the clock tree and the flash cache as they are, `clocks`
+        /// is ignored and clock rates are read back from the hardware. The HAL state (DMA, GPIO
+        /// interrupts and the time driver) is still set up. The bootloader must:
+        ///
+        /// - configure the clock tree, enable the RTC 32 kHz oscillator used by the time driver
+        ///   and set the VDDIO pad voltage ranges
+        /// - enable the FlexSPI cache if the application runs from flash
+        /// - stop DMA transfers and mask the interrupts it used before jumping
+        ///
+        /// Peripherals it leaves enabled, such as a console UART, are not reset when the
+        /// application creates drivers for them, their clock is enabled without a reset pulse.
+        pub bootloader_handoff: bool,
     }
 
     impl Default for Config {
@@ -121,6 +189,8 @@ pub mod config {
                 clocks: ClockConfig::crystal(),
                 #[cfg(feature = "time-driver")]
                 time_interrupt_priority: crate::interrupt::Priority::P0,
+                dma_interrupt_priority: crate::interrupt::Priority::P0,
+                bootloader_handoff: false,
             }
         }
     }
@@ -132,8 +202,43 @@ pub mod config {
                 clocks,
                 #[cfg(feature = "time-driver")]
                 time_interrupt_priority: crate::interrupt::Priority::P0,
+                dma_interrupt_priority: crate::interrupt::Priority::P0,
+                bootloader_handoff: false,
             }
         }
+
+        /// Check that the interrupt priorities are consistent.
+        ///
+        /// The time driver must be at least as urgent as the DMA controller, so timeouts of DMA
+        /// transfers keep running while DMA completions are being handled. Called by [`crate::init`].
+        /// Peripheral driver interrupts are set up by the application and cannot be checked here,
+        /// they should not be more urgent than the DMA controller.
+        pub fn validate(&self) -> Result<(), ConfigError> {
+            // Lower numbers are more urgent
+            #[cfg(feature = "time-driver")]
+            if self.time_interrupt_priority as u8 > self.dma_interrupt_priority as u8 {
+                return Err(ConfigError::InvalidInterruptPriority {
+                    driver: "time driver",
+                    required_relation: "at least as urgent as the DMA interrupt",
+                });
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Invalid HAL configuration
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[non_exhaustive]
+    pub enum ConfigError {
+        /// An interrupt priority breaks the required order between drivers
+        InvalidInterruptPriority {
+            /// Driver whose priority is wrong
+            driver: &'static str,
+            /// What its priority must be
+            required_relation: &'static str,
+        },
     }
 }
 
@@ -141,24 +246,63 @@ pub mod config {
 ///
 /// This returns the peripheral singletons that can be used for creating drivers.
 ///
-/// This should only be called once at startup, otherwise it panics.
+/// This should only be called once at startup, otherwise it panics. An application started by a
+/// bootloader linking this HAL calls it too, with [`config::Config::bootloader_handoff`] set so
+/// the clocks configured by the bootloader are kept.
 pub fn init(config: config::Config) -> Peripherals {
     // Do this first, so that it panics if user is calling `init` a second time
     // before doing anything important.
     let peripherals = Peripherals::take();
 
+    if let Err(e) = config.validate() {
+        panic!("invalid HAL configuration: {:?}", e);
+    }
+
     unsafe {
-        if let Err(e) = clocks::init(config.clocks) {
-            error!("unable to initialize Clocks for reason: {:?}", e);
-            // Panic here?
+        chip_info::init();
+        if config.bootloader_handoff {
+            clocks::init_from_hardware();
+        } else {
+            if let Err(e) = clocks::init(config.clocks) {
+                error!("unable to initialize Clocks for reason: {:?}", e);
+                // Panic here?
+            }
+            flash::init();
         }
-        flash::init();
+        delay::init();
         #[cfg(feature = "time-driver")]
         time_driver::init(config.time_interrupt_priority);
-        dma::init();
+        dma::init_with_priority(config.dma_interrupt_priority);
         gpio::init();
         timer::init();
     }
 
     peripherals
 }
+
+// Drivers must be movable between executors, e.g. into an `embassy_sync` mutex shared by a
+// thread mode and an interrupt mode executor. Timers are left out: channels of one CTIMER
+// module read-modify-write shared match and capture control registers.
+#[allow(dead_code)]
+fn assert_drivers_send() {
+    fn assert_send<T: Send>() {}
+
+    assert_send::<uart::Uart<'static, uart::Blocking>>();
+    assert_send::<uart::Uart<'static, uart::Async>>();
+    assert_send::<uart::UartTx<'static, uart::Async>>();
+    assert_send::<uart::UartRx<'static, uart::Async>>();
+    assert_send::<i2c::master::I2cMaster<'static, i2c::Blocking>>();
+    assert_send::<i2c::master::I2cMaster<'static, i2c::Async>>();
+    assert_send::<i2c::slave::I2cSlave<'static, i2c::Async>>();
+    assert_send::<spi::SpiMaster<'static, spi::Async>>();
+    assert_send::<spi::SpiTarget<'static>>();
+    assert_send::<wwdt::WindowedWatchdog<'static, wwdt::Async>>();
+    #[cfg(feature = "_espi")]
+    assert_send::<espi::Espi<'static>>();
+    assert_send::<gpio::Flex<'static, gpio::SenseEnabled>>();
+    assert_send::<gpio::Input<'static>>();
+    assert_send::<gpio::Output<'static>>();
+    assert_send::<crc::Crc<'static>>();
+    assert_send::<rng::Rng<'static>>();
+    assert_send::<dma::channel::Channel<'static>>();
+}
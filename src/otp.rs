@@ -0,0 +1,206 @@
+//! One-Time Programmable (OTP) fuse access
+//!
+//! Fuses are accessed through the OTP driver exposed by the boot ROM API tree,
+//! which handles the controller busy/error flags and the read/program sequencing.
+//! Reads go directly to the fuse array and leave the boot shadow registers untouched.
+
+use core::marker::PhantomData;
+
+use embassy_hal_internal::{into_ref, Peripheral};
+
+use crate::clocks::{enable_and_reset, ConfigurableClock};
+use crate::peripherals::OTP;
+
+/// Boot ROM API tree location (UM11147 ROM API chapter)
+pub(crate) const ROM_API_TREE: *const RomApiTree = 0x1303_fc00 as *const RomApiTree;
+
+/// Number of 32-bit fuse words
+pub const FUSE_WORD_COUNT: u32 = 512;
+
+/// First fuse word holding the 128-bit device unique ID
+const UUID_FUSE_INDEX: u32 = 184;
+
+/// ROM status group for OTP errors
+const OTP_STATUS_GROUP: u32 = 400;
+
+/// OTP driver of the RT600 boot ROM, `ocotp_driver_t` in the SDK `fsl_otp` driver
+///
+/// UM11147 ROM API chapter, OTP driver API table, ROM API version 1.
+#[repr(C)]
+struct RomOtpDriver {
+    init: unsafe extern "C" fn(src_clk_freq: u32) -> u32,
+    deinit: unsafe extern "C" fn() -> u32,
+    fuse_read: unsafe extern "C" fn(addr: u32, data: *mut u32) -> u32,
+    fuse_program: unsafe extern "C" fn(addr: u32, data: u32, lock: bool) -> u32,
+    #[allow(dead_code)]
+    crc_calc: unsafe extern "C" fn(src: *const u32, count: u32, crc: *mut u32) -> u32,
+    #[allow(dead_code)]
+    reload: unsafe extern "C" fn() -> u32,
+    #[allow(dead_code)]
+    crc_check: unsafe extern "C" fn(start: u32, end: u32, crc_addr: u32) -> u32,
+}
+
+/// Root of the RT600 boot ROM API, `bootloader_tree_t` in the SDK `fsl_iap` driver
+///
+/// UM11147 ROM API chapter, ROM API tree table, ROM API version 1.
+#[repr(C)]
+pub(crate) struct RomApiTree {
+    #[allow(dead_code)]
+    version: u32,
+    #[allow(dead_code)]
+    copyright: *const u8,
+    #[allow(dead_code)]
+    run_bootloader: unsafe extern "C" fn(arg: *mut ()),
+    #[allow(dead_code)]
+    reserved0: *const u32,
+    pub(crate) flexspi_nor_driver: *const crate::flash::RomFlexspiNorDriver,
+    otp_driver: *const RomOtpDriver,
+}
+
+fn rom_otp() -> &'static RomOtpDriver {
+    // SAFETY: the ROM API tree lives in boot ROM and is always mapped and valid
+    unsafe { &*(*ROM_API_TREE).otp_driver }
+}
+
+/// OTP error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Fuse index out of range
+    InvalidAddress,
+
+    /// Programming the fuse failed
+    ProgramFail,
+
+    /// CRC check failed
+    CrcFail,
+
+    /// Controller reported an error
+    Controller,
+
+    /// ECC check failed for the fuse word
+    EccCheckFail,
+
+    /// Fuse word is locked
+    Locked,
+
+    /// Controller busy timeout
+    Timeout,
+
+    /// Read-back after programming did not match
+    VerifyFail,
+
+    /// Unknown ROM status code
+    Unknown(u32),
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn check(status: u32) -> Result<()> {
+    if status == 0 {
+        return Ok(());
+    }
+
+    if status / 100 != OTP_STATUS_GROUP {
+        return Err(Error::Unknown(status));
+    }
+
+    match status % 100 {
+        1 => Err(Error::InvalidAddress),
+        2 => Err(Error::ProgramFail),
+        3 => Err(Error::CrcFail),
+        4 => Err(Error::Controller),
+        5 => Err(Error::EccCheckFail),
+        6 => Err(Error::Locked),
+        7 => Err(Error::Timeout),
+        // CRC check pass
+        8 => Ok(()),
+        9 => Err(Error::VerifyFail),
+        _ => Err(Error::Unknown(status)),
+    }
+}
+
+/// OTP driver
+pub struct Otp<'d> {
+    _lifetime: PhantomData<&'d ()>,
+}
+
+impl<'d> Otp<'d> {
+    /// Create a new OTP driver, `clk` is the system clock feeding the controller
+    pub fn new(_otp: impl Peripheral<P = OTP> + 'd, clk: impl ConfigurableClock) -> Result<Self> {
+        into_ref!(_otp);
+
+        enable_and_reset::<OTP>();
+
+        let freq = clk.get_clock_rate().map_err(|_| Error::Controller)?;
+
+        // SAFETY: ROM routine, OTP peripheral is owned by this driver
+        critical_section::with(|_| check(unsafe { (rom_otp().init)(freq) }))?;
+
+        Ok(Self { _lifetime: PhantomData })
+    }
+
+    /// Read the fuse word at `index`
+    pub fn read_fuse_word(&self, index: u32) -> Result<u32> {
+        if index >= FUSE_WORD_COUNT {
+            return Err(Error::InvalidAddress);
+        }
+
+        let mut data = 0;
+
+        // SAFETY: ROM routine, `data` is valid for writes
+        critical_section::with(|_| check(unsafe { (rom_otp().fuse_read)(index, &mut data) }))?;
+
+        Ok(data)
+    }
+
+    /// Read the 128-bit device unique ID
+    pub fn uuid(&self) -> Result<[u32; 4]> {
+        let mut uuid = [0; 4];
+
+        for (i, word) in uuid.iter_mut().enumerate() {
+            *word = self.read_fuse_word(UUID_FUSE_INDEX + i as u32)?;
+        }
+
+        Ok(uuid)
+    }
+
+    /// Read the device unique ID as bytes
+    pub fn uuid_bytes(&self) -> Result<[u8; 16]> {
+        let mut bytes = [0; 16];
+
+        for (chunk, word) in bytes.chunks_mut(4).zip(self.uuid()?) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    /// Program the fuse word at `index` and verify it by reading it back.
+    ///
+    /// Bits can only be set, never cleared. If `lock` is set the word is
+    /// write-protected afterwards.
+    #[cfg(feature = "otp-write")]
+    pub fn program_fuse_word(&mut self, index: u32, value: u32, lock: bool) -> Result<()> {
+        if index >= FUSE_WORD_COUNT {
+            return Err(Error::InvalidAddress);
+        }
+
+        // SAFETY: ROM routine handles the programming voltage and enable sequencing
+        critical_section::with(|_| check(unsafe { (rom_otp().fuse_program)(index, value, lock) }))?;
+
+        if self.read_fuse_word(index)? & value != value {
+            return Err(Error::VerifyFail);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Otp<'_> {
+    fn drop(&mut self) {
+        // SAFETY: ROM routine, matches the init in `new`
+        critical_section::with(|_| unsafe { (rom_otp().deinit)() });
+    }
+}
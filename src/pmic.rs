@@ -0,0 +1,367 @@
+//! PCA9420 power management IC
+//!
+//! Driver for the PMIC of the RT685 EVK, on the dedicated FLEXCOMM15 I2C bus. The PCA9420 has
+//! four power modes, each with its own set of rail voltages, ship mode and watchdog settings.
+//! Which mode is active is selected by its MODE pins, driven by the PMC, or over I2C.
+//!
+//! Voltages are given in millivolts and checked against the output ranges of the PCA9420
+//! datasheet, settings outside of them are refused with [`Error::VoltageOutOfRange`].
+
+use embedded_hal_async::i2c::I2c;
+
+use crate::gpio::Input;
+use crate::i2c::master::I2cMaster;
+use crate::i2c::Async;
+
+/// 7-bit I2C address of the PCA9420
+pub const PCA9420_ADDRESS: u8 = 0x61;
+
+/// Device information register
+const REG_DEV_INFO: u8 = 0x00;
+/// System interrupt status, write 1 to clear
+const REG_SUB_INT0: u8 = 0x02;
+/// Battery charger interrupt status, write 1 to clear
+const REG_SUB_INT1: u8 = 0x04;
+/// Regulator interrupt status, write 1 to clear
+const REG_SUB_INT2: u8 = 0x06;
+/// Top level control 3, holds the mode selected over I2C
+const REG_TOP_CNTL3: u8 = 0x0C;
+/// First mode configuration register, each mode has four starting here
+const REG_MODECFG_0_0: u8 = 0x22;
+
+/// TOP_CNTL3: mode selected over I2C
+const TOP_CNTL3_MODE_I2C_MASK: u8 = 0x18;
+/// TOP_CNTL3: MODE_I2C field position
+const TOP_CNTL3_MODE_I2C_SHIFT: u8 = 3;
+
+/// MODECFG_x_0: enter ship mode with this mode
+const MODECFG_0_SHIP_EN: u8 = 1 << 7;
+/// MODECFG_x_0: leave this mode on TOP_CNTL3 MODE_I2C instead of the MODE pins
+const MODECFG_0_MODE_CTRL_SEL: u8 = 1 << 6;
+/// MODECFG_x_0: SW1 output voltage
+const MODECFG_0_SW1_OUT_MASK: u8 = 0x3F;
+/// MODECFG_x_1: SW2 output voltage
+const MODECFG_1_SW2_OUT_MASK: u8 = 0x3F;
+/// MODECFG_x_2: LDO1 output voltage
+const MODECFG_2_LDO1_OUT_MASK: u8 = 0xF0;
+/// MODECFG_x_2: LDO1_OUT field position
+const MODECFG_2_LDO1_OUT_SHIFT: u8 = 4;
+/// MODECFG_x_3: watchdog timeout
+const MODECFG_3_WD_TIMER_MASK: u8 = 0xC0;
+/// MODECFG_x_3: WD_TIMER field position
+const MODECFG_3_WD_TIMER_SHIFT: u8 = 6;
+/// MODECFG_x_3: LDO2 output voltage
+const MODECFG_3_LDO2_OUT_MASK: u8 = 0x3F;
+
+/// SUB_INT0: VIN_OK changed
+const SUB_INT0_VIN_OK: u8 = 1 << 0;
+/// SUB_INT0: watchdog timeout
+const SUB_INT0_WD_TIMEOUT: u8 = 1 << 1;
+/// SUB_INT0: ASYS pre-warning
+const SUB_INT0_ASYS_PRE_WARNING: u8 = 1 << 2;
+/// SUB_INT0: thermal shutdown
+const SUB_INT0_THERMAL_SHUTDOWN: u8 = 1 << 3;
+/// SUB_INT0: die temperature warning
+const SUB_INT0_TEMP_WARNING: u8 = 1 << 4;
+
+/// Voltage step of all rails
+const STEP_MV: u16 = 25;
+/// SW1 code selecting its 1.8 V setting, above the stepped range
+const SW1_OUT_1V8: u8 = 0x3F;
+/// SW2 and LDO2 code of the first step of the high range
+const HIGH_RANGE_BASE: u8 = 0x20;
+
+/// PMIC error
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// I2C transfer failed
+    I2c(crate::i2c::Error),
+
+    /// Voltage outside of the output range of the rail, or not a multiple of the step
+    VoltageOutOfRange,
+}
+
+impl From<crate::i2c::Error> for Error {
+    fn from(value: crate::i2c::Error) -> Self {
+        Error::I2c(value)
+    }
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// PCA9420 power mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PowerMode {
+    /// Mode 0
+    Mode0,
+    /// Mode 1
+    Mode1,
+    /// Mode 2
+    Mode2,
+    /// Mode 3
+    Mode3,
+}
+
+impl PowerMode {
+    /// Address of configuration register `n` of the mode
+    fn modecfg(self, n: u8) -> u8 {
+        REG_MODECFG_0_0 + 4 * self as u8 + n
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x3 {
+            0 => PowerMode::Mode0,
+            1 => PowerMode::Mode1,
+            2 => PowerMode::Mode2,
+            _ => PowerMode::Mode3,
+        }
+    }
+}
+
+/// PCA9420 output rail
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rail {
+    /// SW1 buck, 0.5 to 1.5 V or 1.8 V, VDDCORE on the EVK
+    Sw1,
+    /// SW2 buck, 1.5 to 2.1 V or 2.7 to 3.3 V
+    Sw2,
+    /// LDO1, 1.7 to 1.9 V
+    Ldo1,
+    /// LDO2, 1.5 to 2.1 V or 2.7 to 3.3 V
+    Ldo2,
+}
+
+impl Rail {
+    /// Mode configuration register index and field mask of the rail voltage
+    fn field(self) -> (u8, u8) {
+        match self {
+            Rail::Sw1 => (0, MODECFG_0_SW1_OUT_MASK),
+            Rail::Sw2 => (1, MODECFG_1_SW2_OUT_MASK),
+            Rail::Ldo1 => (2, MODECFG_2_LDO1_OUT_MASK),
+            Rail::Ldo2 => (3, MODECFG_3_LDO2_OUT_MASK),
+        }
+    }
+
+    /// Field value for `mv`, `None` outside of the rail range
+    fn encode(self, mv: u16) -> Option<u8> {
+        if mv % STEP_MV != 0 {
+            return None;
+        }
+
+        let code = match self {
+            Rail::Sw1 => match mv {
+                500..=1500 => ((mv - 500) / STEP_MV) as u8,
+                1800 => SW1_OUT_1V8,
+                _ => return None,
+            },
+            Rail::Sw2 | Rail::Ldo2 => match mv {
+                1500..=2100 => ((mv - 1500) / STEP_MV) as u8,
+                2700..=3300 => HIGH_RANGE_BASE + ((mv - 2700) / STEP_MV) as u8,
+                _ => return None,
+            },
+            Rail::Ldo1 => match mv {
+                1700..=1900 => (((mv - 1700) / STEP_MV) as u8) << MODECFG_2_LDO1_OUT_SHIFT,
+                _ => return None,
+            },
+        };
+
+        Some(code)
+    }
+
+    /// Voltage of field value `code`, codes past the end of a range select its top voltage
+    fn decode(self, code: u8) -> u16 {
+        match self {
+            Rail::Sw1 => match code {
+                SW1_OUT_1V8 => 1800,
+                _ => 500 + u16::from(code.min(40)) * STEP_MV,
+            },
+            Rail::Sw2 | Rail::Ldo2 => match code {
+                code if code < HIGH_RANGE_BASE => 1500 + u16::from(code.min(24)) * STEP_MV,
+                _ => 2700 + u16::from((code - HIGH_RANGE_BASE).min(24)) * STEP_MV,
+            },
+            Rail::Ldo1 => 1700 + u16::from((code >> MODECFG_2_LDO1_OUT_SHIFT).min(8)) * STEP_MV,
+        }
+    }
+}
+
+/// PMIC watchdog timeout of a power mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WatchdogTimeout {
+    /// Watchdog disabled
+    Disabled,
+    /// 16 seconds
+    Seconds16,
+    /// 32 seconds
+    Seconds32,
+    /// 64 seconds
+    Seconds64,
+}
+
+/// How the PMIC leaves a power mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ModeControl {
+    /// Follow the MODE pins
+    Pins,
+    /// Follow the mode selected with [`Pca9420::switch_mode`]
+    I2c,
+}
+
+/// Interrupt events reported on INTB, see [`Pca9420::wait_for_event`]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Events {
+    /// Input supply went in or out of its valid range
+    pub vin_ok_changed: bool,
+    /// PMIC watchdog expired
+    pub watchdog_timeout: bool,
+    /// ASYS dropped below its pre-warning threshold
+    pub asys_pre_warning: bool,
+    /// Die temperature reached the shutdown threshold
+    pub thermal_shutdown: bool,
+    /// Die temperature reached the warning threshold
+    pub temperature_warning: bool,
+    /// Raw battery charger interrupt flags (SUB_INT1)
+    pub charger: u8,
+    /// Raw regulator interrupt flags (SUB_INT2)
+    pub regulator: u8,
+}
+
+impl Events {
+    fn any(&self) -> bool {
+        *self != Events::default()
+    }
+}
+
+/// PCA9420 PMIC driver
+pub struct Pca9420<'d> {
+    i2c: I2cMaster<'d, Async>,
+}
+
+impl<'d> Pca9420<'d> {
+    /// Create a driver for the PMIC on `i2c`, usually FLEXCOMM15
+    pub fn new(i2c: I2cMaster<'d, Async>) -> Self {
+        Self { i2c }
+    }
+
+    async fn read_reg(&mut self, reg: u8) -> Result<u8> {
+        let mut value = [0u8];
+        self.i2c.write_read(PCA9420_ADDRESS, &[reg], &mut value).await?;
+        Ok(value[0])
+    }
+
+    async fn write_reg(&mut self, reg: u8, value: u8) -> Result<()> {
+        self.i2c.write(PCA9420_ADDRESS, &[reg, value]).await?;
+        Ok(())
+    }
+
+    async fn modify_reg(&mut self, reg: u8, mask: u8, value: u8) -> Result<()> {
+        let old = self.read_reg(reg).await?;
+        self.write_reg(reg, (old & !mask) | (value & mask)).await
+    }
+
+    /// Device information register
+    pub async fn device_info(&mut self) -> Result<u8> {
+        self.read_reg(REG_DEV_INFO).await
+    }
+
+    /// Output voltage of `rail` in `mode`, in millivolts
+    pub async fn rail_voltage_mv(&mut self, mode: PowerMode, rail: Rail) -> Result<u16> {
+        let (n, mask) = rail.field();
+        let code = self.read_reg(mode.modecfg(n)).await? & mask;
+        Ok(rail.decode(code))
+    }
+
+    /// Set the output voltage of `rail` in `mode`, in millivolts.
+    ///
+    /// Takes effect immediately if `mode` is active. Returns [`Error::VoltageOutOfRange`] for
+    /// voltages the rail cannot output, see [`Rail`], leaving the setting unchanged.
+    pub async fn set_rail_voltage_mv(&mut self, mode: PowerMode, rail: Rail, mv: u16) -> Result<()> {
+        let code = rail.encode(mv).ok_or(Error::VoltageOutOfRange)?;
+        let (n, mask) = rail.field();
+        self.modify_reg(mode.modecfg(n), mask, code).await
+    }
+
+    /// Enter ship mode when the PMIC switches to `mode`
+    pub async fn set_ship_mode(&mut self, mode: PowerMode, enable: bool) -> Result<()> {
+        let value = if enable { MODECFG_0_SHIP_EN } else { 0 };
+        self.modify_reg(mode.modecfg(0), MODECFG_0_SHIP_EN, value).await
+    }
+
+    /// Watchdog timeout while `mode` is active
+    pub async fn set_watchdog(&mut self, mode: PowerMode, timeout: WatchdogTimeout) -> Result<()> {
+        let value = (timeout as u8) << MODECFG_3_WD_TIMER_SHIFT;
+        self.modify_reg(mode.modecfg(3), MODECFG_3_WD_TIMER_MASK, value).await
+    }
+
+    /// Select what moves the PMIC out of `mode`
+    pub async fn set_mode_control(&mut self, mode: PowerMode, control: ModeControl) -> Result<()> {
+        let value = match control {
+            ModeControl::Pins => 0,
+            ModeControl::I2c => MODECFG_0_MODE_CTRL_SEL,
+        };
+        self.modify_reg(mode.modecfg(0), MODECFG_0_MODE_CTRL_SEL, value).await
+    }
+
+    /// Mode selected over I2C
+    pub async fn i2c_mode(&mut self) -> Result<PowerMode> {
+        let bits = self.read_reg(REG_TOP_CNTL3).await? & TOP_CNTL3_MODE_I2C_MASK;
+        Ok(PowerMode::from_bits(bits >> TOP_CNTL3_MODE_I2C_SHIFT))
+    }
+
+    /// Select `mode` over I2C.
+    ///
+    /// The PMIC only switches if the active mode is under [`ModeControl::I2c`], see
+    /// [`set_mode_control`](Self::set_mode_control).
+    pub async fn switch_mode(&mut self, mode: PowerMode) -> Result<()> {
+        let value = (mode as u8) << TOP_CNTL3_MODE_I2C_SHIFT;
+        self.modify_reg(REG_TOP_CNTL3, TOP_CNTL3_MODE_I2C_MASK, value).await
+    }
+
+    /// Read and clear the pending interrupt events
+    pub async fn take_events(&mut self) -> Result<Events> {
+        let system = self.read_reg(REG_SUB_INT0).await?;
+        let charger = self.read_reg(REG_SUB_INT1).await?;
+        let regulator = self.read_reg(REG_SUB_INT2).await?;
+
+        // Clear exactly what was read, events arriving meanwhile stay pending
+        for (reg, flags) in [
+            (REG_SUB_INT0, system),
+            (REG_SUB_INT1, charger),
+            (REG_SUB_INT2, regulator),
+        ] {
+            if flags != 0 {
+                self.write_reg(reg, flags).await?;
+            }
+        }
+
+        Ok(Events {
+            vin_ok_changed: system & SUB_INT0_VIN_OK != 0,
+            watchdog_timeout: system & SUB_INT0_WD_TIMEOUT != 0,
+            asys_pre_warning: system & SUB_INT0_ASYS_PRE_WARNING != 0,
+            thermal_shutdown: system & SUB_INT0_THERMAL_SHUTDOWN != 0,
+            temperature_warning: system & SUB_INT0_TEMP_WARNING != 0,
+            charger,
+            regulator,
+        })
+    }
+
+    /// Wait for the PMIC to assert INTB, then read and clear its interrupt events.
+    ///
+    /// `intb` is the input connected to the open-drain, active low INTB output of the PMIC.
+    pub async fn wait_for_event(&mut self, intb: &mut Input<'_>) -> Result<Events> {
+        loop {
+            let events = self.take_events().await?;
+            if events.any() {
+                return Ok(events);
+            }
+
+            intb.wait_for_low().await;
+        }
+    }
+}
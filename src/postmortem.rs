@@ -0,0 +1,294 @@
+//! Peripheral register snapshots for postmortem debugging
+//!
+//! Drivers register their peripheral when they take it into use. [`capture_all`] copies a few
+//! key registers of every registered peripheral whose clock is running into a RAM region that
+//! is not initialized at reset, so the snapshot survives a reboot. Call it from the panic or
+//! HardFault handler, it takes no locks and does not allocate. After the reboot, read the
+//! previous capture back with [`take_previous`] or log it with [`dump_previous`].
+//!
+//! # Layout
+//!
+//! The region starts with [`MAGIC`] and the number of record words that follow. Each record is
+//! a header word `kind | instance << 8 | count << 16` followed by `count` register values, in
+//! the order documented on [`Kind`].
+
+use core::mem::MaybeUninit;
+use core::ptr::{self, addr_of, addr_of_mut};
+use core::sync::atomic::{compiler_fence, AtomicU8, AtomicUsize, Ordering};
+
+use crate::clocks::{is_enabled, SysconPeripheral};
+
+/// First word of a valid capture
+pub const MAGIC: u32 = 0x504D_5254;
+
+/// Peripherals that can be registered at once
+const MAX_SOURCES: usize = 16;
+/// Longest record
+const MAX_REGISTERS: usize = 5;
+/// Magic and record word count
+const HEADER_WORDS: usize = 2;
+/// Size of the capture region
+const REGION_WORDS: usize = HEADER_WORDS + MAX_SOURCES * (1 + MAX_REGISTERS);
+
+/// PSELID: function the flexcomm is currently set up for
+const PSELID_PERSEL: u32 = 0x7;
+const PERSEL_USART: u32 = 1;
+const PERSEL_SPI: u32 = 2;
+const PERSEL_I2C: u32 = 3;
+
+/// Register layout of a record
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Kind {
+    /// PSELID, then CFG, STAT, INTSTAT and FIFOSTAT of the USART or SPI function, or CFG, STAT
+    /// and INTSTAT of the I2C function
+    Flexcomm = 1,
+    /// CTRL, INTSTAT and ERRINT0
+    Dma = 2,
+    /// MCTRL and MSTAT
+    Espi = 3,
+}
+
+impl Kind {
+    fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            1 => Some(Self::Flexcomm),
+            2 => Some(Self::Dma),
+            3 => Some(Self::Espi),
+            _ => None,
+        }
+    }
+}
+
+/// Registered peripheral
+struct Source {
+    /// Register block base address, 0 while the slot is free or being updated
+    base: AtomicUsize,
+    kind: AtomicU8,
+    instance: AtomicU8,
+    /// `fn() -> bool` telling whether the peripheral clock is running
+    clock_enabled: AtomicUsize,
+}
+
+impl Source {
+    const fn new() -> Self {
+        Self {
+            base: AtomicUsize::new(0),
+            kind: AtomicU8::new(0),
+            instance: AtomicU8::new(0),
+            clock_enabled: AtomicUsize::new(0),
+        }
+    }
+}
+
+static SOURCES: [Source; MAX_SOURCES] = [const { Source::new() }; MAX_SOURCES];
+
+#[link_section = ".uninit.postmortem"]
+static mut REGION: MaybeUninit<[u32; REGION_WORDS]> = MaybeUninit::uninit();
+
+/// Include peripheral `T` with register block at `base` in later captures
+///
+/// Registering the same register block again replaces the earlier registration, e.g. when a
+/// flexcomm is reused for another function.
+pub(crate) fn register<T: SysconPeripheral>(kind: Kind, instance: u8, base: usize) {
+    critical_section::with(|_| {
+        let slot = SOURCES
+            .iter()
+            .find(|s| s.base.load(Ordering::Relaxed) == base)
+            .or_else(|| SOURCES.iter().find(|s| s.base.load(Ordering::Relaxed) == 0));
+
+        if let Some(slot) = slot {
+            // A capture running meanwhile skips the slot until it is consistent again
+            slot.base.store(0, Ordering::Relaxed);
+            slot.kind.store(kind as u8, Ordering::Relaxed);
+            slot.instance.store(instance, Ordering::Relaxed);
+            slot.clock_enabled
+                .store(is_enabled::<T> as fn() -> bool as usize, Ordering::Relaxed);
+            slot.base.store(base, Ordering::Release);
+        }
+    });
+}
+
+/// Read the registers of `kind` at `base` into `regs`, returning how many were read
+///
+/// # Safety
+///
+/// `base` must be the register block of a `kind` peripheral with its clock running.
+unsafe fn snapshot(kind: Kind, base: usize, regs: &mut [u32; MAX_REGISTERS]) -> usize {
+    match kind {
+        Kind::Flexcomm => {
+            let fc = &*(base as *const crate::pac::flexcomm0::RegisterBlock);
+            regs[0] = fc.pselid().read().bits();
+
+            match regs[0] & PSELID_PERSEL {
+                PERSEL_USART => {
+                    let usart = &*(base as *const crate::pac::usart0::RegisterBlock);
+                    regs[1] = usart.cfg().read().bits();
+                    regs[2] = usart.stat().read().bits();
+                    regs[3] = usart.intstat().read().bits();
+                    regs[4] = usart.fifostat().read().bits();
+                    5
+                }
+                PERSEL_SPI => {
+                    let spi = &*(base as *const crate::pac::spi0::RegisterBlock);
+                    regs[1] = spi.cfg().read().bits();
+                    regs[2] = spi.stat().read().bits();
+                    regs[3] = spi.intstat().read().bits();
+                    regs[4] = spi.fifostat().read().bits();
+                    5
+                }
+                PERSEL_I2C => {
+                    let i2c = &*(base as *const crate::pac::i2c0::RegisterBlock);
+                    regs[1] = i2c.cfg().read().bits();
+                    regs[2] = i2c.stat().read().bits();
+                    regs[3] = i2c.intstat().read().bits();
+                    4
+                }
+                _ => 1,
+            }
+        }
+        Kind::Dma => {
+            let dma = &*(base as *const crate::pac::dma0::RegisterBlock);
+            regs[0] = dma.ctrl().read().bits();
+            regs[1] = dma.intstat().read().bits();
+            regs[2] = dma.errint0().read().bits();
+            3
+        }
+        #[cfg(feature = "_espi")]
+        Kind::Espi => {
+            let espi = &*(base as *const crate::pac::espi::RegisterBlock);
+            regs[0] = espi.mctrl().read().bits();
+            regs[1] = espi.mstat().read().bits();
+            2
+        }
+        #[cfg(not(feature = "_espi"))]
+        Kind::Espi => 0,
+    }
+}
+
+/// Snapshot the registers of all registered peripherals into the capture region
+///
+/// Safe to call from a fault or panic handler. Peripherals whose clock is gated are skipped,
+/// reading them would fault.
+pub fn capture_all() {
+    let words = addr_of_mut!(REGION).cast::<u32>();
+    let write = |index: usize, value: u32| {
+        // SAFETY: the region is only accessed through raw pointers and callers stay within
+        // REGION_WORDS, a nested capture simply starts over
+        unsafe { ptr::write_volatile(words.add(index), value) }
+    };
+
+    // Invalidate the previous capture until this one is complete
+    write(0, 0);
+    compiler_fence(Ordering::SeqCst);
+
+    let mut len = 0;
+    for source in SOURCES.iter() {
+        let base = source.base.load(Ordering::Acquire);
+        if base == 0 {
+            continue;
+        }
+
+        let Some(kind) = Kind::from_bits(source.kind.load(Ordering::Relaxed)) else {
+            continue;
+        };
+
+        // SAFETY: `register` only stores `is_enabled::<T>` function pointers
+        let clock_enabled: fn() -> bool =
+            unsafe { core::mem::transmute::<usize, fn() -> bool>(source.clock_enabled.load(Ordering::Relaxed)) };
+        if !clock_enabled() {
+            continue;
+        }
+
+        let mut regs = [0; MAX_REGISTERS];
+        // SAFETY: registered by the driver of a `kind` peripheral, and its clock is running
+        let count = unsafe { snapshot(kind, base, &mut regs) };
+
+        let header =
+            u32::from(kind as u8) | u32::from(source.instance.load(Ordering::Relaxed)) << 8 | (count as u32) << 16;
+        write(HEADER_WORDS + len, header);
+        for (i, reg) in regs[..count].iter().enumerate() {
+            write(HEADER_WORDS + len + 1 + i, *reg);
+        }
+
+        len += 1 + count;
+    }
+
+    write(1, len as u32);
+    compiler_fence(Ordering::SeqCst);
+    write(0, MAGIC);
+}
+
+/// Capture left behind by the previous run, see [`take_previous`]
+pub struct Capture {
+    words: [u32; REGION_WORDS],
+    len: usize,
+}
+
+/// Register snapshot of one peripheral
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Record<'a> {
+    /// Register layout
+    pub kind: Kind,
+    /// Peripheral instance, e.g. the flexcomm number
+    pub instance: u8,
+    /// Register values, in the order documented on [`Kind`]
+    pub registers: &'a [u32],
+}
+
+impl Capture {
+    /// Records of the capture, stops at the first malformed record
+    pub fn records(&self) -> impl Iterator<Item = Record<'_>> {
+        let mut rest = &self.words[HEADER_WORDS..HEADER_WORDS + self.len];
+
+        core::iter::from_fn(move || {
+            let (&header, tail) = rest.split_first()?;
+            let kind = Kind::from_bits(header as u8)?;
+            let count = ((header >> 16) as u8) as usize;
+            if count > tail.len() {
+                return None;
+            }
+
+            let (registers, tail) = tail.split_at(count);
+            rest = tail;
+
+            Some(Record {
+                kind,
+                instance: (header >> 8) as u8,
+                registers,
+            })
+        })
+    }
+}
+
+/// Take the capture of the previous run, if there is one
+///
+/// The capture is invalidated, later calls return `None` until [`capture_all`] runs again.
+pub fn take_previous() -> Option<Capture> {
+    // SAFETY: plain word copy of the region, whatever it holds after reset
+    let words = unsafe { ptr::read_volatile(addr_of!(REGION).cast::<[u32; REGION_WORDS]>()) };
+    let len = words[1] as usize;
+
+    if words[0] != MAGIC || len > REGION_WORDS - HEADER_WORDS {
+        return None;
+    }
+
+    // SAFETY: only invalidates the magic, see above
+    unsafe { ptr::write_volatile(addr_of_mut!(REGION).cast::<u32>(), 0) };
+
+    Some(Capture { words, len })
+}
+
+/// Log the capture of the previous run, if there is one, and invalidate it
+pub fn dump_previous() {
+    let Some(capture) = take_previous() else {
+        return;
+    };
+
+    warn!("postmortem capture of the previous run:");
+    for record in capture.records() {
+        warn!("{:?} {}: {:?}", record.kind, record.instance, record.registers);
+    }
+}
@@ -0,0 +1,20 @@
+//! Commonly used driver types and traits
+//!
+//! `use embassy_imxrt::prelude::*;` brings the pin configuration types, the main driver types and
+//! the driver modules into scope. Types whose names are shared between drivers, like each driver's
+//! `Config`, `Error` or `InterruptHandler`, are reached through their module, e.g. `uart::Config`.
+
+pub use crate::gpio::{
+    DriveMode, DriveStrength, FastOutput, Flex, Function, GpioPin, Input, Inverter, Level, Output, Pull, SlewRate,
+};
+pub use crate::i2c::master::I2cMaster;
+pub use crate::i2c::slave::I2cSlave;
+pub use crate::interrupt::InterruptExt;
+pub use crate::iopctl::IopctlPin;
+pub use crate::spi::{SpiDeviceExclusive, SpiMaster, SpiTarget};
+pub use crate::timer::{CaptureTimer, CountingTimer, TimerClockSource};
+pub use crate::uart::{
+    ClockPolarity, ContinuousClock, DataBits, HalfDuplexUart, LoopbackMode, Operation, Parity, StopBits, SyncRole,
+    Uart, UartRx, UartTx,
+};
+pub use crate::{bind_interrupts, dma, gpio, i2c, interrupt, peripherals, spi, timer, uart, Peripheral, Peripherals};
@@ -29,12 +29,32 @@
 // The timer is reset by the match register that is configured to set the PWM cycle length.
 // When the timer is reset to zero, all currently HIGH match outputs configured as PWM outputs are cleared
 
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+
 /// include the traits that are implemented + exposed via this implementation
 use embassy_hal_internal::{Peripheral, PeripheralRef};
+use embassy_sync::waitqueue::AtomicWaker;
 
+use crate::clocks::{clock_guard, ClockGuard};
+pub use crate::gpio::Level;
+use crate::interrupt;
+use crate::interrupt::typelevel::Interrupt;
 /// include pac definitions for instancing
 use crate::pac;
 
+/// SCT input slot the fault signal is routed to through the input mux
+const FAULT_INPUT: u32 = 7;
+
+/// SCT event raised while the fault input is active. Events 0-9 drive the channels, 10 is the period limit.
+const FAULT_EVENT: u32 = 11;
+
+/// SCT event number of the period limit
+const LIMIT_EVENT: u32 = 10;
+
+static FAULT_WAKER: AtomicWaker = AtomicWaker::new();
+
 /// clock source indicator for selecting while powering on the `SCTimer`
 #[derive(Copy, Clone, Debug)]
 pub enum SCTClockSource {
@@ -133,6 +153,107 @@ impl Channel {
     }
 }
 
+/// PWM fault error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The fault input is still active
+    FaultActive,
+
+    /// No fault input has been configured
+    NoFault,
+}
+
+/// Source of the fault signal, routed to the `SCTimer` through the input mux
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FaultInput {
+    /// `SCT0_GPI0` pin function, the pin must be muxed to it by the caller
+    Gpi0,
+    /// `SCT0_GPI1` pin function, the pin must be muxed to it by the caller
+    Gpi1,
+    /// `SCT0_GPI2` pin function, the pin must be muxed to it by the caller
+    Gpi2,
+    /// `SCT0_GPI3` pin function, the pin must be muxed to it by the caller
+    Gpi3,
+    /// `SCT0_GPI4` pin function, the pin must be muxed to it by the caller
+    Gpi4,
+    /// `SCT0_GPI5` pin function, the pin must be muxed to it by the caller
+    Gpi5,
+    /// `SCT0_GPI6` pin function, the pin must be muxed to it by the caller
+    Gpi6,
+    /// `SCT0_GPI7` pin function, the pin must be muxed to it by the caller
+    Gpi7,
+    /// Analog comparator output
+    AcmpOutput,
+}
+
+impl FaultInput {
+    /// SCT0 input mux selection
+    fn sel(&self) -> u32 {
+        match self {
+            FaultInput::Gpi0 => 0,
+            FaultInput::Gpi1 => 1,
+            FaultInput::Gpi2 => 2,
+            FaultInput::Gpi3 => 3,
+            FaultInput::Gpi4 => 4,
+            FaultInput::Gpi5 => 5,
+            FaultInput::Gpi6 => 6,
+            FaultInput::Gpi7 => 7,
+            FaultInput::AcmpOutput => 16,
+        }
+    }
+}
+
+/// Hardware fault (brake) configuration
+///
+/// While the fault input is at `active_level` the counter is stopped and every channel
+/// with a safe state is driven to it, without software involvement. The input is
+/// synchronized to the SCT clock (2 clocks) and the fault event drives the outputs on the
+/// following clock, so outputs reach their safe state 3 SCT function clocks after the input
+/// changes, 63 ns with the 48 MHz FFRO. If the fault coincides with the period limit event,
+/// conflict resolution favours the limit and the safe state follows one clock later.
+#[derive(Copy, Clone, Debug)]
+pub struct FaultConfig {
+    /// Fault signal source
+    pub input: FaultInput,
+
+    /// Level of the fault signal that indicates a fault
+    pub active_level: Level,
+
+    /// Level each channel is forced to on a fault, `None` leaves the channel frozen at its current level
+    pub safe_state: [Option<Level>; 10],
+}
+
+impl FaultConfig {
+    /// Masks of the fault event in the OUT_SET / OUT_CLR registers of `channel`
+    fn out_masks(&self, channel: &Channel) -> (u32, u32) {
+        match self.safe_state[channel.number()] {
+            Some(Level::High) => (1 << FAULT_EVENT, 0),
+            Some(Level::Low) => (0, 1 << FAULT_EVENT),
+            None => (0, 0),
+        }
+    }
+}
+
+/// `SCTimer` interrupt handler
+pub struct InterruptHandler<T: sealed::SCTimer> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: sealed::SCTimer> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // SAFETY: only the fault event enable is touched, the flag is left for the driver (unsafe also due to .bits)
+        let sct0 = unsafe { pac::Sct0::steal() };
+
+        if sct0.evflag().read().bits() & (1 << FAULT_EVENT) != 0 {
+            sct0.even()
+                .modify(|r, w| unsafe { w.bits(r.bits() & !(1 << FAULT_EVENT)) });
+            FAULT_WAKER.wake();
+        }
+    }
+}
+
 // non-reexported (sealed) traits
 mod sealed {
     use embassy_hal_internal::Peripheral;
@@ -140,6 +261,8 @@ mod sealed {
     use crate::clocks::SysconPeripheral;
 
     pub trait SCTimer: Peripheral<P = Self> + SysconPeripheral + 'static + Send {
+        type Interrupt: crate::interrupt::typelevel::Interrupt;
+
         fn set_clock_source(clock: super::SCTClockSource);
         fn get_clock_rate(clock: super::SCTClockSource) -> super::Hertz;
         fn set_divisor(divisor: u8);
@@ -193,6 +316,8 @@ impl From<MicroSeconds> for Hertz {
 
 // only allow specified instances to SCTPwm construct
 impl sealed::SCTimer for crate::peripherals::SCT0 {
+    type Interrupt = crate::interrupt::typelevel::SCT0;
+
     fn set_clock_source(clock: self::SCTClockSource) {
         use SCTClockSource::{AudioPLL, Main, MainPLL, None, AUX0PLL, AUX1PLL, FFRO};
 
@@ -222,8 +347,6 @@ impl sealed::SCTimer for crate::peripherals::SCT0 {
                 clkctl0.sctfclksel().write(|w| w.sel().none());
             }
         }
-
-        enable_and_reset::<SCT0>();
     }
 
     fn get_clock_rate(clock: self::SCTClockSource) -> Hertz {
@@ -299,17 +422,19 @@ impl sealed::SCTimer for crate::peripherals::SCT0 {
         // configure the SCT for simple PWM operation (count up)
         sct0.ctrl().modify(|_, w| w.bidir_l().up());
 
-        // unhalt the SCT
-        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit().stop_l().clear_bit());
+        // unhalt the SCT, a latched fault keeps it stopped
+        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit());
     }
 }
 
 /// Basic PWM Object, Consumes a `SCTimer` peripheral hardware instance on construction
 pub struct SCTPwm<'d, T: sealed::SCTimer> {
     _p: PeripheralRef<'d, T>,
+    _clock: ClockGuard,
     period: MicroSeconds,
     clock: SCTClockSource,
     count_max: u32,
+    fault: Option<FaultConfig>,
 }
 
 impl<'d, T: sealed::SCTimer> SCTPwm<'d, T> {
@@ -340,6 +465,7 @@ impl<'d, T: sealed::SCTimer> SCTPwm<'d, T> {
         // This sets the limit for what COUNTER can be
 
         // now that the input configuration for rate has been validated, we can set the divisor accordingly
+        let _clock = clock_guard::<T>();
         T::set_clock_source(clock);
         //T::set_divisor((clock_rate.0 / requested_pwm_rate.0) as u8);
         // TODO: if further precision is needed for rates beyond u32::MAX clock_rate to pwm_Rate conversions, we can scale up to 256x
@@ -349,11 +475,153 @@ impl<'d, T: sealed::SCTimer> SCTPwm<'d, T> {
 
         Self {
             _p: sct.into_ref(),
+            _clock,
             period,
             clock,
             count_max: factor,
+            fault: None,
         }
     }
+
+    /// Route a fault signal that forces the outputs to their safe state in hardware.
+    ///
+    /// See [`FaultConfig`] for the latency. Once a fault occurred the outputs stay in their
+    /// safe state until [`Self::clear_fault`] is called, even if the fault input goes inactive.
+    pub fn enable_fault(
+        &mut self,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        config: FaultConfig,
+    ) {
+        // SAFETY: safe so long as SCTPwm is not used across multiple executors
+        let sct0 = unsafe { pac::Sct0::steal() };
+        // SAFETY: only the SCT0 input slot owned by this driver is written
+        let inputmux = unsafe { pac::Inputmux::steal() };
+
+        // halt the timer so that it can be configured
+        sct0.ctrl().modify(|_, w| w.halt_l().set_bit());
+
+        // SAFETY: unsafe only due to .bits(), the selection comes from FaultInput
+        inputmux
+            .sct0_inmux(FAULT_INPUT as usize)
+            .write(|w| unsafe { w.bits(config.input.sel()) });
+
+        // IO condition event on the (synchronized) fault input, evaluated at the active level
+        // so the outputs keep being forced while the fault lasts
+        let iocond = match config.active_level {
+            Level::Low => 0,
+            Level::High => 3,
+        };
+        sct0.ev(FAULT_EVENT as usize).ev_ctrl().write(|w|
+            // SAFETY: COMBMODE = IO (2), OUTSEL = input, IOSEL = fault input, IOCOND = level
+            unsafe { w.bits((2 << 12) | (iocond << 10) | (FAULT_INPUT << 6)) });
+        sct0.ev(FAULT_EVENT as usize).ev_state().write(|w|
+            // SAFETY: unsafe due to .bits, the event is allowed in all states
+            unsafe { w.statemskn().bits(0xFF) });
+
+        // Stop rather than halt the counter on a fault: no match events occur while stopped,
+        // but the fault event is still evaluated and keeps the outputs in their safe state
+        // SAFETY: unsafe only due to .bits(), only the fault event bit is added
+        sct0.stop()
+            .modify(|r, w| unsafe { w.bits(r.bits() | (1 << FAULT_EVENT)) });
+
+        self.fault = Some(config);
+
+        for channel in CHANNELS.iter() {
+            self.set_out_events(channel);
+        }
+
+        // clear any stale fault flag
+        // SAFETY: unsafe only due to .bits(), EVFLAG is write-one-to-clear
+        sct0.evflag().write(|w| unsafe { w.bits(1 << FAULT_EVENT) });
+
+        T::Interrupt::unpend();
+        // SAFETY: the handler only touches the fault event
+        unsafe { T::Interrupt::enable() };
+
+        // unhalt the SCT
+        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit());
+    }
+
+    /// Returns `true` while a fault is latched
+    pub fn is_faulted(&self) -> bool {
+        // SAFETY: read only access
+        let sct0 = unsafe { pac::Sct0::steal() };
+
+        self.fault.is_some() && sct0.evflag().read().bits() & (1 << FAULT_EVENT) != 0
+    }
+
+    /// Wait until a fault forced the outputs to their safe state.
+    ///
+    /// Returns immediately if a fault is already latched.
+    pub async fn wait_for_fault(&mut self) -> Result<(), Error> {
+        if self.fault.is_none() {
+            return Err(Error::NoFault);
+        }
+
+        // SAFETY: safe so long as SCTPwm is not used across multiple executors
+        let sct0 = unsafe { pac::Sct0::steal() };
+
+        poll_fn(|cx| {
+            FAULT_WAKER.register(cx.waker());
+
+            if sct0.evflag().read().bits() & (1 << FAULT_EVENT) != 0 {
+                Poll::Ready(Ok(()))
+            } else {
+                sct0.even()
+                    .modify(|r, w| unsafe { w.bits(r.bits() | (1 << FAULT_EVENT)) });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Re-enable the outputs after a fault.
+    ///
+    /// Fails if the fault input is still active. The counter restarts from zero, channels
+    /// leave their safe state at the start of the next PWM period.
+    pub fn clear_fault(&mut self) -> Result<(), Error> {
+        let Some(config) = self.fault else {
+            return Err(Error::NoFault);
+        };
+
+        // SAFETY: safe so long as SCTPwm is not used across multiple executors
+        let sct0 = unsafe { pac::Sct0::steal() };
+
+        // synchronized input state
+        let level = Level::from(sct0.input().read().bits() & (1 << (16 + FAULT_INPUT)) != 0);
+        if level == config.active_level {
+            return Err(Error::FaultActive);
+        }
+
+        sct0.ctrl().modify(|_, w| w.halt_l().set_bit().clrctr_l().set_bit());
+        // SAFETY: unsafe only due to .bits(), EVFLAG is write-one-to-clear
+        sct0.evflag().write(|w| unsafe { w.bits(1 << FAULT_EVENT) });
+        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit().stop_l().clear_bit());
+
+        Ok(())
+    }
+
+    /// Program the events that set and clear the output of `channel`
+    fn set_out_events(&self, channel: &Channel) {
+        // SAFETY: safe so long as SCTPwm is not used across multiple executors
+        let sct0 = unsafe { pac::Sct0::steal() };
+
+        let (fault_set, fault_clr) = self.fault.map(|f| f.out_masks(channel)).unwrap_or((0, 0));
+        let enabled = sct0.ev(channel.number()).ev_state().read().statemskn().bits() != 0;
+        let (pwm_set, pwm_clr) = if enabled {
+            (1 << LIMIT_EVENT, channel.bit())
+        } else {
+            (0, 0)
+        };
+
+        sct0.out(channel.number()).out_clr().write(|w|
+            // SAFETY: unsafe only required here due to bits(), only events owned by the driver are selected
+            unsafe { w.clr().bits((pwm_clr | fault_clr) as u16) });
+
+        sct0.out(channel.number()).out_set().write(|w|
+            // SAFETY: unsafe only required here due to bits(), only events owned by the driver are selected
+            unsafe { w.set_().bits((pwm_set | fault_set) as u16) });
+    }
 }
 
 impl<T: sealed::SCTimer> Drop for SCTPwm<'_, T> {
@@ -365,9 +633,6 @@ impl<T: sealed::SCTimer> Drop for SCTPwm<'_, T> {
 
 pub use embedded_hal_02::Pwm;
 
-use crate::clocks::enable_and_reset;
-use crate::peripherals::SCT0;
-
 impl<T: sealed::SCTimer> embedded_hal_02::Pwm for SCTPwm<'_, T> {
     type Channel = Channel;
     type Time = MicroSeconds;
@@ -386,8 +651,8 @@ impl<T: sealed::SCTimer> embedded_hal_02::Pwm for SCTPwm<'_, T> {
             // SAFETY: unsafe only required here due to bits() (missing match select specifier), no new conditions from above unsafe (single executor)
                 unsafe { w.statemskn().bits(0) });
 
-        // unhalt the SCT
-        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit().stop_l().clear_bit());
+        // unhalt the SCT, a latched fault keeps it stopped
+        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit());
     }
 
     fn enable(&mut self, channel: Self::Channel) {
@@ -435,13 +700,8 @@ impl<T: sealed::SCTimer> embedded_hal_02::Pwm for SCTPwm<'_, T> {
         // IOn 111111111110000000000
         // match register n is essentially the tick during period P where we turn "off" IOn
 
-        sct0.out(channel.number()).out_clr().modify(|_, w|
-            // SAFETY: unsafe only required here due to bits() (missing match select specifier), no new conditions from above unsafe (single executor)
-                unsafe { w.clr().bits(channel.bit() as u16) });
-
-        sct0.out(channel.number()).out_set().modify(|_, w|
-                    // SAFETY: unsafe only required here due to bits(), no new conditions imposed
-                unsafe { w.set_().bits(1 << 10) });
+        // a configured fault event keeps driving the output as well
+        self.set_out_events(&channel);
 
         // set conflict resolution to SET so that 100% is not treated as 0%
         sct0.res().modify(|_, w| match channel {
@@ -471,8 +731,8 @@ impl<T: sealed::SCTimer> embedded_hal_02::Pwm for SCTPwm<'_, T> {
             Channel::Ch9 => w.setclr9().independent(),
         });
 
-        // unhalt the SCT
-        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit().stop_l().clear_bit());
+        // unhalt the SCT, a latched fault keeps it stopped
+        sct0.ctrl().modify(|_, w| w.halt_l().clear_bit());
     }
 
     fn get_period(&self) -> Self::Time {
@@ -9,7 +9,7 @@ use embassy_hal_internal::into_ref;
 use embassy_sync::waitqueue::AtomicWaker;
 use rand_core::{CryptoRng, RngCore};
 
-use crate::clocks::{enable_and_reset, SysconPeripheral};
+use crate::clocks::{clock_guard, ClockGuard, SysconPeripheral};
 use crate::interrupt::typelevel::Interrupt;
 use crate::{interrupt, peripherals, Peripheral};
 
@@ -59,6 +59,7 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
 /// RNG driver.
 pub struct Rng<'d> {
     info: Info,
+    _clock: ClockGuard,
     _lifetime: PhantomData<&'d ()>,
 }
 
@@ -68,12 +69,13 @@ impl<'d> Rng<'d> {
         _inner: impl Peripheral<P = T> + 'd,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
     ) -> Self {
-        enable_and_reset::<T>();
+        let _clock = clock_guard::<T>();
 
         into_ref!(_inner);
 
         let mut random = Self {
             info: T::info(),
+            _clock,
             _lifetime: PhantomData,
         };
         random.init();
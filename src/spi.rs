@@ -0,0 +1,1532 @@
+//! Serial Peripheral Interface (SPI) driver.
+
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{compiler_fence, Ordering};
+use core::task::Poll;
+
+use embassy_futures::select::{select, Either};
+use embassy_hal_internal::drop::OnDrop;
+use embassy_hal_internal::{into_ref, Peripheral};
+use embassy_sync::waitqueue::AtomicWaker;
+pub use embedded_hal_1::spi::{Mode as SpiMode, Phase, Polarity, MODE_0, MODE_1, MODE_2, MODE_3};
+use paste::paste;
+
+use crate::clocks::ClockGuard;
+use crate::delay::Delay;
+use crate::dma::channel::Channel;
+use crate::dma::transfer::{Transfer, Width};
+use crate::dma::{ChannelDescriptor, DmaChannelStatus, XferCfg};
+use crate::flexcomm::FIFO_DEPTH;
+use crate::gpio::GpioPin as Pin;
+use crate::interrupt::typelevel::Interrupt;
+use crate::iopctl::{DriveMode, DriveStrength, Inverter, IopctlPin, Pull, SlewRate};
+use crate::{dma, interrupt};
+
+/// Driver move trait.
+#[allow(private_bounds)]
+pub trait Mode: sealed::Sealed {}
+
+/// Blocking mode.
+pub struct Blocking;
+impl sealed::Sealed for Blocking {}
+impl Mode for Blocking {}
+
+/// Async mode.
+pub struct Async;
+impl sealed::Sealed for Async {}
+impl Mode for Async {}
+
+/// Pattern clocked out by [`SpiMaster::self_test`].
+const SELF_TEST_PATTERN: [u8; 8] = [0xA5, 0x5A, 0x00, 0xFF, 0x3C, 0xC3, 0x69, 0x96];
+
+/// SPI master driver.
+pub struct SpiMaster<'a, M: Mode> {
+    info: Info,
+    _clock: ClockGuard,
+    manual_cs: bool,
+    dma_threshold: usize,
+    tx_fifo_level: u8,
+    rx_fifo_level: u8,
+    _tx_dma: Option<Channel<'a>>,
+    _rx_dma: Option<Channel<'a>>,
+    _phantom: PhantomData<(&'a (), M)>,
+}
+
+/// SPI config
+#[derive(Clone, Copy)]
+pub struct Config {
+    /// SCK frequency in Hz
+    pub frequency: u32,
+    /// Clock polarity and phase
+    pub mode: SpiMode,
+    /// Internal loopback (TX connected to RX, no external wiring needed)
+    pub loopback: bool,
+    /// Transfer order of the bits in a frame
+    pub bit_order: BitOrder,
+    /// Clock type, the SCK divider is derived from its frequency at init
+    pub clock: crate::flexcomm::Clock,
+    /// Minimum time from chip select assertion to the first SCK edge (tCSS) in ns
+    pub cs_pre_delay_ns: u16,
+    /// Minimum time from the last SCK edge to chip select deassertion (tCSH) in ns
+    pub cs_post_delay_ns: u16,
+    /// Leave chip select asserted after each transfer, it is only released by
+    /// [`SpiMaster::deassert_cs`]
+    pub manual_cs: bool,
+    /// Async transfers shorter than this many bytes are driven by FIFO level interrupts instead
+    /// of DMA, 0 always uses DMA
+    pub dma_threshold: usize,
+    /// FIFO driven writes are woken up to refill the TX FIFO once it holds at most this many
+    /// frames, `0..FIFO_DEPTH`
+    pub tx_fifo_level: u8,
+    /// FIFO driven transfers are woken up to drain the RX FIFO once it holds this many frames,
+    /// `1..=FIFO_DEPTH`
+    pub rx_fifo_level: u8,
+}
+
+/// Default configuration, usable in const context
+const DEFAULT_CONFIG: Config = Config {
+    frequency: 1_000_000,
+    mode: MODE_0,
+    loopback: false,
+    bit_order: BitOrder::MsbFirst,
+    clock: crate::flexcomm::Clock::Sfro,
+    cs_pre_delay_ns: 0,
+    cs_post_delay_ns: 0,
+    manual_cs: false,
+    dma_threshold: 0,
+    tx_fifo_level: 4,
+    rx_fifo_level: 4,
+};
+
+impl Default for Config {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+/// Configuration for an SCK of `target_sclk_hz` with chip select setup (tCSS) and hold (tCSH)
+/// times of `pre_ns` and `post_ns`, the other fields are the defaults.
+///
+/// The times are checked against the range of the chip select delays, 15 SCK periods at
+/// `target_sclk_hz`. Evaluated in a const context, a time that does not fit fails to compile,
+/// otherwise it panics. The actual SCK can only be slower than `target_sclk_hz`, so a
+/// configuration accepted here is also accepted at init, where the delays are converted to
+/// whole periods of the actual SCK.
+///
+/// ```rust,ignore
+/// // 100 ns tCSS at 1 MHz
+/// const ADC_SPI: spi::Config = spi::spi_timing_for(1_000_000, 100, 0);
+/// ```
+pub const fn spi_timing_for(target_sclk_hz: u32, pre_ns: u32, post_ns: u32) -> Config {
+    core::assert!(target_sclk_hz != 0, "SCK frequency must not be 0");
+    core::assert!(
+        pre_ns <= u16::MAX as u32 && delay_clocks(pre_ns as u16, target_sclk_hz).is_ok(),
+        "chip select setup time too long for the SCK frequency"
+    );
+    core::assert!(
+        post_ns <= u16::MAX as u32 && delay_clocks(post_ns as u16, target_sclk_hz).is_ok(),
+        "chip select hold time too long for the SCK frequency"
+    );
+
+    Config {
+        frequency: target_sclk_hz,
+        cs_pre_delay_ns: pre_ns as u16,
+        cs_post_delay_ns: post_ns as u16,
+        ..DEFAULT_CONFIG
+    }
+}
+
+/// SPI frame bit order
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitOrder {
+    /// Most significant bit first, as used by most devices
+    MsbFirst,
+    /// Least significant bit first
+    LsbFirst,
+}
+
+/// SPI Errors
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// RX FIFO overflow
+    Overrun,
+
+    /// TX FIFO underflow
+    Underrun,
+
+    /// Invalid argument
+    InvalidArgument,
+
+    /// Configuration requested is not supported
+    UnsupportedConfiguration,
+
+    /// Other failure
+    Other,
+
+    /// Timed out waiting for chip select
+    Timeout,
+
+    /// DMA transfer could not be set up
+    Dma(dma::Error),
+}
+
+impl From<dma::Error> for Error {
+    fn from(value: dma::Error) -> Self {
+        Error::Dma(value)
+    }
+}
+
+/// shorthand for -> `Result<T>`
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Largest value of the DLY register delay fields, in SPI clock periods
+const MAX_DELAY_CLOCKS: u32 = 15;
+
+/// Number of SPI clock periods covering at least `ns`
+const fn delay_clocks(ns: u16, sck_hz: u32) -> Result<u8> {
+    let clocks = (ns as u64 * sck_hz as u64).div_ceil(1_000_000_000);
+
+    if clocks > MAX_DELAY_CLOCKS as u64 {
+        return Err(Error::UnsupportedConfiguration);
+    }
+
+    Ok(clocks as u8)
+}
+
+impl<'a, M: Mode> SpiMaster<'a, M> {
+    fn new_inner<T: Instance>(
+        config: &Config,
+        _clock: ClockGuard,
+        _tx_dma: Option<Channel<'a>>,
+        _rx_dma: Option<Channel<'a>>,
+    ) -> Self {
+        Self {
+            info: T::info(),
+            _clock,
+            manual_cs: config.manual_cs,
+            dma_threshold: config.dma_threshold,
+            tx_fifo_level: config.tx_fifo_level,
+            rx_fifo_level: config.rx_fifo_level,
+            _tx_dma,
+            _rx_dma,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn init<T: Instance>(config: Config) -> Result<ClockGuard> {
+        if config.frequency == 0
+            || usize::from(config.tx_fifo_level) >= FIFO_DEPTH
+            || !(1..=FIFO_DEPTH).contains(&usize::from(config.rx_fifo_level))
+        {
+            return Err(Error::InvalidArgument);
+        }
+
+        let clock = T::enable(config.clock);
+
+        let source_clock_hz = T::clock_hz();
+        if source_clock_hz == 0 {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        let div = source_clock_hz.div_ceil(config.frequency).max(1) - 1;
+        if div > u32::from(u16::MAX) {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        // Chip select delays are inserted in whole SCK periods
+        let sck_hz = source_clock_hz / (div + 1);
+        let pre_delay = delay_clocks(config.cs_pre_delay_ns, sck_hz)?;
+        let post_delay = delay_clocks(config.cs_post_delay_ns, sck_hz)?;
+
+        T::into_spi();
+
+        let regs = T::info().regs;
+
+        regs.cfg().write(|w| w.enable().clear_bit());
+
+        regs.fifocfg().modify(|_, w| {
+            w.enabletx()
+                .set_bit()
+                .enablerx()
+                .set_bit()
+                .emptytx()
+                .set_bit()
+                .emptyrx()
+                .set_bit()
+        });
+
+        // clear FIFO errors
+        regs.fifostat().write(|w| w.txerr().set_bit().rxerr().set_bit());
+
+        // SAFETY: unsafe only used for .bits()
+        regs.div().write(|w| unsafe { w.divval().bits(div as u16) });
+
+        // SAFETY: unsafe only used for .bits()
+        regs.dly()
+            .write(|w| unsafe { w.pre_delay().bits(pre_delay).post_delay().bits(post_delay) });
+
+        regs.cfg().modify(|_, w| {
+            w.master()
+                .set_bit()
+                .cpol()
+                .bit(config.mode.polarity == Polarity::IdleHigh)
+                .cpha()
+                .bit(config.mode.phase == Phase::CaptureOnSecondTransition)
+                .lsbf()
+                .bit(config.bit_order == BitOrder::LsbFirst)
+                .loop_()
+                .bit(config.loopback)
+        });
+
+        regs.cfg().modify(|_, w| w.enable().set_bit());
+
+        Ok(clock)
+    }
+
+    /// Write one frame to the TX FIFO, optionally ignoring the received frame.
+    fn write_frame(&mut self, byte: u8, rx_ignore: bool, last: bool) {
+        while self.info.regs.fifostat().read().txnotfull().bit_is_clear() {}
+
+        // SAFETY: unsafe only used for .bits()
+        self.info.regs.fifowr().write(|w| unsafe {
+            w.txdata()
+                .bits(u16::from(byte))
+                .txssel0_n()
+                .clear_bit()
+                .eot()
+                .bit(last && !self.manual_cs)
+                .rxignore()
+                .bit(rx_ignore)
+                .len()
+                .bits(7)
+        });
+    }
+
+    fn read_frame(&mut self) -> Result<u8> {
+        loop {
+            let stat = self.info.regs.fifostat().read();
+
+            if stat.rxerr().bit_is_set() {
+                self.info.regs.fifocfg().modify(|_, w| w.emptyrx().set_bit());
+                self.info.regs.fifostat().write(|w| w.rxerr().set_bit());
+                return Err(Error::Overrun);
+            }
+
+            if stat.rxnotempty().bit_is_set() {
+                return Ok(self.info.regs.fiford().read().rxdata().bits() as u8);
+            }
+        }
+    }
+
+    /// Transfer `buf` in place, blocking execution until done.
+    fn blocking_transfer_in_place_inner(&mut self, buf: &mut [u8]) -> Result<()> {
+        let len = buf.len();
+
+        for (i, b) in buf.iter_mut().enumerate() {
+            self.write_frame(*b, false, i + 1 == len);
+            *b = self.read_frame()?;
+        }
+
+        Ok(())
+    }
+
+    fn blocking_flush_inner(&mut self) -> Result<()> {
+        while self.info.regs.fifostat().read().txempty().bit_is_clear() {}
+        while self.info.regs.stat().read().mstidle().bit_is_clear() {}
+        Ok(())
+    }
+
+    fn set_loopback(&mut self, enable: bool) {
+        let regs = self.info.regs;
+
+        regs.cfg().modify(|_, w| w.enable().clear_bit());
+        regs.cfg().modify(|_, w| w.loop_().bit(enable));
+        regs.cfg().modify(|_, w| w.enable().set_bit());
+    }
+
+    /// Write the control half of FIFOWR, used for the following data-only writes.
+    fn write_tx_control(&mut self, ctrl: u16) {
+        // SAFETY: 16-bit write to the upper half of FIFOWR only updates the
+        // control bits held for subsequent data-only writes.
+        unsafe {
+            (self.info.regs.fifowr().as_ptr() as *mut u16)
+                .add(1)
+                .write_volatile(ctrl);
+        }
+    }
+
+    /// Select SSEL0 for the following transfers and keep it asserted between them.
+    ///
+    /// SSEL0 goes active with the next frame, `cs_pre_delay_ns` before its first SCK edge, and
+    /// stays active across transfers until [`SpiMaster::deassert_cs`]. Only available with
+    /// [`Config::manual_cs`], returns [`Error::UnsupportedConfiguration`] otherwise.
+    ///
+    /// The application is responsible for the sequencing: nothing else may use the bus while chip
+    /// select is held, and chip select must be deasserted before its polarity is changed.
+    pub fn assert_cs(&mut self) -> Result<()> {
+        if !self.manual_cs {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        // Frame length 8 bits, SSEL0 asserted, no end of transfer
+        self.write_tx_control((7 << 8) | 0b1110);
+
+        Ok(())
+    }
+
+    /// Release SSEL0 held by [`SpiMaster::assert_cs`] once the frames already queued are sent.
+    ///
+    /// Only available with [`Config::manual_cs`], returns [`Error::UnsupportedConfiguration`] otherwise.
+    pub fn deassert_cs(&mut self) -> Result<()> {
+        if !self.manual_cs {
+            return Err(Error::UnsupportedConfiguration);
+        }
+
+        while self.info.regs.fifostat().read().txempty().bit_is_clear() {}
+
+        // Ends the transfer as if the last frame had EOT set, honouring `cs_post_delay_ns`
+        self.info.regs.stat().write(|w| w.endtransfer().set_bit());
+
+        while self.info.regs.stat().read().mstidle().bit_is_clear() {}
+
+        // Frame length 8 bits, no slave selected
+        self.write_tx_control((7 << 8) | 0b1111);
+
+        Ok(())
+    }
+}
+
+impl<'a> SpiMaster<'a, Blocking> {
+    /// Create a new blocking SPI master
+    pub fn new_blocking<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        sck: impl Peripheral<P = impl SckPin<T>> + 'a,
+        mosi: impl Peripheral<P = impl MosiPin<T>> + 'a,
+        miso: impl Peripheral<P = impl MisoPin<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(sck);
+        into_ref!(mosi);
+        into_ref!(miso);
+
+        sck.as_sck();
+        mosi.as_mosi();
+        miso.as_miso();
+
+        let clock = Self::init::<T>(config)?;
+
+        Ok(Self::new_inner::<T>(&config, clock, None, None))
+    }
+
+    /// Create a new blocking SPI master driving its chip select on `ssel`
+    pub fn new_blocking_with_cs<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        sck: impl Peripheral<P = impl SckPin<T>> + 'a,
+        mosi: impl Peripheral<P = impl MosiPin<T>> + 'a,
+        miso: impl Peripheral<P = impl MisoPin<T>> + 'a,
+        ssel: impl Peripheral<P = impl SselPin<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(ssel);
+        ssel.as_ssel();
+
+        Self::new_blocking(_inner, sck, mosi, miso, config)
+    }
+
+    /// Read into `buf`, clocking out zeros, blocking execution until done.
+    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<()> {
+        buf.fill(0);
+        self.blocking_transfer_in_place_inner(buf)
+    }
+
+    /// Transmit the provided buffer blocking execution until done.
+    pub fn blocking_write(&mut self, buf: &[u8]) -> Result<()> {
+        let len = buf.len();
+
+        for (i, b) in buf.iter().enumerate() {
+            self.write_frame(*b, true, i + 1 == len);
+        }
+
+        self.blocking_flush_inner()
+    }
+
+    /// Transmit `segments` back to back as a single transfer, blocking execution until done.
+    ///
+    /// Chip select stays asserted from the first byte of the first segment to the last byte of
+    /// the last one, e.g. to send a command header followed by a payload held in another buffer.
+    pub fn write_vectored(&mut self, segments: &[&[u8]]) -> Result<()> {
+        let len: usize = segments.iter().map(|s| s.len()).sum();
+        let mut sent = 0;
+
+        for b in segments.iter().flat_map(|s| s.iter()) {
+            sent += 1;
+            self.write_frame(*b, true, sent == len);
+        }
+
+        self.blocking_flush_inner()
+    }
+
+    /// Transmit `write` while receiving into `read`, blocking execution until done.
+    ///
+    /// If the buffers differ in length, the shorter one is padded: extra
+    /// frames are clocked out as zeros and extra received frames are dropped.
+    pub fn blocking_transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<()> {
+        let len = read.len().max(write.len());
+
+        for i in 0..len {
+            let byte = write.get(i).copied().unwrap_or(0);
+            self.write_frame(byte, false, i + 1 == len);
+            let rx = self.read_frame()?;
+
+            if let Some(r) = read.get_mut(i) {
+                *r = rx;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transmit `buf` and replace its contents with the received data.
+    pub fn blocking_transfer_in_place(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.blocking_transfer_in_place_inner(buf)
+    }
+
+    /// Flush SPI TX blocking execution until done.
+    pub fn blocking_flush(&mut self) -> Result<()> {
+        self.blocking_flush_inner()
+    }
+
+    /// Verify the data path by transferring a test pattern over internal loopback.
+    ///
+    /// Loopback is enabled for the duration of the test and the previous
+    /// setting is restored afterwards. Returns [`Error::Other`] on mismatch.
+    pub fn self_test(&mut self) -> Result<()> {
+        let was_loopback = self.info.regs.cfg().read().loop_().bit_is_set();
+
+        self.set_loopback(true);
+
+        let mut buf = SELF_TEST_PATTERN;
+        let res = self.blocking_transfer_in_place_inner(&mut buf);
+
+        self.set_loopback(was_loopback);
+
+        res?;
+
+        if buf == SELF_TEST_PATTERN {
+            Ok(())
+        } else {
+            Err(Error::Other)
+        }
+    }
+}
+
+impl<'a> SpiMaster<'a, Async> {
+    /// Create a new DMA enabled SPI master
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_async<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        sck: impl Peripheral<P = impl SckPin<T>> + 'a,
+        mosi: impl Peripheral<P = impl MosiPin<T>> + 'a,
+        miso: impl Peripheral<P = impl MisoPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        tx_dma: impl Peripheral<P = impl TxDma<T>> + 'a,
+        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(sck);
+        into_ref!(mosi);
+        into_ref!(miso);
+
+        sck.as_sck();
+        mosi.as_mosi();
+        miso.as_miso();
+
+        let clock = Self::init::<T>(config)?;
+
+        T::Interrupt::unpend();
+        unsafe { T::Interrupt::enable() };
+
+        let tx_dma = dma::Dma::reserve_channel(tx_dma);
+        let rx_dma = dma::Dma::reserve_channel(rx_dma);
+
+        Ok(Self::new_inner::<T>(&config, clock, tx_dma, rx_dma))
+    }
+
+    /// Create a new DMA enabled SPI master driving its chip select on `ssel`
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_async_with_cs<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        sck: impl Peripheral<P = impl SckPin<T>> + 'a,
+        mosi: impl Peripheral<P = impl MosiPin<T>> + 'a,
+        miso: impl Peripheral<P = impl MisoPin<T>> + 'a,
+        ssel: impl Peripheral<P = impl SselPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        tx_dma: impl Peripheral<P = impl TxDma<T>> + 'a,
+        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(ssel);
+        ssel.as_ssel();
+
+        Self::new_async(_inner, sck, mosi, miso, _irq, tx_dma, rx_dma, config)
+    }
+
+    /// Program the FIFOWR control half used for subsequent DMA data writes.
+    fn set_tx_control(&mut self, rx_ignore: bool) {
+        // Frame length 8 bits, SSEL0 asserted, EOT left to the idle state.
+        self.write_tx_control((7 << 8) | (u16::from(rx_ignore) << 6) | 0b1110);
+    }
+
+    /// Transmit the provided buffer asynchronously.
+    pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        if buf.len() < self.dma_threshold {
+            return self.write_fifo(buf, true).await;
+        }
+
+        self.write_dma(buf).await?;
+        self.flush().await
+    }
+
+    /// Transmit `data` asynchronously, discarding the received bytes.
+    ///
+    /// The received frames are ignored by the SPI block itself, so only the TX DMA channel is
+    /// used and the RX FIFO cannot overflow.
+    pub async fn write_dummy(&mut self, data: &[u8]) -> Result<()> {
+        self.write(data).await
+    }
+
+    /// Transmit `tx` and then receive into `rx`, clocking out zeros, asynchronously.
+    ///
+    /// Chip select stays asserted between the two phases, e.g. for a command followed by its
+    /// response. Bytes received during `tx` are discarded.
+    pub async fn write_then_read(&mut self, tx: &[u8], rx: &mut [u8]) -> Result<()> {
+        if rx.is_empty() {
+            return self.write(tx).await;
+        }
+
+        // No end of transfer after the write phase, the read phase continues the same transfer
+        if tx.len() < self.dma_threshold {
+            self.write_fifo(tx, false).await?;
+        } else {
+            self.write_dma(tx).await?;
+        }
+
+        self.read(rx).await
+    }
+
+    /// Transmit `tx` while receiving into `rx` asynchronously.
+    ///
+    /// Both buffers must have the same length, returns [`Error::InvalidArgument`] otherwise. With
+    /// DMA, TX and RX run on their own channels and the transfer completes once both are done.
+    pub async fn transfer_exact(&mut self, tx: &[u8], rx: &mut [u8]) -> Result<()> {
+        if tx.len() != rx.len() {
+            return Err(Error::InvalidArgument);
+        }
+
+        if rx.len() < self.dma_threshold {
+            return self.transfer_fifo(Some(tx), rx).await;
+        }
+
+        self.set_tx_control(false);
+
+        for (tx_chunk, rx_chunk) in tx.chunks(1024).zip(rx.chunks_mut(1024)) {
+            self.transfer_dma(tx_chunk, rx_chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Write `buf` to the TX FIFO by DMA, without waiting for it to be clocked out.
+    async fn write_dma(&mut self, buf: &[u8]) -> Result<()> {
+        let regs = self.info.regs;
+
+        self.set_tx_control(true);
+
+        for chunk in buf.chunks(1024) {
+            regs.fifocfg().modify(|_, w| w.dmatx().set_bit());
+
+            let res = Transfer::new_write(
+                self._tx_dma.as_ref().unwrap(),
+                chunk,
+                regs.fifowr().as_ptr() as *mut u8,
+                Default::default(),
+            )?
+            .await;
+
+            regs.fifocfg().modify(|_, w| w.dmatx().clear_bit());
+            res?;
+        }
+
+        Ok(())
+    }
+
+    /// Transmit `segments` back to back as a single transfer asynchronously.
+    ///
+    /// The segments are chained as linked DMA descriptors, so the TX FIFO is fed without gaps
+    /// and chip select stays asserted across segment boundaries. Each descriptor moves up to
+    /// 1024 bytes and at most [`VECTORED_MAX_DESCRIPTORS`] are available, returns
+    /// [`Error::InvalidArgument`] if the segments need more. Empty segments are skipped.
+    pub async fn write_vectored_async(&mut self, segments: &[&[u8]]) -> Result<()> {
+        let regs = self.info.regs;
+
+        let count: usize = segments
+            .iter()
+            .map(|s| s.len().div_ceil(VECTORED_MAX_DESCRIPTOR_LEN))
+            .sum();
+        if count == 0 {
+            return Ok(());
+        }
+        if count > VECTORED_MAX_DESCRIPTORS {
+            return Err(Error::InvalidArgument);
+        }
+
+        for segment in segments.iter().filter(|s| !s.is_empty()) {
+            dma::buffer::check_source(segment.as_ptr(), segment.len(), Width::Bit8)?;
+        }
+
+        // SAFETY: the descriptors of an SPI instance are only used by the master owning it, and
+        // `&mut self` keeps a second vectored write from starting while this one runs
+        let descriptors = unsafe { &mut (*addr_of_mut!(VECTORED_DESCRIPTORS))[self.info.index] };
+
+        let chunks = segments.iter().flat_map(|s| s.chunks(VECTORED_MAX_DESCRIPTOR_LEN));
+        for (i, chunk) in chunks.enumerate() {
+            let last = i + 1 == count;
+
+            let xfercfg = XferCfg::new(Width::Bit8, chunk.len()).src_increment();
+            let xfercfg = if last {
+                xfercfg.clear_trigger().interrupt_a()
+            } else {
+                xfercfg.reload()
+            };
+
+            descriptors[i] = ChannelDescriptor {
+                reserved: xfercfg.bits(),
+                src_data_end_addr: chunk.as_ptr() as u32 + chunk.len() as u32 - 1,
+                dst_data_end_addr: regs.fifowr().as_ptr() as u32,
+                nxt_desc_link_addr: if last {
+                    0
+                } else {
+                    &descriptors[i + 1] as *const _ as u32
+                },
+            };
+        }
+
+        // The DMA controller reads the chain from memory once started
+        compiler_fence(Ordering::SeqCst);
+        cortex_m::asm::dsb();
+
+        self.set_tx_control(true);
+        regs.fifocfg().modify(|_, w| w.dmatx().set_bit());
+
+        let ch = self._tx_dma.as_ref().unwrap();
+        let on_drop = OnDrop::new(|| {
+            ch.abort();
+            regs.fifocfg().modify(|_, w| w.dmatx().clear_bit());
+        });
+
+        ch.start_linked(&descriptors[0]);
+
+        let res = poll_fn(|cx| {
+            ch.get_waker().register(cx.waker());
+
+            // An error also wakes the channel, it must not look like a completion
+            if let DmaChannelStatus::Error(_) = ch.status() {
+                Poll::Ready(Err(Error::Dma(dma::Error::TransferError)))
+            } else if !ch.is_active() {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        drop(on_drop);
+        res?;
+
+        self.flush().await
+    }
+
+    /// Read into `buf` asynchronously, clocking out zeros.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<()> {
+        buf.fill(0);
+        self.transfer_in_place(buf).await
+    }
+
+    /// Transmit `buf` and replace its contents with the received data asynchronously.
+    ///
+    /// TX and RX work on `buf` directly, no bounce buffer is used. Transfers shorter than
+    /// [`Config::dma_threshold`] are driven by FIFO level interrupts, longer ones by DMA.
+    pub async fn transfer_in_place(&mut self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() < self.dma_threshold {
+            return self.transfer_fifo(None, buf).await;
+        }
+
+        self.set_tx_control(false);
+
+        for chunk in buf.chunks_mut(1024) {
+            let len = chunk.len();
+            let ptr = chunk.as_mut_ptr();
+
+            // SAFETY: TX always runs ahead of RX, so every byte is read by
+            // the TX channel before the RX channel writes its response.
+            let (tx_buf, rx_buf) = unsafe {
+                (
+                    core::slice::from_raw_parts(ptr as *const u8, len),
+                    core::slice::from_raw_parts_mut(ptr, len),
+                )
+            };
+
+            self.transfer_dma(tx_buf, rx_buf).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Transfer one chunk of at most 1024 bytes by DMA, TX and RX on their own channels.
+    async fn transfer_dma(&mut self, tx_buf: &[u8], rx_buf: &mut [u8]) -> Result<()> {
+        let regs = self.info.regs;
+
+        // Both channels wait for the FIFO requests, which are only enabled once
+        // both transfers have been set up
+        let rx = Transfer::new_read(
+            self._rx_dma.as_ref().unwrap(),
+            regs.fiford().as_ptr() as *const u8,
+            rx_buf,
+            Default::default(),
+        )?;
+        let tx = Transfer::new_write(
+            self._tx_dma.as_ref().unwrap(),
+            tx_buf,
+            regs.fifowr().as_ptr() as *mut u8,
+            Default::default(),
+        )?;
+
+        regs.fifocfg().modify(|_, w| w.dmarx().set_bit().dmatx().set_bit());
+        let on_drop = OnDrop::new(|| {
+            regs.fifocfg().modify(|_, w| w.dmarx().clear_bit().dmatx().clear_bit());
+        });
+
+        let (tx_res, rx_res) = embassy_futures::join::join(tx, rx).await;
+
+        drop(on_drop);
+        tx_res?;
+        rx_res?;
+
+        if regs.fifostat().read().rxerr().bit_is_set() {
+            regs.fifocfg().modify(|_, w| w.emptyrx().set_bit());
+            regs.fifostat().write(|w| w.rxerr().set_bit());
+            return Err(Error::Overrun);
+        }
+
+        Ok(())
+    }
+
+    /// Write `buf` through the TX FIFO, refilling it from the TX level interrupt.
+    ///
+    /// With `end` the last frame ends the transfer and the write is flushed, otherwise the
+    /// transfer is left open for the frames that follow.
+    async fn write_fifo(&mut self, buf: &[u8], end: bool) -> Result<()> {
+        let regs = self.info.regs;
+        let len = buf.len();
+        let mut sent = 0;
+
+        while sent < len {
+            while sent < len && regs.fifostat().read().txnotfull().bit_is_set() {
+                self.write_frame(buf[sent], true, end && sent + 1 == len);
+                sent += 1;
+            }
+
+            if sent == len {
+                break;
+            }
+
+            let level = u32::from(self.tx_fifo_level);
+            // SAFETY: unsafe only used for .bits(), the level is below the FIFO depth
+            regs.fifotrig()
+                .write(|w| unsafe { w.txlvlena().set_bit().txlvl().bits(level as u8) });
+
+            self.wait_on(
+                |me| {
+                    if u32::from(me.info.regs.fifostat().read().txlvl().bits()) <= level {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                },
+                |me| {
+                    me.info.regs.fifointenset().write(|w| w.txlvl().set_bit());
+                },
+            )
+            .await;
+        }
+
+        if end {
+            self.flush().await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Transmit `tx`, or `buf` itself when `None`, through the FIFOs while receiving into `buf`,
+    /// draining RX from the RX level interrupt.
+    async fn transfer_fifo(&mut self, tx: Option<&[u8]>, buf: &mut [u8]) -> Result<()> {
+        let regs = self.info.regs;
+        let len = buf.len();
+        let mut sent = 0;
+        let mut received = 0;
+
+        while received < len {
+            // At most a FIFO worth of frames in flight, so RX cannot overflow while we wait
+            while sent < len && sent - received < FIFO_DEPTH && regs.fifostat().read().txnotfull().bit_is_set() {
+                // In place, a byte is always sent before its slot receives
+                let byte = tx.map_or(buf[sent], |tx| tx[sent]);
+                self.write_frame(byte, false, sent + 1 == len);
+                sent += 1;
+            }
+
+            while received < sent && regs.fifostat().read().rxnotempty().bit_is_set() {
+                buf[received] = self.read_frame()?;
+                received += 1;
+            }
+
+            if received == len {
+                break;
+            }
+
+            // Fewer frames than the trigger level may be left in flight at the end
+            let level = usize::from(self.rx_fifo_level).min(sent - received) as u32;
+            // SAFETY: unsafe only used for .bits(), the level is at most the FIFO depth. The trigger
+            // fires at RXLVL + 1 frames
+            regs.fifotrig()
+                .write(|w| unsafe { w.rxlvlena().set_bit().rxlvl().bits((level - 1) as u8) });
+
+            self.wait_on(
+                |me| {
+                    let stat = me.info.regs.fifostat().read();
+                    if u32::from(stat.rxlvl().bits()) >= level || stat.rxerr().bit_is_set() {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                },
+                |me| {
+                    me.info
+                        .regs
+                        .fifointenset()
+                        .write(|w| w.rxlvl().set_bit().rxerr().set_bit());
+                },
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Flush SPI TX asynchronously.
+    ///
+    /// Completes once every queued frame has been clocked out and the master is idle, which
+    /// includes the end of transfer chip select delay. Data received by the transfers above has
+    /// already been drained from the RX FIFO when they return.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.wait_on(
+            |me| {
+                if me.info.regs.fifostat().read().txempty().bit_is_set()
+                    && me.info.regs.stat().read().mstidle().bit_is_set()
+                {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            },
+            |me| {
+                me.info.regs.intenset().write(|w| w.mstidleen().set_bit());
+            },
+        )
+        .await
+    }
+
+    /// Calls `f` to check if we are ready or not.
+    /// If not, `g` is called once the waker is set (to eg enable the required interrupts).
+    async fn wait_on<F, U, G>(&mut self, mut f: F, mut g: G) -> U
+    where
+        F: FnMut(&mut Self) -> Poll<U>,
+        G: FnMut(&mut Self),
+    {
+        poll_fn(|cx| {
+            let r = f(self);
+
+            if r.is_pending() {
+                SPI_WAKERS[self.info.index].register(cx.waker());
+                g(self);
+            }
+
+            r
+        })
+        .await
+    }
+}
+
+/// SPI target (slave) driver.
+///
+/// Frames are clocked by the controller, so reception is done by DMA armed ahead of
+/// the chip select. Transaction boundaries are reported by the SSEL0 assert and
+/// deassert interrupts.
+pub struct SpiTarget<'a> {
+    info: Info,
+    _clock: ClockGuard,
+    #[cfg(feature = "time")]
+    cs_timeout: Option<embassy_time::Duration>,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> SpiTarget<'a> {
+    /// Create a new SPI target using SSEL0 as chip select.
+    ///
+    /// Only `mode` and `clock` of `config` apply, the SCK frequency is set by the controller.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_async<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        sck: impl Peripheral<P = impl SckPin<T>> + 'a,
+        mosi: impl Peripheral<P = impl MosiPin<T>> + 'a,
+        miso: impl Peripheral<P = impl MisoPin<T>> + 'a,
+        ssel: impl Peripheral<P = impl SselPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(sck);
+        into_ref!(mosi);
+        into_ref!(miso);
+        into_ref!(ssel);
+
+        sck.as_sck();
+        mosi.as_mosi();
+        miso.as_miso();
+        ssel.as_ssel();
+
+        let _clock = T::enable(config.clock);
+        T::into_spi();
+
+        let regs = T::info().regs;
+
+        regs.cfg().write(|w| w.enable().clear_bit());
+
+        regs.fifocfg().modify(|_, w| {
+            w.enabletx()
+                .set_bit()
+                .enablerx()
+                .set_bit()
+                .emptytx()
+                .set_bit()
+                .emptyrx()
+                .set_bit()
+        });
+        regs.fifostat().write(|w| w.txerr().set_bit().rxerr().set_bit());
+
+        regs.cfg().modify(|_, w| {
+            w.master()
+                .clear_bit()
+                .cpol()
+                .bit(config.mode.polarity == Polarity::IdleHigh)
+                .cpha()
+                .bit(config.mode.phase == Phase::CaptureOnSecondTransition)
+                .lsbf()
+                .bit(config.bit_order == BitOrder::LsbFirst)
+        });
+
+        regs.cfg().modify(|_, w| w.enable().set_bit());
+
+        T::Interrupt::unpend();
+        unsafe { T::Interrupt::enable() };
+
+        Ok(Self {
+            info: T::info(),
+            _clock,
+            #[cfg(feature = "time")]
+            cs_timeout: None,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Limit how long [`Self::receive_dma`] waits for the chip select to assert, `None` waits forever.
+    #[cfg(feature = "time")]
+    pub fn set_cs_timeout(&mut self, timeout: Option<embassy_time::Duration>) {
+        self.cs_timeout = timeout;
+    }
+
+    /// Wait for the controller to assert chip select.
+    pub async fn wait_for_select(&mut self) {
+        let regs = self.info.regs;
+
+        poll_fn(|cx| {
+            SPI_WAKERS[self.info.index].register(cx.waker());
+
+            if regs.stat().read().ssa().bit_is_set() {
+                regs.stat().write(|w| w.ssa().set_bit());
+                return Poll::Ready(());
+            }
+
+            regs.intenset().write(|w| w.ssaen().set_bit());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Wait for the controller to deassert chip select, ending the transaction.
+    pub async fn wait_for_deselect(&mut self) {
+        let regs = self.info.regs;
+
+        poll_fn(|cx| {
+            SPI_WAKERS[self.info.index].register(cx.waker());
+
+            if regs.stat().read().ssd().bit_is_set() {
+                regs.stat().write(|w| w.ssd().set_bit());
+                return Poll::Ready(());
+            }
+
+            regs.intenset().write(|w| w.ssden().set_bit());
+            Poll::Pending
+        })
+        .await
+    }
+
+    async fn wait_for_select_timeout(&mut self) -> Result<()> {
+        #[cfg(feature = "time")]
+        if let Some(timeout) = self.cs_timeout {
+            return embassy_time::with_timeout(timeout, self.wait_for_select())
+                .await
+                .map_err(|_| Error::Timeout);
+        }
+
+        self.wait_for_select().await;
+        Ok(())
+    }
+
+    /// Receive one transaction into `buf` with DMA, returning the number of bytes received.
+    ///
+    /// The DMA transfer is armed before chip select asserts, so no data is lost at high
+    /// SCK rates. Completes when `buf` is full or the controller deasserts chip select,
+    /// whichever comes first. Nothing is transmitted, the target drives MISO with
+    /// whatever the empty TX FIFO provides.
+    ///
+    /// Buffers longer than 1024 bytes, the most one DMA transfer moves, are received in chunks.
+    /// The RX FIFO covers the re-arming between chunks, [`Error::Overrun`] is returned if the
+    /// controller clocks faster than that.
+    pub async fn receive_dma(&mut self, buf: &mut [u8], dma: &Channel<'_>) -> Result<usize> {
+        let regs = self.info.regs;
+
+        // Start from a clean slate, stale select events belong to earlier transactions
+        regs.stat().write(|w| w.ssa().set_bit().ssd().set_bit());
+        regs.fifocfg().modify(|_, w| w.emptyrx().set_bit().emptytx().set_bit());
+        regs.fifostat().write(|w| w.txerr().set_bit().rxerr().set_bit());
+        regs.fifocfg().modify(|_, w| w.dmarx().set_bit());
+
+        let mut received = 0;
+        let mut res = Ok(());
+        for (i, chunk) in buf.chunks_mut(1024).enumerate() {
+            match self.receive_dma_chunk(chunk, dma, i == 0).await {
+                Ok((len, complete)) => {
+                    received += len;
+                    if !complete {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    res = Err(e);
+                    break;
+                }
+            }
+        }
+
+        regs.fifocfg().modify(|_, w| w.dmarx().clear_bit());
+
+        if regs.fifostat().read().rxerr().bit_is_set() {
+            regs.fifocfg().modify(|_, w| w.emptyrx().set_bit());
+            regs.fifostat().write(|w| w.rxerr().set_bit());
+            return Err(Error::Overrun);
+        }
+
+        res.map(|()| received)
+    }
+
+    /// Receive one chunk of at most 1024 bytes by DMA, waiting for chip select to assert first
+    /// if `first`.
+    ///
+    /// Returns the number of bytes received and whether the chunk was filled, `false` once chip
+    /// select deasserted.
+    async fn receive_dma_chunk(&mut self, buf: &mut [u8], dma: &Channel<'_>, first: bool) -> Result<(usize, bool)> {
+        let regs = self.info.regs;
+        let len = buf.len();
+
+        let mut transfer = Transfer::new_read(dma, regs.fiford().as_ptr() as *const u8, buf, Default::default())?;
+
+        if first {
+            self.wait_for_select_timeout().await?;
+        }
+
+        match select(&mut transfer, self.wait_for_deselect()).await {
+            Either::First(Ok(())) => Ok((len, true)),
+            Either::First(Err(e)) => Err(e.into()),
+            Either::Second(()) => {
+                // Let the DMA drain the frames still in the FIFO
+                while regs.fifostat().read().rxnotempty().bit_is_set() && dma.is_active() {}
+
+                let received = if dma.is_active() {
+                    len - (usize::from(dma.get_xfer_count()) + 1)
+                } else {
+                    len
+                };
+
+                Ok((received, false))
+            }
+        }
+    }
+}
+
+impl embedded_hal_1::spi::Error for Error {
+    fn kind(&self) -> embedded_hal_1::spi::ErrorKind {
+        match *self {
+            Self::Overrun => embedded_hal_1::spi::ErrorKind::Overrun,
+            Self::Underrun
+            | Self::InvalidArgument
+            | Self::UnsupportedConfiguration
+            | Self::Other
+            | Self::Timeout
+            | Self::Dma(_) => embedded_hal_1::spi::ErrorKind::Other,
+        }
+    }
+}
+
+impl embedded_hal_1::spi::ErrorType for SpiMaster<'_, Blocking> {
+    type Error = Error;
+}
+
+impl embedded_hal_1::spi::SpiBus for SpiMaster<'_, Blocking> {
+    fn read(&mut self, words: &mut [u8]) -> Result<()> {
+        self.blocking_read(words)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<()> {
+        self.blocking_write(words)
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<()> {
+        self.blocking_transfer(read, write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<()> {
+        self.blocking_transfer_in_place(words)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.blocking_flush()
+    }
+}
+
+/// [`embedded_hal_1::spi::SpiDevice`] owning a blocking bus and a GPIO chip select
+///
+/// Chip select is driven low for the duration of each transaction, the bus is not shared with
+/// other devices. Errors of the chip select pin are reported as [`Error::Other`].
+pub struct SpiDeviceExclusive<'a, CS: embedded_hal_1::digital::OutputPin> {
+    bus: SpiMaster<'a, Blocking>,
+    cs: CS,
+}
+
+impl<'a, CS: embedded_hal_1::digital::OutputPin> SpiDeviceExclusive<'a, CS> {
+    /// Create a device on `bus`, deasserting `cs`
+    pub fn new(bus: SpiMaster<'a, Blocking>, mut cs: CS) -> Result<Self> {
+        cs.set_high().map_err(|_| Error::Other)?;
+
+        Ok(Self { bus, cs })
+    }
+
+    /// Release the bus and the chip select pin
+    pub fn release(self) -> (SpiMaster<'a, Blocking>, CS) {
+        (self.bus, self.cs)
+    }
+}
+
+impl<CS: embedded_hal_1::digital::OutputPin> embedded_hal_1::spi::ErrorType for SpiDeviceExclusive<'_, CS> {
+    type Error = Error;
+}
+
+impl<CS: embedded_hal_1::digital::OutputPin> embedded_hal_1::spi::SpiDevice for SpiDeviceExclusive<'_, CS> {
+    fn transaction(&mut self, operations: &mut [embedded_hal_1::spi::Operation<'_, u8>]) -> Result<()> {
+        use embedded_hal_1::spi::Operation;
+
+        self.cs.set_low().map_err(|_| Error::Other)?;
+
+        let res = operations.iter_mut().try_for_each(|op| match op {
+            Operation::Read(buf) => self.bus.blocking_read(buf),
+            Operation::Write(buf) => self.bus.blocking_write(buf),
+            Operation::Transfer(read, write) => self.bus.blocking_transfer(read, write),
+            Operation::TransferInPlace(buf) => self.bus.blocking_transfer_in_place(buf),
+            Operation::DelayNs(ns) => {
+                embedded_hal_1::delay::DelayNs::delay_ns(&mut Delay, *ns);
+                Ok(())
+            }
+        });
+
+        // Chip select must not be released before the last frame left the shifter
+        let flush = self.bus.blocking_flush();
+        let cs = self.cs.set_high().map_err(|_| Error::Other);
+
+        res.and(flush).and(cs)
+    }
+}
+
+struct Info {
+    regs: &'static crate::pac::spi0::RegisterBlock,
+    index: usize,
+}
+
+// SAFETY: static MMIO reference owned through the FLEXCOMM peripheral, no other driver uses it.
+unsafe impl Send for Info {}
+
+trait SealedInstance {
+    fn info() -> Info;
+    fn index() -> usize;
+}
+
+/// SPI interrupt handler.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+const SPI_COUNT: usize = 9;
+static SPI_WAKERS: [AtomicWaker; SPI_COUNT] = [const { AtomicWaker::new() }; SPI_COUNT];
+
+/// Linked DMA descriptors available to a [`SpiMaster::write_vectored_async`]
+pub const VECTORED_MAX_DESCRIPTORS: usize = 8;
+
+/// Largest chunk a single DMA descriptor can move
+const VECTORED_MAX_DESCRIPTOR_LEN: usize = 1024;
+
+static mut VECTORED_DESCRIPTORS: [[ChannelDescriptor; VECTORED_MAX_DESCRIPTORS]; SPI_COUNT] =
+    [[ChannelDescriptor::EMPTY; VECTORED_MAX_DESCRIPTORS]; SPI_COUNT];
+
+impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        let waker = &SPI_WAKERS[T::index()];
+        let regs = T::info().regs;
+
+        let stat = regs.intstat().read();
+
+        if stat.mstidle().bit_is_set() {
+            regs.intenclr().write(|w| w.mstidle().set_bit());
+        }
+
+        // FIFO driven transfers
+        let fifo_stat = regs.fifointstat().read();
+        if fifo_stat.txlvl().bit_is_set() || fifo_stat.rxlvl().bit_is_set() || fifo_stat.rxerr().bit_is_set() {
+            regs.fifointenclr()
+                .write(|w| w.txlvl().set_bit().rxlvl().set_bit().rxerr().set_bit());
+        }
+
+        // Target chip select asserted or deasserted
+        if stat.ssa().bit_is_set() {
+            regs.intenclr().write(|w| w.ssaen().set_bit());
+        }
+
+        if stat.ssd().bit_is_set() {
+            regs.intenclr().write(|w| w.ssden().set_bit());
+        }
+
+        waker.wake();
+    }
+}
+
+/// SPI instance trait.
+#[allow(private_bounds)]
+pub trait Instance: crate::flexcomm::IntoSpi + SealedInstance + Peripheral<P = Self> + 'static + Send {
+    /// Interrupt for this SPI instance.
+    type Interrupt: interrupt::typelevel::Interrupt;
+}
+
+macro_rules! impl_instance {
+    ($($n:expr),*) => {
+        $(
+            paste!{
+                impl SealedInstance for crate::peripherals::[<FLEXCOMM $n>] {
+                    fn info() -> Info {
+                        Info {
+                            regs: unsafe { &*crate::pac::[<Spi $n>]::ptr() },
+                            index: Self::index(),
+                        }
+                    }
+
+                    #[inline]
+                    fn index() -> usize {
+                        if $n == 14 {
+                            return 8;
+                        }
+
+                        $n
+                    }
+                }
+
+                impl Instance for crate::peripherals::[<FLEXCOMM $n>] {
+                    type Interrupt = crate::interrupt::typelevel::[<FLEXCOMM $n>];
+                }
+            }
+        )*
+    };
+}
+
+impl_instance!(0, 1, 2, 3, 4, 5, 6, 7, 14);
+
+mod sealed {
+    /// simply seal a trait
+    pub trait Sealed {}
+}
+
+impl<T: Pin> sealed::Sealed for T {}
+
+/// io configuration trait for SPI clock
+pub trait SckPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
+    /// convert the pin to appropriate function for SPI clock usage
+    fn as_sck(&self);
+}
+
+/// io configuration trait for SPI MOSI
+pub trait MosiPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
+    /// convert the pin to appropriate function for SPI MOSI usage
+    fn as_mosi(&self);
+}
+
+/// io configuration trait for SPI MISO
+pub trait MisoPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
+    /// convert the pin to appropriate function for SPI MISO usage
+    fn as_miso(&self);
+}
+
+/// io configuration trait for SPI chip select 0
+pub trait SselPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
+    /// convert the pin to appropriate function for SPI SSEL0 usage
+    fn as_ssel(&self);
+}
+
+macro_rules! impl_pin_trait {
+    ($fcn:ident, $mode:ident, $($pin:ident, $fn:ident),*) => {
+        paste! {
+            $(
+                impl [<$mode:camel Pin>]<crate::peripherals::$fcn> for crate::peripherals::$pin {
+                    fn [<as_ $mode>](&self) {
+                        // UM11147 table 507 pg 495
+                        self.set_function(crate::iopctl::Function::$fn)
+                            .set_pull(Pull::None)
+                            .enable_input_buffer()
+                            .set_slew_rate(SlewRate::Standard)
+                            .set_drive_strength(DriveStrength::Normal)
+                            .disable_analog_multiplex()
+                            .set_drive_mode(DriveMode::PushPull)
+                            .set_input_inverter(Inverter::Disabled);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+// FLEXCOMM0
+impl_pin_trait!(FLEXCOMM0, sck, PIO0_0, F1, PIO3_0, F5);
+impl_pin_trait!(FLEXCOMM0, miso, PIO0_1, F1, PIO3_1, F5);
+impl_pin_trait!(FLEXCOMM0, mosi, PIO0_2, F1, PIO3_2, F5);
+impl_pin_trait!(FLEXCOMM0, ssel, PIO0_3, F1, PIO3_3, F5);
+
+// FLEXCOMM1
+impl_pin_trait!(FLEXCOMM1, sck, PIO0_7, F1, PIO7_25, F1);
+impl_pin_trait!(FLEXCOMM1, miso, PIO0_8, F1, PIO7_26, F1);
+impl_pin_trait!(FLEXCOMM1, mosi, PIO0_9, F1, PIO7_27, F1);
+impl_pin_trait!(FLEXCOMM1, ssel, PIO0_10, F1, PIO7_28, F1);
+
+// FLEXCOMM2
+impl_pin_trait!(FLEXCOMM2, sck, PIO0_14, F1);
+impl_pin_trait!(FLEXCOMM2, miso, PIO0_15, F1, PIO7_30, F5);
+impl_pin_trait!(FLEXCOMM2, mosi, PIO0_16, F1, PIO7_31, F5);
+impl_pin_trait!(FLEXCOMM2, ssel, PIO0_17, F1, PIO4_8, F5);
+
+// FLEXCOMM3
+impl_pin_trait!(FLEXCOMM3, sck, PIO0_21, F1);
+impl_pin_trait!(FLEXCOMM3, miso, PIO0_22, F1);
+impl_pin_trait!(FLEXCOMM3, mosi, PIO0_23, F1);
+impl_pin_trait!(FLEXCOMM3, ssel, PIO0_24, F1);
+
+// FLEXCOMM4
+impl_pin_trait!(FLEXCOMM4, sck, PIO0_28, F1);
+impl_pin_trait!(FLEXCOMM4, miso, PIO0_29, F1);
+impl_pin_trait!(FLEXCOMM4, mosi, PIO0_30, F1);
+impl_pin_trait!(FLEXCOMM4, ssel, PIO0_31, F1);
+
+// FLEXCOMM5
+impl_pin_trait!(FLEXCOMM5, sck, PIO1_3, F1, PIO3_15, F5);
+impl_pin_trait!(FLEXCOMM5, miso, PIO1_4, F1, PIO3_16, F5);
+impl_pin_trait!(FLEXCOMM5, mosi, PIO1_5, F1, PIO3_17, F5);
+impl_pin_trait!(FLEXCOMM5, ssel, PIO1_6, F1, PIO3_18, F5);
+
+// FLEXCOMM6
+impl_pin_trait!(FLEXCOMM6, sck, PIO3_25, F1);
+impl_pin_trait!(FLEXCOMM6, miso, PIO3_26, F1);
+impl_pin_trait!(FLEXCOMM6, mosi, PIO3_27, F1);
+impl_pin_trait!(FLEXCOMM6, ssel, PIO3_28, F1);
+
+// FLEXCOMM7
+impl_pin_trait!(FLEXCOMM7, sck, PIO4_0, F1);
+impl_pin_trait!(FLEXCOMM7, miso, PIO4_1, F1);
+impl_pin_trait!(FLEXCOMM7, mosi, PIO4_2, F1);
+impl_pin_trait!(FLEXCOMM7, ssel, PIO4_3, F1);
+
+// FLEXCOMM14
+impl_pin_trait!(FLEXCOMM14, sck, PIO1_11, F1);
+impl_pin_trait!(FLEXCOMM14, miso, PIO1_12, F1);
+impl_pin_trait!(FLEXCOMM14, mosi, PIO1_13, F1);
+impl_pin_trait!(FLEXCOMM14, ssel, PIO1_14, F1);
+
+/// SPI Tx DMA trait.
+#[allow(private_bounds)]
+pub trait TxDma<T: Instance>: dma::Instance {}
+
+/// SPI Rx DMA trait.
+#[allow(private_bounds)]
+pub trait RxDma<T: Instance>: dma::Instance {}
+
+macro_rules! impl_dma {
+    ($fcn:ident, $mode:ident, $dma:ident) => {
+        paste! {
+            impl [<$mode Dma>]<crate::peripherals::$fcn> for crate::peripherals::$dma {}
+        }
+    };
+}
+
+impl_dma!(FLEXCOMM0, Rx, DMA0_CH0);
+impl_dma!(FLEXCOMM0, Tx, DMA0_CH1);
+
+impl_dma!(FLEXCOMM1, Rx, DMA0_CH2);
+impl_dma!(FLEXCOMM1, Tx, DMA0_CH3);
+
+impl_dma!(FLEXCOMM2, Rx, DMA0_CH4);
+impl_dma!(FLEXCOMM2, Tx, DMA0_CH5);
+
+impl_dma!(FLEXCOMM3, Rx, DMA0_CH6);
+impl_dma!(FLEXCOMM3, Tx, DMA0_CH7);
+
+impl_dma!(FLEXCOMM4, Rx, DMA0_CH8);
+impl_dma!(FLEXCOMM4, Tx, DMA0_CH9);
+
+impl_dma!(FLEXCOMM5, Rx, DMA0_CH10);
+impl_dma!(FLEXCOMM5, Tx, DMA0_CH11);
+
+impl_dma!(FLEXCOMM6, Rx, DMA0_CH12);
+impl_dma!(FLEXCOMM6, Tx, DMA0_CH13);
+
+impl_dma!(FLEXCOMM7, Rx, DMA0_CH14);
+impl_dma!(FLEXCOMM7, Tx, DMA0_CH15);
+
+impl_dma!(FLEXCOMM14, Rx, DMA0_CH26);
+impl_dma!(FLEXCOMM14, Tx, DMA0_CH27);
@@ -1,9 +1,12 @@
 use core::cell::{Cell, RefCell};
-use core::sync::atomic::{compiler_fence, AtomicU32, Ordering};
+use core::future::poll_fn;
+use core::sync::atomic::{compiler_fence, AtomicBool, AtomicU32, Ordering};
+use core::task::Poll;
 
 use critical_section::CriticalSection;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::waitqueue::AtomicWaker;
 use embassy_time_driver::Driver;
 use embassy_time_queue_utils::Queue;
 
@@ -14,6 +17,23 @@ fn rtc() -> &'static pac::rtc::RegisterBlock {
     unsafe { &*pac::Rtc::ptr() }
 }
 
+/// SUBSEC: 15-bit counter of the 32 kHz clock
+const SUBSEC_MASK: u32 = 0x7FFF;
+
+/// The 1 Hz alarm is re-armed for the next second on each match
+static PERIODIC_ENABLED: AtomicBool = AtomicBool::new(false);
+/// A periodic tick arrived that `wait_tick` has not consumed yet
+static TICK_PENDING: AtomicBool = AtomicBool::new(false);
+static TICK_WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Arm the 1 Hz alarm for the next second
+fn arm_periodic_alarm() {
+    let r = rtc();
+    // safety: any MATCH value is valid, the alarm fires once COUNT reaches it
+    r.match_()
+        .write(|w| unsafe { w.bits(r.count().read().bits().wrapping_add(1)) });
+}
+
 /// Calculate the timestamp from the period count and the tick count.
 ///
 /// To get `now()`, `period` is read first, then `counter` is read. If the counter value matches
@@ -96,6 +116,18 @@ impl TimerDriver {
     #[cfg(feature = "rt")]
     fn on_interrupt(&self) {
         let r = rtc();
+
+        // The 1 Hz alarm shares the interrupt, it only drives `RtcDatetime::wait_tick`
+        if r.ctrl().read().alarm1hz().bit_is_set() {
+            // Writing 0 leaves a pending 1kHz wake flag for the code below
+            r.ctrl().modify(|_r, w| w.alarm1hz().set_bit().wake1khz().clear_bit());
+            if PERIODIC_ENABLED.load(Ordering::Relaxed) {
+                arm_periodic_alarm();
+                TICK_PENDING.store(true, Ordering::Release);
+                TICK_WAKER.wake();
+            }
+        }
+
         // This interrupt fires every 10 ticks of the 1kHz RTC high res clk and adds
         // 10 to the 31 bit counter gpreg0. The 32nd bit is used for parity detection
         // This is done to avoid needing to calculate # of ticks spent on interrupt
@@ -104,7 +136,8 @@ impl TimerDriver {
         // TODO: this is admittedly not great for power that we're generating this
         // many interrupts, will probably get updated in future iterations.
         if r.ctrl().read().wake1khz().bit_is_set() {
-            r.ctrl().modify(|_r, w| w.wake1khz().set_bit());
+            // Writing 0 leaves a pending 1 Hz alarm flag for the next interrupt
+            r.ctrl().modify(|_r, w| w.wake1khz().set_bit().alarm1hz().clear_bit());
             // safety: writing a value to the 1kHz RTC wake counter is always considered unsafe.
             // The following reloads 10 into the count-down timer after it triggers an int.
             // The countdown begins anew after the write so time can continue to be measured.
@@ -271,13 +304,25 @@ impl Default for Datetime {
         }
     }
 }
+/// Rate of the periodic wakeup set by [`RtcDatetime::set_periodic_wakeup`]
+///
+/// The RTC has no programmable prescaler: the 1 kHz wake timer is the tick of the time driver,
+/// so only the 1 Hz alarm is left for periodic wakeups.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum RtcPeriod {
+    /// Once per second, on the 1 Hz alarm
+    Hz1,
+}
+
 /// Represents a real-time clock datetime.
 pub struct RtcDatetime<'r> {
     _p: PeripheralRef<'r, peripherals::RTC>,
 }
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(PartialEq)]
 /// Represents the result of a datetime validation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
     /// The year is invalid.
     InvalidYear,
@@ -467,6 +512,9 @@ impl<'r> RtcDatetime<'r> {
         self.is_valid_datetime(datetime)?;
         let secs = self.convert_datetime_to_secs(datetime);
         r.count().write(|w| unsafe { w.bits(secs) });
+        if PERIODIC_ENABLED.load(Ordering::Relaxed) {
+            arm_periodic_alarm();
+        }
         r.ctrl().modify(|_r, w| w.rtc_en().enable());
         Ok(())
     }
@@ -493,6 +541,78 @@ impl<'r> RtcDatetime<'r> {
             (datetime, res)
         }
     }
+
+    /// Start periodic wakeups at `period`, awaited with [`Self::wait_tick`].
+    ///
+    /// The RTC keeps running while the main clocks are gated, so the wakeups can serve as a low
+    /// power system tick. Setting the datetime restarts the period.
+    pub fn set_periodic_wakeup(&mut self, period: RtcPeriod) -> Result<(), Error> {
+        let r = rtc();
+        if r.ctrl().read().rtc_en().bit_is_clear() {
+            return Err(Error::RTCNotEnabled);
+        }
+
+        match period {
+            RtcPeriod::Hz1 => {
+                critical_section::with(|_| {
+                    PERIODIC_ENABLED.store(true, Ordering::Relaxed);
+                    TICK_PENDING.store(false, Ordering::Relaxed);
+                    arm_periodic_alarm();
+                    r.ctrl().modify(|_r, w| w.alarm1hz().set_bit().wake1khz().clear_bit());
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stop the periodic wakeups started by [`Self::set_periodic_wakeup`].
+    pub fn disable_periodic_wakeup(&mut self) {
+        PERIODIC_ENABLED.store(false, Ordering::Relaxed);
+    }
+
+    /// Wait for the next periodic wakeup.
+    ///
+    /// Returns right away if a wakeup arrived since the previous call, so ticks are not lost
+    /// while the caller is busy, several missed ticks are reported as one.
+    pub async fn wait_tick(&mut self) {
+        poll_fn(|cx| {
+            TICK_WAKER.register(cx.waker());
+
+            if TICK_PENDING.swap(false, Ordering::Acquire) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Read the sub-second counter, in 1/32768 s since the last full second.
+    ///
+    /// The counter is enabled on first use and starts counting at the next second.
+    pub fn read_sub_second(&self) -> u16 {
+        let r = rtc();
+        if r.ctrl().read().rtc_subsec_ena().bit_is_clear() {
+            r.ctrl().modify(|_r, w| {
+                w.rtc_subsec_ena()
+                    .enable()
+                    .alarm1hz()
+                    .clear_bit()
+                    .wake1khz()
+                    .clear_bit()
+            });
+        }
+
+        // The counter runs asynchronously to the bus clock, read until two reads agree
+        loop {
+            let subsec1 = r.subsec().read().bits();
+            let subsec2 = r.subsec().read().bits();
+            if subsec1 == subsec2 {
+                return (subsec1 & SUBSEC_MASK) as u16;
+            }
+        }
+    }
 }
 
 #[cfg(feature = "rt")]
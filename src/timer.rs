@@ -1,24 +1,38 @@
 //! Timer module for the NXP RT6xx family of microcontrollers
 use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{AtomicU8, Ordering};
 use core::task::Poll;
 
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_sync::waitqueue::AtomicWaker;
 use paste::paste;
 
-use crate::clocks::{enable_and_reset, ClockConfig, ConfigurableClock};
+use crate::clocks::{acquire_clock, enable_and_reset, get_ctimer_clock_hz, release_clock};
+use crate::dma::channel::Channel;
+use crate::dma::transfer::{TransferOptions, Trigger, Width};
+use crate::dma::ChannelDescriptor;
+use crate::gpio::GpioPin;
 use crate::iopctl::{DriveMode, DriveStrength, Inverter, IopctlPin as Pin, Pull, SlewRate};
-use crate::pac::clkctl1::ct32bitfclksel::Sel;
+use crate::pac::ctimer0::ctcr::{Cinsel, Ctmode};
 use crate::pac::Clkctl1;
 use crate::pwm::{CentiPercent, Hertz, MicroSeconds};
-use crate::{interrupt, peripherals, Peripheral};
+use crate::{dma, interrupt, peripherals, Peripheral};
 
+const MODULE_COUNT: usize = 5;
 const COUNT_CHANNEL: usize = 20;
 const CAPTURE_CHANNEL: usize = 20;
 const TOTAL_CHANNELS: usize = COUNT_CHANNEL + CAPTURE_CHANNEL;
 const CHANNEL_PER_MODULE: usize = 4;
 const PWM_PRECISION_CLK_TICKS_PER_PERIOD: u32 = 500;
+const CAPTURE_STREAM_SEGMENTS: usize = 4;
+const DMA_MAX_TRANSFERS: usize = 1024;
+
+/// DMA descriptor chains of the capture streams, one per capture channel
+static mut CAPTURE_STREAM_DESCRIPTORS: [[ChannelDescriptor; CAPTURE_STREAM_SEGMENTS]; CAPTURE_CHANNEL] =
+    [[ChannelDescriptor::EMPTY; CAPTURE_STREAM_SEGMENTS]; CAPTURE_CHANNEL];
 
 /// Enum representing timer channels
 #[derive(Copy, Clone, Debug)]
@@ -45,6 +59,30 @@ pub enum Error {
 
     /// Pwm length channel and output channel does not belong to same CTimer
     PwmChannelMismatch,
+
+    /// Capture stream buffer is empty, too large or can't be split into equal DMA segments
+    InvalidStreamBuffer,
+
+    /// Capture stream DMA overwrote timestamps before they were read
+    StreamOverrun,
+
+    /// One-shot capture stream already returned its filled buffer
+    StreamComplete,
+
+    /// Capture stream event pin is not on PIO0 or PIO1, the ports covered by the pin interrupts
+    InvalidStreamPin,
+
+    /// Capture channels of a pulse width capture do not belong to the same CTimer
+    CaptureChannelMismatch,
+
+    /// Other channels of the CTimer module still rely on its counter
+    CounterInUse,
+
+    /// Match output channels do not belong to the same CTimer
+    MatchChannelMismatch,
+
+    /// Match time does not fit the 32-bit counter at the module clock rate
+    MatchTimeOutOfRange,
 }
 
 /// Enum representing the logical capture channel input.
@@ -110,13 +148,37 @@ const TIMER_CHANNELS_ARR: [TimerChannelNum; CHANNEL_PER_MODULE] = [
 
 static WAKERS: [AtomicWaker; TOTAL_CHANNELS] = [const { AtomicWaker::new() }; TOTAL_CHANNELS];
 
+/// Number of drivers using each CTimer module, the module clock is gated while it is 0
+static MODULE_USERS: [AtomicU8; MODULE_COUNT] = [const { AtomicU8::new(0) }; MODULE_COUNT];
+
+/// Channels of each CTimer module relying on the running counter, one bit per channel.
+///
+/// Counting timers only count while a wait is armed, all other drivers for as long as they live.
+static ACTIVE_CHANNELS: [AtomicU8; MODULE_COUNT] = [const { AtomicU8::new(0) }; MODULE_COUNT];
+
 #[derive(PartialEq, Clone, Copy)]
 /// Enum representing the edge type for capture channels.
+///
+/// Edges refer to the signal at the pin. On pins with the input inverter enabled the driver
+/// captures the opposite internal edge, see [`CaptureEvent::input_inverter`].
 pub enum CaptureChEdge {
     /// Rising edge
     Rising,
     /// Falling edge
     Falling,
+    /// Both rising and falling edges
+    Both,
+}
+
+impl CaptureChEdge {
+    /// Edge seen by the timer for this pin edge behind `inverter`
+    fn through(self, inverter: Inverter) -> Self {
+        match (self, inverter) {
+            (CaptureChEdge::Rising, Inverter::Enabled) => CaptureChEdge::Falling,
+            (CaptureChEdge::Falling, Inverter::Enabled) => CaptureChEdge::Rising,
+            (edge, _) => edge,
+        }
+    }
 }
 
 mod sealed {
@@ -153,6 +215,8 @@ pub struct CountingTimer<M: Mode> {
     id: usize,
     clk_freq: u32,
     timeout: u32,
+    /// The module counts edges of an external clock pin
+    external_clock: bool,
     _phantom: core::marker::PhantomData<M>,
     info: Info,
 }
@@ -162,6 +226,8 @@ struct Info {
     inputmux: &'static crate::pac::inputmux::RegisterBlock,
     module: usize,
     channel: usize,
+    enable_module: fn(),
+    disable_module: fn(),
 }
 
 trait SealedInstance {
@@ -183,6 +249,89 @@ pub struct CtimerInterruptHandler<T: Instance> {
 }
 
 impl Info {
+    /// Takes a reference on the module.
+    ///
+    /// The first user enables and resets the module and selects its functional clock. Later users
+    /// share the running module as is, so channels already in use keep their configuration.
+    fn acquire_module(&self, clock: TimerClockSource) {
+        critical_section::with(|_| {
+            let users = MODULE_USERS[self.module].load(Ordering::Relaxed);
+
+            if users == 0 {
+                // Initialization steps from NXP TRM
+                //
+                // • Enable the clock to the CTIMER in the CLKCTL1_PSCCTL2 register
+                //          This enables the register interface and the peripheral function clock.
+                // • Clear the CTIMER peripheral reset in the RSTCTL1_PRSTCTL2 register
+                // (Section 4.5.4.4) by writing to the RSTCTL1_PRSTCTL2_CLR register (Section 4.5.4.10).
+                (self.enable_module)();
+
+                // • Select a clock source for the CTIMER using the appropriate CT32BIT0FCLKSEL
+                // register (see Section 4.5.2.55 through Section 4.5.2.59).
+                // SAFETY: only the clock select of a module without users is written
+                let reg = unsafe { Clkctl1::steal() };
+                reg.ct32bitfclksel(self.module).write(|w| match clock {
+                    TimerClockSource::Sfro => w.sel().sfro_clk(),
+                    TimerClockSource::Ffro => w.sel().ffro_clk(),
+                    TimerClockSource::MainClk => w.sel().main_clk(),
+                    TimerClockSource::Lposc => w.sel().lposc(),
+                });
+            }
+
+            MODULE_USERS[self.module].store(users + 1, Ordering::Relaxed);
+        });
+
+        self.set_counter_active(true);
+    }
+
+    /// Drops a reference on the module, gating its clock when the last user is gone.
+    fn release_module(&self) {
+        self.set_counter_active(false);
+
+        critical_section::with(|_| {
+            let users = MODULE_USERS[self.module].load(Ordering::Relaxed).saturating_sub(1);
+            MODULE_USERS[self.module].store(users, Ordering::Relaxed);
+
+            if users == 0 {
+                // Stop the counter so the module restarts from a clean state on next use
+                self.regs.tcr().write(|w| w.cen().disabled());
+                (self.disable_module)();
+            }
+        });
+    }
+
+    /// Mark whether this channel relies on the module counter
+    fn set_counter_active(&self, active: bool) {
+        let bit = 1 << self.channel;
+
+        if active {
+            ACTIVE_CHANNELS[self.module].fetch_or(bit, Ordering::Relaxed);
+        } else {
+            ACTIVE_CHANNELS[self.module].fetch_and(!bit, Ordering::Relaxed);
+        }
+    }
+
+    /// Stop and reset the module counter, unless another channel relies on it.
+    ///
+    /// The next counting timer wait restarts it from 0.
+    fn stop_counter(&self) -> Result<()> {
+        critical_section::with(|_| {
+            if ACTIVE_CHANNELS[self.module].load(Ordering::Relaxed) & !(1 << self.channel) != 0 {
+                return Err(Error::CounterInUse);
+            }
+
+            self.regs.tcr().write(|w| w.cen().disabled().crst().enabled());
+            self.regs.tcr().write(|w| w.crst().disabled());
+
+            Ok(())
+        })
+    }
+
+    /// Functional clock frequency of the module
+    fn clock_freq(&self) -> u32 {
+        get_ctimer_clock_hz(self.module)
+    }
+
     fn cap_timer_interrupt_enable(&self) {
         let reg = self.regs;
         let channel = self.channel;
@@ -302,6 +451,25 @@ impl Info {
             }
         }
     }
+    /// Captures only on `edge`, as seen by the timer
+    fn cap_timer_select_edge(&self, edge: CaptureChEdge) {
+        self.cap_timer_disable_rising_edge_event();
+        self.cap_timer_disable_falling_edge_event();
+
+        match edge {
+            CaptureChEdge::Rising => {
+                self.cap_timer_enable_rising_edge_event();
+            }
+            CaptureChEdge::Falling => {
+                self.cap_timer_enable_falling_edge_event();
+            }
+            CaptureChEdge::Both => {
+                self.cap_timer_enable_rising_edge_event();
+                self.cap_timer_enable_falling_edge_event();
+            }
+        }
+    }
+
     fn count_timer_enable_interrupt(&self) {
         let reg = self.regs;
         let channel = self.channel;
@@ -351,36 +519,6 @@ impl Info {
         }
     }
 
-    fn pwm_get_clock_freq(&self) -> u32 {
-        // SAFETY: This has no safety impact as we are getting a singleton register instance here and its dropped it the end of the function
-        let reg = unsafe { Clkctl1::steal() };
-
-        let clksel = reg.ct32bitfclksel(self.channel).read().sel().variant();
-        let mut freq: u32 = 0;
-
-        if let Some(clk) = clksel {
-            match clk {
-                Sel::MainClk => {
-                    freq = ClockConfig::crystal().main_clk.get_clock_rate().unwrap();
-                }
-                Sel::SfroClk => {
-                    freq = ClockConfig::crystal().sfro.get_clock_rate().unwrap();
-                }
-                Sel::FfroClk => {
-                    freq = ClockConfig::crystal().ffro.get_clock_rate().unwrap();
-                }
-                Sel::Lposc => {
-                    freq = ClockConfig::crystal().lposc.get_clock_rate().unwrap();
-                }
-                //TODO: Add get clock frequency for clock sources audio pll, mclk_in
-                _ => {
-                    freq = 0;
-                }
-            }
-        }
-        freq
-    }
-
     fn pwm_configure(&self, period: u32) {
         let reg = self.regs;
         let len_channel = self.channel;
@@ -406,6 +544,208 @@ impl Info {
             }
         }
     }
+
+    fn pwm_output_disable(&self) {
+        // To disable PWM:
+        // Clear PWM enable bit in PWM control register
+
+        let reg = self.regs;
+        match TIMER_CHANNELS_ARR[self.channel] {
+            TimerChannelNum::Channel0 => {
+                reg.pwmc().modify(|_, w| w.pwmen0().match_());
+            }
+            TimerChannelNum::Channel1 => {
+                reg.pwmc().modify(|_, w| w.pwmen1().match_());
+            }
+            TimerChannelNum::Channel2 => {
+                reg.pwmc().modify(|_, w| w.pwmen2().match_());
+            }
+            TimerChannelNum::Channel3 => {
+                reg.pwmc().modify(|_, w| w.pwmen3().match_());
+            }
+        }
+    }
+
+    fn pwm_output_enable(&self) {
+        let reg = self.regs;
+
+        // To enable PWM output for a channel:
+        // 1. Disable stop and reset when match register matches the value in TC
+        // 2. Enable interrupt generation when match register matches the value in TC
+        // 3. Clear external match bit in match control register
+        // 4. Write 2 to external match control bit to set match output bit/pin when match register matches the value in TC
+        // 5. Clear interrupt flag
+        // 6. Set PWM enable bit in PWM control register
+
+        match TIMER_CHANNELS_ARR[self.channel] {
+            TimerChannelNum::Channel0 => {
+                reg.mcr().modify(|_, w| w.mr0r().clear_bit());
+                reg.mcr().modify(|_, w| w.mr0s().clear_bit());
+                reg.mcr().modify(|_, w| w.mr0i().set_bit());
+
+                reg.emr().modify(|_, w| w.em0().clear_bit());
+                reg.emr().modify(|_, w| w.emc0().set_());
+
+                reg.ir().modify(|_, w| w.mr0int().clear_bit_by_one());
+
+                reg.pwmc().modify(|_, w| w.pwmen0().pwm());
+            }
+            TimerChannelNum::Channel1 => {
+                reg.mcr().modify(|_, w| w.mr1r().clear_bit());
+                reg.mcr().modify(|_, w| w.mr1s().clear_bit());
+                reg.mcr().modify(|_, w| w.mr1i().set_bit());
+
+                reg.emr().modify(|_, w| w.em1().clear_bit());
+                reg.emr().modify(|_, w| w.emc1().set_());
+
+                // Write 1 to IR bit to clear interrupt
+                reg.ir().modify(|_, w| w.mr1int().clear_bit_by_one());
+
+                reg.pwmc().modify(|_, w| w.pwmen1().pwm());
+            }
+            TimerChannelNum::Channel2 => {
+                reg.mcr().modify(|_, w| w.mr2r().clear_bit());
+                reg.mcr().modify(|_, w| w.mr2s().clear_bit());
+                reg.mcr().modify(|_, w| w.mr2i().set_bit());
+
+                reg.emr().modify(|_, w| w.em2().clear_bit());
+                reg.emr().modify(|_, w| w.emc2().set_());
+
+                reg.ir().modify(|_, w| w.mr2int().clear_bit_by_one());
+
+                reg.pwmc().modify(|_, w| w.pwmen2().pwm());
+            }
+            TimerChannelNum::Channel3 => {
+                reg.mcr().modify(|_, w| w.mr3r().clear_bit());
+                reg.mcr().modify(|_, w| w.mr3s().clear_bit());
+                reg.mcr().modify(|_, w| w.mr3i().set_bit());
+
+                reg.emr().modify(|_, w| w.em3().clear_bit());
+                reg.emr().modify(|_, w| w.emc3().set_());
+
+                reg.ir().modify(|_, w| w.mr3int().clear_bit_by_one());
+
+                reg.pwmc().modify(|_, w| w.pwmen3().pwm());
+            }
+        }
+
+        // Reset and enable timer
+        if reg.tcr().read().cen().is_disabled() {
+            reg.tcr().write(|w| w.crst().set_bit());
+            reg.tcr().write(|w| w.crst().clear_bit());
+            reg.tcr().write(|w| w.cen().set_bit());
+        }
+    }
+
+    fn pwm_get_duty(&self, count_max: u32) -> CentiPercent {
+        let scaled = self.regs.mr(self.channel).read().bits();
+
+        CentiPercent::from_scaled(count_max - scaled, count_max)
+    }
+
+    fn pwm_set_duty(&self, count_max: u32, duty: CentiPercent) {
+        // When set duty cycle is called on an already running PWM, output could stay low for a PWM period
+        // before new duty cycle is updated
+        let scaled = duty.as_scaled(count_max);
+
+        // PWM output is low at the beginning of PWM cycle
+        // PWM output is set to high when timer count reaches match register value
+        // For active high PWM, set match register such that output is high for PWM cycle length*dutycycle
+        self.regs.mr(self.channel).write(|w|
+            //SAFETY: No safety impact as we are writing match register here
+            unsafe { w.match_().bits(count_max - scaled)});
+    }
+
+    /// Converts a time in us to counts of the module clock
+    fn match_counts(&self, time_us: u32) -> Result<u32> {
+        let counts = time_us as u64 * self.clock_freq() as u64 / 1_000_000;
+        u32::try_from(counts).map_err(|_| Error::MatchTimeOutOfRange)
+    }
+
+    /// Replaces the MCR actions of this channel with `control`
+    fn match_set_control(&self, control: MatchControl) {
+        let MatchControl { interrupt, reset, stop } = control;
+
+        self.regs.mcr().modify(|_, w| match self.channel {
+            0 => w.mr0i().bit(interrupt).mr0r().bit(reset).mr0s().bit(stop),
+            1 => w.mr1i().bit(interrupt).mr1r().bit(reset).mr1s().bit(stop),
+            2 => w.mr2i().bit(interrupt).mr2r().bit(reset).mr2s().bit(stop),
+            _ => w.mr3i().bit(interrupt).mr3r().bit(reset).mr3s().bit(stop),
+        });
+    }
+
+    fn match_set_action(&self, action: MatchAction) {
+        macro_rules! emc {
+            ($w:ident . $field:ident) => {
+                match action {
+                    MatchAction::Nothing => $w.$field().do_nothing(),
+                    MatchAction::Clear => $w.$field().clear(),
+                    MatchAction::Set => $w.$field().set_(),
+                    MatchAction::Toggle => $w.$field().toggle(),
+                }
+            };
+        }
+
+        self.regs.emr().modify(|_, w| match self.channel {
+            0 => emc!(w.emc0),
+            1 => emc!(w.emc1),
+            2 => emc!(w.emc2),
+            _ => emc!(w.emc3),
+        });
+    }
+
+    fn match_set_level(&self, high: bool) {
+        self.regs.emr().modify(|_, w| match self.channel {
+            0 => w.em0().bit(high),
+            1 => w.em1().bit(high),
+            2 => w.em2().bit(high),
+            _ => w.em3().bit(high),
+        });
+    }
+
+    fn match_level(&self) -> bool {
+        let emr = self.regs.emr().read();
+        match self.channel {
+            0 => emr.em0().bit_is_set(),
+            1 => emr.em1().bit_is_set(),
+            2 => emr.em2().bit_is_set(),
+            _ => emr.em3().bit_is_set(),
+        }
+    }
+
+    /// Stops the module counter and holds it at 0 for a match output to restart it.
+    ///
+    /// Match outputs time their events from the start of the counter, so it must not be relied on
+    /// by channels other than `own_channels`, a bit mask of the caller's channels.
+    fn hold_counter(&self, own_channels: u8) -> Result<()> {
+        critical_section::with(|_| {
+            if ACTIVE_CHANNELS[self.module].load(Ordering::Relaxed) & !own_channels != 0 {
+                return Err(Error::CounterInUse);
+            }
+
+            self.regs.tcr().write(|w| w.cen().disabled().crst().enabled());
+
+            Ok(())
+        })
+    }
+
+    /// Starts the counter held by [`Info::hold_counter`] from 0
+    fn release_counter(&self) {
+        self.regs.tcr().write(|w| w.cen().enabled().crst().disabled());
+    }
+
+    /// Arms a match at `counts` with the MCR actions `control`, clearing a stale match flag
+    fn match_arm(&self, counts: u32, control: MatchControl) {
+        // SAFETY: unsafe only used for .bits()
+        self.regs.mr(self.channel).write(|w| unsafe { w.match_().bits(counts) });
+        self.regs.ir().write(|w| match self.channel {
+            0 => w.mr0int().clear_bit_by_one(),
+            1 => w.mr1int().clear_bit_by_one(),
+            2 => w.mr2int().clear_bit_by_one(),
+            _ => w.mr3int().clear_bit_by_one(),
+        });
+        self.match_set_control(control);
+    }
 }
 
 macro_rules! impl_instance {
@@ -419,6 +759,8 @@ macro_rules! impl_instance {
                         inputmux: unsafe { &*crate::pac::Inputmux::ptr() },
                         module: $n,
                         channel: $channel,
+                        enable_module: acquire_clock::<crate::peripherals::[<CTIMER $n _ COUNT _ CHANNEL0>]>,
+                        disable_module: release_clock::<crate::peripherals::[<CTIMER $n _ COUNT _ CHANNEL0>]>,
                     }
                 }
             }
@@ -429,6 +771,8 @@ macro_rules! impl_instance {
                         inputmux: unsafe { &*crate::pac::Inputmux::ptr() },
                         module: $n,
                         channel: $channel,
+                        enable_module: acquire_clock::<crate::peripherals::[<CTIMER $n _ COUNT _ CHANNEL0>]>,
+                        disable_module: release_clock::<crate::peripherals::[<CTIMER $n _ COUNT _ CHANNEL0>]>,
                     }
                 }
             }
@@ -551,27 +895,40 @@ impl<M: Mode, P: CaptureEvent> CaptureTimer<M, P> {
     }
 
     fn capture_timer_setup(&self, edge: CaptureChEdge) {
-        match edge {
-            CaptureChEdge::Rising => {
-                self.info.cap_timer_enable_rising_edge_event();
-            }
-            CaptureChEdge::Falling => {
-                self.info.cap_timer_enable_falling_edge_event();
-            }
+        self.info
+            .cap_timer_select_edge(edge.through(self.event_pin.input_inverter()));
+    }
+
+    /// Arm the capture channel for the next `edge`. The first capture also starts the timer.
+    fn arm_capture(&mut self, edge: CaptureChEdge, first: bool) {
+        if first {
+            self.start(edge);
+        } else {
+            self.capture_timer_setup(edge);
+            self.info.cap_timer_interrupt_enable();
         }
     }
+
+    /// Converts the clock counts between two captures into microseconds
+    fn elapsed_time_us(&mut self, from: u32, to: u32) -> u32 {
+        self.event_clock_counts = to.wrapping_sub(from);
+        self.get_event_capture_time_us()
+    }
 }
 
 impl<P: CaptureEvent> CaptureTimer<Async, P> {
     /// Creates a new `CaptureTimer` in asynchronous mode.
-    pub fn new_async<T: Instance>(_inst: T, pin: P, clk: impl ConfigurableClock) -> Self {
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new_async<T: Instance>(_inst: T, pin: P, clock: TimerClockSource) -> Self {
         let info = T::info();
         let module = info.module;
+        info.acquire_module(clock);
         T::interrupt_enable();
         Self {
             id: COUNT_CHANNEL + module * CHANNEL_PER_MODULE + info.channel,
             event_clock_counts: 0,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: info.clock_freq(),
             _phantom: core::marker::PhantomData,
             info,
             event_pin: pin,
@@ -643,18 +1000,61 @@ impl<P: CaptureEvent> CaptureTimer<Async, P> {
         })
         .await
     }
+
+    /// Waits for the next `edge` and returns the raw capture register value
+    async fn capture_edge(&mut self, edge: CaptureChEdge, first: bool) -> u32 {
+        self.arm_capture(edge, first);
+
+        poll_fn(|cx| {
+            WAKERS[self.id].register(cx.waker());
+
+            if self.info.input_event_captured() {
+                Poll::Ready(self.info.regs.cr(self.info.channel).read().bits())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Capture a rising edge followed by a falling edge, return the high time in us
+    pub async fn capture_high_time_us(&mut self) -> u32 {
+        let rise = self.capture_edge(CaptureChEdge::Rising, true).await;
+        let fall = self.capture_edge(CaptureChEdge::Falling, false).await;
+        self.info.cap_timer_interrupt_disable();
+
+        self.elapsed_time_us(rise, fall)
+    }
+
+    /// Capture rise, fall, rise, fall and return `(high_time_us, period_us)`
+    pub async fn capture_duty_cycle(&mut self) -> (u32, u32) {
+        let rise0 = self.capture_edge(CaptureChEdge::Rising, true).await;
+        let fall0 = self.capture_edge(CaptureChEdge::Falling, false).await;
+        let rise1 = self.capture_edge(CaptureChEdge::Rising, false).await;
+        let fall1 = self.capture_edge(CaptureChEdge::Falling, false).await;
+        self.info.cap_timer_interrupt_disable();
+
+        let high0 = self.elapsed_time_us(rise0, fall0);
+        let high1 = self.elapsed_time_us(rise1, fall1);
+        let period = self.elapsed_time_us(rise0, rise1);
+
+        (((high0 as u64 + high1 as u64) / 2) as u32, period)
+    }
 }
 
 impl<P: CaptureEvent> CaptureTimer<Blocking, P> {
     /// Creates a new `CaptureTimer` in blocking mode.
-    pub fn new_blocking<T: Instance>(_inst: T, pin: P, clk: impl ConfigurableClock) -> Self {
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new_blocking<T: Instance>(_inst: T, pin: P, clock: TimerClockSource) -> Self {
         let info = T::info();
         let module = info.module;
+        info.acquire_module(clock);
         T::interrupt_enable();
         Self {
             id: COUNT_CHANNEL + module * CHANNEL_PER_MODULE + info.channel,
             event_clock_counts: 0,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: info.clock_freq(),
             _phantom: core::marker::PhantomData,
             info,
             event_pin: pin,
@@ -710,6 +1110,39 @@ impl<P: CaptureEvent> CaptureTimer<Blocking, P> {
             }
         }
     }
+
+    /// Waits for the next `edge` and returns the raw capture register value
+    fn capture_edge(&mut self, edge: CaptureChEdge, first: bool) -> u32 {
+        self.arm_capture(edge, first);
+
+        while !self.info.input_event_captured() {}
+
+        self.info.regs.cr(self.info.channel).read().bits()
+    }
+
+    /// Capture a rising edge followed by a falling edge, return the high time in us
+    pub fn capture_high_time_us(&mut self) -> u32 {
+        let rise = self.capture_edge(CaptureChEdge::Rising, true);
+        let fall = self.capture_edge(CaptureChEdge::Falling, false);
+        self.info.cap_timer_interrupt_disable();
+
+        self.elapsed_time_us(rise, fall)
+    }
+
+    /// Capture rise, fall, rise, fall and return `(high_time_us, period_us)`
+    pub fn capture_duty_cycle(&mut self) -> (u32, u32) {
+        let rise0 = self.capture_edge(CaptureChEdge::Rising, true);
+        let fall0 = self.capture_edge(CaptureChEdge::Falling, false);
+        let rise1 = self.capture_edge(CaptureChEdge::Rising, false);
+        let fall1 = self.capture_edge(CaptureChEdge::Falling, false);
+        self.info.cap_timer_interrupt_disable();
+
+        let high0 = self.elapsed_time_us(rise0, fall0);
+        let high1 = self.elapsed_time_us(rise1, fall1);
+        let period = self.elapsed_time_us(rise0, rise1);
+
+        (((high0 as u64 + high1 as u64) / 2) as u32, period)
+    }
 }
 
 impl<M: Mode> CountingTimer<M> {
@@ -724,7 +1157,7 @@ impl<M: Mode> CountingTimer<M> {
 
     fn start(&mut self, count_us: u32) {
         let info = &self.info;
-        let dur = (count_us as u64 * self.clk_freq as u64) / 1000000;
+        let dur = (count_us as u64 * self.counter_hz() as u64) / 1000000;
         let cycles = dur as u32;
         let reg = self.info.regs;
         let channel = self.info.channel;
@@ -749,29 +1182,146 @@ impl<M: Mode> CountingTimer<M> {
             }
         }
 
+        info.set_counter_active(true);
         info.count_timer_enable_interrupt();
 
         self.reset_and_enable();
     }
-}
 
-impl CountingTimer<Async> {
-    /// Creates a new `CountingTimer` in asynchronous mode.
-    pub fn new_async<T: Instance>(_inst: T, clk: impl ConfigurableClock) -> Self {
+    /// Disarms a pending wait, the module counter keeps running.
+    fn cancel(&self) {
+        self.info.count_timer_disable_interrupt();
+        self.info.regs.mr(self.info.channel).write(|w| unsafe {
+            // SAFETY: It has no safety impact as we are clearing match register here
+            w.match_().bits(0)
+        });
+        self.info.set_counter_active(false);
+    }
+
+    /// Stops and resets the counter of the CTimer module.
+    ///
+    /// The counter is shared by all channels of the module, so it is only stopped when no other
+    /// channel relies on it: capture, PWM and match-output channels for as long as they exist,
+    /// other counting timers while they are waiting. Returns [`Error::CounterInUse`] otherwise,
+    /// leaving the counter running. The next wait restarts the counter from 0.
+    ///
+    /// Dropping the timer does this automatically.
+    pub fn stop_timer(&mut self) -> Result<()> {
+        self.cancel();
+        self.info.stop_counter()
+    }
+
+    /// Longest delay in microseconds a single `start` can count at the current clock rate.
+    fn max_wait_us(&self) -> u32 {
+        let max_us = (u32::MAX as u64 * 1_000_000) / self.counter_hz() as u64;
+        max_us.min(u32::MAX as u64) as u32
+    }
+
+    /// Rate of the module counter, waits are converted to counts with it.
+    fn counter_hz(&self) -> u32 {
+        assert!(self.clk_freq != 0, "external clock frequency not set");
+        self.clk_freq
+    }
+
+    /// Creates a new `CountingTimer` whose module counts edges of `clk_pin` instead of its
+    /// functional clock.
+    ///
+    /// The pin is routed through INPUTMUX to the CAP input matching this channel, and the module
+    /// counter is switched to counter mode on `clock.edge`. The functional clock selected by
+    /// `clock.sampling_clock` then only samples the pin, it must run at more than twice the
+    /// external clock frequency.
+    ///
+    /// Counter mode applies to the whole module, so it must not be in use by any other driver
+    /// yet, [`Error::CounterInUse`] is returned otherwise. Drivers created on the module later
+    /// count the same edges.
+    ///
+    /// The external clock rate is unknown to the driver, waits panic until it is set with
+    /// [`set_external_freq_hz`](Self::set_external_freq_hz).
+    pub fn new_with_external_clock<T: Instance, P: CaptureEvent>(
+        _inst: T,
+        clk_pin: P,
+        clock: TimerExternalClock,
+    ) -> Result<Self> {
         let info = T::info();
+        info.acquire_module(clock.sampling_clock);
+        // Only counts while a wait is armed
+        info.set_counter_active(false);
+
+        if MODULE_USERS[info.module].load(Ordering::Relaxed) != 1 {
+            info.release_module();
+            return Err(Error::CounterInUse);
+        }
+
+        clk_pin.configure_for_event_capture();
+        info.inputmux
+            .ct32bit_cap(info.module)
+            .ct32bit_cap_sel(info.channel)
+            .modify(|_, w| w.capn_sel().variant(clk_pin.get_trigger_input().into()));
+
+        let ctmode = match clock.edge.through(clk_pin.input_inverter()) {
+            CaptureChEdge::Rising => Ctmode::CounterRisingEdge,
+            CaptureChEdge::Falling => Ctmode::CounterFallingEdge,
+            CaptureChEdge::Both => Ctmode::CounterDualEdge,
+        };
+        // The TC counts the CAP input of this channel
+        let cinsel = match info.channel {
+            0 => Cinsel::Channel0,
+            1 => Cinsel::Channel1,
+            2 => Cinsel::Channel2,
+            _ => Cinsel::Channel3,
+        };
+        info.regs
+            .ctcr()
+            .write(|w| w.ctmode().variant(ctmode).cinsel().variant(cinsel));
+
         T::interrupt_enable();
-        Self {
+        Ok(Self {
             id: info.module * CHANNEL_PER_MODULE + info.channel,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: 0,
             timeout: 0,
+            external_clock: true,
             _phantom: core::marker::PhantomData,
             info,
-        }
+        })
+    }
+
+    /// Sets the frequency of the external clock counted by a timer created with
+    /// [`new_with_external_clock`](Self::new_with_external_clock), waits are timed by it.
+    pub fn set_external_freq_hz(&mut self, freq_hz: u32) {
+        debug_assert!(self.external_clock, "timer does not count an external clock");
+        self.clk_freq = freq_hz;
+    }
+}
+
+impl CountingTimer<Async> {
+    /// Creates a new `CountingTimer` in asynchronous mode.
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new_async<T: Instance>(_inst: T, clock: TimerClockSource) -> Self {
+        let info = T::info();
+        info.acquire_module(clock);
+        // Only counts while a wait is armed
+        info.set_counter_active(false);
+        T::interrupt_enable();
+        Self {
+            id: info.module * CHANNEL_PER_MODULE + info.channel,
+            clk_freq: info.clock_freq(),
+            timeout: 0,
+            external_clock: false,
+            _phantom: core::marker::PhantomData,
+            info,
+        }
     }
     /// Waits asynchronously for the countdown timer to complete.
+    ///
+    /// Dropping the future before it completes disarms the match, so no stale interrupt fires
+    /// later. The module counter (TC) keeps running as it may be shared with other channels, use
+    /// [`stop_timer`](Self::stop_timer) to stop it.
     pub async fn wait_us(&mut self, count_us: u32) {
         self.start(count_us);
 
+        let on_drop = OnDrop::new(|| self.cancel());
+
         // Implementation of waiting for the interrupt
         poll_fn(|cx| {
             // Register the waker
@@ -783,18 +1333,48 @@ impl CountingTimer<Async> {
             Poll::Pending
         })
         .await;
+
+        on_drop.defuse();
+        self.info.set_counter_active(false);
+    }
+
+    /// Waits asynchronously for the given number of milliseconds.
+    pub async fn wait_ms(&mut self, count_ms: u32) {
+        self.wait_us_long(count_ms as u64 * 1_000).await;
+    }
+
+    /// Waits asynchronously for the given number of seconds.
+    pub async fn wait_s(&mut self, count_s: u32) {
+        self.wait_us_long(count_s as u64 * 1_000_000).await;
+    }
+
+    /// Splits a long delay into intervals the 32-bit counter can represent.
+    async fn wait_us_long(&mut self, mut total_us: u64) {
+        let max_us = self.max_wait_us() as u64;
+
+        while total_us > 0 {
+            let count_us = total_us.min(max_us);
+            self.wait_us(count_us as u32).await;
+            total_us -= count_us;
+        }
     }
 }
 
 impl CountingTimer<Blocking> {
     /// Creates a new `CountingTimer` in blocking mode.
-    pub fn new_blocking<T: Instance>(_inst: T, clk: impl ConfigurableClock) -> Self {
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new_blocking<T: Instance>(_inst: T, clock: TimerClockSource) -> Self {
         let info = T::info();
+        info.acquire_module(clock);
+        // Only counts while a wait is armed
+        info.set_counter_active(false);
         T::interrupt_enable();
         Self {
             id: info.module * CHANNEL_PER_MODULE + info.channel,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: info.clock_freq(),
             timeout: 0,
+            external_clock: false,
             _phantom: core::marker::PhantomData,
             info,
         }
@@ -809,16 +1389,311 @@ impl CountingTimer<Blocking> {
                 break;
             }
         }
+
+        self.info.set_counter_active(false);
+    }
+
+    /// Waits synchronously for the given number of milliseconds.
+    pub fn wait_ms(&mut self, count_ms: u32) {
+        self.wait_us_long(count_ms as u64 * 1_000);
+    }
+
+    /// Waits synchronously for the given number of seconds.
+    pub fn wait_s(&mut self, count_s: u32) {
+        self.wait_us_long(count_s as u64 * 1_000_000);
+    }
+
+    /// Splits a long delay into intervals the 32-bit counter can represent.
+    fn wait_us_long(&mut self, mut total_us: u64) {
+        let max_us = self.max_wait_us() as u64;
+
+        while total_us > 0 {
+            let count_us = total_us.min(max_us);
+            self.wait_us(count_us as u32);
+            total_us -= count_us;
+        }
     }
 }
 
 impl<M: Mode> Drop for CountingTimer<M> {
     fn drop(&mut self) {
-        self.info.count_timer_disable_interrupt();
-        self.info.regs.mr(self.info.channel).write(|w| unsafe {
-            // SAFETY: It has no safety impact as we are clearing match register here
-            w.match_().bits(0)
-        });
+        // Leaves the counter running if other channels still use it
+        let _ = self.stop_timer();
+        if self.external_clock {
+            // Back to timer mode, counting the functional clock
+            self.info.regs.ctcr().write(|w| w.ctmode().timer());
+        }
+        self.info.release_module();
+    }
+}
+
+/// Pin interrupts can select pins of PIO0 and PIO1 only
+const PINT_MAX_PIN_INDEX: usize = 2 * 32;
+
+/// Pin interrupt used to turn capture edges into DMA triggers
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum PinIntSlot {
+    /// Pin interrupt 0
+    PinInt0,
+    /// Pin interrupt 1
+    PinInt1,
+    /// Pin interrupt 2
+    PinInt2,
+    /// Pin interrupt 3
+    PinInt3,
+}
+
+impl PinIntSlot {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn dma_trigger(self) -> Trigger {
+        match self {
+            PinIntSlot::PinInt0 => Trigger::PinInt0,
+            PinIntSlot::PinInt1 => Trigger::PinInt1,
+            PinIntSlot::PinInt2 => Trigger::PinInt2,
+            PinIntSlot::PinInt3 => Trigger::PinInt3,
+        }
+    }
+}
+
+/// Capture stream buffer mode
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum StreamMode {
+    /// Fill the buffer once
+    OneShot,
+    /// Fill the buffer continuously, reporting each completed half
+    Circular,
+}
+
+/// Streams capture timestamps into a buffer with DMA, without a CPU interrupt per edge.
+///
+/// The CTIMER latches the counter on each edge of the event pin. The same pin is
+/// routed to a pin interrupt, whose edge detection triggers a DMA transfer of the
+/// capture register into the next buffer entry. The pin interrupt slot must not be
+/// used for anything else while the stream exists, and the event pin must be on PIO0
+/// or PIO1. The capture and pin interrupt routing is reset when the stream is dropped.
+pub struct CaptureStream<'d, P: CaptureEvent> {
+    info: Info,
+    event_pin: P,
+    dma_ch: Channel<'d>,
+    buf: &'d mut [u32],
+    mode: StreamMode,
+    segments: usize,
+    slot: PinIntSlot,
+    start_count: u32,
+    consumed: u32,
+}
+
+impl<'d, P: CaptureEvent + GpioPin> CaptureStream<'d, P> {
+    /// Start streaming `edge` timestamps of `pin` into `buf`.
+    ///
+    /// Buffers up to 4096 timestamps are supported. In circular mode the buffer
+    /// length must be even. `clock` is only applied if no other driver is using
+    /// the CTimer module yet, see [`TimerClockSource`]. Returns
+    /// [`Error::InvalidStreamPin`] if `pin` cannot be routed to a pin interrupt.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<T: Instance>(
+        _inst: T,
+        pin: P,
+        clock: TimerClockSource,
+        dma_ch: impl Peripheral<P = impl dma::Instance> + 'd,
+        slot: PinIntSlot,
+        edge: CaptureChEdge,
+        buf: &'d mut [u32],
+        mode: StreamMode,
+    ) -> Result<Self> {
+        let info = T::info();
+        let id = info.module * CHANNEL_PER_MODULE + info.channel;
+
+        if crate::gpio::pin_index(&pin) >= PINT_MAX_PIN_INDEX {
+            return Err(Error::InvalidStreamPin);
+        }
+
+        let segments = Self::segment_count(buf.len(), mode).ok_or(Error::InvalidStreamBuffer)?;
+        dma::buffer::check_destination(buf.as_ptr() as *const u8, buf.len() * 4, Width::Bit32)
+            .map_err(|_| Error::InvalidStreamBuffer)?;
+        let dma_ch = dma::Dma::reserve_channel(dma_ch).ok_or(Error::InvalidStreamBuffer)?;
+
+        info.acquire_module(clock);
+
+        let mut stream = Self {
+            info,
+            event_pin: pin,
+            dma_ch,
+            buf,
+            mode,
+            segments,
+            slot,
+            start_count: 0,
+            consumed: 0,
+        };
+
+        stream.start(id, edge);
+
+        Ok(stream)
+    }
+
+    /// Smallest number of equal DMA segments for `len` timestamps, even in circular mode
+    fn segment_count(len: usize, mode: StreamMode) -> Option<usize> {
+        let step = if mode == StreamMode::Circular { 2 } else { 1 };
+
+        (step..=CAPTURE_STREAM_SEGMENTS)
+            .step_by(step)
+            .find(|&n| len > 0 && len % n == 0 && len / n <= DMA_MAX_TRANSFERS)
+    }
+
+    fn start(&mut self, id: usize, edge: CaptureChEdge) {
+        // The CTIMER and the pin interrupt both see the signal behind the input inverter
+        let edge = edge.through(self.event_pin.input_inverter());
+        let module = self.info.module;
+        let channel = self.info.channel;
+        let inputmux = self.info.inputmux;
+
+        // Capture edges without CTIMER interrupts
+        self.info.cap_timer_interrupt_disable();
+        self.info.cap_timer_disable_rising_edge_event();
+        self.info.cap_timer_disable_falling_edge_event();
+        if edge != CaptureChEdge::Falling {
+            self.info.cap_timer_enable_rising_edge_event();
+        }
+        if edge != CaptureChEdge::Rising {
+            self.info.cap_timer_enable_falling_edge_event();
+        }
+
+        self.event_pin.configure_for_event_capture();
+
+        inputmux
+            .ct32bit_cap(module)
+            .ct32bit_cap_sel(channel)
+            .modify(|_, w| w.capn_sel().variant(self.event_pin.get_trigger_input().into()));
+
+        // Route the same pin to the pin interrupt generating the DMA trigger
+        let slot = self.slot.index();
+        // SAFETY: unsafe only used for .bits()
+        inputmux
+            .pint_sel(slot)
+            .write(|w| unsafe { w.intpin().bits(crate::gpio::pin_index(&self.event_pin) as u8) });
+
+        // SAFETY: the pin interrupt slot is owned by this stream, unsafe only used for .bits()
+        let pint = unsafe { crate::pac::Pint::steal() };
+        pint.isel().modify(|r, w| unsafe { w.bits(r.bits() & !(1 << slot)) });
+        pint.cienr().write(|w| unsafe { w.bits(1 << slot) });
+        pint.cienf().write(|w| unsafe { w.bits(1 << slot) });
+        pint.ist().write(|w| unsafe { w.bits(1 << slot) });
+        if edge != CaptureChEdge::Falling {
+            pint.sienr().write(|w| unsafe { w.bits(1 << slot) });
+        }
+        if edge != CaptureChEdge::Rising {
+            pint.sienf().write(|w| unsafe { w.bits(1 << slot) });
+        }
+
+        // One DMA segment per descriptor, all copying the capture register
+        let seg_len = self.buf.len() / self.segments;
+        let base = self.buf.as_mut_ptr();
+        let mut dst = [core::ptr::null_mut(); CAPTURE_STREAM_SEGMENTS];
+        for (i, d) in dst.iter_mut().take(self.segments).enumerate() {
+            *d = base.wrapping_add(i * seg_len);
+        }
+
+        // SAFETY: the descriptors of a capture channel are only used by the stream owning it
+        let reload = unsafe { &mut (*addr_of_mut!(CAPTURE_STREAM_DESCRIPTORS))[id] };
+
+        self.dma_ch.configure_chain(
+            self.info.regs.cr(channel).as_ptr() as *const u32,
+            &dst[..self.segments],
+            seg_len * 4,
+            TransferOptions {
+                width: Width::Bit32,
+                ..Default::default()
+            },
+            reload,
+            self.mode == StreamMode::Circular,
+        );
+        self.dma_ch.set_hw_trigger(self.slot.dma_trigger());
+
+        self.start_count = self.dma_ch.completion_count();
+        self.dma_ch.enable_channel();
+
+        let reg = self.info.regs;
+        if reg.tcr().read().cen().is_disabled() {
+            reg.tcr().write(|w| w.crst().enabled());
+            reg.tcr().write(|w| w.crst().disabled());
+            reg.tcr().write(|w| w.cen().enabled());
+        }
+    }
+
+    /// Wait for captured timestamps.
+    ///
+    /// In one-shot mode this waits until the whole buffer is filled and returns it, later
+    /// calls return [`Error::StreamComplete`]. In circular mode it returns each half of the buffer as soon as it is filled,
+    /// or [`Error::StreamOverrun`] once if a half was overwritten before being read.
+    pub async fn next(&mut self) -> Result<&[u32]> {
+        let per_chunk = match self.mode {
+            StreamMode::OneShot => self.segments as u32,
+            StreamMode::Circular => self.segments as u32 / 2,
+        };
+
+        if self.mode == StreamMode::OneShot && self.consumed >= per_chunk {
+            return Err(Error::StreamComplete);
+        }
+
+        let completed = poll_fn(|cx| {
+            self.dma_ch.get_waker().register(cx.waker());
+
+            let completed = self.dma_ch.completion_count().wrapping_sub(self.start_count);
+
+            if completed >= self.consumed + per_chunk {
+                Poll::Ready(completed)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        if self.mode == StreamMode::OneShot {
+            self.consumed = per_chunk;
+            return Ok(self.buf);
+        }
+
+        // The DMA went on through the other half and into this one again
+        if completed - self.consumed >= 2 * per_chunk {
+            self.consumed = (completed / per_chunk - 1) * per_chunk;
+            return Err(Error::StreamOverrun);
+        }
+
+        let half_len = self.buf.len() / 2;
+        let half = (self.consumed / per_chunk) as usize % 2;
+        self.consumed += per_chunk;
+
+        Ok(&self.buf[half * half_len..(half + 1) * half_len])
+    }
+}
+
+impl<P: CaptureEvent> Drop for CaptureStream<'_, P> {
+    fn drop(&mut self) {
+        let slot = self.slot.index();
+
+        self.dma_ch.abort();
+
+        // SAFETY: the pin interrupt slot is owned by this stream, unsafe only used for .bits()
+        let pint = unsafe { crate::pac::Pint::steal() };
+        pint.cienr().write(|w| unsafe { w.bits(1 << slot) });
+        pint.cienf().write(|w| unsafe { w.bits(1 << slot) });
+        pint.ist().write(|w| unsafe { w.bits(1 << slot) });
+
+        // Undo the routing of the event pin to the capture input and the pin interrupt
+        let inputmux = self.info.inputmux;
+        inputmux
+            .ct32bit_cap(self.info.module)
+            .ct32bit_cap_sel(self.info.channel)
+            .reset();
+        inputmux.pint_sel(slot).reset();
+
+        self.info.cap_timer_disable_falling_edge_event();
+        self.info.cap_timer_disable_rising_edge_event();
+        self.info.release_module();
     }
 }
 
@@ -827,6 +1702,130 @@ impl<M: Mode, P: CaptureEvent> Drop for CaptureTimer<M, P> {
         self.info.cap_timer_interrupt_disable();
         self.info.cap_timer_disable_falling_edge_event();
         self.info.cap_timer_disable_rising_edge_event();
+        self.info.release_module();
+    }
+}
+
+/// Measures high pulses with two capture channels of one CTimer latching the same input.
+///
+/// The rising edge is latched by the first channel and the falling edge by the second, both
+/// in hardware, so pulses shorter than the interrupt latency are measured correctly. The
+/// interrupts only tell the driver that both values are ready.
+pub struct PulseWidthCapture<P: CaptureEvent> {
+    rise: Info,
+    fall: Info,
+    clk_freq: u32,
+    event_pin: P,
+}
+
+impl<P: CaptureEvent> PulseWidthCapture<P> {
+    /// Creates a pulse width capture on `pin` using the capture channels `rise_channel` and
+    /// `fall_channel`, which must belong to the same CTimer module.
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new<A: Instance, B: Instance>(
+        _rise_channel: A,
+        _fall_channel: B,
+        pin: P,
+        clock: TimerClockSource,
+    ) -> Result<Self> {
+        let rise = A::info();
+        let fall = B::info();
+
+        if rise.module != fall.module {
+            return Err(Error::CaptureChannelMismatch);
+        }
+
+        rise.acquire_module(clock);
+        A::interrupt_enable();
+
+        pin.configure_for_event_capture();
+
+        // Both channels sample the same trigger input
+        let trigger = pin.get_trigger_input();
+        for info in [&rise, &fall] {
+            info.cap_timer_interrupt_disable();
+            info.cap_timer_disable_rising_edge_event();
+            info.cap_timer_disable_falling_edge_event();
+            info.inputmux
+                .ct32bit_cap(info.module)
+                .ct32bit_cap_sel(info.channel)
+                .modify(|_, w| w.capn_sel().variant(trigger.into()));
+        }
+
+        let reg = rise.regs;
+        if reg.tcr().read().cen().is_disabled() {
+            reg.tcr().write(|w| w.crst().enabled());
+            reg.tcr().write(|w| w.crst().disabled());
+            reg.tcr().write(|w| w.cen().enabled());
+        }
+
+        Ok(Self {
+            clk_freq: rise.clock_freq(),
+            rise,
+            fall,
+            event_pin: pin,
+        })
+    }
+
+    fn waker_id(info: &Info) -> usize {
+        COUNT_CHANNEL + info.module * CHANNEL_PER_MODULE + info.channel
+    }
+
+    /// Waits for the next high pulse on the pin and returns its width in us
+    ///
+    /// A pulse already in progress when this is called is skipped.
+    pub async fn measure_pulse_us(&mut self) -> u32 {
+        let inverter = self.event_pin.input_inverter();
+        let reg = self.rise.regs;
+
+        self.rise.cap_timer_select_edge(CaptureChEdge::Rising.through(inverter));
+        self.fall
+            .cap_timer_select_edge(CaptureChEdge::Falling.through(inverter));
+        self.rise.cap_timer_interrupt_enable();
+        self.fall.cap_timer_interrupt_enable();
+
+        let counts = poll_fn(|cx| {
+            WAKERS[Self::waker_id(&self.rise)].register(cx.waker());
+            WAKERS[Self::waker_id(&self.fall)].register(cx.waker());
+
+            if !(self.rise.input_event_captured() && self.fall.input_event_captured()) {
+                return Poll::Pending;
+            }
+
+            let rise = reg.cr(self.rise.channel).read().bits();
+            let fall = reg.cr(self.fall.channel).read().bits();
+
+            // Ages relative to the free running counter stay correct across counter wraparound
+            let now = reg.tc().read().bits();
+            if now.wrapping_sub(rise) < now.wrapping_sub(fall) {
+                // The falling edge of a pulse in progress came first, wait for the one after the rise
+                self.fall.cap_timer_interrupt_enable();
+                return Poll::Pending;
+            }
+
+            Poll::Ready(fall.wrapping_sub(rise))
+        })
+        .await;
+
+        // Stop latching until the next measurement
+        for info in [&self.rise, &self.fall] {
+            info.cap_timer_disable_rising_edge_event();
+            info.cap_timer_disable_falling_edge_event();
+        }
+
+        ((counts as u64 * 1_000_000) / self.clk_freq as u64) as u32
+    }
+}
+
+impl<P: CaptureEvent> Drop for PulseWidthCapture<P> {
+    fn drop(&mut self) {
+        for info in [&self.rise, &self.fall] {
+            info.cap_timer_interrupt_disable();
+            info.cap_timer_disable_rising_edge_event();
+            info.cap_timer_disable_falling_edge_event();
+        }
+        self.rise.release_module();
     }
 }
 
@@ -853,98 +1852,14 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
     type Duty = CentiPercent;
 
     fn disable(&mut self, _: ()) {
-        // To disable PWM:
-        // Clear PWM enable bit in PWM control register
-
-        let reg = self.info.regs;
-        match TIMER_CHANNELS_ARR[self.info.channel] {
-            TimerChannelNum::Channel0 => {
-                reg.pwmc().modify(|_, w| w.pwmen0().match_());
-            }
-            TimerChannelNum::Channel1 => {
-                reg.pwmc().modify(|_, w| w.pwmen1().match_());
-            }
-            TimerChannelNum::Channel2 => {
-                reg.pwmc().modify(|_, w| w.pwmen2().match_());
-            }
-            TimerChannelNum::Channel3 => {
-                reg.pwmc().modify(|_, w| w.pwmen3().match_());
-            }
-        }
+        self.info.pwm_output_disable();
     }
 
     fn enable(&mut self, _: ()) {
-        let reg = self.info.regs;
-
         // Set duty cycle to 0
         self.set_duty((), CentiPercent(0, 0));
 
-        // To enable PWM output for a channel:
-        // 1. Disable stop and reset when match register matches the value in TC
-        // 2. Enable interrupt generation when match register matches the value in TC
-        // 3. Clear external match bit in match control register
-        // 4. Write 2 to external match control bit to set match output bit/pin when match register matches the value in TC
-        // 5. Clear interrupt flag
-        // 6. Set PWM enable bit in PWM control register
-
-        match TIMER_CHANNELS_ARR[self.info.channel] {
-            TimerChannelNum::Channel0 => {
-                reg.mcr().modify(|_, w| w.mr0r().clear_bit());
-                reg.mcr().modify(|_, w| w.mr0s().clear_bit());
-                reg.mcr().modify(|_, w| w.mr0i().set_bit());
-
-                reg.emr().modify(|_, w| w.em0().clear_bit());
-                reg.emr().modify(|_, w| w.emc0().set_());
-
-                reg.ir().modify(|_, w| w.mr0int().clear_bit_by_one());
-
-                reg.pwmc().modify(|_, w| w.pwmen0().pwm());
-            }
-            TimerChannelNum::Channel1 => {
-                reg.mcr().modify(|_, w| w.mr1r().clear_bit());
-                reg.mcr().modify(|_, w| w.mr1s().clear_bit());
-                reg.mcr().modify(|_, w| w.mr1i().set_bit());
-
-                reg.emr().modify(|_, w| w.em1().clear_bit());
-                reg.emr().modify(|_, w| w.emc1().set_());
-
-                // Write 1 to IR bit to clear interrupt
-                reg.ir().modify(|_, w| w.mr1int().clear_bit_by_one());
-
-                reg.pwmc().modify(|_, w| w.pwmen1().pwm());
-            }
-            TimerChannelNum::Channel2 => {
-                reg.mcr().modify(|_, w| w.mr2r().clear_bit());
-                reg.mcr().modify(|_, w| w.mr2s().clear_bit());
-                reg.mcr().modify(|_, w| w.mr2i().set_bit());
-
-                reg.emr().modify(|_, w| w.em2().clear_bit());
-                reg.emr().modify(|_, w| w.emc2().set_());
-
-                reg.ir().modify(|_, w| w.mr2int().clear_bit_by_one());
-
-                reg.pwmc().modify(|_, w| w.pwmen2().pwm());
-            }
-            TimerChannelNum::Channel3 => {
-                reg.mcr().modify(|_, w| w.mr3r().clear_bit());
-                reg.mcr().modify(|_, w| w.mr3s().clear_bit());
-                reg.mcr().modify(|_, w| w.mr3i().set_bit());
-
-                reg.emr().modify(|_, w| w.em3().clear_bit());
-                reg.emr().modify(|_, w| w.emc3().set_());
-
-                reg.ir().modify(|_, w| w.mr3int().clear_bit_by_one());
-
-                reg.pwmc().modify(|_, w| w.pwmen3().pwm());
-            }
-        }
-
-        // Reset and enable timer
-        if reg.tcr().read().cen().is_disabled() {
-            reg.tcr().write(|w| w.crst().set_bit());
-            reg.tcr().write(|w| w.crst().clear_bit());
-            reg.tcr().write(|w| w.cen().set_bit());
-        }
+        self.info.pwm_output_enable();
     }
 
     fn get_period(&self) -> Self::Time {
@@ -952,10 +1867,7 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
     }
 
     fn get_duty(&self, _: ()) -> Self::Duty {
-        let reg = self.info.regs;
-        let scaled = reg.mr(self.info.channel).read().bits();
-
-        CentiPercent::from_scaled(self.count_max - scaled, self.count_max)
+        self.info.pwm_get_duty(self.count_max)
     }
 
     fn get_max_duty(&self) -> Self::Duty {
@@ -963,17 +1875,7 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
     }
 
     fn set_duty(&mut self, _: (), duty: Self::Duty) {
-        // When set duty cycle is called on an already running PWM, output could stay low for a PWM period
-        // before new duty cycle is updated
-        let scaled = duty.as_scaled(self.count_max);
-        let reg = self.info.regs;
-
-        // PWM output is low at the beginning of PWM cycle
-        // PWM output is set to high when timer count reaches match register value
-        // For active high PWM, set match register such that output is high for PWM cycle length*dutycycle
-        reg.mr(self.info.channel).write(|w|
-            //SAFETY: No safety impact as we are writing match register here
-            unsafe { w.match_().bits(self.count_max - scaled)});
+        self.info.pwm_set_duty(self.count_max, duty);
     }
 
     fn set_period<P>(&mut self, period: P)
@@ -983,7 +1885,7 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
         // Updating period for one channel will impact all channels configured for PWM on the same timer
         // Period update also updates duty cycle which can cause an out of spec pulse in PWM output(output could stay low for a PWM period
         // before new duty cycle is updated)
-        let clock_rate = Hertz(self.info.pwm_get_clock_freq());
+        let clock_rate = Hertz(self.info.clock_freq());
 
         let requested_pwm_rate: Hertz = period.into().into();
 
@@ -1047,19 +1949,29 @@ impl<'p> CTimerPwm<'p> {
 
 impl<'p> CTimerPwmPeriodChannel<'p> {
     /// Take the `CTimer` instance supplied and use it as a simple PWM driver. Function returns constructed Pwm instance.
-    pub fn new<T: Instance>(_length_channel: impl Peripheral<P = T> + 'p, period: MicroSeconds) -> Result<Self> {
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new<T: Instance>(
+        _length_channel: impl Peripheral<P = T> + 'p,
+        period: MicroSeconds,
+        clock: TimerClockSource,
+    ) -> Result<Self> {
         let channel_info = T::info();
 
-        let clock_rate = Hertz(channel_info.pwm_get_clock_freq());
-
-        let requested_pwm_rate: Hertz = period.into();
-
         // we cannot clock faster than the supplied clock rate
         if period.0 == 0 {
             return Err(Error::InvalidPwmPeriod);
         }
+
+        channel_info.acquire_module(clock);
+
+        let clock_rate = Hertz(channel_info.clock_freq());
+
+        let requested_pwm_rate: Hertz = period.into();
+
         // assure precision is possible (PWM_PRECISION_CLK_TICKS_PER_PERIOD ticks within PWM minimum)
         if requested_pwm_rate.0 > clock_rate.0 / PWM_PRECISION_CLK_TICKS_PER_PERIOD {
+            channel_info.release_module();
             return Err(Error::PwmPrecisionNotSupported);
         }
 
@@ -1078,33 +1990,469 @@ impl<'p> CTimerPwmPeriodChannel<'p> {
     }
 }
 
-/// Initializes the timer modules and returns a `CTimerManager` in the initialized state.
+impl Drop for CTimerPwmPeriodChannel<'_> {
+    fn drop(&mut self) {
+        self.info.release_module();
+    }
+}
+
+/// PWM output of a [`CTimerPwmBank`], a match channel together with its output pin
+pub struct CTimerPwmOutput<'p> {
+    _lifetime: PhantomData<&'p ()>,
+    info: Info,
+}
+
+impl<'p> CTimerPwmOutput<'p> {
+    /// Use `match_channel` to drive `matchoutput_pin`.
+    pub fn new<T: Instance>(
+        _match_channel: impl Peripheral<P = T> + 'p,
+        matchoutput_pin: impl CTimerMatchOutput,
+    ) -> Self {
+        matchoutput_pin.configure_for_ctimer_match_output();
+
+        Self {
+            _lifetime: PhantomData,
+            info: T::info(),
+        }
+    }
+}
+
+/// PWM outputs with independent duty cycles sharing the period of one CTimer module.
+///
+/// All channels of a module share its counter, so one channel sets the period and the other
+/// (up to 3) channels each drive an output. The period channel is owned by the bank, outputs are
+/// addressed by their index in the array passed to [`CTimerPwmBank::new`].
+pub struct CTimerPwmBank<'p, const N: usize> {
+    period_channel: CTimerPwmPeriodChannel<'p>,
+    outputs: [CTimerPwmOutput<'p>; N],
+}
+
+impl<'p, const N: usize> CTimerPwmBank<'p, N> {
+    /// Use `period_channel` to set the PWM period of `outputs`, which must be on the same CTimer module.
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new<T: Instance>(
+        period_channel: impl Peripheral<P = T> + 'p,
+        period: MicroSeconds,
+        clock: TimerClockSource,
+        outputs: [CTimerPwmOutput<'p>; N],
+    ) -> Result<Self> {
+        let module = T::info().module;
+
+        if outputs.iter().any(|output| output.info.module != module) {
+            return Err(Error::PwmChannelMismatch);
+        }
+
+        let period_channel = CTimerPwmPeriodChannel::new(period_channel, period, clock)?;
+
+        Ok(Self {
+            period_channel,
+            outputs,
+        })
+    }
+
+    /// Number of outputs in the bank
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the bank has no outputs
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+impl<const N: usize> embedded_hal_02::Pwm for CTimerPwmBank<'_, N> {
+    type Channel = usize;
+    type Time = MicroSeconds;
+    type Duty = CentiPercent;
+
+    fn disable(&mut self, channel: usize) {
+        self.outputs[channel].info.pwm_output_disable();
+    }
+
+    fn enable(&mut self, channel: usize) {
+        // Set duty cycle to 0
+        self.set_duty(channel, CentiPercent(0, 0));
+
+        self.outputs[channel].info.pwm_output_enable();
+    }
+
+    fn get_period(&self) -> Self::Time {
+        self.period_channel.period
+    }
+
+    fn get_duty(&self, channel: usize) -> Self::Duty {
+        self.outputs[channel].info.pwm_get_duty(self.period_channel.count_max)
+    }
+
+    fn get_max_duty(&self) -> Self::Duty {
+        CentiPercent::MAX
+    }
+
+    fn set_duty(&mut self, channel: usize, duty: Self::Duty) {
+        self.outputs[channel]
+            .info
+            .pwm_set_duty(self.period_channel.count_max, duty);
+    }
+
+    fn set_period<P>(&mut self, period: P)
+    where
+        P: Into<Self::Time>,
+    {
+        // Period update also updates duty cycles which can cause an out of spec pulse in PWM output
+        let period = period.into();
+        let clock_rate = Hertz(self.period_channel.info.clock_freq());
+        let requested_pwm_rate: Hertz = period.into();
+
+        // period cannot be faster than supplied PWM clock source
+        assert!(requested_pwm_rate.0 > 0);
+        assert!(requested_pwm_rate.0 <= clock_rate.0 / PWM_PRECISION_CLK_TICKS_PER_PERIOD);
+
+        // record current duty cycles
+        let duty_cycles = self
+            .outputs
+            .each_ref()
+            .map(|output| output.info.pwm_get_duty(self.period_channel.count_max));
+
+        self.period_channel.period = period;
+        self.period_channel.count_max = clock_rate.0 / requested_pwm_rate.0;
+        self.period_channel.info.pwm_configure(self.period_channel.count_max);
+
+        // update duty cycle match registers according to new scale factor
+        for (output, duty) in self.outputs.iter().zip(duty_cycles) {
+            output.info.pwm_set_duty(self.period_channel.count_max, duty);
+        }
+    }
+}
+
+/// MCR actions of a match channel when the counter reaches its match value
+#[derive(Copy, Clone)]
+struct MatchControl {
+    /// Raise the match interrupt (MRnI)
+    interrupt: bool,
+    /// Reset the counter (MRnR)
+    reset: bool,
+    /// Stop the counter (MRnS)
+    stop: bool,
+}
+
+impl MatchControl {
+    /// No action, the match only drives the pin
+    const NONE: Self = Self {
+        interrupt: false,
+        reset: false,
+        stop: false,
+    };
+}
+
+/// Action on the match output pin when the counter reaches the match value
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MatchAction {
+    /// Leave the pin unchanged
+    Nothing = 0,
+    /// Drive the pin low
+    Clear = 1,
+    /// Drive the pin high
+    Set = 2,
+    /// Invert the pin level
+    Toggle = 3,
+}
+
+/// Counter behavior on a match of a [`MatchOutput`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MatchMode {
+    /// The counter stops on match, the action happens once
+    OneShot,
+    /// The counter restarts from 0 on match, the action repeats every match time
+    Repeating,
+}
+
+/// Hardware timed match output of a CTimer count channel.
+///
+/// The pin follows the external match bit of the channel, changed by the configured
+/// [`MatchAction`] when the module counter reaches the match value, with no software latency.
+///
+/// Timing restarts the module counter, so it must not be relied on by other channels of the
+/// module while a match is started, see [`MatchOutput::start`].
+pub struct MatchOutput<'d> {
+    _lifetime: PhantomData<&'d ()>,
+    id: usize,
+    info: Info,
+}
+
+impl<'d> MatchOutput<'d> {
+    /// Use `match_channel` to drive `matchoutput_pin`, which must be the match output of that channel.
+    ///
+    /// The pin starts low. `clock` is only applied if no other driver is using the CTimer module yet,
+    /// see [`TimerClockSource`].
+    pub fn new<T: Instance>(
+        _match_channel: impl Peripheral<P = T> + 'd,
+        matchoutput_pin: impl CTimerMatchOutput,
+        clock: TimerClockSource,
+    ) -> Self {
+        let info = T::info();
+        info.acquire_module(clock);
+        T::interrupt_enable();
+
+        info.match_set_control(MatchControl::NONE);
+        info.match_set_action(MatchAction::Nothing);
+        info.match_set_level(false);
+        matchoutput_pin.configure_for_ctimer_match_output();
+
+        Self {
+            _lifetime: PhantomData,
+            id: info.module * CHANNEL_PER_MODULE + info.channel,
+            info,
+        }
+    }
+
+    /// Drives the pin to `high` immediately
+    pub fn set_level(&mut self, high: bool) {
+        self.info.match_set_level(high);
+    }
+
+    /// Current level of the pin
+    pub fn level(&self) -> bool {
+        self.info.match_level()
+    }
+
+    /// Applies `action` to the pin `time_us` from now.
+    ///
+    /// In [`MatchMode::Repeating`] the action repeats every `time_us`, a [`MatchAction::Toggle`]
+    /// generates a square wave with a period of twice `time_us`.
+    ///
+    /// The module counter is restarted from 0 and stops or restarts on the match, so
+    /// [`Error::CounterInUse`] is returned if other channels of the module rely on it.
+    pub fn start(&mut self, action: MatchAction, time_us: u32, mode: MatchMode) -> Result<()> {
+        let counts = match mode {
+            // A match at 0 would be missed when the counter starts
+            MatchMode::OneShot => self.info.match_counts(time_us)?.max(1),
+            // The counter resets one count after the match
+            MatchMode::Repeating => self.info.match_counts(time_us)?.saturating_sub(1).max(1),
+        };
+
+        self.info.hold_counter(1 << self.info.channel)?;
+        self.info.match_set_action(action);
+        self.info.match_arm(
+            counts,
+            match mode {
+                MatchMode::OneShot => MatchControl {
+                    interrupt: true,
+                    reset: false,
+                    stop: true,
+                },
+                // No interrupt, the handler would clear the match value
+                MatchMode::Repeating => MatchControl {
+                    interrupt: false,
+                    reset: true,
+                    stop: false,
+                },
+            },
+        );
+        self.info.release_counter();
+
+        Ok(())
+    }
+
+    /// Waits for the match of a [`MatchMode::OneShot`] start.
+    ///
+    /// Returns immediately if no one-shot match is pending.
+    pub async fn match_done(&mut self) {
+        poll_fn(|cx| {
+            WAKERS[self.id].register(cx.waker());
+
+            if self.info.has_count_timer_expired() {
+                return Poll::Ready(());
+            }
+            Poll::Pending
+        })
+        .await;
+    }
+
+    /// Cancels a started match, the pin keeps its level
+    pub fn stop(&mut self) {
+        self.info.match_set_control(MatchControl::NONE);
+        self.info.match_set_action(MatchAction::Nothing);
+    }
+}
+
+impl Drop for MatchOutput<'_> {
+    fn drop(&mut self) {
+        self.stop();
+        self.info.release_module();
+    }
+}
+
+/// Hardware timed single pulses on a CTimer match output.
+///
+/// The output channel runs in PWM mode, raising its pin when the counter reaches the pulse
+/// start. A second channel of the same module resets and stops the counter at the pulse end,
+/// which drops the pin again. Both edges are timed by the hardware alone.
+pub struct MatchPulse<'d> {
+    _lifetime: PhantomData<&'d ()>,
+    id: usize,
+    output: Info,
+    end: Info,
+}
+
+impl<'d> MatchPulse<'d> {
+    /// Use `output_channel` to drive `matchoutput_pin`, which must be the match output of that
+    /// channel, and `end_channel` of the same module to end the pulses.
+    ///
+    /// `clock` is only applied if no other driver is using the CTimer module yet, see [`TimerClockSource`].
+    pub fn new<O: Instance, E: Instance>(
+        _output_channel: impl Peripheral<P = O> + 'd,
+        _end_channel: impl Peripheral<P = E> + 'd,
+        matchoutput_pin: impl CTimerMatchOutput,
+        clock: TimerClockSource,
+    ) -> Result<Self> {
+        let output = O::info();
+        let end = E::info();
+
+        if output.module != end.module {
+            return Err(Error::MatchChannelMismatch);
+        }
+
+        output.acquire_module(clock);
+        end.set_counter_active(true);
+        E::interrupt_enable();
+
+        // PWM mode keeps the pin low until the counter reaches the match value
+        output.match_set_control(MatchControl::NONE);
+        // SAFETY: unsafe only used for .bits()
+        output
+            .regs
+            .mr(output.channel)
+            .write(|w| unsafe { w.match_().bits(u32::MAX) });
+        // SAFETY: unsafe only used for .bits()
+        output
+            .regs
+            .pwmc()
+            .modify(|r, w| unsafe { w.bits(r.bits() | 1 << output.channel) });
+        end.match_set_control(MatchControl::NONE);
+        matchoutput_pin.configure_for_ctimer_match_output();
+
+        Ok(Self {
+            _lifetime: PhantomData,
+            id: end.module * CHANNEL_PER_MODULE + end.channel,
+            output,
+            end,
+        })
+    }
+
+    /// Starts a high pulse of `width_us` on the pin, `delay_us` from now.
+    ///
+    /// The module counter is restarted from 0 and stopped at the end of the pulse, so
+    /// [`Error::CounterInUse`] is returned if other channels of the module rely on it.
+    pub fn pulse(&mut self, delay_us: u32, width_us: u32) -> Result<()> {
+        // The pin is high while the counter is at or past the start, so it must not be 0 where
+        // the counter stops
+        let start = self.output.match_counts(delay_us)?.max(1);
+        // The counter resets one count after the end match
+        let end = start
+            .checked_add(self.output.match_counts(width_us)?.max(1) - 1)
+            .ok_or(Error::MatchTimeOutOfRange)?;
+
+        self.output
+            .hold_counter((1 << self.output.channel) | (1 << self.end.channel))?;
+
+        // SAFETY: unsafe only used for .bits()
+        self.output
+            .regs
+            .mr(self.output.channel)
+            .write(|w| unsafe { w.match_().bits(start) });
+        self.end.match_arm(
+            end,
+            MatchControl {
+                interrupt: true,
+                reset: true,
+                stop: true,
+            },
+        );
+
+        self.output.release_counter();
+
+        Ok(())
+    }
+
+    /// Waits for the end of the pulse started by [`MatchPulse::pulse`].
+    ///
+    /// Returns immediately if no pulse is in progress.
+    pub async fn pulse_done(&mut self) {
+        poll_fn(|cx| {
+            WAKERS[self.id].register(cx.waker());
+
+            if self.end.has_count_timer_expired() {
+                return Poll::Ready(());
+            }
+            Poll::Pending
+        })
+        .await;
+    }
+}
+
+impl Drop for MatchPulse<'_> {
+    fn drop(&mut self) {
+        self.end.match_set_control(MatchControl::NONE);
+        self.output.pwm_output_disable();
+        self.end.set_counter_active(false);
+        self.output.release_module();
+    }
+}
+
+/// Functional clock source of a CTimer module
+///
+/// The first driver created on a module selects its clock source, drivers created while the module
+/// is in use share the running clock and derive their timing from it. The module clock is gated
+/// again once the last driver using it is dropped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TimerClockSource {
+    /// 16 MHz SFRO, low power
+    Sfro,
+    /// FFRO, 48 or 60 MHz depending on its trim range, for high resolution
+    Ffro,
+    /// Main clock
+    MainClk,
+    /// 1 MHz low power oscillator
+    Lposc,
+}
+
+/// External count clock of a CTimer module, see [`CountingTimer::new_with_external_clock`]
+#[derive(Copy, Clone)]
+pub struct TimerExternalClock {
+    /// Pin edges advancing the counter
+    pub edge: CaptureChEdge,
+    /// Functional clock of the module, sampling the pin
+    pub sampling_clock: TimerClockSource,
+}
+
+impl Default for TimerExternalClock {
+    fn default() -> Self {
+        Self {
+            edge: CaptureChEdge::Rising,
+            sampling_clock: TimerClockSource::Sfro,
+        }
+    }
+}
+
+/// Initializes the state shared by all timer modules.
+///
+/// The CTimer modules themselves stay gated until a driver using them is created.
 pub fn init() {
-    // SAFETY: This has no safety impact as we are getting a singleton register instance here and its dropped it the end of the function
-    let reg = unsafe { Clkctl1::steal() };
-
-    // Initialization steps from NXP TRM
-    //
-    // • Enable the clock to the CTIMER in the CLKCTL1_PSCCTL2 register
-    //          This enables the register interface and the peripheral function clock.
-    // • Clear the CTIMER peripheral reset in the RSTCTL1_PRSTCTL2 register
-    // (Section 4.5.4.4) by writing to the RSTCTL1_PRSTCTL2_CLR register (Section 4.5.4.10).
-    enable_and_reset::<peripherals::CTIMER0_COUNT_CHANNEL0>();
-    enable_and_reset::<peripherals::CTIMER1_COUNT_CHANNEL0>();
-    enable_and_reset::<peripherals::CTIMER2_COUNT_CHANNEL0>();
-    enable_and_reset::<peripherals::CTIMER3_COUNT_CHANNEL0>();
-    enable_and_reset::<peripherals::CTIMER4_COUNT_CHANNEL0>();
     enable_and_reset::<peripherals::PIMCTL>();
-
-    // • Select a clock source for the CTIMER using the appropriate CT32BIT0FCLKSEL
-    // register (see Section 4.5.2.55 through Section 4.5.2.59).
-    reg.ct32bitfclksel(0).write(|w| w.sel().sfro_clk());
-    reg.ct32bitfclksel(1).write(|w| w.sel().sfro_clk());
-    reg.ct32bitfclksel(2).write(|w| w.sel().sfro_clk());
-    reg.ct32bitfclksel(3).write(|w| w.sel().sfro_clk());
-    reg.ct32bitfclksel(4).write(|w| w.sel().sfro_clk());
 }
 
+/// Initializes the timer modules, formerly clocking CTimer `n` from `clocks[n]`.
+///
+/// The clock source of a module is now passed to the first driver created on it, see
+/// [`TimerClockSource`]. The modules are initialized by [`crate::init`], `clocks` is ignored.
+#[deprecated(note = "pass the clock source to the first driver created on a CTimer module")]
+pub fn init_with_clocks(_clocks: [TimerClockSource; 5]) {}
+
 impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for CtimerInterruptHandler<T> {
     unsafe fn on_interrupt() {
         let module = T::info().module;
@@ -1177,6 +2525,10 @@ pub trait CaptureEvent: Pin + crate::Peripheral {
     fn configure_for_event_capture(&self);
     /// Get trigger input of event pin
     fn get_trigger_input(&self) -> TriggerInput;
+    /// Input inverter enabled by [`CaptureEvent::configure_for_event_capture`]
+    ///
+    /// Requested capture edges are flipped for inverted pins, so they always match the pin level.
+    fn input_inverter(&self) -> Inverter;
 }
 macro_rules! impl_pin {
     ($piom_n:ident, $fn:ident, $invert:ident, $trig:ident) => {
@@ -1195,6 +2547,10 @@ macro_rules! impl_pin {
             fn get_trigger_input(&self) -> TriggerInput {
                 TriggerInput::$trig
             }
+
+            fn input_inverter(&self) -> Inverter {
+                Inverter::$invert
+            }
         }
     };
 }
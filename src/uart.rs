@@ -2,22 +2,39 @@
 
 use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{compiler_fence, AtomicU8, Ordering};
 use core::task::Poll;
 
+use cortex_m::peripheral::DWT;
+use embassy_futures::join::join;
 use embassy_futures::select::{select, Either};
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
 use embassy_sync::waitqueue::AtomicWaker;
 use paste::paste;
 
+use crate::clocks::{core_clock_rate, ClockGuard};
+use crate::delay::Delay;
 use crate::dma::channel::Channel;
-use crate::dma::transfer::Transfer;
+use crate::dma::transfer::{Transfer, Width};
+use crate::dma::{ChannelDescriptor, XferCfg};
+use crate::flexcomm::FIFO_DEPTH;
 use crate::gpio::{AnyPin, GpioPin as Pin};
 use crate::interrupt::typelevel::Interrupt;
 use crate::iopctl::{DriveMode, DriveStrength, Inverter, IopctlPin, Pull, SlewRate};
-use crate::pac::usart0::cfg::{Clkpol, Datalen, Loop, Paritysel as Parity, Stoplen, Syncen, Syncmst};
+use crate::pac::usart0::cfg::{Clkpol, Datalen, Loop, Paritysel, Stoplen, Syncen, Syncmst};
 use crate::pac::usart0::ctl::Cc;
 use crate::{dma, interrupt};
 
+/// Length of `chars` characters of `frame_bits` bits each at `baudrate`, in microseconds
+///
+/// Rounded up, so a gap of this length is never shorter than the characters it stands for.
+fn char_gap_us(baudrate: u32, frame_bits: u32, chars: u8) -> u32 {
+    let bits = u64::from(frame_bits) * u64::from(chars);
+    (bits * 1_000_000).div_ceil(u64::from(baudrate.max(1))) as u32
+}
+
 /// Driver move trait.
 #[allow(private_bounds)]
 pub trait Mode: sealed::Sealed {}
@@ -42,57 +59,283 @@ pub struct Uart<'a, M: Mode> {
 /// Uart TX driver.
 pub struct UartTx<'a, M: Mode> {
     info: Info,
+    clock: ClockGuard,
+    suspended: Option<Registers>,
     _tx_dma: Option<Channel<'a>>,
+    last_progress: Option<TransferProgress>,
     _phantom: PhantomData<(&'a (), M)>,
 }
 
 /// Uart RX driver.
 pub struct UartRx<'a, M: Mode> {
     info: Info,
+    clock: ClockGuard,
+    suspended: Option<Registers>,
     _rx_dma: Option<Channel<'a>>,
+    error_policy: ErrorPolicy,
+    error_stats: ErrorStats,
+    last_progress: Option<TransferProgress>,
     _phantom: PhantomData<(&'a (), M)>,
 }
 
+/// What an async read does on a recoverable line error (framing, parity or noise)
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorPolicy {
+    /// Stop the read and return the error, the data received so far is discarded
+    #[default]
+    Abort,
+    /// Count the error and keep the DMA transfer running
+    ///
+    /// The DMA has already moved the affected byte into the buffer by the time the error is
+    /// seen, so it cannot be removed from the data, only accounted for.
+    SkipAndCount,
+}
+
+/// Line errors seen by async reads since the counters were last reset
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ErrorStats {
+    /// Framing errors
+    pub framing: u32,
+    /// Parity errors
+    pub parity: u32,
+    /// Noise errors
+    pub noise: u32,
+    /// RX FIFO overruns, these always abort the read
+    pub overrun: u32,
+}
+
+/// How far an async read or write got before it was cancelled
+///
+/// The TX and RX FIFOs are 16 entries deep, so the bytes moved by DMA and the bytes on the wire
+/// differ by up to the FIFO level.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransferProgress {
+    /// Bytes moved between the buffer and the FIFO, counted from the start of the buffer
+    ///
+    /// For a write these are committed: the FIFO is not emptied on cancellation, so they all go
+    /// out on the wire. For a read they have been stored in the buffer.
+    pub transferred: usize,
+    /// FIFO level when the transfer was cancelled
+    ///
+    /// For a write, the number of `transferred` bytes that were not on the wire yet. For a read,
+    /// bytes received but not moved into the buffer, they are left for the next read.
+    pub fifo_level: usize,
+}
+
+/// Number of data bits per character
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DataBits {
+    /// 7 data bits
+    Seven,
+    /// 8 data bits
+    Eight,
+    /// 9 data bits
+    Nine,
+}
+
+impl From<DataBits> for Datalen {
+    fn from(bits: DataBits) -> Self {
+        match bits {
+            DataBits::Seven => Datalen::Bit7,
+            DataBits::Eight => Datalen::Bit8,
+            DataBits::Nine => Datalen::Bit9,
+        }
+    }
+}
+
+/// Parity bit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Parity {
+    /// No parity bit
+    None,
+    /// Even parity
+    Even,
+    /// Odd parity
+    Odd,
+}
+
+impl From<Parity> for Paritysel {
+    fn from(parity: Parity) -> Self {
+        match parity {
+            Parity::None => Paritysel::NoParity,
+            Parity::Even => Paritysel::EvenParity,
+            Parity::Odd => Paritysel::OddParity,
+        }
+    }
+}
+
+/// Number of stop bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StopBits {
+    /// 1 stop bit
+    One,
+    /// 2 stop bits
+    Two,
+}
+
+impl From<StopBits> for Stoplen {
+    fn from(bits: StopBits) -> Self {
+        match bits {
+            StopBits::One => Stoplen::Bit1,
+            StopBits::Two => Stoplen::Bits2,
+        }
+    }
+}
+
+/// SCLK edge on which data is sampled in synchronous mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockPolarity {
+    /// Sample on the falling edge of SCLK
+    FallingEdge,
+    /// Sample on the rising edge of SCLK
+    RisingEdge,
+}
+
+impl From<ClockPolarity> for Clkpol {
+    fn from(polarity: ClockPolarity) -> Self {
+        match polarity {
+            ClockPolarity::FallingEdge => Clkpol::FallingEdge,
+            ClockPolarity::RisingEdge => Clkpol::RisingEdge,
+        }
+    }
+}
+
+/// Asynchronous (UART) or synchronous (clocked by SCLK) operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Operation {
+    /// Asynchronous mode
+    Asynchronous,
+    /// Synchronous mode
+    Synchronous,
+}
+
+impl From<Operation> for Syncen {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Asynchronous => Syncen::AsynchronousMode,
+            Operation::Synchronous => Syncen::SynchronousMode,
+        }
+    }
+}
+
+/// Role in synchronous mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SyncRole {
+    /// SCLK is an input
+    Slave,
+    /// SCLK is generated by this USART
+    Master,
+}
+
+impl From<SyncRole> for Syncmst {
+    fn from(role: SyncRole) -> Self {
+        match role {
+            SyncRole::Slave => Syncmst::Slave,
+            SyncRole::Master => Syncmst::Master,
+        }
+    }
+}
+
+/// SCLK generation in synchronous master mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ContinuousClock {
+    /// SCLK only runs while a character is transferred
+    OnCharacter,
+    /// SCLK runs continuously, also clocking in characters while nothing is sent
+    Continuous,
+}
+
+impl From<ContinuousClock> for Cc {
+    fn from(clock: ContinuousClock) -> Self {
+        match clock {
+            ContinuousClock::OnCharacter => Cc::ClockOnCharacter,
+            ContinuousClock::Continuous => Cc::ContinousClock,
+        }
+    }
+}
+
+/// Internal loopback of TX to RX
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoopbackMode {
+    /// Normal operation
+    Normal,
+    /// TX is looped back to RX internally, the RX pin is not used
+    Loopback,
+}
+
+impl From<LoopbackMode> for Loop {
+    fn from(mode: LoopbackMode) -> Self {
+        match mode {
+            LoopbackMode::Normal => Loop::Normal,
+            LoopbackMode::Loopback => Loop::Loopback,
+        }
+    }
+}
+
 /// UART config
 #[derive(Clone, Copy)]
 pub struct Config {
     /// Baudrate of the Uart
     pub baudrate: u32,
     /// data length
-    pub data_bits: Datalen,
+    pub data_bits: DataBits,
     /// Parity
     pub parity: Parity,
     /// Stop bits
-    pub stop_bits: Stoplen,
+    pub stop_bits: StopBits,
     /// Polarity of the clock
-    pub clock_polarity: Clkpol,
+    pub clock_polarity: ClockPolarity,
     /// Sync/ Async operation selection
-    pub operation: Syncen,
+    pub operation: Operation,
     /// Sync master/slave mode selection (only applicable in sync mode)
-    pub sync_mode_master_select: Syncmst,
+    pub sync_mode_master_select: SyncRole,
     /// USART continuous Clock generation enable in synchronous master mode.
-    pub continuous_clock: Cc,
+    pub continuous_clock: ContinuousClock,
     /// Normal/ loopback mode
-    pub loopback_mode: Loop,
-    /// Source clock in Hz
+    pub loopback_mode: LoopbackMode,
+    /// Invert the RX line, for signals passing through an inverting level shifter
+    ///
+    /// Done by the input inverter of the RX pin, so it has no effect in internal loopback mode.
+    pub rx_invert: bool,
+    /// Invert the TX line, done by the USART before the data reaches the pin
+    pub tx_invert: bool,
+    /// Source clock in Hz, ignored
+    #[deprecated(note = "ignored, the source clock rate is now read from the clock selected by `clock`")]
     pub source_clock_hz: u32,
-    /// Clock type
+    /// Functional clock of the flexcomm, the baudrate is derived from its frequency at init
+    ///
+    /// SFRO (16 MHz) is the low power choice for slow links such as debug consoles, FFRO (48 or
+    /// 60 MHz) reaches up to 3 Mbit/s.
     pub clock: crate::flexcomm::Clock,
 }
 
 impl Default for Config {
     /// Default configuration for single channel sampling.
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             baudrate: 115_200,
-            data_bits: Datalen::Bit8,
-            parity: Parity::NoParity,
-            stop_bits: Stoplen::Bit1,
-            clock_polarity: Clkpol::FallingEdge,
-            operation: Syncen::AsynchronousMode,
-            sync_mode_master_select: Syncmst::Slave,
-            continuous_clock: Cc::ClockOnCharacter,
-            loopback_mode: Loop::Normal,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            clock_polarity: ClockPolarity::FallingEdge,
+            operation: Operation::Asynchronous,
+            sync_mode_master_select: SyncRole::Slave,
+            continuous_clock: ContinuousClock::OnCharacter,
+            loopback_mode: LoopbackMode::Normal,
+            rx_invert: false,
+            tx_invert: false,
             source_clock_hz: 16_000_000,
             clock: crate::flexcomm::Clock::Sfro,
         }
@@ -106,7 +349,7 @@ pub enum Error {
     /// Read error
     Read,
 
-    /// Buffer overflow
+    /// RX FIFO overrun, received data was lost
     Overrun,
 
     /// Noise error
@@ -135,18 +378,82 @@ pub enum Error {
 
     /// TX Busy
     TxBusy,
+
+    /// DMA transfer could not be set up
+    Dma(dma::Error),
+
+    /// Half-duplex echo did not match the transmitted data
+    CollisionDetected,
+}
+
+impl From<dma::Error> for Error {
+    fn from(value: dma::Error) -> Self {
+        Error::Dma(value)
+    }
 }
+
 /// shorthand for -> `Result<T>`
 pub type Result<T> = core::result::Result<T, Error>;
 
 impl<'a, M: Mode> UartTx<'a, M> {
-    fn new_inner<T: Instance>(_tx_dma: Option<Channel<'a>>) -> Self {
+    fn new_inner<T: Instance>(clock: ClockGuard, _tx_dma: Option<Channel<'a>>) -> Self {
         Self {
             info: T::info(),
+            clock,
+            suspended: None,
             _tx_dma,
+            last_progress: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Number of bytes that can be written without waiting.
+    ///
+    /// In async mode a running DMA write keeps refilling the FIFO, so this is only meaningful
+    /// between writes.
+    pub fn space(&self) -> usize {
+        FIFO_DEPTH - self.info.regs.fifostat().read().txlvl().bits() as usize
+    }
+
+    /// Quiesce the transmitter and give up its flexcomm clock reference.
+    ///
+    /// Waits until the data already written has left the shifter, await [`UartTx::drain`] first
+    /// to avoid busy-waiting. The USART is disabled once the receiver is suspended too, and the
+    /// flexcomm clock is only gated (putting the flexcomm into reset) when no half or other
+    /// driver uses it anymore, so both halves of a split UART must be suspended before entering
+    /// a low power mode that requires it. Does nothing if already suspended.
+    ///
+    /// The transmitter must not be used until [`UartTx::resume`].
+    pub fn suspend(&mut self) {
+        if self.suspended.is_some() {
+            return;
+        }
+
+        let regs = self.info.regs;
+        while regs.stat().read().txidle().bit_is_clear() {}
+
+        self.suspended = Some(Registers::save(&self.info, Half::Tx));
+        regs.intenclr().write(|w| w.txidleclr().set_bit());
+        modify_fifocfg(regs, |_, w| w.dmatx().disabled().enabletx().disabled());
+        self.info.disable_if_idle();
+
+        self.clock.suspend();
+    }
+
+    /// Undo [`UartTx::suspend`], restoring baudrate, frame format, FIFO and DMA enables.
+    ///
+    /// Does nothing if not suspended.
+    pub fn resume(&mut self) {
+        if let Some(saved) = self.suspended.take() {
+            self.clock.resume();
+            saved.restore(&self.info);
+        }
+    }
+
+    /// Whether the transmitter is suspended, see [`UartTx::suspend`]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
 }
 
 impl<'a> UartTx<'a, Blocking> {
@@ -162,9 +469,9 @@ impl<'a> UartTx<'a, Blocking> {
         tx.as_tx();
 
         let mut _tx = tx.map_into();
-        Uart::<Blocking>::init::<T>(Some(_tx.reborrow()), None, None, None, config)?;
+        let clock = Uart::<Blocking>::init::<T>(Some(_tx.reborrow()), None, None, None, config)?;
 
-        Ok(Self::new_inner::<T>(None))
+        Ok(Self::new_inner::<T>(clock, None))
     }
 
     fn write_byte_internal(&mut self, byte: u8) -> Result<()> {
@@ -225,13 +532,83 @@ impl<'a> UartTx<'a, Blocking> {
 }
 
 impl<'a, M: Mode> UartRx<'a, M> {
-    fn new_inner<T: Instance>(_rx_dma: Option<Channel<'a>>) -> Self {
+    fn new_inner<T: Instance>(clock: ClockGuard, _rx_dma: Option<Channel<'a>>) -> Self {
         Self {
             info: T::info(),
+            clock,
+            suspended: None,
             _rx_dma,
+            error_policy: ErrorPolicy::Abort,
+            error_stats: ErrorStats::default(),
+            last_progress: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Number of received bytes that can be read without waiting.
+    ///
+    /// In async mode a running DMA read drains the FIFO, so this only counts bytes received
+    /// between reads.
+    pub fn available(&self) -> usize {
+        self.info.regs.fifostat().read().rxlvl().bits() as usize
+    }
+
+    /// Stop receiving and give up the flexcomm clock reference.
+    ///
+    /// Data still in the RX FIFO is discarded. The USART is disabled once the transmitter is
+    /// suspended too, see [`UartTx::suspend`] for when the flexcomm clock is gated. Does nothing
+    /// if already suspended.
+    ///
+    /// The receiver must not be used until [`UartRx::resume`].
+    pub fn suspend(&mut self) {
+        if self.suspended.is_some() {
+            return;
+        }
+
+        let regs = self.info.regs;
+        self.suspended = Some(Registers::save(&self.info, Half::Rx));
+        regs.intenclr().write(|w| {
+            w.framerrclr()
+                .set_bit()
+                .parityerrclr()
+                .set_bit()
+                .rxnoiseclr()
+                .set_bit()
+                .aberrclr()
+                .set_bit()
+        });
+        regs.fifointenclr().write(|w| w.rxerr().set_bit());
+        modify_fifocfg(regs, |_, w| w.dmarx().disabled().enablerx().disabled());
+        self.info.disable_if_idle();
+
+        self.clock.suspend();
+    }
+
+    /// Undo [`UartRx::suspend`], restoring baudrate, frame format, FIFO and DMA enables.
+    ///
+    /// Does nothing if not suspended.
+    pub fn resume(&mut self) {
+        if let Some(saved) = self.suspended.take() {
+            self.clock.resume();
+            saved.restore(&self.info);
+        }
+    }
+
+    /// Whether the receiver is suspended, see [`UartRx::suspend`]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
+
+    /// Returns the next received byte without removing it from the FIFO.
+    ///
+    /// Like [`Self::available`], in async mode this only sees bytes received between reads.
+    pub fn peek(&self) -> Option<u8> {
+        if self.info.regs.fifostat().read().rxnotempty().bit_is_clear() {
+            return None;
+        }
+
+        Some(self.info.regs.fifordnopop().read().rxdata().bits() as u8)
+    }
 }
 
 impl<'a> UartRx<'a, Blocking> {
@@ -246,18 +623,19 @@ impl<'a> UartRx<'a, Blocking> {
         rx.as_rx();
 
         let mut _rx = rx.map_into();
-        Uart::<Blocking>::init::<T>(None, Some(_rx.reborrow()), None, None, config)?;
+        let clock = Uart::<Blocking>::init::<T>(None, Some(_rx.reborrow()), None, None, config)?;
 
-        Ok(Self::new_inner::<T>(None))
+        Ok(Self::new_inner::<T>(clock, None))
     }
 }
 
 impl UartRx<'_, Blocking> {
     fn read_byte_internal(&mut self) -> Result<u8> {
         if self.info.regs.fifostat().read().rxerr().bit_is_set() {
-            self.info.regs.fifocfg().modify(|_, w| w.emptyrx().set_bit());
-            self.info.regs.fifostat().modify(|_, w| w.rxerr().set_bit());
-            Err(Error::Read)
+            // Bytes received after the overflow are gone, drop the stale FIFO contents as well
+            modify_fifocfg(self.info.regs, |_, w| w.emptyrx().set_bit());
+            self.info.regs.fifostat().write(|w| w.rxerr().set_bit());
+            Err(Error::Overrun)
         } else if self.info.regs.stat().read().parityerrint().bit_is_set() {
             self.info.regs.stat().modify(|_, w| w.parityerrint().clear_bit_by_one());
             Err(Error::Parity)
@@ -282,10 +660,32 @@ impl UartRx<'_, Blocking> {
     }
 
     fn blocking_read_byte(&mut self) -> Result<u8> {
+        if self.info.regs.fifostat().read().rxnotempty().bit_is_clear() {
+            self.clock_in_character();
+        }
+
         while self.info.regs.fifostat().read().rxnotempty().bit_is_clear() {}
         self.read_byte_internal()
     }
 
+    /// In synchronous master mode SCLK only runs while a character is transmitted, so a peer
+    /// that shifts data out on our clock never gets to answer a read. Unless continuous clock
+    /// is already enabled, run the clock for exactly one received character.
+    fn clock_in_character(&mut self) {
+        let cfg = self.info.regs.cfg().read();
+
+        if cfg.syncen().is_synchronous_mode()
+            && cfg.syncmst().is_master()
+            && self.info.regs.ctl().read().cc().bit_is_clear()
+        {
+            // CLRCCONRX clears CC again once a complete character has been received
+            self.info
+                .regs
+                .ctl()
+                .modify(|_, w| w.cc().set_bit().clrcconrx().set_bit());
+        }
+    }
+
     /// Read from UART RX.
     pub fn read(&mut self, buf: &mut [u8]) -> Result<()> {
         for b in buf.iter_mut() {
@@ -303,6 +703,40 @@ impl UartRx<'_, Blocking> {
 
         Ok(())
     }
+
+    /// Read a frame delimited by line silence, e.g. a Modbus RTU frame, blocking execution until
+    /// done.
+    ///
+    /// Waits for the first byte, then returns the number of bytes received once the line has been
+    /// idle for `timeout_chars` character times at the configured baudrate and frame format, or
+    /// once `buf` is full. The USART has no receive timeout, the gap is timed on the DWT cycle
+    /// counter while polling the RX FIFO.
+    pub fn blocking_read_with_char_timeout(&mut self, buf: &mut [u8], timeout_chars: u8) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let gap_us = u64::from(self.info.char_gap_us(timeout_chars));
+        let gap_cycles = (gap_us * u64::from(core_clock_rate())).div_ceil(1_000_000);
+        // The cycle counter wraps after 2^32 cycles, longer gaps cannot be timed
+        let gap_cycles = gap_cycles.min(u64::from(u32::MAX)) as u32;
+
+        buf[0] = self.blocking_read_byte()?;
+        let mut received = 1;
+        let mut last = DWT::cycle_count();
+
+        while received < buf.len() {
+            if self.info.regs.fifostat().read().rxnotempty().bit_is_set() {
+                buf[received] = self.read_byte_internal()?;
+                received += 1;
+                last = DWT::cycle_count();
+            } else if DWT::cycle_count().wrapping_sub(last) >= gap_cycles {
+                break;
+            }
+        }
+
+        Ok(received)
+    }
 }
 
 impl<'a, M: Mode> Uart<'a, M> {
@@ -312,21 +746,27 @@ impl<'a, M: Mode> Uart<'a, M> {
         rts: Option<PeripheralRef<'_, AnyPin>>,
         cts: Option<PeripheralRef<'_, AnyPin>>,
         config: Config,
-    ) -> Result<()> {
-        T::enable(config.clock);
+    ) -> Result<ClockGuard> {
+        let clock = T::enable(config.clock);
         T::into_usart();
 
         let regs = T::info().regs;
 
         if tx.is_some() {
-            regs.fifocfg().modify(|_, w| w.emptytx().set_bit().enabletx().enabled());
+            modify_fifocfg(regs, |_, w| w.emptytx().set_bit().enabletx().enabled());
 
             // clear FIFO error
             regs.fifostat().write(|w| w.txerr().set_bit());
         }
 
-        if rx.is_some() {
-            regs.fifocfg().modify(|_, w| w.emptyrx().set_bit().enablerx().enabled());
+        if let Some(rx) = &rx {
+            rx.set_input_inverter(if config.rx_invert {
+                Inverter::Enabled
+            } else {
+                Inverter::Disabled
+            });
+
+            modify_fifocfg(regs, |_, w| w.emptyrx().set_bit().enablerx().enabled());
 
             // clear FIFO error
             regs.fifostat().write(|w| w.rxerr().set_bit());
@@ -336,30 +776,40 @@ impl<'a, M: Mode> Uart<'a, M> {
             regs.cfg().modify(|_, w| w.ctsen().enabled());
         }
 
-        Self::set_baudrate_inner::<T>(config.baudrate, config.source_clock_hz)?;
+        Self::set_baudrate_inner::<T>(&config, T::clock_hz())?;
         Self::set_uart_config::<T>(config);
 
-        Ok(())
+        Ok(clock)
     }
 
-    fn set_baudrate_inner<T: Instance>(baudrate: u32, source_clock_hz: u32) -> Result<()> {
-        if baudrate == 0 || source_clock_hz == 0 {
-            return Err(Error::InvalidArgument);
-        }
-
+    fn set_baudrate_inner<T: Instance>(config: &Config, source_clock_hz: u32) -> Result<()> {
+        let baudrate = config.baudrate;
         let regs = T::info().regs;
 
-        // If synchronous master mode is enabled, only configure the BRG value.
-        if regs.cfg().read().syncen().is_synchronous_mode() {
-            // Master
-            if regs.cfg().read().syncmst().is_master() {
+        // If synchronous mode is enabled, only configure the BRG value. The OSR is not used
+        // since every bit is sampled on an SCLK edge, and a slave takes its clock from SCLK.
+        if config.operation == Operation::Synchronous {
+            if config.sync_mode_master_select == SyncRole::Master {
+                if baudrate == 0 || source_clock_hz < baudrate {
+                    return Err(Error::InvalidArgument);
+                }
+
                 // Calculate the BRG value
                 let brgval = (source_clock_hz / baudrate) - 1;
 
+                // Value over range
+                if brgval > 65535 {
+                    return Err(Error::UnsupportedBaudrate);
+                }
+
                 // SAFETY: unsafe only used for .bits()
                 regs.brg().write(|w| unsafe { w.brgval().bits(brgval as u16) });
             }
         } else {
+            if baudrate == 0 || source_clock_hz == 0 {
+                return Err(Error::InvalidArgument);
+            }
+
             // Smaller values of OSR can make the sampling position within a
             // data bit less accurate and may potentially cause more noise
             // errors or incorrect data.
@@ -410,19 +860,26 @@ impl<'a, M: Mode> Uart<'a, M> {
 
         regs.cfg().modify(|_, w| {
             w.datalen()
-                .variant(config.data_bits)
+                .variant(config.data_bits.into())
                 .stoplen()
-                .variant(config.stop_bits)
+                .variant(config.stop_bits.into())
                 .paritysel()
-                .variant(config.parity)
+                .variant(config.parity.into())
                 .loop_()
-                .variant(config.loopback_mode)
+                .variant(config.loopback_mode.into())
                 .syncen()
-                .variant(config.operation)
+                .variant(config.operation.into())
+                .syncmst()
+                .variant(config.sync_mode_master_select.into())
                 .clkpol()
-                .variant(config.clock_polarity)
+                .variant(config.clock_polarity.into())
+                .txpol()
+                .bit(config.tx_invert)
         });
 
+        regs.ctl()
+            .modify(|_, w| w.cc().variant(config.continuous_clock.into()).clrcconrx().clear_bit());
+
         regs.cfg().modify(|_, w| w.enable().enabled());
     }
 
@@ -446,10 +903,7 @@ impl<'a, M: Mode> Uart<'a, M> {
         });
 
         // Disable dma requests
-        self.info
-            .regs
-            .fifocfg()
-            .modify(|_, w| w.dmatx().clear_bit().dmarx().clear_bit());
+        modify_fifocfg(self.info.regs, |_, w| w.dmatx().clear_bit().dmarx().clear_bit());
 
         // Disable peripheral
         self.info.regs.cfg().modify(|_, w| w.enable().disabled());
@@ -457,6 +911,33 @@ impl<'a, M: Mode> Uart<'a, M> {
         Ok(())
     }
 
+    /// Number of received bytes that can be read without waiting, see [`UartRx::available`].
+    pub fn available(&self) -> usize {
+        self.rx.available()
+    }
+
+    /// Returns the next received byte without removing it, see [`UartRx::peek`].
+    pub fn peek(&self) -> Option<u8> {
+        self.rx.peek()
+    }
+
+    /// Number of bytes that can be written without waiting, see [`UartTx::space`].
+    pub fn space(&self) -> usize {
+        self.tx.space()
+    }
+
+    /// Suspend both directions, see [`UartTx::suspend`] and [`UartRx::suspend`].
+    pub fn suspend(&mut self) {
+        self.tx.suspend();
+        self.rx.suspend();
+    }
+
+    /// Resume both directions after [`Uart::suspend`].
+    pub fn resume(&mut self) {
+        self.tx.resume();
+        self.rx.resume();
+    }
+
     /// Split the Uart into a transmitter and receiver, which is particularly
     /// useful when having two tasks correlating to transmitting and receiving.
     pub fn split(self) -> (UartTx<'a, M>, UartRx<'a, M>) {
@@ -489,12 +970,74 @@ impl<'a> Uart<'a, Blocking> {
         let mut tx = tx.map_into();
         let mut rx = rx.map_into();
 
-        Self::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
+        let clock = Self::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
+
+        Ok(Self {
+            info: T::info(),
+            tx: UartTx::new_inner::<T>(clock.clone(), None),
+            rx: UartRx::new_inner::<T>(clock, None),
+        })
+    }
+
+    /// Create a new blocking UART in synchronous master mode, driving SCLK at `config.baudrate`
+    ///
+    /// Reads clock in one character at a time unless `config.continuous_clock` keeps SCLK
+    /// running.
+    pub fn new_sync_master<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        sclk: impl Peripheral<P = impl SclkPin<T>> + 'a,
+        mut config: Config,
+    ) -> Result<Self> {
+        config.operation = Operation::Synchronous;
+        config.sync_mode_master_select = SyncRole::Master;
+
+        Self::new_sync_inner(_inner, tx, rx, sclk, config)
+    }
+
+    /// Create a new blocking UART in synchronous slave mode, clocked by the SCLK of the peer
+    ///
+    /// `config.baudrate` and `config.continuous_clock` are not used.
+    pub fn new_sync_slave<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        sclk: impl Peripheral<P = impl SclkPin<T>> + 'a,
+        mut config: Config,
+    ) -> Result<Self> {
+        config.operation = Operation::Synchronous;
+        config.sync_mode_master_select = SyncRole::Slave;
+        config.continuous_clock = ContinuousClock::OnCharacter;
+
+        Self::new_sync_inner(_inner, tx, rx, sclk, config)
+    }
+
+    fn new_sync_inner<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        sclk: impl Peripheral<P = impl SclkPin<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(tx);
+        into_ref!(rx);
+        into_ref!(sclk);
+
+        tx.as_tx();
+        rx.as_rx();
+        sclk.as_sclk();
+
+        let mut tx = tx.map_into();
+        let mut rx = rx.map_into();
+
+        let clock = Self::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
 
         Ok(Self {
             info: T::info(),
-            tx: UartTx::new_inner::<T>(None),
-            rx: UartRx::new_inner::<T>(None),
+            tx: UartTx::new_inner::<T>(clock.clone(), None),
+            rx: UartRx::new_inner::<T>(clock, None),
         })
     }
 
@@ -503,6 +1046,12 @@ impl<'a> Uart<'a, Blocking> {
         self.rx.blocking_read(buf)
     }
 
+    /// Read a frame delimited by `timeout_chars` character times of line silence, see
+    /// [`UartRx::blocking_read_with_char_timeout`].
+    pub fn blocking_read_with_char_timeout(&mut self, buf: &mut [u8], timeout_chars: u8) -> Result<usize> {
+        self.rx.blocking_read_with_char_timeout(buf, timeout_chars)
+    }
+
     /// Read from UART Rx.
     pub fn read(&mut self, buf: &mut [u8]) -> Result<()> {
         self.rx.read(buf)
@@ -543,85 +1092,72 @@ impl<'a> UartTx<'a, Async> {
         tx.as_tx();
 
         let mut _tx = tx.map_into();
-        Uart::<Async>::init::<T>(Some(_tx.reborrow()), None, None, None, config)?;
+        let clock = Uart::<Async>::init::<T>(Some(_tx.reborrow()), None, None, None, config)?;
 
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
 
         let tx_dma = dma::Dma::reserve_channel(tx_dma);
 
-        Ok(Self::new_inner::<T>(tx_dma))
+        Ok(Self::new_inner::<T>(clock, tx_dma))
     }
 
     /// Transmit the provided buffer asynchronously.
+    ///
+    /// If the future is dropped before it completes, the bytes already moved into the TX FIFO
+    /// are still sent and [`Self::last_transfer_progress`] reports how many there were.
     pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
         let regs = self.info.regs;
+        let dma = self._tx_dma.as_ref().unwrap();
+        let progress = &mut self.last_progress;
+        let mut written = 0;
 
-        for chunk in buf.chunks(1024) {
-            regs.fifocfg().modify(|_, w| w.dmatx().enabled());
-
-            let transfer = Transfer::new_write(
-                self._tx_dma.as_ref().unwrap(),
-                chunk,
-                regs.fifowr().as_ptr() as *mut u8,
-                Default::default(),
-            );
-
-            let res = select(
-                transfer,
-                poll_fn(|cx| {
-                    UART_WAKERS[self.info.index].register(cx.waker());
-
-                    self.info.regs.intenset().write(|w| {
-                        w.framerren()
-                            .set_bit()
-                            .parityerren()
-                            .set_bit()
-                            .rxnoiseen()
-                            .set_bit()
-                            .aberren()
-                            .set_bit()
-                    });
+        *progress = None;
 
-                    let stat = self.info.regs.stat().read();
-
-                    self.info.regs.stat().write(|w| {
-                        w.framerrint()
-                            .clear_bit_by_one()
-                            .parityerrint()
-                            .clear_bit_by_one()
-                            .rxnoiseint()
-                            .clear_bit_by_one()
-                            .aberr()
-                            .clear_bit_by_one()
-                    });
+        for chunk in buf.chunks(1024) {
+            modify_fifocfg(regs, |_, w| w.dmatx().enabled());
+
+            let mut transfer = Transfer::new_write(dma, chunk, regs.fifowr().as_ptr() as *mut u8, Default::default())?;
+
+            // Runs before the transfer is dropped, which aborts the channel and loses its count
+            let progress = &mut *progress;
+            let on_drop = OnDrop::new(move || {
+                dma.pause();
+                *progress = Some(TransferProgress {
+                    transferred: written + chunk.len() - dma.remaining(),
+                    fifo_level: regs.fifostat().read().txlvl().bits() as usize,
+                });
+                modify_fifocfg(regs, |_, w| w.dmatx().disabled());
+            });
 
-                    if stat.framerrint().bit_is_set() {
-                        Poll::Ready(Err(Error::Framing))
-                    } else if stat.parityerrint().bit_is_set() {
-                        Poll::Ready(Err(Error::Parity))
-                    } else if stat.rxnoiseint().bit_is_set() {
-                        Poll::Ready(Err(Error::Noise))
-                    } else if stat.aberr().bit_is_set() {
-                        Poll::Ready(Err(Error::Fail))
-                    } else {
-                        Poll::Pending
-                    }
-                }),
-            )
-            .await;
+            // Line errors are receive conditions, they are reported by the reader
+            let res = (&mut transfer).await;
+            on_drop.defuse();
 
-            regs.fifocfg().modify(|_, w| w.dmatx().disabled());
+            modify_fifocfg(regs, |_, w| w.dmatx().disabled());
+            res?;
 
-            match res {
-                Either::First(()) | Either::Second(Ok(())) => (),
-                Either::Second(e) => return e,
-            }
+            written += chunk.len();
         }
 
         Ok(())
     }
 
+    /// Progress of the last [`Self::write`], if it was cancelled before completing
+    ///
+    /// `None` while a write has not been cancelled since the last one started.
+    pub fn last_transfer_progress(&self) -> Option<TransferProgress> {
+        self.last_progress
+    }
+
+    /// Wait until the TX FIFO and the shifter are empty, without busy-waiting.
+    ///
+    /// Use before [`UartTx::suspend`] so it does not have to spin.
+    pub async fn drain(&mut self) {
+        // Waiting for the idle flag cannot fail
+        let _ = self.flush().await;
+    }
+
     /// Flush UART TX asynchronously.
     pub async fn flush(&mut self) -> Result<()> {
         self.wait_on(
@@ -650,7 +1186,7 @@ impl<'a> UartTx<'a, Async> {
             let r = f(self);
 
             if r.is_pending() {
-                UART_WAKERS[self.info.index].register(cx.waker());
+                UART_STATE[self.info.index].tx.register(cx.waker());
                 g(self);
             }
 
@@ -660,98 +1196,351 @@ impl<'a> UartTx<'a, Async> {
     }
 }
 
-impl<'a> UartRx<'a, Async> {
-    /// Create a new DMA enabled UART which can only receive data
-    pub fn new_async<T: Instance>(
-        _inner: impl Peripheral<P = T> + 'a,
-        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
-        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
-        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
-        config: Config,
-    ) -> Result<Self> {
-        into_ref!(_inner);
-        into_ref!(rx);
-        rx.as_rx();
+/// Descriptor slots of a [`UartTxDmaStream`]
+const TX_STREAM_SLOTS: usize = 4;
+
+/// Largest chunk a single DMA descriptor can move
+const TX_STREAM_MAX_SLOT_LEN: usize = 1024;
+
+static mut TX_STREAM_DESCRIPTORS: [[ChannelDescriptor; TX_STREAM_SLOTS]; UART_COUNT] =
+    [[ChannelDescriptor::EMPTY; TX_STREAM_SLOTS]; UART_COUNT];
+
+/// Continuous UART TX DMA stream.
+///
+/// Keeps TX DMA requests enabled and queues writes as linked DMA descriptors, so
+/// back-to-back writes go out without gaps between them. Data is copied into a
+/// staging buffer split into equal slots, one per descriptor.
+pub struct UartTxDmaStream<'a> {
+    tx: UartTx<'a, Async>,
+    staging: &'a mut [u8],
+    slot_len: usize,
+    descriptors: &'a mut [ChannelDescriptor; TX_STREAM_SLOTS],
+    start_count: u32,
+    queued: u32,
+}
 
-        let mut _rx = rx.map_into();
-        Uart::<Async>::init::<T>(None, Some(_rx.reborrow()), None, None, config)?;
+impl<'a> UartTxDmaStream<'a> {
+    /// Create a TX stream using `staging` to hold queued data.
+    ///
+    /// `staging` must be in SRAM, each of its four slots holds up to 1024 bytes.
+    pub fn new(tx: UartTx<'a, Async>, staging: &'a mut [u8]) -> Result<Self> {
+        let slot_len = (staging.len() / TX_STREAM_SLOTS).min(TX_STREAM_MAX_SLOT_LEN);
+        if slot_len == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        dma::buffer::check_source(staging.as_ptr(), staging.len(), Width::Bit8)?;
 
-        T::Interrupt::unpend();
-        unsafe { T::Interrupt::enable() };
+        // SAFETY: the descriptors of a UART are only used by the stream owning its transmitter
+        let descriptors = unsafe { &mut (*addr_of_mut!(TX_STREAM_DESCRIPTORS))[tx.info.index] };
+        descriptors.fill(ChannelDescriptor::EMPTY);
 
-        let rx_dma = dma::Dma::reserve_channel(rx_dma);
+        let start_count = tx._tx_dma.as_ref().unwrap().completion_count();
+
+        modify_fifocfg(tx.info.regs, |_, w| w.dmatx().enabled());
 
-        Ok(Self::new_inner::<T>(rx_dma))
+        Ok(Self {
+            tx,
+            staging,
+            slot_len,
+            descriptors,
+            start_count,
+            queued: 0,
+        })
     }
 
-    /// Read from UART RX asynchronously.
-    pub async fn read(&mut self, buf: &mut [u8]) -> Result<()> {
-        let regs = self.info.regs;
+    fn dma_ch(&self) -> &Channel<'a> {
+        self.tx._tx_dma.as_ref().unwrap()
+    }
 
-        for chunk in buf.chunks_mut(1024) {
-            regs.fifocfg().modify(|_, w| w.dmarx().enabled());
+    fn completed(&self) -> u32 {
+        self.dma_ch().completion_count().wrapping_sub(self.start_count)
+    }
 
-            let transfer = Transfer::new_read(
-                self._rx_dma.as_ref().unwrap(),
-                regs.fiford().as_ptr() as *mut u8,
-                chunk,
-                Default::default(),
-            );
+    /// Queue `buf` for transmission, waiting for free descriptor slots as needed.
+    ///
+    /// Returns once all of `buf` is queued, not when it has been sent.
+    pub async fn write_queued(&mut self, buf: &[u8]) -> Result<()> {
+        for chunk in buf.chunks(self.slot_len) {
+            // One slot always stays invalid, that is where the channel stops when it runs dry
+            poll_fn(|cx| {
+                self.dma_ch().get_waker().register(cx.waker());
 
-            let res = select(
-                transfer,
-                poll_fn(|cx| {
-                    UART_WAKERS[self.info.index].register(cx.waker());
+                if self.queued.wrapping_sub(self.completed()) < (TX_STREAM_SLOTS - 1) as u32 {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
 
-                    self.info.regs.intenset().write(|w| {
-                        w.framerren()
-                            .set_bit()
-                            .parityerren()
-                            .set_bit()
-                            .rxnoiseen()
-                            .set_bit()
-                            .aberren()
+            self.queue(chunk);
+        }
+
+        Ok(())
+    }
+
+    fn queue(&mut self, chunk: &[u8]) {
+        let slot = self.queued as usize % TX_STREAM_SLOTS;
+        let next = (slot + 1) % TX_STREAM_SLOTS;
+
+        // Completed long ago, invalidate so the channel stops there until it is queued
+        self.descriptors[next].reserved = 0;
+
+        let data = &mut self.staging[slot * self.slot_len..slot * self.slot_len + chunk.len()];
+        data.copy_from_slice(chunk);
+
+        // Interrupt A and B alternate so the handler can tell consecutive completions apart
+        let xfercfg = XferCfg::new(Width::Bit8, chunk.len()).reload().src_increment();
+        let xfercfg = if self.queued % 2 == 1 {
+            xfercfg.interrupt_b()
+        } else {
+            xfercfg.interrupt_a()
+        };
+
+        self.descriptors[slot] = ChannelDescriptor {
+            reserved: 0,
+            src_data_end_addr: data.as_ptr() as u32 + chunk.len() as u32 - 1,
+            dst_data_end_addr: self.tx.info.regs.fifowr().as_ptr() as u32,
+            nxt_desc_link_addr: &self.descriptors[next] as *const _ as u32,
+        };
+
+        // Only publish the configuration once the rest of the descriptor is in place
+        compiler_fence(Ordering::SeqCst);
+        // SAFETY: the descriptor is valid for writes, volatile since the DMA controller reads it
+        unsafe { core::ptr::write_volatile(&mut self.descriptors[slot].reserved, xfercfg.bits()) };
+        cortex_m::asm::dsb();
+
+        self.queued = self.queued.wrapping_add(1);
+
+        // The channel may have stopped on this slot before it was valid, or have been idle
+        let ch = self.dma_ch();
+        if !ch.is_active() || !ch.is_config_valid() {
+            ch.start_linked(&self.descriptors[slot]);
+        }
+    }
+
+    /// Wait until everything queued has been sent
+    pub async fn flush(&mut self) -> Result<()> {
+        poll_fn(|cx| {
+            self.dma_ch().get_waker().register(cx.waker());
+
+            if self.completed() == self.queued {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        self.tx.flush().await
+    }
+}
+
+impl Drop for UartTxDmaStream<'_> {
+    fn drop(&mut self) {
+        self.dma_ch().abort();
+        modify_fifocfg(self.tx.info.regs, |_, w| w.dmatx().disabled());
+    }
+}
+
+impl<'a> UartRx<'a, Async> {
+    /// Create a new DMA enabled UART which can only receive data
+    pub fn new_async<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
+        config: Config,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(rx);
+        rx.as_rx();
+
+        let mut _rx = rx.map_into();
+        let clock = Uart::<Async>::init::<T>(None, Some(_rx.reborrow()), None, None, config)?;
+
+        T::Interrupt::unpend();
+        unsafe { T::Interrupt::enable() };
+
+        let rx_dma = dma::Dma::reserve_channel(rx_dma);
+
+        Ok(Self::new_inner::<T>(clock, rx_dma))
+    }
+
+    /// Set how [`Self::read`] handles framing, parity and noise errors
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.error_policy = policy;
+    }
+
+    /// Line errors counted by [`Self::read`]
+    ///
+    /// Errors of the same kind that occur before the driver gets to handle the first one are
+    /// counted once.
+    pub fn error_stats(&self) -> ErrorStats {
+        self.error_stats
+    }
+
+    /// Reset the line error counters
+    pub fn reset_error_stats(&mut self) {
+        self.error_stats = ErrorStats::default();
+    }
+
+    /// Read from UART RX asynchronously.
+    ///
+    /// Line errors end the read according to the [`ErrorPolicy`], an RX FIFO overrun always does.
+    ///
+    /// If the future is dropped before it completes, [`Self::last_transfer_progress`] reports how
+    /// much of the buffer was filled.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<()> {
+        let regs = self.info.regs;
+        let policy = self.error_policy;
+        let stats = &mut self.error_stats;
+        let dma = self._rx_dma.as_ref().unwrap();
+        let progress = &mut self.last_progress;
+        let mut read = 0;
+
+        *progress = None;
+
+        for chunk in buf.chunks_mut(1024) {
+            modify_fifocfg(regs, |_, w| w.dmarx().enabled());
+
+            let len = chunk.len();
+            let mut transfer = Transfer::new_read(dma, regs.fiford().as_ptr() as *mut u8, chunk, Default::default())?;
+
+            // Runs before the transfer is dropped, which aborts the channel and loses its count
+            let progress = &mut *progress;
+            let on_drop = OnDrop::new(move || {
+                dma.pause();
+                *progress = Some(TransferProgress {
+                    transferred: read + len - dma.remaining(),
+                    fifo_level: regs.fifostat().read().rxlvl().bits() as usize,
+                });
+                modify_fifocfg(regs, |_, w| w.dmarx().disabled());
+            });
+
+            let res = select(
+                &mut transfer,
+                poll_fn(|cx| {
+                    UART_STATE[self.info.index].rx.register(cx.waker());
+
+                    regs.intenset().write(|w| {
+                        w.framerren()
+                            .set_bit()
+                            .parityerren()
+                            .set_bit()
+                            .rxnoiseen()
+                            .set_bit()
+                            .aberren()
                             .set_bit()
                     });
+                    regs.fifointenset().write(|w| w.rxerr().set_bit());
 
-                    let stat = self.info.regs.stat().read();
-
-                    self.info.regs.stat().write(|w| {
-                        w.framerrint()
-                            .clear_bit_by_one()
-                            .parityerrint()
-                            .clear_bit_by_one()
-                            .rxnoiseint()
-                            .clear_bit_by_one()
-                            .aberr()
-                            .clear_bit_by_one()
-                    });
+                    let errors = take_rx_errors(regs, &UART_STATE[self.info.index]);
 
-                    if stat.framerrint().bit_is_set() {
-                        Poll::Ready(Err(Error::Framing))
-                    } else if stat.parityerrint().bit_is_set() {
-                        Poll::Ready(Err(Error::Parity))
-                    } else if stat.rxnoiseint().bit_is_set() {
-                        Poll::Ready(Err(Error::Noise))
-                    } else if stat.aberr().bit_is_set() {
-                        Poll::Ready(Err(Error::Fail))
-                    } else {
-                        Poll::Pending
+                    if regs.fifostat().read().rxerr().bit_is_set() {
+                        modify_fifocfg(regs, |_, w| w.emptyrx().set_bit());
+                        regs.fifostat().write(|w| w.rxerr().set_bit());
+                        stats.overrun += 1;
+                        return Poll::Ready(Err(Error::Overrun));
+                    }
+
+                    if errors.autobaud {
+                        return Poll::Ready(Err(Error::Fail));
+                    }
+
+                    let mut error = None;
+                    if errors.noise {
+                        stats.noise += 1;
+                        error = Some(Error::Noise);
+                    }
+                    if errors.parity {
+                        stats.parity += 1;
+                        error = Some(Error::Parity);
+                    }
+                    if errors.framing {
+                        stats.framing += 1;
+                        error = Some(Error::Framing);
+                    }
+
+                    match (error, policy) {
+                        (Some(e), ErrorPolicy::Abort) => Poll::Ready(Err(e)),
+                        _ => Poll::Pending,
                     }
                 }),
             )
             .await;
+            on_drop.defuse();
 
-            regs.fifocfg().modify(|_, w| w.dmarx().disabled());
+            modify_fifocfg(regs, |_, w| w.dmarx().disabled());
+            regs.intenclr().write(|w| {
+                w.framerrclr()
+                    .set_bit()
+                    .parityerrclr()
+                    .set_bit()
+                    .rxnoiseclr()
+                    .set_bit()
+                    .aberrclr()
+                    .set_bit()
+            });
+            regs.fifointenclr().write(|w| w.rxerr().set_bit());
 
             match res {
-                Either::First(()) | Either::Second(Ok(())) => (),
+                Either::First(Ok(())) | Either::Second(Ok(())) => (),
+                Either::First(Err(e)) => return Err(e.into()),
                 Either::Second(e) => return e,
             }
+
+            read += len;
         }
 
         Ok(())
     }
+
+    /// Read a frame delimited by line silence, e.g. a Modbus RTU frame, asynchronously.
+    ///
+    /// Async counterpart of [`UartRx::blocking_read_with_char_timeout`], waits for the first byte
+    /// and returns the number of bytes received once the line has been idle for `timeout_chars`
+    /// character times, or once `buf` is full. The gap is timed with embassy-time in windows of
+    /// the gap length, so it is detected after one to two gap lengths rounded up to the tick.
+    #[cfg(feature = "time")]
+    pub async fn read_with_char_timeout(&mut self, buf: &mut [u8], timeout_chars: u8) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let gap = embassy_time::Duration::from_micros(u64::from(self.info.char_gap_us(timeout_chars)));
+
+        self.read(&mut buf[..1]).await?;
+        let mut received = 1;
+
+        while received < buf.len() {
+            let res = select(self.read(&mut buf[received..]), embassy_time::Timer::after(gap)).await;
+            if let Either::First(res) = res {
+                return res.map(|()| buf.len());
+            }
+
+            // The cancelled read reports what it stored, bytes still in the FIFO are picked up here
+            let mut window = self.last_progress.map_or(0, |p| p.transferred);
+            while received + window < buf.len() && self.info.regs.fifostat().read().rxnotempty().bit_is_set() {
+                buf[received + window] = self.info.regs.fiford().read().rxdata().bits() as u8;
+                window += 1;
+            }
+
+            if window == 0 {
+                break;
+            }
+            received += window;
+        }
+
+        Ok(received)
+    }
+
+    /// Progress of the last [`Self::read`], if it was cancelled before completing
+    ///
+    /// `None` while a read has not been cancelled since the last one started.
+    pub fn last_transfer_progress(&self) -> Option<TransferProgress> {
+        self.last_progress
+    }
 }
 
 impl<'a> Uart<'a, Async> {
@@ -778,12 +1567,12 @@ impl<'a> Uart<'a, Async> {
         let tx_dma = dma::Dma::reserve_channel(tx_dma);
         let rx_dma = dma::Dma::reserve_channel(rx_dma);
 
-        Self::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
+        let clock = Self::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
 
         Ok(Self {
             info: T::info(),
-            tx: UartTx::new_inner::<T>(tx_dma),
-            rx: UartRx::new_inner::<T>(rx_dma),
+            tx: UartTx::new_inner::<T>(clock.clone(), tx_dma),
+            rx: UartRx::new_inner::<T>(clock, rx_dma),
         })
     }
 
@@ -818,7 +1607,7 @@ impl<'a> Uart<'a, Async> {
         let tx_dma = dma::Dma::reserve_channel(tx_dma);
         let rx_dma = dma::Dma::reserve_channel(rx_dma);
 
-        Self::init::<T>(
+        let clock = Self::init::<T>(
             Some(tx.reborrow()),
             Some(rx.reborrow()),
             Some(rts.reborrow()),
@@ -828,8 +1617,8 @@ impl<'a> Uart<'a, Async> {
 
         Ok(Self {
             info: T::info(),
-            tx: UartTx::new_inner::<T>(tx_dma),
-            rx: UartRx::new_inner::<T>(rx_dma),
+            tx: UartTx::new_inner::<T>(clock.clone(), tx_dma),
+            rx: UartRx::new_inner::<T>(clock, rx_dma),
         })
     }
 
@@ -838,15 +1627,285 @@ impl<'a> Uart<'a, Async> {
         self.rx.read(buf).await
     }
 
+    /// Read a frame delimited by `timeout_chars` character times of line silence, see
+    /// [`UartRx::read_with_char_timeout`].
+    #[cfg(feature = "time")]
+    pub async fn read_with_char_timeout(&mut self, buf: &mut [u8], timeout_chars: u8) -> Result<usize> {
+        self.rx.read_with_char_timeout(buf, timeout_chars).await
+    }
+
+    /// Set how [`Self::read`] handles framing, parity and noise errors
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.rx.set_error_policy(policy)
+    }
+
+    /// Line errors counted by [`Self::read`]
+    pub fn error_stats(&self) -> ErrorStats {
+        self.rx.error_stats()
+    }
+
+    /// Reset the line error counters
+    pub fn reset_error_stats(&mut self) {
+        self.rx.reset_error_stats()
+    }
+
     /// Transmit the provided buffer.
     pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
         self.tx.write(buf).await
     }
 
+    /// Progress of the last cancelled [`Self::write`], see [`UartTx::last_transfer_progress`]
+    pub fn last_write_progress(&self) -> Option<TransferProgress> {
+        self.tx.last_transfer_progress()
+    }
+
+    /// Progress of the last cancelled [`Self::read`], see [`UartRx::last_transfer_progress`]
+    pub fn last_read_progress(&self) -> Option<TransferProgress> {
+        self.rx.last_transfer_progress()
+    }
+
     /// Flush UART TX.
     pub async fn flush(&mut self) -> Result<()> {
         self.tx.flush().await
     }
+
+    /// Wait until all written data is on the wire, see [`UartTx::drain`].
+    pub async fn drain(&mut self) {
+        self.tx.drain().await
+    }
+}
+
+/// Single-wire half-duplex settings, see [`HalfDuplexUart`]
+#[derive(Clone, Copy, Default)]
+pub struct HalfDuplexConfig {
+    /// Time the line is left to settle after the last stop bit before the receiver listens
+    /// again, in nanoseconds
+    pub turnaround_ns: u32,
+    /// Keep the receiver on while transmitting and compare the echo with the written data
+    ///
+    /// A mismatch means another device drove the line at the same time, the write stops and
+    /// fails with [`Error::CollisionDetected`]. Data received but not read before the write
+    /// shows up as a mismatch as well.
+    pub collision_detection: bool,
+}
+
+/// Single-wire half-duplex UART
+///
+/// The flexcomm pin mux has no pad that carries both the TX and the RX function of a USART,
+/// so the TX and RX pads are tied together on the board and form the single wire. Both are
+/// set up open-drain with the internal pull-up, any device on the wire can pull it low.
+///
+/// Unless collision detection is enabled, the receiver is turned off while transmitting so
+/// our own data is not echoed back, and turned on again by `flush` once the last stop bit and
+/// the turnaround time have passed. Reads flush a pending transmission first.
+pub struct HalfDuplexUart<'a, M: Mode> {
+    info: Info,
+    tx: UartTx<'a, M>,
+    rx: UartRx<'a, M>,
+    config: HalfDuplexConfig,
+    /// Written data has not been flushed yet
+    turnaround_pending: bool,
+}
+
+impl<'a, M: Mode> HalfDuplexUart<'a, M> {
+    fn new_inner<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        tx_dma: Option<Channel<'a>>,
+        rx_dma: Option<Channel<'a>>,
+        config: Config,
+        half_duplex: HalfDuplexConfig,
+    ) -> Result<Self> {
+        into_ref!(_inner);
+        into_ref!(tx);
+        into_ref!(rx);
+
+        tx.as_tx();
+        rx.as_rx();
+
+        let mut tx = tx.map_into();
+        let mut rx = rx.map_into();
+
+        tx.set_drive_mode(DriveMode::OpenDrain).set_pull(Pull::Up);
+        rx.set_pull(Pull::Up);
+
+        let clock = Uart::<M>::init::<T>(Some(tx.reborrow()), Some(rx.reborrow()), None, None, config)?;
+
+        Ok(Self {
+            info: T::info(),
+            tx: UartTx::new_inner::<T>(clock.clone(), tx_dma),
+            rx: UartRx::new_inner::<T>(clock, rx_dma),
+            config: half_duplex,
+            turnaround_pending: false,
+        })
+    }
+
+    /// Stop listening before transmitting, unless the echo is checked for collisions
+    fn start_transmit(&mut self) {
+        if !self.config.collision_detection {
+            modify_fifocfg(self.info.regs, |_, w| w.enablerx().disabled());
+        }
+
+        self.turnaround_pending = true;
+    }
+
+    /// Listen again after the turnaround time, dropping anything the receiver saw meanwhile
+    fn end_transmit(&mut self) {
+        let regs = self.info.regs;
+
+        if !self.config.collision_detection {
+            modify_fifocfg(regs, |_, w| w.emptyrx().set_bit().enablerx().enabled());
+            regs.fifostat().write(|w| w.rxerr().set_bit());
+            take_rx_errors(regs, &UART_STATE[self.info.index]);
+        }
+
+        self.turnaround_pending = false;
+    }
+
+    /// Set how reads handle framing, parity and noise errors
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.rx.set_error_policy(policy)
+    }
+}
+
+impl<'a> HalfDuplexUart<'a, Blocking> {
+    /// Create a new blocking single-wire UART on the TX and RX pads tied together
+    pub fn new_blocking<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        config: Config,
+        half_duplex: HalfDuplexConfig,
+    ) -> Result<Self> {
+        Self::new_inner(_inner, tx, rx, None, None, config, half_duplex)
+    }
+
+    /// Read from UART RX.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<()> {
+        if self.turnaround_pending {
+            self.blocking_flush()?;
+        }
+
+        self.rx.read(buf)
+    }
+
+    /// Read from UART RX blocking execution until done.
+    pub fn blocking_read(&mut self, buf: &mut [u8]) -> Result<()> {
+        if self.turnaround_pending {
+            self.blocking_flush()?;
+        }
+
+        self.rx.blocking_read(buf)
+    }
+
+    /// Transmit the provided buffer blocking execution until done.
+    ///
+    /// With collision detection every byte is checked against its echo before the next one
+    /// is sent.
+    pub fn blocking_write(&mut self, buf: &[u8]) -> Result<()> {
+        self.start_transmit();
+
+        if !self.config.collision_detection {
+            return self.tx.blocking_write(buf);
+        }
+
+        for &byte in buf {
+            self.tx.blocking_write_byte(byte)?;
+
+            if self.rx.blocking_read_byte() != Ok(byte) {
+                self.blocking_flush()?;
+                return Err(Error::CollisionDetected);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the transmission to complete and the turnaround time to pass, then listen
+    /// again.
+    pub fn blocking_flush(&mut self) -> Result<()> {
+        self.tx.blocking_flush()?;
+
+        if self.turnaround_pending {
+            embedded_hal_1::delay::DelayNs::delay_ns(&mut Delay, self.config.turnaround_ns);
+            self.end_transmit();
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> HalfDuplexUart<'a, Async> {
+    /// Create a new DMA enabled single-wire UART on the TX and RX pads tied together
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_async<T: Instance>(
+        _inner: impl Peripheral<P = T> + 'a,
+        tx: impl Peripheral<P = impl TxPin<T>> + 'a,
+        rx: impl Peripheral<P = impl RxPin<T>> + 'a,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
+        tx_dma: impl Peripheral<P = impl TxDma<T>> + 'a,
+        rx_dma: impl Peripheral<P = impl RxDma<T>> + 'a,
+        config: Config,
+        half_duplex: HalfDuplexConfig,
+    ) -> Result<Self> {
+        let tx_dma = dma::Dma::reserve_channel(tx_dma);
+        let rx_dma = dma::Dma::reserve_channel(rx_dma);
+
+        let this = Self::new_inner(_inner, tx, rx, tx_dma, rx_dma, config, half_duplex)?;
+
+        T::Interrupt::unpend();
+        unsafe { T::Interrupt::enable() };
+
+        Ok(this)
+    }
+
+    /// Read from UART RX.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<()> {
+        if self.turnaround_pending {
+            self.flush().await?;
+        }
+
+        self.rx.read(buf).await
+    }
+
+    /// Transmit the provided buffer.
+    ///
+    /// With collision detection the echo is received alongside, one FIFO worth at a time.
+    pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        self.start_transmit();
+
+        if !self.config.collision_detection {
+            return self.tx.write(buf).await;
+        }
+
+        let mut echo = [0u8; FIFO_DEPTH];
+        for chunk in buf.chunks(FIFO_DEPTH) {
+            let echo = &mut echo[..chunk.len()];
+            let (tx_res, rx_res) = join(self.tx.write(chunk), self.rx.read(echo)).await;
+            tx_res?;
+
+            if rx_res.is_err() || echo != chunk {
+                self.flush().await?;
+                return Err(Error::CollisionDetected);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the transmission to complete and the turnaround time to pass, then listen
+    /// again.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.tx.flush().await?;
+
+        if self.turnaround_pending {
+            embedded_hal_async::delay::DelayNs::delay_ns(&mut Delay, self.config.turnaround_ns).await;
+            self.end_transmit();
+        }
+
+        Ok(())
+    }
 }
 
 impl embedded_hal_02::serial::Read<u8> for UartRx<'_, Blocking> {
@@ -1031,6 +2090,18 @@ impl embedded_io::Write for UartTx<'_, Blocking> {
     }
 }
 
+impl embedded_io::ReadReady for UartRx<'_, Blocking> {
+    fn read_ready(&mut self) -> core::result::Result<bool, Self::Error> {
+        Ok(self.available() > 0)
+    }
+}
+
+impl embedded_io::WriteReady for UartTx<'_, Blocking> {
+    fn write_ready(&mut self) -> core::result::Result<bool, Self::Error> {
+        Ok(self.space() > 0)
+    }
+}
+
 impl embedded_io::Read for Uart<'_, Blocking> {
     fn read(&mut self, buf: &mut [u8]) -> core::result::Result<usize, Self::Error> {
         embedded_io::Read::read(&mut self.rx, buf)
@@ -1047,6 +2118,18 @@ impl embedded_io::Write for Uart<'_, Blocking> {
     }
 }
 
+impl embedded_io::ReadReady for Uart<'_, Blocking> {
+    fn read_ready(&mut self) -> core::result::Result<bool, Self::Error> {
+        embedded_io::ReadReady::read_ready(&mut self.rx)
+    }
+}
+
+impl embedded_io::WriteReady for Uart<'_, Blocking> {
+    fn write_ready(&mut self) -> core::result::Result<bool, Self::Error> {
+        embedded_io::WriteReady::write_ready(&mut self.tx)
+    }
+}
+
 impl embedded_io_async::ErrorType for UartRx<'_, Async> {
     type Error = Error;
 }
@@ -1091,11 +2174,219 @@ impl embedded_io_async::Write for Uart<'_, Async> {
     }
 }
 
+/// Read-modify-write FIFOCFG, which holds the enable and DMA bits of both directions
+///
+/// The halves of a split UART may run at different priorities, the critical section keeps one
+/// half from writing back a stale copy of the other half's bits.
+fn modify_fifocfg<F>(regs: &crate::pac::usart0::RegisterBlock, f: F)
+where
+    F: for<'w> FnOnce(
+        &crate::pac::usart0::fifocfg::R,
+        &'w mut crate::pac::usart0::fifocfg::W,
+    ) -> &'w mut crate::pac::usart0::fifocfg::W,
+{
+    critical_section::with(|_| regs.fifocfg().modify(f));
+}
+
+/// Line error flags of STAT, all owned by the receiver
+#[derive(Clone, Copy)]
+struct RxErrors {
+    framing: bool,
+    parity: bool,
+    noise: bool,
+    autobaud: bool,
+}
+
+impl RxErrors {
+    /// Read the line error flags set in STAT and clear exactly those, a TX side flag is never
+    /// consumed here
+    fn take(regs: &crate::pac::usart0::RegisterBlock) -> Self {
+        let stat = regs.stat().read();
+        let errors = Self {
+            framing: stat.framerrint().bit_is_set(),
+            parity: stat.parityerrint().bit_is_set(),
+            noise: stat.rxnoiseint().bit_is_set(),
+            autobaud: stat.aberr().bit_is_set(),
+        };
+
+        regs.stat().write(|w| {
+            if errors.framing {
+                w.framerrint().clear_bit_by_one();
+            }
+            if errors.parity {
+                w.parityerrint().clear_bit_by_one();
+            }
+            if errors.noise {
+                w.rxnoiseint().clear_bit_by_one();
+            }
+            if errors.autobaud {
+                w.aberr().clear_bit_by_one();
+            }
+            w
+        });
+
+        errors
+    }
+
+    /// Packed form latched in [`UartState::rx_errors`]
+    fn to_latch(self) -> u8 {
+        u8::from(self.framing) | u8::from(self.parity) << 1 | u8::from(self.noise) << 2 | u8::from(self.autobaud) << 3
+    }
+
+    fn from_latch(latch: u8) -> Self {
+        Self {
+            framing: latch & 1 << 0 != 0,
+            parity: latch & 1 << 1 != 0,
+            noise: latch & 1 << 2 != 0,
+            autobaud: latch & 1 << 3 != 0,
+        }
+    }
+}
+
+/// Take the line error flags the receiver has not seen yet
+///
+/// Merges the flags latched by the interrupt handler with those still set in STAT, which are
+/// cleared.
+fn take_rx_errors(regs: &crate::pac::usart0::RegisterBlock, state: &UartState) -> RxErrors {
+    let pending = RxErrors::take(regs);
+    RxErrors::from_latch(state.rx_errors.swap(0, Ordering::Relaxed) | pending.to_latch())
+}
+
 struct Info {
     regs: &'static crate::pac::usart0::RegisterBlock,
     index: usize,
+    into_usart: fn(),
+    clock_hz: fn() -> u32,
 }
 
+impl Info {
+    /// Disable the USART once neither direction is enabled
+    ///
+    /// Checked and applied in one critical section, like [`Registers::restore`], so the other half
+    /// cannot re-enable its direction in between and be left with a disabled USART.
+    fn disable_if_idle(&self) {
+        critical_section::with(|_| {
+            let fifocfg = self.regs.fifocfg().read();
+            if fifocfg.enabletx().is_disabled() && fifocfg.enablerx().is_disabled() {
+                self.regs.cfg().modify(|_, w| w.enable().disabled());
+            }
+        });
+    }
+
+    /// Length of `chars` characters at the programmed baudrate and frame format, in microseconds
+    fn char_gap_us(&self, chars: u8) -> u32 {
+        let cfg = self.regs.cfg().read();
+        let data_bits = match cfg.datalen().variant() {
+            Some(Datalen::Bit7) => 7,
+            Some(Datalen::Bit9) => 9,
+            _ => 8,
+        };
+        let parity_bits = u32::from(!cfg.paritysel().is_no_parity());
+        let stop_bits = match cfg.stoplen().variant() {
+            Stoplen::Bit1 => 1,
+            Stoplen::Bits2 => 2,
+        };
+
+        let osr = self.regs.osr().read().bits() + 1;
+        let brg = self.regs.brg().read().bits() + 1;
+        let baudrate = (self.clock_hz)() / (osr * brg);
+
+        char_gap_us(baudrate, 1 + data_bits + parity_bits + stop_bits, chars)
+    }
+}
+
+/// Direction of a UART half
+#[derive(Clone, Copy)]
+enum Half {
+    Tx,
+    Rx,
+}
+
+/// Configuration of a suspended UART half, see [`UartTx::suspend`]
+#[derive(Clone, Copy)]
+struct Registers {
+    half: Half,
+    cfg: u32,
+    ctl: u32,
+    brg: u32,
+    osr: u32,
+    /// FIFO enable of this half
+    fifo_enabled: bool,
+    /// DMA request enable of this half
+    dma_enabled: bool,
+}
+
+impl Registers {
+    fn save(info: &Info, half: Half) -> Self {
+        let regs = info.regs;
+        let fifocfg = regs.fifocfg().read();
+
+        let (fifo_enabled, dma_enabled) = match half {
+            Half::Tx => (fifocfg.enabletx().bit_is_set(), fifocfg.dmatx().bit_is_set()),
+            Half::Rx => (fifocfg.enablerx().bit_is_set(), fifocfg.dmarx().bit_is_set()),
+        };
+
+        Self {
+            half,
+            cfg: regs.cfg().read().bits(),
+            ctl: regs.ctl().read().bits(),
+            brg: regs.brg().read().bits(),
+            osr: regs.osr().read().bits(),
+            fifo_enabled,
+            dma_enabled,
+        }
+    }
+
+    /// Restore the shared configuration unless the other half kept the USART running, then the
+    /// FIFO and DMA enables of this half, after emptying its FIFO
+    fn restore(&self, info: &Info) {
+        let regs = info.regs;
+
+        // The other half's Info::disable_if_idle must not run between the ENABLE check and the
+        // FIFOCFG update
+        critical_section::with(|_| {
+            // Also the case after the flexcomm went through reset while everything was suspended
+            if regs.cfg().read().enable().is_disabled() {
+                (info.into_usart)();
+
+                // SAFETY: unsafe only used for .bits(), writes back values read from the registers
+                unsafe {
+                    regs.brg().write(|w| w.bits(self.brg));
+                    regs.osr().write(|w| w.bits(self.osr));
+                    regs.ctl().write(|w| w.bits(self.ctl));
+                    regs.cfg().write(|w| w.bits(self.cfg).enable().disabled());
+                    regs.cfg().write(|w| w.bits(self.cfg));
+                }
+            }
+
+            // Only this half's FIFO bits are changed
+            match self.half {
+                Half::Tx => modify_fifocfg(regs, |_, w| {
+                    w.enabletx()
+                        .bit(self.fifo_enabled)
+                        .dmatx()
+                        .bit(self.dma_enabled)
+                        .emptytx()
+                        .set_bit()
+                }),
+                Half::Rx => modify_fifocfg(regs, |_, w| {
+                    w.enablerx()
+                        .bit(self.fifo_enabled)
+                        .dmarx()
+                        .bit(self.dma_enabled)
+                        .emptyrx()
+                        .set_bit()
+                }),
+            }
+        });
+    }
+}
+
+// SAFETY: `regs` points at MMIO that exists for the whole program. TX and RX halves only touch
+// their own FIFO, interrupt and DMA enables, and FIFOCFG, which holds the bits of both, is only
+// changed through `modify_fifocfg`. Each half may live on a different executor.
+unsafe impl Send for Info {}
+
 trait SealedInstance {
     fn info() -> Info;
     fn index() -> usize;
@@ -1107,24 +2398,49 @@ pub struct InterruptHandler<T: Instance> {
 }
 
 const UART_COUNT: usize = 8;
-static UART_WAKERS: [AtomicWaker; UART_COUNT] = [const { AtomicWaker::new() }; UART_COUNT];
+
+/// Per-direction interrupt state, so split halves awaited from different tasks don't steal each
+/// other's wakeups or errors
+struct UartState {
+    tx: AtomicWaker,
+    rx: AtomicWaker,
+    /// Line error flags latched by the interrupt handler, see [`take_rx_errors`]
+    rx_errors: AtomicU8,
+}
+
+static UART_STATE: [UartState; UART_COUNT] = [const {
+    UartState {
+        tx: AtomicWaker::new(),
+        rx: AtomicWaker::new(),
+        rx_errors: AtomicU8::new(0),
+    }
+}; UART_COUNT];
 
 impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
     unsafe fn on_interrupt() {
-        let waker = &UART_WAKERS[T::index()];
+        let state = &UART_STATE[T::index()];
         let regs = T::info().regs;
+        // Only reports interrupts that are enabled
         let stat = regs.intstat().read();
 
-        if stat.txidle().bit_is_set()
-            || stat.framerrint().bit_is_set()
+        // TX owns the idle interrupt
+        if stat.txidle().bit_is_set() {
+            regs.intenclr().write(|w| w.txidleclr().set_bit());
+            state.tx.wake();
+        }
+
+        // RX owns the line error interrupts. The flags are latched for the reader before they are
+        // cleared, so each error is reported once, and only to the receiver
+        if stat.framerrint().bit_is_set()
             || stat.parityerrint().bit_is_set()
             || stat.rxnoiseint().bit_is_set()
             || stat.aberrint().bit_is_set()
         {
+            let errors = RxErrors::take(regs);
+            state.rx_errors.fetch_or(errors.to_latch(), Ordering::Relaxed);
+
             regs.intenclr().write(|w| {
-                w.txidleclr()
-                    .set_bit()
-                    .framerrclr()
+                w.framerrclr()
                     .set_bit()
                     .parityerrclr()
                     .set_bit()
@@ -1133,9 +2449,14 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
                     .aberrclr()
                     .set_bit()
             });
+            state.rx.wake();
         }
 
-        waker.wake();
+        // RX also owns the FIFO overrun interrupt
+        if regs.fifointstat().read().rxerr().bit_is_set() {
+            regs.fifointenclr().write(|w| w.rxerr().set_bit());
+            state.rx.wake();
+        }
     }
 }
 
@@ -1155,6 +2476,8 @@ macro_rules! impl_instance {
 			Info {
 			    regs: unsafe { &*crate::pac::[<Usart $n>]::ptr() },
 			    index: $n,
+			    into_usart: <crate::peripherals::[<FLEXCOMM $n>] as crate::flexcomm::SealedIntoUsart>::into_usart,
+			    clock_hz: <crate::peripherals::[<FLEXCOMM $n>] as crate::flexcomm::FlexcommLowLevel>::clock_hz,
 			}
 		    }
 
@@ -1205,6 +2528,12 @@ pub trait RtsPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
     fn as_rts(&self);
 }
 
+/// io configuration trait for Uart Sclk, used in synchronous mode
+pub trait SclkPin<T: Instance>: Pin + sealed::Sealed + Peripheral {
+    /// convert the pin to appropriate function for Uart Sclk usage
+    fn as_sclk(&self);
+}
+
 macro_rules! impl_pin_trait {
     ($fcn:ident, $mode:ident, $($pin:ident, $fn:ident),*) => {
         paste! {
@@ -1232,48 +2561,56 @@ impl_pin_trait!(FLEXCOMM0, tx, PIO0_1, F1, PIO3_1, F5);
 impl_pin_trait!(FLEXCOMM0, rx, PIO0_2, F1, PIO3_2, F5);
 impl_pin_trait!(FLEXCOMM0, cts, PIO0_3, F1, PIO3_3, F5);
 impl_pin_trait!(FLEXCOMM0, rts, PIO0_4, F1, PIO3_4, F5);
+impl_pin_trait!(FLEXCOMM0, sclk, PIO0_0, F1, PIO3_0, F5);
 
 // FLEXCOMM1
 impl_pin_trait!(FLEXCOMM1, tx, PIO0_8, F1, PIO7_26, F1);
 impl_pin_trait!(FLEXCOMM1, rx, PIO0_9, F1, PIO7_27, F1);
 impl_pin_trait!(FLEXCOMM1, cts, PIO0_10, F1, PIO7_28, F1);
 impl_pin_trait!(FLEXCOMM1, rts, PIO0_11, F1, PIO7_29, F1);
+impl_pin_trait!(FLEXCOMM1, sclk, PIO0_7, F1, PIO7_25, F1);
 
 // FLEXCOMM2
 impl_pin_trait!(FLEXCOMM2, tx, PIO0_15, F1, PIO7_30, F5);
 impl_pin_trait!(FLEXCOMM2, rx, PIO0_16, F1, PIO7_31, F5);
 impl_pin_trait!(FLEXCOMM2, cts, PIO0_17, F1, PIO4_8, F5);
 impl_pin_trait!(FLEXCOMM2, rts, PIO0_18, F1);
+impl_pin_trait!(FLEXCOMM2, sclk, PIO0_14, F1);
 
 // FLEXCOMM3
 impl_pin_trait!(FLEXCOMM3, tx, PIO0_22, F1);
 impl_pin_trait!(FLEXCOMM3, rx, PIO0_23, F1);
 impl_pin_trait!(FLEXCOMM3, cts, PIO0_24, F1);
 impl_pin_trait!(FLEXCOMM3, rts, PIO0_25, F1);
+impl_pin_trait!(FLEXCOMM3, sclk, PIO0_21, F1);
 
 // FLEXCOMM4
 impl_pin_trait!(FLEXCOMM4, tx, PIO0_29, F1);
 impl_pin_trait!(FLEXCOMM4, rx, PIO0_30, F1);
 impl_pin_trait!(FLEXCOMM4, cts, PIO0_31, F1);
 impl_pin_trait!(FLEXCOMM4, rts, PIO1_0, F1);
+impl_pin_trait!(FLEXCOMM4, sclk, PIO0_28, F1);
 
 // FLEXCOMM5
 impl_pin_trait!(FLEXCOMM5, tx, PIO1_4, F1, PIO3_16, F5);
 impl_pin_trait!(FLEXCOMM5, rx, PIO1_5, F1, PIO3_17, F5);
 impl_pin_trait!(FLEXCOMM5, cts, PIO1_6, F1, PIO3_18, F5);
 impl_pin_trait!(FLEXCOMM5, rts, PIO1_7, F1, PIO3_23, F5);
+impl_pin_trait!(FLEXCOMM5, sclk, PIO1_3, F1, PIO3_15, F5);
 
 // FLEXCOMM6
 impl_pin_trait!(FLEXCOMM6, tx, PIO3_26, F1);
 impl_pin_trait!(FLEXCOMM6, rx, PIO3_27, F1);
 impl_pin_trait!(FLEXCOMM6, cts, PIO3_28, F1);
 impl_pin_trait!(FLEXCOMM6, rts, PIO3_29, F1);
+impl_pin_trait!(FLEXCOMM6, sclk, PIO3_25, F1);
 
 // FLEXCOMM7
 impl_pin_trait!(FLEXCOMM7, tx, PIO4_1, F1);
 impl_pin_trait!(FLEXCOMM7, rx, PIO4_2, F1);
 impl_pin_trait!(FLEXCOMM7, cts, PIO4_3, F1);
 impl_pin_trait!(FLEXCOMM7, rts, PIO4_4, F1);
+impl_pin_trait!(FLEXCOMM7, sclk, PIO4_0, F1);
 
 /// UART Tx DMA trait.
 #[allow(private_bounds)]
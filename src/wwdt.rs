@@ -1,22 +1,63 @@
 //! Windowed Watchdog Timer (WWDT)
+//!
+//! WDT0 and WDT1 are driven independently, each with its own clock, configuration and
+//! warning interrupt.
 
+use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::task::Poll;
 
+use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_hal_internal::{into_ref, Peripheral};
+use embassy_sync::waitqueue::AtomicWaker;
 
 use crate::clocks::{enable_and_reset, SysconPeripheral};
+use crate::interrupt;
 use crate::peripherals::{WDT0, WDT1};
 
+const WWDT_COUNT: usize = 2;
+
+static WWDT_WAKERS: [AtomicWaker; WWDT_COUNT] = [const { AtomicWaker::new() }; WWDT_COUNT];
+
+/// Driver mode.
+#[allow(private_bounds)]
+pub trait Mode: sealed::Sealed {}
+
+/// Blocking mode, the warning is only available by polling [`WindowedWatchdog::warning`].
+pub struct Blocking;
+impl sealed::Sealed for Blocking {}
+impl Mode for Blocking {}
+
+/// Async mode, the warning interrupt wakes [`WindowedWatchdog::wait_for_warning`].
+pub struct Async;
+impl sealed::Sealed for Async {}
+impl Mode for Async {}
+
+mod sealed {
+    /// simply seal a trait
+    pub trait Sealed {}
+}
+
 /// Windowed watchdog timer (WWDT) driver.
-pub struct WindowedWatchdog<'d> {
+///
+/// Dropping the driver leaves the watchdog hardware untouched. Once [`Self::unleash`] has been
+/// called the watchdog cannot be stopped by software, so an application that drops the driver
+/// must keep the counter fed by other means or a timeout event will follow. When
+/// [`Self::is_locked`] returns true the watchdog clock cannot be powered down either.
+pub struct WindowedWatchdog<'d, M: Mode = Blocking> {
     info: Info,
-    _phantom: PhantomData<&'d ()>,
+    _phantom: PhantomData<(&'d (), M)>,
 }
 
 struct Info {
     regs: &'static crate::pac::wwdt0::RegisterBlock,
+    index: usize,
+    irq: crate::pac::Interrupt,
 }
 
+// SAFETY: the watchdog registers are static MMIO used only by the driver owning the WDT instance.
+unsafe impl Send for Info {}
+
 trait SealedInstance {
     /// Returns a new Info, containing a reference to the register block.
     fn info() -> Info;
@@ -27,13 +68,37 @@ trait SealedInstance {
 
 /// WWDT instance trait
 #[allow(private_bounds)]
-pub trait Instance: SealedInstance + Peripheral<P = Self> + SysconPeripheral + 'static + Send {}
+pub trait Instance: SealedInstance + Peripheral<P = Self> + SysconPeripheral + 'static + Send {
+    /// Interrupt for this WWDT instance.
+    type Interrupt: interrupt::typelevel::Interrupt;
+}
+
+/// WWDT interrupt handler.
+///
+/// The warning flag stays set until cleared, so the handler masks the interrupt and
+/// [`WindowedWatchdog::wait_for_warning`] unmasks it again.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        let info = T::info();
+
+        if info.regs.mod_().read().wdint().bit_is_set() {
+            info.irq.disable();
+            WWDT_WAKERS[info.index].wake();
+        }
+    }
+}
 
 // Cortex-M33 watchdog
 impl SealedInstance for crate::peripherals::WDT0 {
     fn info() -> Info {
         Info {
             regs: unsafe { &*crate::pac::Wwdt0::ptr() },
+            index: 0,
+            irq: interrupt::WDT0,
         }
     }
 
@@ -51,13 +116,17 @@ impl SealedInstance for crate::peripherals::WDT0 {
         enable_and_reset::<WDT0>();
     }
 }
-impl Instance for crate::peripherals::WDT0 {}
+impl Instance for crate::peripherals::WDT0 {
+    type Interrupt = crate::interrupt::typelevel::WDT0;
+}
 
 // HiFi4 DSP watchdog
 impl SealedInstance for crate::peripherals::WDT1 {
     fn info() -> Info {
         Info {
             regs: unsafe { &*crate::pac::Wwdt1::ptr() },
+            index: 1,
+            irq: interrupt::WDT1,
         }
     }
 
@@ -71,7 +140,9 @@ impl SealedInstance for crate::peripherals::WDT1 {
         enable_and_reset::<WDT1>();
     }
 }
-impl Instance for crate::peripherals::WDT1 {}
+impl Instance for crate::peripherals::WDT1 {
+    type Interrupt = crate::interrupt::typelevel::WDT1;
+}
 
 // Fixed watchdog clock prescaler
 const PSC: u32 = 4;
@@ -113,7 +184,7 @@ fn init_lposc() {
     while clkctl0.lposcctl0().read().clkrdy().bit_is_clear() {}
 }
 
-impl<'d> WindowedWatchdog<'d> {
+impl<'d> WindowedWatchdog<'d, Blocking> {
     /// Creates a WWDT (Windowed Watchdog Timer) instance with a given timeout value in microseconds.
     ///
     /// [Self] has to be started with [`Self::unleash`], but should be configured beforehand.
@@ -127,6 +198,47 @@ impl<'d> WindowedWatchdog<'d> {
     /// This is not automatically cleared here because application code may wish to check
     /// if it is set via a call to [`Self::timed_out`] to determine if a watchdog reset occurred previously.
     pub fn new<T: Instance>(_instance: impl Peripheral<P = T> + 'd, timeout_us: u32) -> Self {
+        Self::new_inner(_instance, timeout_us)
+    }
+}
+
+impl<'d> WindowedWatchdog<'d, Async> {
+    /// Creates a WWDT instance like [`WindowedWatchdog::new`], with its warning interrupt bound
+    /// so [`Self::wait_for_warning`] can be awaited.
+    pub fn new_async<T: Instance>(
+        _instance: impl Peripheral<P = T> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        timeout_us: u32,
+    ) -> Self {
+        let wwdt = Self::new_inner(_instance, timeout_us);
+
+        wwdt.info.irq.unpend();
+
+        wwdt
+    }
+
+    /// Waits until the watchdog counter falls below the warning threshold.
+    ///
+    /// Returns immediately if the warning flag is already set, it must be cleared with
+    /// [`Self::clear_warning_flag`] before waiting for the next warning.
+    pub async fn wait_for_warning(&mut self) {
+        poll_fn(|cx| {
+            WWDT_WAKERS[self.info.index].register(cx.waker());
+
+            if self.warning() {
+                Poll::Ready(())
+            } else {
+                // SAFETY: the interrupt is bound to `InterruptHandler` in the constructor
+                unsafe { self.info.irq.enable() };
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'d, M: Mode> WindowedWatchdog<'d, M> {
+    fn new_inner<T: Instance>(_instance: impl Peripheral<P = T> + 'd, timeout_us: u32) -> Self {
         into_ref!(_instance);
 
         let mut wwdt = Self {
@@ -209,7 +321,7 @@ impl<'d> WindowedWatchdog<'d> {
     }
 }
 
-impl WindowedWatchdog<'_> {
+impl<M: Mode> WindowedWatchdog<'_, M> {
     /// Returns true if the warning flag is set.
     ///
     /// Flag is set if watchdog timeout counter has fallen below the time
@@ -221,6 +333,20 @@ impl WindowedWatchdog<'_> {
         self.info.regs.mod_().read().wdint().bit_is_set()
     }
 
+    /// Returns true if [`Self::lock`] was called, the watchdog clock then cannot be powered
+    /// down until reset.
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        self.info.regs.mod_().read().lock().bit_is_set()
+    }
+
+    /// Returns true if the watchdog has been started with [`Self::unleash`], it then cannot be
+    /// stopped until reset.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.info.regs.mod_().read().wden().bit_is_set()
+    }
+
     /// Clears the warning interrupt flag.
     pub fn clear_warning_flag(&mut self) {
         // Warning flag is cleared by writing a 1